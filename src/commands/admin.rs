@@ -0,0 +1,686 @@
+//! Platform-admin commands for the global Config account
+//!
+//! Every action is checked against the current on-chain config before the
+//! platform authority is asked to confirm. With `--dry-run` or `--export-tx`
+//! the checks still run, so operators can review exactly what would change
+//! before anything is signed.
+
+use crate::commands::show_payee::volume_tier_name;
+use crate::config::TallyCliConfig;
+use crate::errors::{config_account_not_found, CliError, ErrorCode};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use tally_sdk::program_types::{Config, InitConfigArgs, UpdateConfigFeesArgs, VolumeTier};
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{SimpleTallyClient, UsdcAmount};
+use tracing::info;
+
+/// Maximum value for any fee expressed in basis points (100%)
+const MAX_BPS: u16 = 10_000;
+
+/// Settings for a new Config account
+///
+/// The key that signs `admin init-config` becomes the platform authority, so
+/// it is filled in once the signer has been loaded.
+#[derive(Debug, Clone)]
+pub struct InitConfigSettings {
+    pub min_platform_fee_bps: u16,
+    pub max_platform_fee_bps: u16,
+    pub keeper_fee_bps: u16,
+    /// Maximum amount per withdrawal in micro-units
+    pub max_withdrawal_amount: u64,
+    pub min_period_seconds: u64,
+    pub default_allowance_periods: u8,
+    pub max_grace_period_seconds: u64,
+    pub allowed_mint: Pubkey,
+}
+
+impl InitConfigSettings {
+    /// Instruction arguments with `platform_authority` as the new authority
+    #[must_use]
+    pub const fn args(&self, platform_authority: Pubkey) -> InitConfigArgs {
+        InitConfigArgs {
+            platform_authority,
+            max_platform_fee_bps: self.max_platform_fee_bps,
+            min_platform_fee_bps: self.min_platform_fee_bps,
+            min_period_seconds: self.min_period_seconds,
+            default_allowance_periods: self.default_allowance_periods,
+            allowed_mint: self.allowed_mint,
+            max_withdrawal_amount: self.max_withdrawal_amount,
+            max_grace_period_seconds: self.max_grace_period_seconds,
+            keeper_fee_bps: self.keeper_fee_bps,
+        }
+    }
+}
+
+/// A change to the global Config account
+#[derive(Debug, Clone)]
+pub enum AdminAction {
+    /// Create the Config account, with the signer as platform authority
+    InitConfig(InitConfigSettings),
+    /// Change platform fee bounds and/or the keeper fee
+    SetFees(UpdateConfigFeesArgs),
+    /// Emergency pause (`true`) or unpause (`false`)
+    SetPaused(bool),
+    /// Propose a new platform authority (step 1 of 2)
+    TransferAuthority { new_authority: Pubkey },
+    /// Accept a pending platform authority transfer (step 2 of 2)
+    AcceptAuthority,
+    /// Move a payee to a different volume tier
+    SetPayeeTier { payee: Pubkey, tier: VolumeTier },
+}
+
+impl AdminAction {
+    /// Short description used in prompts and status messages
+    #[must_use]
+    pub const fn title(&self) -> &'static str {
+        match self {
+            Self::InitConfig(_) => "Initialize global config",
+            Self::SetFees(_) => "Update platform fees",
+            Self::SetPaused(true) => "Pause the protocol",
+            Self::SetPaused(false) => "Unpause the protocol",
+            Self::TransferAuthority { .. } => "Propose new platform authority",
+            Self::AcceptAuthority => "Accept platform authority",
+            Self::SetPayeeTier { .. } => "Change payee volume tier",
+        }
+    }
+}
+
+/// Request to run an admin action
+pub struct AdminRequest<'a> {
+    pub action: AdminAction,
+    /// Platform authority keypair (or the pending authority for accept)
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Outcome of an admin command, with the changes checked before signing
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AdminResult {
+    pub action: String,
+    /// What the action changes, as "field: old -> new" lines
+    pub changes: Vec<String>,
+    #[serde(flatten)]
+    pub outcome: TxOutcome<AdminSubmitted>,
+}
+
+/// A submitted admin transaction
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AdminSubmitted {
+    pub signature: String,
+}
+
+impl CommandOutput for AdminSubmitted {
+    const KIND: &'static str = "admin.submitted";
+
+    fn human(&self) -> Result<String> {
+        Ok(format!(
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        ))
+    }
+}
+
+impl CommandOutput for AdminResult {
+    const KIND: &'static str = "admin";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        if matches!(self.outcome, TxOutcome::Sent(_)) {
+            writeln!(
+                &mut output,
+                "{}",
+                Theme::success(&format!("{} succeeded!", self.action))
+            )?;
+        } else {
+            writeln!(&mut output, "{}", Theme::info("Pre-flight checks passed:"))?;
+        }
+        for change in &self.changes {
+            writeln!(&mut output, "  {change}")?;
+        }
+        if !matches!(self.outcome, TxOutcome::Sent(_)) {
+            writeln!(&mut output)?;
+        }
+        output.push_str(&self.outcome.human()?);
+        Ok(output)
+    }
+
+    fn success(&self) -> bool {
+        self.outcome.success()
+    }
+}
+
+/// Execute an admin command
+///
+/// # Errors
+/// Returns error if the pre-flight checks fail, the user declines the
+/// confirmation prompt, or the transaction fails
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &AdminRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<AdminResult> {
+    let action = &request.action;
+    info!("Starting admin action: {}", action.title());
+
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    let signer = authority.pubkey();
+
+    let current = tally_client
+        .get_config()
+        .context("Failed to fetch config account - check RPC connection and account state")?;
+    let current_tier = match action {
+        AdminAction::SetPayeeTier { payee, .. } => Some(
+            tally_client
+                .get_payee(payee)
+                .context("Failed to fetch payee account - check RPC connection and account state")?
+                .ok_or_else(|| anyhow!("Payee account not found: {payee}"))?
+                .volume_tier,
+        ),
+        _ => None,
+    };
+    preflight(action, current.as_ref(), current_tier, &signer)?;
+
+    let changes = describe(action, current.as_ref(), current_tier, &signer);
+
+    let plan = || -> Result<TxPlan> {
+        let instructions = match action {
+            AdminAction::InitConfig(settings) => {
+                tally_client.init_config_instructions(&signer, settings.args(signer))
+            }
+            AdminAction::SetFees(args) => {
+                tally_client.update_config_fees_instructions(&signer, args.clone())
+            }
+            AdminAction::SetPaused(paused) => {
+                tally_client.set_config_paused_instructions(&signer, *paused)
+            }
+            AdminAction::TransferAuthority { new_authority } => {
+                tally_client.transfer_platform_authority_instructions(&signer, new_authority)
+            }
+            AdminAction::AcceptAuthority => {
+                tally_client.accept_platform_authority_instructions(&signer)
+            }
+            AdminAction::SetPayeeTier { payee, tier } => {
+                tally_client.set_payee_volume_tier_instructions(&signer, payee, *tier)
+            }
+        }
+        .map_err(|e| anyhow!("Failed to build {} transaction: {e}", action.title()))?;
+        Ok(TxPlan::new(action.title(), signer, instructions))
+    };
+
+    let result = |outcome| AdminResult {
+        action: action.title().to_string(),
+        changes: changes.clone(),
+        outcome,
+    };
+    if !request.tx_mode.is_send() {
+        return Ok(result(transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        )?));
+    }
+
+    if !request.skip_confirmation {
+        eprintln!("{}", Theme::warning(action.title()));
+        for change in &changes {
+            eprintln!("  {change}");
+        }
+        let confirmed = Confirm::new()
+            .with_prompt("Submit this change to the global config?")
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("{} canceled by user", action.title()));
+        }
+    }
+
+    if let TxSigner::External(external) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, external.as_ref())?;
+        return Ok(result(TxOutcome::Sent(AdminSubmitted {
+            signature: signature.to_string(),
+        })));
+    }
+    let authority = authority.into_keypair()?;
+
+    let spinner = progress::create_spinner(&format!("{}...", action.title()));
+    let sent = match action {
+        AdminAction::InitConfig(settings) => tally_client
+            .init_config(&authority, settings.args(signer))
+            .map(|(_, signature)| signature),
+        AdminAction::SetFees(args) => tally_client.update_config_fees(&authority, args.clone()),
+        AdminAction::SetPaused(paused) => tally_client.set_config_paused(&authority, *paused),
+        AdminAction::TransferAuthority { new_authority } => {
+            tally_client.transfer_platform_authority(&authority, new_authority)
+        }
+        AdminAction::AcceptAuthority => tally_client.accept_platform_authority(&authority),
+        AdminAction::SetPayeeTier { payee, tier } => {
+            tally_client.set_payee_volume_tier(&authority, payee, *tier)
+        }
+    }
+    .map_err(|e| {
+        let failed = format!("{} failed", action.title());
+        transaction::explain_failure(tally_client, plan, &failed, e)
+    });
+
+    match &sent {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, &format!("{} succeeded", action.title()));
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, &format!("{} failed", action.title()));
+        }
+    }
+
+    Ok(result(TxOutcome::Sent(AdminSubmitted { signature: sent? })))
+}
+
+/// Check an action against the current config and signer
+///
+/// `current_tier` is the payee's volume tier for `SetPayeeTier` and `None`
+/// for every other action.
+///
+/// # Errors
+/// Returns error describing why the action would be rejected
+pub fn preflight(
+    action: &AdminAction,
+    current: Option<&Config>,
+    current_tier: Option<VolumeTier>,
+    signer: &Pubkey,
+) -> Result<()> {
+    if let AdminAction::InitConfig(settings) = action {
+        if current.is_some() {
+            return Err(anyhow!(
+                "Config account is already initialized.\n\
+                 Use 'tally-merchant config show' to inspect it or the other admin commands to change it."
+            ));
+        }
+        return validate_fees(
+            settings.min_platform_fee_bps,
+            settings.max_platform_fee_bps,
+            settings.keeper_fee_bps,
+        );
+    }
+
+    let cfg = current.ok_or_else(config_account_not_found)?;
+
+    if matches!(action, AdminAction::AcceptAuthority) {
+        return match cfg.pending_authority {
+            Some(pending) if pending == *signer => Ok(()),
+            Some(pending) => Err(anyhow!(
+                "Platform authority is pending transfer to {pending}, but the loaded keypair is {signer}"
+            )),
+            None => Err(anyhow!(
+                "No platform authority transfer is pending.\n\
+                 The current authority must first run 'tally-merchant admin transfer-authority'."
+            )),
+        };
+    }
+
+    check_platform_authority(&cfg.platform_authority, signer)?;
+
+    match action {
+        AdminAction::SetFees(args) => {
+            if args.min_platform_fee_bps.is_none()
+                && args.max_platform_fee_bps.is_none()
+                && args.keeper_fee_bps.is_none()
+            {
+                return Err(anyhow!(
+                    "Nothing to update - pass --min-platform-fee-bps, --max-platform-fee-bps and/or --keeper-fee-bps"
+                ));
+            }
+            validate_fees(
+                args.min_platform_fee_bps
+                    .unwrap_or(cfg.min_platform_fee_bps),
+                args.max_platform_fee_bps
+                    .unwrap_or(cfg.max_platform_fee_bps),
+                args.keeper_fee_bps.unwrap_or(cfg.keeper_fee_bps),
+            )
+        }
+        AdminAction::SetPaused(paused) if cfg.paused == *paused => Err(anyhow!(
+            "Protocol is already {}",
+            if *paused { "paused" } else { "unpaused" }
+        )),
+        AdminAction::TransferAuthority { new_authority }
+            if *new_authority == cfg.platform_authority =>
+        {
+            Err(anyhow!("{new_authority} is already the platform authority"))
+        }
+        AdminAction::SetPayeeTier { payee, tier } if current_tier == Some(*tier) => Err(anyhow!(
+            "Payee {payee} is already on the {} volume tier",
+            volume_tier_name(*tier)
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Check that `signer` is the platform authority
+///
+/// # Errors
+/// Returns error if the keys differ
+pub fn check_platform_authority(platform_authority: &Pubkey, signer: &Pubkey) -> Result<()> {
+    if platform_authority != signer {
+        return Err(CliError::new(
+            ErrorCode::AuthorityMismatch,
+            format!(
+                "Authority mismatch: the platform authority is {platform_authority}, but the \
+                 loaded keypair is {signer}."
+            ),
+        )
+        .with_suggestion("Pass --authority <KEYPAIR> with the platform authority keypair.")
+        .into());
+    }
+    Ok(())
+}
+
+/// Check fee bounds are within 0-100% and min does not exceed max
+///
+/// # Errors
+/// Returns error if any bound is invalid
+pub fn validate_fees(
+    min_platform_fee_bps: u16,
+    max_platform_fee_bps: u16,
+    keeper_fee_bps: u16,
+) -> Result<()> {
+    for (name, value) in [
+        ("Minimum platform fee", min_platform_fee_bps),
+        ("Maximum platform fee", max_platform_fee_bps),
+        ("Keeper fee", keeper_fee_bps),
+    ] {
+        if value > MAX_BPS {
+            return Err(anyhow!(
+                "{name} must be at most {MAX_BPS} bps (100%), got {value}"
+            ));
+        }
+    }
+    if min_platform_fee_bps > max_platform_fee_bps {
+        return Err(anyhow!(
+            "Minimum platform fee ({min_platform_fee_bps} bps) cannot exceed maximum platform fee ({max_platform_fee_bps} bps)"
+        ));
+    }
+    Ok(())
+}
+
+/// Describe what an action will change, as "field: old -> new" lines
+fn describe(
+    action: &AdminAction,
+    current: Option<&Config>,
+    current_tier: Option<VolumeTier>,
+    signer: &Pubkey,
+) -> Vec<String> {
+    let bps_change = |name: &str, old: Option<u16>, new: u16| {
+        old.map_or_else(
+            || format!("{name}: {new} bps"),
+            |old| format!("{name}: {old} bps -> {new} bps"),
+        )
+    };
+
+    match action {
+        AdminAction::InitConfig(settings) => vec![
+            format!("Platform authority: {signer}"),
+            format!("Allowed mint: {}", settings.allowed_mint),
+            format!(
+                "Platform fee bounds: {}-{} bps",
+                settings.min_platform_fee_bps, settings.max_platform_fee_bps
+            ),
+            format!("Keeper fee: {} bps", settings.keeper_fee_bps),
+            format!("Minimum period: {} seconds", settings.min_period_seconds),
+            format!(
+                "Default allowance periods: {}",
+                settings.default_allowance_periods
+            ),
+            format!(
+                "Max withdrawal amount: {} USDC",
+                UsdcAmount::from_microlamports(settings.max_withdrawal_amount)
+            ),
+            format!(
+                "Max grace period: {} seconds",
+                settings.max_grace_period_seconds
+            ),
+        ],
+        AdminAction::SetFees(args) => {
+            let mut changes = Vec::new();
+            if let Some(new) = args.min_platform_fee_bps {
+                changes.push(bps_change(
+                    "Minimum platform fee",
+                    current.map(|c| c.min_platform_fee_bps),
+                    new,
+                ));
+            }
+            if let Some(new) = args.max_platform_fee_bps {
+                changes.push(bps_change(
+                    "Maximum platform fee",
+                    current.map(|c| c.max_platform_fee_bps),
+                    new,
+                ));
+            }
+            if let Some(new) = args.keeper_fee_bps {
+                changes.push(bps_change(
+                    "Keeper fee",
+                    current.map(|c| c.keeper_fee_bps),
+                    new,
+                ));
+            }
+            changes
+        }
+        AdminAction::SetPaused(true) => vec![
+            "Paused: false -> true".to_string(),
+            "New agreements and payments will be rejected until unpaused".to_string(),
+        ],
+        AdminAction::SetPaused(false) => vec!["Paused: true -> false".to_string()],
+        AdminAction::TransferAuthority { new_authority } => vec![
+            format!(
+                "Current authority: {}",
+                current.map_or_else(|| "unknown".to_string(), |c| c.platform_authority.to_string())
+            ),
+            format!("Proposed authority: {new_authority}"),
+            "The new authority must run 'tally-merchant admin accept-authority' to complete the transfer"
+                .to_string(),
+        ],
+        AdminAction::AcceptAuthority => vec![format!(
+            "Platform authority: {} -> pending authority",
+            current.map_or_else(|| "unknown".to_string(), |c| c.platform_authority.to_string())
+        )],
+        AdminAction::SetPayeeTier { payee, tier } => vec![
+            format!("Payee: {payee}"),
+            format!(
+                "Volume tier: {} -> {}",
+                current_tier.map_or("unknown", volume_tier_name),
+                volume_tier_name(*tier)
+            ),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorDetails;
+
+    fn config(authority: Pubkey) -> Config {
+        Config {
+            platform_authority: authority,
+            pending_authority: None,
+            max_platform_fee_bps: 100,
+            min_platform_fee_bps: 10,
+            min_period_seconds: 86_400,
+            default_allowance_periods: 3,
+            allowed_mint: Pubkey::new_unique(),
+            max_withdrawal_amount: 1_000_000_000,
+            max_grace_period_seconds: 604_800,
+            paused: false,
+            keeper_fee_bps: 25,
+            bump: 255,
+        }
+    }
+
+    fn init_settings() -> InitConfigSettings {
+        InitConfigSettings {
+            max_platform_fee_bps: 100,
+            min_platform_fee_bps: 10,
+            min_period_seconds: 86_400,
+            default_allowance_periods: 3,
+            allowed_mint: Pubkey::new_unique(),
+            max_withdrawal_amount: 1_000_000_000,
+            max_grace_period_seconds: 604_800,
+            keeper_fee_bps: 25,
+        }
+    }
+
+    #[test]
+    fn test_validate_fees() {
+        assert!(validate_fees(10, 100, 25).is_ok());
+        assert!(validate_fees(0, 10_000, 10_000).is_ok());
+        assert!(validate_fees(100, 10, 25).is_err());
+        assert!(validate_fees(10, 10_001, 25).is_err());
+        assert!(validate_fees(10, 100, 10_001).is_err());
+    }
+
+    #[test]
+    fn test_init_config_requires_missing_config() {
+        let authority = Pubkey::new_unique();
+        let action = AdminAction::InitConfig(init_settings());
+        assert!(preflight(&action, None, None, &authority).is_ok());
+
+        let err = preflight(&action, Some(&config(authority)), None, &authority)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("already initialized"));
+    }
+
+    #[test]
+    fn test_actions_require_platform_authority() {
+        let authority = Pubkey::new_unique();
+        let cfg = config(authority);
+        let action = AdminAction::SetPaused(true);
+
+        assert!(preflight(&action, Some(&cfg), None, &authority).is_ok());
+        let err = preflight(&action, Some(&cfg), None, &Pubkey::new_unique()).unwrap_err();
+        let details = ErrorDetails::new(&err);
+        assert_eq!(details.code, ErrorCode::AuthorityMismatch);
+        assert!(details.message.contains("Authority mismatch"));
+    }
+
+    #[test]
+    fn test_actions_require_config() {
+        let err = preflight(
+            &AdminAction::SetPaused(true),
+            None,
+            None,
+            &Pubkey::new_unique(),
+        )
+        .unwrap_err();
+        assert_eq!(ErrorDetails::new(&err).code, ErrorCode::AccountNotFound);
+        assert!(err.to_string().contains("init-config"));
+    }
+
+    #[test]
+    fn test_pause_rejects_no_op() {
+        let authority = Pubkey::new_unique();
+        let cfg = config(authority);
+        assert!(preflight(&AdminAction::SetPaused(false), Some(&cfg), None, &authority).is_err());
+    }
+
+    #[test]
+    fn test_payee_tier_rejects_no_op() {
+        let authority = Pubkey::new_unique();
+        let cfg = config(authority);
+        let action = AdminAction::SetPayeeTier {
+            payee: Pubkey::new_unique(),
+            tier: VolumeTier::Growth,
+        };
+
+        let err = preflight(&action, Some(&cfg), Some(VolumeTier::Growth), &authority)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("already on the Growth volume tier"));
+        assert!(preflight(&action, Some(&cfg), Some(VolumeTier::Standard), &authority).is_ok());
+    }
+
+    #[test]
+    fn test_set_fees_checks_resulting_bounds() {
+        let authority = Pubkey::new_unique();
+        let cfg = config(authority);
+
+        let empty = AdminAction::SetFees(UpdateConfigFeesArgs::default());
+        assert!(preflight(&empty, Some(&cfg), None, &authority).is_err());
+
+        // Raising the minimum above the current maximum (100 bps) is rejected
+        let too_high = AdminAction::SetFees(UpdateConfigFeesArgs {
+            min_platform_fee_bps: Some(150),
+            ..UpdateConfigFeesArgs::default()
+        });
+        assert!(preflight(&too_high, Some(&cfg), None, &authority).is_err());
+
+        let valid = AdminAction::SetFees(UpdateConfigFeesArgs {
+            keeper_fee_bps: Some(50),
+            ..UpdateConfigFeesArgs::default()
+        });
+        assert!(preflight(&valid, Some(&cfg), None, &authority).is_ok());
+    }
+
+    #[test]
+    fn test_accept_authority_requires_pending_signer() {
+        let authority = Pubkey::new_unique();
+        let pending = Pubkey::new_unique();
+        let mut cfg = config(authority);
+
+        assert!(preflight(&AdminAction::AcceptAuthority, Some(&cfg), None, &pending).is_err());
+
+        cfg.pending_authority = Some(pending);
+        assert!(preflight(&AdminAction::AcceptAuthority, Some(&cfg), None, &pending).is_ok());
+        assert!(preflight(&AdminAction::AcceptAuthority, Some(&cfg), None, &authority).is_err());
+    }
+
+    #[test]
+    fn test_describe_set_fees() {
+        let cfg = config(Pubkey::new_unique());
+        let action = AdminAction::SetFees(UpdateConfigFeesArgs {
+            keeper_fee_bps: Some(50),
+            ..UpdateConfigFeesArgs::default()
+        });
+        assert_eq!(
+            describe(&action, Some(&cfg), None, &Pubkey::new_unique()),
+            vec!["Keeper fee: 25 bps -> 50 bps".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_init_config_makes_signer_platform_authority() {
+        let signer = Pubkey::new_unique();
+        let settings = init_settings();
+        assert_eq!(settings.args(signer).platform_authority, signer);
+
+        let changes = describe(&AdminAction::InitConfig(settings), None, None, &signer);
+        assert_eq!(changes[0], format!("Platform authority: {signer}"));
+    }
+
+    #[test]
+    fn test_describe_payee_tier() {
+        let action = AdminAction::SetPayeeTier {
+            payee: Pubkey::new_unique(),
+            tier: VolumeTier::Scale,
+        };
+        let changes = describe(
+            &action,
+            None,
+            Some(VolumeTier::Standard),
+            &Pubkey::new_unique(),
+        );
+        assert_eq!(changes[1], "Volume tier: Standard -> Scale");
+    }
+}
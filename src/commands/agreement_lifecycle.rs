@@ -0,0 +1,332 @@
+//! Payment agreement lifecycle commands: pause, resume and close
+
+use crate::config::TallyCliConfig;
+use crate::errors::enhance_account_not_found_error;
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::SimpleTallyClient;
+use tracing::info;
+
+/// Lifecycle transition to apply to a payment agreement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgreementAction {
+    /// Stop payments temporarily (revokes the delegate)
+    Pause,
+    /// Restart payments after a pause
+    Resume,
+    /// End the relationship and reclaim rent
+    Close,
+}
+
+impl AgreementAction {
+    /// Lowercase name used in JSON output
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Close => "close",
+        }
+    }
+
+    /// Capitalized verb for prompts
+    #[must_use]
+    pub const fn verb(self) -> &'static str {
+        match self {
+            Self::Pause => "Pause",
+            Self::Resume => "Resume",
+            Self::Close => "Close",
+        }
+    }
+
+    /// Past-tense verb for status messages
+    #[must_use]
+    pub const fn past_tense(self) -> &'static str {
+        match self {
+            Self::Pause => "paused",
+            Self::Resume => "resumed",
+            Self::Close => "closed",
+        }
+    }
+
+    /// Whether the action interrupts payments and should be confirmed first
+    #[must_use]
+    pub const fn is_destructive(self) -> bool {
+        matches!(self, Self::Pause | Self::Close)
+    }
+
+    /// Check that the agreement is in a state this action can be applied to
+    ///
+    /// # Errors
+    /// Returns error if pausing an already paused agreement or resuming an active one
+    pub fn validate(self, agreement: &Pubkey, active: bool) -> Result<()> {
+        match (self, active) {
+            (Self::Pause, false) => Err(anyhow!(
+                "Payment agreement {agreement} is already paused.\n\
+                 Use 'tally-merchant agreement resume --agreement {agreement}' to restart payments."
+            )),
+            (Self::Resume, true) => Err(anyhow!(
+                "Payment agreement {agreement} is already active - nothing to resume."
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Request to change the lifecycle state of a payment agreement
+pub struct AgreementLifecycleRequest<'a> {
+    /// Payment Agreement PDA address
+    pub agreement: &'a str,
+    /// Transition to apply
+    pub action: AgreementAction,
+    /// Signer keypair path or URI, from `--authority` or else
+    /// [`RuntimeContext::signer_path`](crate::context::RuntimeContext::signer_path)
+    /// (`TALLY_WALLET`, the profile, the config defaults, then the Solana CLI keypair)
+    pub authority_path: Option<&'a str>,
+    /// Skip the confirmation prompt for destructive actions
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// A payment agreement paused, resumed or closed
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AgreementLifecycleResult {
+    /// `pause`, `resume` or `close`
+    pub action: String,
+    pub agreement: String,
+    pub payer: String,
+    pub payment_terms: String,
+    pub signature: String,
+    #[serde(skip)]
+    past_tense: &'static str,
+}
+
+impl CommandOutput for AgreementLifecycleResult {
+    const KIND: &'static str = "agreement.lifecycle";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success(&format!(
+                "Payment agreement {} successfully!",
+                self.past_tense
+            ))
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Agreement:"),
+            Theme::highlight(&self.agreement)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payer:"),
+            Theme::value(&self.payer)
+        )?;
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
+}
+
+/// Execute a pause, resume or close command
+///
+/// # Errors
+/// Returns an error if:
+/// * Agreement public key cannot be parsed
+/// * Agreement account cannot be fetched or does not exist
+/// * The agreement is not in a valid state for the action
+/// * The user declines the confirmation prompt
+/// * The transaction fails
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &AgreementLifecycleRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<AgreementLifecycleResult>> {
+    let action = request.action;
+    info!("Starting agreement {}", action.as_str());
+
+    let agreement_address = Pubkey::from_str(request.agreement).map_err(|e| {
+        anyhow!(
+            "Invalid payment agreement address '{}': {e}",
+            request.agreement
+        )
+    })?;
+
+    // Check current state before asking for signatures
+    let agreement = tally_client
+        .get_payment_agreement(&agreement_address)
+        .context(
+            "Failed to fetch payment agreement account - check RPC connection and account state",
+        )?
+        .ok_or_else(|| enhance_account_not_found_error("payment agreement", &agreement_address))?;
+    action.validate(&agreement_address, agreement.active)?;
+
+    let signer = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load signer keypair")?;
+    info!("Using signer: {}", signer.pubkey());
+
+    let signer_pubkey = signer.pubkey();
+    let plan =
+        || -> Result<TxPlan> {
+            let instructions =
+                match action {
+                    AgreementAction::Pause => tally_client
+                        .pause_agreement_instructions(&signer_pubkey, &agreement_address),
+                    AgreementAction::Resume => tally_client
+                        .resume_agreement_instructions(&signer_pubkey, &agreement_address),
+                    AgreementAction::Close => tally_client
+                        .close_agreement_instructions(&signer_pubkey, &agreement_address),
+                }
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to build {}_agreement transaction: {e}",
+                        action.as_str()
+                    )
+                })?;
+            Ok(TxPlan::new(
+                format!("{} payment agreement {agreement_address}", action.verb()),
+                signer_pubkey,
+                instructions,
+            ))
+        };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(tally_client, &plan()?, request.tx_mode, &signer);
+    }
+
+    if action.is_destructive() && !request.skip_confirmation {
+        let prompt = format!(
+            "{} payment agreement {agreement_address} (payer {})?",
+            action.verb(),
+            agreement.payer
+        );
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+
+        if !confirmed {
+            return Err(anyhow!("Agreement {} canceled by user", action.as_str()));
+        }
+    }
+
+    let signature = if let TxSigner::External(external) = &signer {
+        transaction::send_plan(tally_client, &plan()?, external.as_ref())?.to_string()
+    } else {
+        send_with_keypair(tally_client, signer, action, &agreement_address, plan)?
+    };
+
+    Ok(TxOutcome::Sent(AgreementLifecycleResult {
+        action: action.as_str().to_string(),
+        agreement: agreement_address.to_string(),
+        payer: agreement.payer.to_string(),
+        payment_terms: agreement.payment_terms.to_string(),
+        signature,
+        past_tense: action.past_tense(),
+    }))
+}
+
+/// Apply `action` with the SDK's convenience method, showing a spinner
+///
+/// `plan` rebuilds the transaction to explain a failure.
+fn send_with_keypair(
+    tally_client: &SimpleTallyClient,
+    signer: TxSigner,
+    action: AgreementAction,
+    agreement_address: &Pubkey,
+    plan: impl FnOnce() -> Result<TxPlan>,
+) -> Result<String> {
+    let signer = signer.into_keypair()?;
+
+    let spinner = progress::create_spinner(&format!(
+        "Submitting {}_agreement transaction...",
+        action.as_str()
+    ));
+    let result = match action {
+        AgreementAction::Pause => tally_client.pause_agreement(&signer, agreement_address),
+        AgreementAction::Resume => tally_client.resume_agreement(&signer, agreement_address),
+        AgreementAction::Close => tally_client.close_agreement(&signer, agreement_address),
+    }
+    .map_err(|e| {
+        let action = format!("Failed to {} payment agreement", action.as_str());
+        transaction::explain_failure(tally_client, plan, &action, e)
+    });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(
+                &spinner,
+                &format!("Payment agreement {}", action.past_tense()),
+            );
+            info!("Transaction confirmed: {signature}");
+        }
+        Err(_) => {
+            progress::finish_progress_error(
+                &spinner,
+                &format!("Failed to {} payment agreement", action.as_str()),
+            );
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destructive_actions() {
+        assert!(AgreementAction::Pause.is_destructive());
+        assert!(AgreementAction::Close.is_destructive());
+        assert!(!AgreementAction::Resume.is_destructive());
+    }
+
+    #[test]
+    fn test_validate_pause_requires_active() {
+        let agreement = Pubkey::new_unique();
+        assert!(AgreementAction::Pause.validate(&agreement, true).is_ok());
+
+        let err = AgreementAction::Pause
+            .validate(&agreement, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("already paused"));
+        assert!(err.contains("agreement resume"));
+    }
+
+    #[test]
+    fn test_validate_resume_requires_paused() {
+        let agreement = Pubkey::new_unique();
+        assert!(AgreementAction::Resume.validate(&agreement, false).is_ok());
+        assert!(AgreementAction::Resume
+            .validate(&agreement, true)
+            .unwrap_err()
+            .to_string()
+            .contains("already active"));
+    }
+
+    #[test]
+    fn test_validate_close_any_state() {
+        let agreement = Pubkey::new_unique();
+        assert!(AgreementAction::Close.validate(&agreement, true).is_ok());
+        assert!(AgreementAction::Close.validate(&agreement, false).is_ok());
+    }
+}
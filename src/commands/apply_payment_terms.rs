@@ -0,0 +1,786 @@
+//! Apply payment terms command implementation
+//!
+//! Reconciles a declarative TOML catalog of payment terms against the terms
+//! on-chain: missing terms are created, changed terms are updated and active
+//! terms absent from the catalog are deactivated. Re-running an applied
+//! catalog is a no-op.
+
+use crate::config::TallyCliConfig;
+use crate::output::CommandOutput;
+use crate::utils::amount::deserialize_usdc_amount;
+use crate::utils::authority::verify_payee_authority;
+use crate::utils::colors::Theme;
+use crate::utils::duration::{format_period, period_from_fields, warn_calendar_drift};
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+use tally_sdk::program_types::{CreatePaymentTermsArgs, UpdatePaymentTermsArgs};
+use tally_sdk::solana_sdk::instruction::Instruction;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{PaymentPeriod, SimpleTallyClient, TermsId, UsdcAmount};
+use tracing::info;
+
+/// Arguments for applying a payment terms catalog
+pub struct ApplyPaymentTermsRequest<'a> {
+    pub catalog_path: &'a Path,
+    pub payee: Pubkey,
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Catalog file layout
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogFile {
+    #[serde(default)]
+    terms: Vec<CatalogEntry>,
+}
+
+/// One `[[terms]]` table of the catalog
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogEntry {
+    id: String,
+    #[serde(deserialize_with = "deserialize_usdc_amount")]
+    amount_usdc: UsdcAmount,
+    /// Duration such as `"1mo"` or `"P1Y"`
+    period: Option<String>,
+    period_days: Option<u64>,
+    period_months: Option<u64>,
+}
+
+/// Validated payment terms the catalog asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesiredTerms {
+    pub id: String,
+    pub amount_usdc: u64,
+    pub period_secs: u64,
+}
+
+impl DesiredTerms {
+    /// Validate terms with the same rules as `payment-terms create`
+    ///
+    /// # Errors
+    /// Returns error if the id is invalid, the amount is zero, or the period is
+    /// shorter than a day
+    pub fn new(id: &str, amount: UsdcAmount, period_secs: u64) -> Result<Self> {
+        let terms_id = TermsId::new(id).with_context(|| {
+            format!("Invalid terms id '{id}' - use only alphanumeric, underscores, and hyphens")
+        })?;
+
+        if amount.microlamports() == 0 {
+            return Err(anyhow!("Amount must be greater than 0 USDC"));
+        }
+
+        let period = PaymentPeriod::from_seconds(period_secs)
+            .context("Invalid period - must be at least 1 day")?;
+
+        Ok(Self {
+            id: terms_id.as_str().to_string(),
+            amount_usdc: amount.microlamports(),
+            period_secs: period.seconds(),
+        })
+    }
+
+    /// Arguments for the `create_payment_terms` instruction
+    ///
+    /// # Errors
+    /// Returns error if the id is not a valid terms id
+    pub fn create_args(&self) -> Result<CreatePaymentTermsArgs> {
+        let terms_id = TermsId::new(&self.id).context("Invalid terms ID")?;
+        Ok(CreatePaymentTermsArgs {
+            terms_id: self.id.clone(),
+            terms_id_bytes: terms_id.to_padded_bytes(),
+            amount_usdc: self.amount_usdc,
+            period_secs: self.period_secs,
+        })
+    }
+}
+
+/// Payment terms as they exist on-chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrentTerms {
+    pub address: Pubkey,
+    pub id: String,
+    pub amount_usdc: u64,
+    pub period_secs: u64,
+    pub active: bool,
+}
+
+/// One step needed to bring the chain in line with the catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermsChange {
+    Create(DesiredTerms),
+    Update {
+        id: String,
+        address: Pubkey,
+        /// `(current, desired)` when the amount changes
+        amount_usdc: Option<(u64, u64)>,
+        /// `(current, desired)` when the period changes
+        period_secs: Option<(u64, u64)>,
+    },
+    Deactivate {
+        id: String,
+        address: Pubkey,
+    },
+}
+
+/// Outcome of `payment-terms apply`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CatalogApplied {
+    pub payee: String,
+    /// Number of terms in the catalog
+    pub catalog_terms: usize,
+    /// Changes needed to match the catalog, empty if it already matches
+    pub changes: Vec<CatalogChange>,
+    /// What was done with the changes: one `sent` entry, or one simulated,
+    /// exported or proposed transaction per change; empty when there were none
+    pub outcomes: Vec<TxOutcome<ChangesApplied>>,
+    /// The plan as printed for humans
+    #[serde(skip)]
+    diff: String,
+}
+
+/// One change in a catalog plan
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CatalogChange {
+    pub action: ChangeAction,
+    pub terms_id: String,
+    /// Existing payment terms PDA; absent for creates
+    pub payment_terms: Option<String>,
+    /// New amount, if it is set or changes
+    pub amount_microlamports: Option<u64>,
+    /// New period, if it is set or changes
+    pub period_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Deactivate,
+}
+
+/// Changes submitted one transaction each
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ChangesApplied {
+    pub applied: Vec<AppliedChange>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AppliedChange {
+    /// e.g. "Create payment terms 'pro'"
+    pub description: String,
+    /// The planned change this transaction made
+    #[serde(flatten)]
+    pub change: CatalogChange,
+    pub signature: String,
+}
+
+impl From<&TermsChange> for CatalogChange {
+    fn from(change: &TermsChange) -> Self {
+        match change {
+            TermsChange::Create(terms) => Self {
+                action: ChangeAction::Create,
+                terms_id: terms.id.clone(),
+                payment_terms: None,
+                amount_microlamports: Some(terms.amount_usdc),
+                period_seconds: Some(terms.period_secs),
+            },
+            TermsChange::Update {
+                id,
+                address,
+                amount_usdc,
+                period_secs,
+            } => Self {
+                action: ChangeAction::Update,
+                terms_id: id.clone(),
+                payment_terms: Some(address.to_string()),
+                amount_microlamports: amount_usdc.map(|(_, desired)| desired),
+                period_seconds: period_secs.map(|(_, desired)| desired),
+            },
+            TermsChange::Deactivate { id, address } => Self {
+                action: ChangeAction::Deactivate,
+                terms_id: id.clone(),
+                payment_terms: Some(address.to_string()),
+                amount_microlamports: None,
+                period_seconds: None,
+            },
+        }
+    }
+}
+
+impl CommandOutput for ChangesApplied {
+    const KIND: &'static str = "payment-terms.apply.changes";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        for change in &self.applied {
+            writeln!(&mut output, "{} {}", change.description, change.signature)?;
+        }
+        Ok(output)
+    }
+}
+
+impl CommandOutput for CatalogApplied {
+    const KIND: &'static str = "payment-terms.apply";
+
+    fn human(&self) -> Result<String> {
+        match self.outcomes.as_slice() {
+            [] => Ok(format!(
+                "{} Payment terms for payee {} already match the catalog ({} terms)",
+                Theme::success("✓"),
+                self.payee,
+                self.catalog_terms
+            )),
+            [outcome @ TxOutcome::Sent(applied)] => Ok(format!(
+                "{}{}",
+                outcome.human()?,
+                Theme::success(&format!(
+                    "Applied {} changes to payee {}",
+                    applied.applied.len(),
+                    self.payee
+                ))
+            )),
+            outcomes => {
+                let previews = outcomes
+                    .iter()
+                    .map(CommandOutput::human)
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(format!("{}\n{}", self.diff, previews.join("\n\n")))
+            }
+        }
+    }
+
+    fn success(&self) -> bool {
+        self.outcomes.iter().all(CommandOutput::success)
+    }
+}
+
+/// Parse and validate a catalog
+///
+/// # Errors
+/// Returns error if the TOML is malformed, an entry is invalid, or a terms id
+/// appears twice
+pub fn parse_catalog(contents: &str) -> Result<Vec<DesiredTerms>> {
+    let file: CatalogFile = toml::from_str(contents).context("Invalid catalog TOML")?;
+
+    let mut seen = HashSet::new();
+    let mut desired = Vec::with_capacity(file.terms.len());
+    for entry in file.terms {
+        let terms = period_from_fields(
+            entry.period.as_deref(),
+            entry.period_days,
+            entry.period_months,
+        )
+        .and_then(|period_secs| DesiredTerms::new(&entry.id, entry.amount_usdc, period_secs))
+        .with_context(|| format!("Terms '{}'", entry.id))?;
+        if !seen.insert(terms.id.clone()) {
+            return Err(anyhow!(
+                "Terms id '{}' appears more than once in the catalog",
+                terms.id
+            ));
+        }
+        desired.push(terms);
+    }
+    Ok(desired)
+}
+
+/// Compute the changes that reconcile `current` with `desired`
+///
+/// Changes are ordered creates and updates first (in catalog order), then
+/// deactivations (by terms id).
+///
+/// # Errors
+/// Returns error if the catalog lists terms that are deactivated on-chain,
+/// which cannot be reactivated
+pub fn diff_catalog(
+    desired: &[DesiredTerms],
+    current: &[CurrentTerms],
+) -> Result<Vec<TermsChange>> {
+    let current_by_id: BTreeMap<&str, &CurrentTerms> = current
+        .iter()
+        .map(|terms| (terms.id.as_str(), terms))
+        .collect();
+    let desired_ids: HashSet<&str> = desired.iter().map(|terms| terms.id.as_str()).collect();
+
+    let mut changes = Vec::new();
+    for want in desired {
+        let Some(have) = current_by_id.get(want.id.as_str()) else {
+            changes.push(TermsChange::Create(want.clone()));
+            continue;
+        };
+        if !have.active {
+            return Err(anyhow!(
+                "Terms '{}' ({}) are deactivated on-chain and cannot be reactivated.\n\
+                 Give the catalog entry a new id.",
+                want.id,
+                have.address
+            ));
+        }
+        let amount_usdc =
+            (have.amount_usdc != want.amount_usdc).then_some((have.amount_usdc, want.amount_usdc));
+        let period_secs =
+            (have.period_secs != want.period_secs).then_some((have.period_secs, want.period_secs));
+        if amount_usdc.is_some() || period_secs.is_some() {
+            changes.push(TermsChange::Update {
+                id: want.id.clone(),
+                address: have.address,
+                amount_usdc,
+                period_secs,
+            });
+        }
+    }
+
+    for (id, have) in &current_by_id {
+        if have.active && !desired_ids.contains(id) {
+            changes.push(TermsChange::Deactivate {
+                id: (*id).to_string(),
+                address: have.address,
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Execute the apply payment terms command
+///
+/// # Errors
+/// Returns error if the catalog is invalid, the on-chain terms cannot be
+/// fetched, the authority does not own the payee, or a transaction fails
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &ApplyPaymentTermsRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<CatalogApplied> {
+    info!(
+        "Applying payment terms catalog {}",
+        request.catalog_path.display()
+    );
+
+    let contents = std::fs::read_to_string(request.catalog_path)
+        .with_context(|| format!("Failed to read catalog {}", request.catalog_path.display()))?;
+    let desired = parse_catalog(&contents)
+        .with_context(|| format!("Invalid catalog {}", request.catalog_path.display()))?;
+    let periods: BTreeSet<u64> = desired.iter().map(|terms| terms.period_secs).collect();
+    periods.into_iter().for_each(warn_calendar_drift);
+
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    info!("Using authority: {}", authority.pubkey());
+    verify_payee_authority(tally_client, &request.payee, &authority.pubkey())?;
+
+    let current: Vec<CurrentTerms> = tally_client
+        .list_payment_terms(&request.payee)
+        .context("Failed to fetch payment terms - check RPC connection and payee account state")?
+        .into_iter()
+        .map(|(address, terms)| CurrentTerms {
+            address,
+            id: terms.terms_id_str(),
+            amount_usdc: terms.amount_usdc,
+            period_secs: terms.period_secs,
+            active: terms.active,
+        })
+        .collect();
+
+    let changes = diff_catalog(&desired, &current)?;
+    let diff = format_diff(&changes)?;
+    let applied = |outcomes| CatalogApplied {
+        payee: request.payee.to_string(),
+        catalog_terms: desired.len(),
+        changes: changes.iter().map(CatalogChange::from).collect(),
+        outcomes,
+        diff: diff.clone(),
+    };
+    if changes.is_empty() {
+        return Ok(applied(Vec::new()));
+    }
+
+    if !request.tx_mode.is_send() {
+        // One transaction per change, exactly as they would be sent
+        let plans = changes
+            .iter()
+            .map(|change| {
+                Ok(TxPlan::new(
+                    describe_change(change),
+                    authority.pubkey(),
+                    change_instructions(tally_client, &authority.pubkey(), change)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let outcomes = transaction::simulate_or_export_each(
+            tally_client,
+            &plans,
+            request.tx_mode,
+            &authority,
+        )?;
+        return Ok(applied(outcomes));
+    }
+
+    // Show the plan even with --yes, so CI logs record what went on-chain
+    eprintln!("{diff}");
+    if !request.skip_confirmation {
+        let confirmed = Confirm::new()
+            .with_prompt(format!("Apply {} changes?", changes.len()))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Apply canceled by user"));
+        }
+    }
+
+    let mut done = Vec::with_capacity(changes.len());
+    for change in &changes {
+        let spinner = progress::create_spinner(&format!("{}...", describe_change(change)));
+        match apply_change(tally_client, &authority, change) {
+            Ok(signature) => {
+                progress::finish_progress_success(&spinner, &describe_change(change));
+                info!("Transaction confirmed: {}", signature);
+                done.push(AppliedChange {
+                    description: describe_change(change),
+                    change: CatalogChange::from(change),
+                    signature,
+                });
+            }
+            Err(e) => {
+                progress::finish_progress_error(&spinner, &describe_change(change));
+                return Err(e.context(format!(
+                    "Applied {} of {} changes before failing - re-run apply to continue",
+                    done.len(),
+                    changes.len()
+                )));
+            }
+        }
+    }
+
+    Ok(applied(vec![TxOutcome::Sent(ChangesApplied {
+        applied: done,
+    })]))
+}
+
+/// Submit one change with the authority keypair
+fn apply_change(
+    tally_client: &SimpleTallyClient,
+    authority: &TxSigner,
+    change: &TermsChange,
+) -> Result<String> {
+    if let TxSigner::External(signer) = authority {
+        let plan = TxPlan::new(
+            describe_change(change),
+            signer.pubkey(),
+            change_instructions(tally_client, &signer.pubkey(), change)?,
+        );
+        let signature = transaction::send(tally_client, &plan, signer.as_ref())?;
+        return Ok(signature.to_string());
+    }
+    let authority_pubkey = authority.pubkey();
+    let authority = authority
+        .keypair()
+        .ok_or_else(|| anyhow!("Applying the catalog requires the authority keypair"))?;
+    let plan = || -> Result<TxPlan> {
+        Ok(TxPlan::new(
+            describe_change(change),
+            authority_pubkey,
+            change_instructions(tally_client, &authority_pubkey, change)?,
+        ))
+    };
+    let failed = |action: String, e| transaction::explain_failure(tally_client, plan, &action, e);
+    match change {
+        TermsChange::Create(terms) => {
+            let (_, signature) = tally_client
+                .create_payment_terms(authority, terms.create_args()?)
+                .map_err(|e| failed(format!("Failed to create payment terms '{}'", terms.id), e))?;
+            Ok(signature)
+        }
+        TermsChange::Update {
+            id,
+            address,
+            amount_usdc,
+            period_secs,
+        } => tally_client
+            .update_payment_terms(authority, address, update_args(*amount_usdc, *period_secs))
+            .map_err(|e| failed(format!("Failed to update payment terms '{id}'"), e)),
+        TermsChange::Deactivate { id, address } => tally_client
+            .deactivate_payment_terms(authority, address)
+            .map_err(|e| failed(format!("Failed to deactivate payment terms '{id}'"), e)),
+    }
+}
+
+/// Build the instructions for one change without signing them
+fn change_instructions(
+    tally_client: &SimpleTallyClient,
+    authority: &Pubkey,
+    change: &TermsChange,
+) -> Result<Vec<Instruction>> {
+    match change {
+        TermsChange::Create(terms) => tally_client
+            .create_payment_terms_instructions(authority, terms.create_args()?)
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to build payment terms '{}' transaction: {e}",
+                    terms.id
+                )
+            }),
+        TermsChange::Update {
+            id,
+            address,
+            amount_usdc,
+            period_secs,
+        } => tally_client
+            .update_payment_terms_instructions(
+                authority,
+                address,
+                update_args(*amount_usdc, *period_secs),
+            )
+            .map_err(|e| anyhow!("Failed to build payment terms '{id}' update: {e}")),
+        TermsChange::Deactivate { id, address } => tally_client
+            .deactivate_payment_terms_instructions(authority, address)
+            .map_err(|e| anyhow!("Failed to build payment terms '{id}' deactivation: {e}")),
+    }
+}
+
+const fn update_args(
+    amount_usdc: Option<(u64, u64)>,
+    period_secs: Option<(u64, u64)>,
+) -> UpdatePaymentTermsArgs {
+    UpdatePaymentTermsArgs {
+        amount_usdc: match amount_usdc {
+            Some((_, desired)) => Some(desired),
+            None => None,
+        },
+        period_secs: match period_secs {
+            Some((_, desired)) => Some(desired),
+            None => None,
+        },
+    }
+}
+
+/// One-line description of a change, e.g. "Create payment terms 'pro'"
+fn describe_change(change: &TermsChange) -> String {
+    match change {
+        TermsChange::Create(terms) => format!("Create payment terms '{}'", terms.id),
+        TermsChange::Update { id, .. } => format!("Update payment terms '{id}'"),
+        TermsChange::Deactivate { id, .. } => format!("Deactivate payment terms '{id}'"),
+    }
+}
+
+/// Format the changes as a diff: `+` create, `~` update, `-` deactivate
+///
+/// # Errors
+/// Returns error if string formatting fails
+pub fn format_diff(changes: &[TermsChange]) -> Result<String> {
+    let mut output = String::new();
+    writeln!(&mut output, "{}", Theme::header("Payment terms plan:"))?;
+    for change in changes {
+        match change {
+            TermsChange::Create(terms) => writeln!(
+                &mut output,
+                "  {} {}  {} USDC every {}",
+                Theme::success("+"),
+                terms.id,
+                UsdcAmount::from_microlamports(terms.amount_usdc),
+                format_period(terms.period_secs)
+            )?,
+            TermsChange::Update {
+                id,
+                address,
+                amount_usdc,
+                period_secs,
+            } => {
+                writeln!(
+                    &mut output,
+                    "  {} {id}  {}",
+                    Theme::warning("~"),
+                    Theme::dim(&address.to_string())
+                )?;
+                if let Some((current, desired)) = amount_usdc {
+                    writeln!(
+                        &mut output,
+                        "      Amount: {} -> {} USDC",
+                        UsdcAmount::from_microlamports(*current),
+                        UsdcAmount::from_microlamports(*desired)
+                    )?;
+                }
+                if let Some((current, desired)) = period_secs {
+                    writeln!(
+                        &mut output,
+                        "      Period: {} -> {}",
+                        format_period(*current),
+                        format_period(*desired)
+                    )?;
+                }
+            }
+            TermsChange::Deactivate { id, address } => writeln!(
+                &mut output,
+                "  {} {id}  {}",
+                Theme::error("-"),
+                Theme::dim(&address.to_string())
+            )?,
+        }
+    }
+
+    let count = |f: fn(&TermsChange) -> bool| changes.iter().filter(|c| f(c)).count();
+    write!(
+        &mut output,
+        "{} to create, {} to update, {} to deactivate",
+        count(|c| matches!(c, TermsChange::Create(_))),
+        count(|c| matches!(c, TermsChange::Update { .. })),
+        count(|c| matches!(c, TermsChange::Deactivate { .. }))
+    )?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::duration::{SECONDS_PER_MONTH, SECONDS_PER_YEAR};
+
+    fn desired(id: &str, amount_usdc: u64, days: u64) -> DesiredTerms {
+        DesiredTerms {
+            id: id.to_string(),
+            amount_usdc,
+            period_secs: days * 86_400,
+        }
+    }
+
+    fn current(id: &str, amount_usdc: u64, days: u64, active: bool) -> CurrentTerms {
+        CurrentTerms {
+            address: Pubkey::new_unique(),
+            id: id.to_string(),
+            amount_usdc,
+            period_secs: days * 86_400,
+            active,
+        }
+    }
+
+    #[test]
+    fn test_parse_catalog() {
+        let catalog = parse_catalog(
+            r#"
+            [[terms]]
+            id = "basic"
+            amount_usdc = "9.99"
+            period_days = 30
+
+            [[terms]]
+            id = "pro-annual"
+            amount_usdc = 99
+            period_months = 12
+
+            [[terms]]
+            id = "team"
+            amount_usdc = 49
+            period = "P1M"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(catalog[0], desired("basic", 9_990_000, 30));
+        assert_eq!(catalog[1].period_secs, SECONDS_PER_YEAR);
+        assert_eq!(catalog[2].period_secs, SECONDS_PER_MONTH);
+    }
+
+    #[test]
+    fn test_parse_catalog_rejects_invalid_entries() {
+        let cases = [
+            "[[terms]]\nid = \"a\"\namount_usdc = 0\nperiod_days = 30",
+            "[[terms]]\nid = \"a\"\namount_usdc = 1\nperiod_days = 0",
+            "[[terms]]\nid = \"a\"\namount_usdc = 1",
+            "[[terms]]\nid = \"a\"\namount_usdc = 1\nperiod_days = 7\nperiod_months = 1",
+            "[[terms]]\nid = \"a\"\namount_usdc = 1\nperiod = \"1m\"",
+            "[[terms]]\nid = \"a\"\namount_usdc = 1\nperiod = \"12h\"",
+            "[[terms]]\nid = \"a\"\namount_usdc = 1\nperiod_days = 7\nprice = 3",
+        ];
+        for case in cases {
+            assert!(parse_catalog(case).is_err(), "accepted: {case}");
+        }
+
+        let err = parse_catalog(
+            "[[terms]]\nid = \"a\"\namount_usdc = 1\nperiod_days = 7\n\
+             [[terms]]\nid = \"a\"\namount_usdc = 2\nperiod_days = 7",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("more than once"));
+    }
+
+    #[test]
+    fn test_diff_create_update_deactivate() {
+        let basic = current("basic", 5_000_000, 30, true);
+        let legacy = current("legacy", 1_000_000, 30, true);
+        let changes = diff_catalog(
+            &[
+                desired("pro", 20_000_000, 30),
+                desired("basic", 9_000_000, 30),
+            ],
+            &[basic.clone(), legacy.clone()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            changes,
+            vec![
+                TermsChange::Create(desired("pro", 20_000_000, 30)),
+                TermsChange::Update {
+                    id: "basic".to_string(),
+                    address: basic.address,
+                    amount_usdc: Some((5_000_000, 9_000_000)),
+                    period_secs: None,
+                },
+                TermsChange::Deactivate {
+                    id: "legacy".to_string(),
+                    address: legacy.address,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_is_idempotent() {
+        let changes = diff_catalog(
+            &[desired("basic", 5_000_000, 30)],
+            &[
+                current("basic", 5_000_000, 30, true),
+                current("retired", 1_000_000, 30, false),
+            ],
+        )
+        .unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_diff_rejects_reactivation() {
+        let err = diff_catalog(
+            &[desired("retired", 1_000_000, 30)],
+            &[current("retired", 1_000_000, 30, false)],
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("cannot be reactivated"));
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let changes = vec![
+            TermsChange::Create(desired("pro", 20_000_000, 30)),
+            TermsChange::Deactivate {
+                id: "legacy".to_string(),
+                address: Pubkey::new_unique(),
+            },
+        ];
+        let diff = format_diff(&changes).unwrap();
+        assert!(diff.contains("+ pro"));
+        assert!(diff.contains("- legacy"));
+        assert!(diff.contains("1 to create, 0 to update, 1 to deactivate"));
+    }
+}
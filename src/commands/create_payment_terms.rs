@@ -1,26 +1,90 @@
 //! Create payment terms command implementation
 
 use crate::config::TallyCliConfig;
+use crate::output::CommandOutput;
+use crate::utils::authority::verify_payee_authority;
 use crate::utils::colors::Theme;
+use crate::utils::duration::format_period;
 use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
 use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::fmt::Write as _;
-use std::str::FromStr;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
-use tally_sdk::solana_sdk::signature::Signer;
 use tally_sdk::{
-    load_keypair, pda_v2, program_types::CreatePaymentTermsArgs, PaymentPeriod, SimpleTallyClient,
-    TermsId, UsdcAmount,
+    program_types::CreatePaymentTermsArgs, PaymentPeriod, SimpleTallyClient, TermsId, UsdcAmount,
 };
 use tracing::info;
 
 /// Arguments for creating payment terms
 pub struct CreatePaymentTermsRequest<'a> {
-    pub payee_str: &'a str,
+    pub payee: Pubkey,
     pub terms_id: &'a str,
-    pub amount_usdc_float: f64,
-    pub period_days: u64,
+    pub amount: UsdcAmount,
+    /// Billing period in seconds (see [`crate::utils::duration`])
+    pub period_secs: u64,
     pub authority_path: Option<&'a str>,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Payment terms created by `payment-terms create`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PaymentTermsCreated {
+    /// Payment terms PDA; not reported when signed by an external signer
+    pub payment_terms: Option<String>,
+    pub terms_id: String,
+    pub amount_usdc: String,
+    pub amount_microlamports: u64,
+    pub period_seconds: u64,
+    pub signature: String,
+}
+
+impl CommandOutput for PaymentTermsCreated {
+    const KIND: &'static str = "payment-terms.create";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Payment terms created successfully!")
+        )?;
+        if let Some(payment_terms) = &self.payment_terms {
+            writeln!(
+                &mut output,
+                "{} {}",
+                Theme::info("Payment Terms PDA:"),
+                Theme::highlight(payment_terms)
+            )?;
+        }
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Terms ID:"),
+            Theme::value(&self.terms_id)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Amount:"),
+            Theme::value(&self.amount_usdc)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Period:"),
+            Theme::value(&format_period(self.period_seconds))
+        )?;
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
 }
 
 /// Execute the create payment terms command
@@ -31,41 +95,35 @@ pub async fn execute(
     tally_client: &SimpleTallyClient,
     request: &CreatePaymentTermsRequest<'_>,
     _config: &TallyCliConfig,
-) -> Result<String> {
+) -> Result<TxOutcome<PaymentTermsCreated>> {
     info!("Starting payment terms creation");
 
-    // Parse payee PDA address (for validation)
-    let expected_payee_pda = Pubkey::from_str(request.payee_str)
-        .map_err(|e| anyhow!("Invalid payee PDA address '{}': {}", request.payee_str, e))?;
+    let expected_payee_pda = request.payee;
     info!("Expected payee PDA: {expected_payee_pda}");
 
     // Load authority keypair
-    let authority =
-        load_keypair(request.authority_path).context("Failed to load authority keypair")?;
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
     info!("Using authority: {}", authority.pubkey());
 
-    // Validate authority matches the provided payee PDA
-    let authority_pubkey = Pubkey::from(authority.pubkey().to_bytes());
-    let computed_payee_pda: Pubkey = pda_v2::payee(&authority_pubkey)?.into();
-    if expected_payee_pda != computed_payee_pda {
-        return Err(anyhow!(
-            "Authority mismatch: expected payee PDA {} for authority {}, but got {}",
-            computed_payee_pda,
-            authority.pubkey(),
-            expected_payee_pda
-        ));
-    }
+    // Validate authority is the current authority of the payee
+    verify_payee_authority(tally_client, &expected_payee_pda, &authority.pubkey())?;
 
     // Create type-safe domain types
     let terms_id = TermsId::new(request.terms_id)
         .context("Invalid terms ID - use only alphanumeric, underscores, and hyphens")?;
-    let amount = UsdcAmount::from_usdc(request.amount_usdc_float);
-    let period = PaymentPeriod::days(request.period_days)
+    let amount = request.amount;
+    if amount.microlamports() == 0 {
+        return Err(anyhow!("Amount must be greater than 0 USDC"));
+    }
+    let period = PaymentPeriod::from_seconds(request.period_secs)
         .context("Invalid period - must be at least 1 day")?;
 
     info!(
         "Creating payment terms: id={}, amount={}, period={}",
-        terms_id, amount, period
+        terms_id,
+        amount,
+        format_period(period.seconds())
     );
 
     // Convert to SDK types (until SDK is fully migrated)
@@ -79,11 +137,47 @@ pub async fn execute(
         period_secs,
     };
 
+    let authority_pubkey = authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .create_payment_terms_instructions(&authority_pubkey, terms_args.clone())
+            .map_err(|e| anyhow!("Failed to build payment terms transaction: {e}"))?;
+        Ok(TxPlan::new(
+            format!("Create payment terms '{terms_id}'"),
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        );
+    }
+
+    let created = |payment_terms: Option<Pubkey>, signature: String| PaymentTermsCreated {
+        payment_terms: payment_terms.map(|pda| pda.to_string()),
+        terms_id: terms_id.as_str().to_string(),
+        amount_usdc: amount.to_string(),
+        amount_microlamports: amount.microlamports(),
+        period_seconds: period_secs,
+        signature,
+    };
+    if let TxSigner::External(signer) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(created(None, signature.to_string())));
+    }
+    let authority = authority.into_keypair()?;
+
     // Use tally-sdk's high-level convenience method with progress indicator
     let spinner = progress::create_spinner("Creating payment terms and submitting transaction...");
     let result = tally_client
-        .create_payment_terms(&authority, terms_args)
-        .map_err(|e| anyhow!("Failed to create payment terms: {e}"));
+        .create_payment_terms(&authority, terms_args.clone())
+        .map_err(|e| {
+            transaction::explain_failure(tally_client, plan, "Failed to create payment terms", e)
+        });
 
     match &result {
         Ok((_, signature)) => {
@@ -96,45 +190,5 @@ pub async fn execute(
     }
 
     let (payment_terms_pda, signature) = result?;
-
-    // Build output with colors - use write! to construct string
-    let mut output = String::new();
-    writeln!(
-        &mut output,
-        "{}",
-        Theme::success("Payment terms created successfully!")
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Payment Terms PDA:"),
-        Theme::highlight(&payment_terms_pda.to_string())
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Terms ID:"),
-        Theme::value(terms_id.as_str())
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Amount:"),
-        Theme::value(&amount.to_string())
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Period:"),
-        Theme::value(&period.to_string())
-    )?;
-
-    // Write signature - using format! directly to avoid clippy warnings
-    write!(
-        &mut output,
-        "{} {signature}",
-        Theme::info("Transaction signature:")
-    )?;
-
-    Ok(output)
+    Ok(TxOutcome::Sent(created(Some(payment_terms_pda), signature)))
 }
@@ -1,512 +1,675 @@
 //! Dashboard commands implementation
 
+use crate::config::TallyCliConfig;
+use crate::context::RuntimeContext;
+use crate::errors::{parse_merchant_pda, parse_plan_pda, CliError, ErrorCode};
+use crate::output::{ndjson_records, CommandOutput, Output};
+use crate::utils::duration::format_period;
+use crate::utils::formatting::{
+    format_csv, format_event_human, format_events_csv, EventEntry, EventInfo,
+};
 use anyhow::{Context, Result};
-use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::fmt::Write as _;
 use std::str::FromStr;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
-use tally_sdk::{DashboardClient, SimpleTallyClient, UsdcAmount};
+use tally_sdk::{DashboardClient, UsdcAmount};
 
-#[derive(Clone, Debug, ValueEnum)]
-pub enum OutputFormat {
-    Human,
-    Json,
-    Csv,
+/// A dashboard request with every argument resolved and validated
+///
+/// Built by the command router from the parsed CLI arguments so that handlers
+/// receive typed addresses and filters instead of re-parsing strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DashboardRequest {
+    /// Payee overview statistics
+    Overview {
+        /// Payee (merchant) account address
+        merchant: Pubkey,
+    },
+    /// Analytics for a single set of payment terms
+    Analytics {
+        /// Payment terms (plan) account address
+        plan: Pubkey,
+    },
+    /// Recent events for a payee
+    Events {
+        /// Payee (merchant) account address
+        merchant: Pubkey,
+        /// Only include events at or after this unix timestamp
+        since: i64,
+    },
+    /// Payment agreements for a payee
+    Subscriptions {
+        /// Payee (merchant) account address
+        merchant: Pubkey,
+        /// Only include active agreements
+        active_only: bool,
+    },
 }
 
-/// Wrapper function for backwards compatibility with main.rs
-///
-/// # Errors
-/// Returns error if dashboard operation fails or merchant/plan not found
-pub fn execute<T: std::fmt::Debug + Send + Sync>(
-    _tally_client: &SimpleTallyClient,
-    command: &T,
-    output_format: &OutputFormat,
-    rpc_url: &str,
-) -> Result<String> {
-    execute_dashboard_command(command, output_format, rpc_url)
+impl DashboardRequest {
+    /// Build an overview request, falling back to the configured payee
+    ///
+    /// # Errors
+    /// Returns error if no merchant is available or the address is invalid
+    pub fn overview(merchant: Option<&str>, context: &RuntimeContext) -> Result<Self> {
+        Ok(Self::Overview {
+            merchant: resolve_merchant(merchant, context)?,
+        })
+    }
+
+    /// Build an analytics request for a plan address
+    ///
+    /// # Errors
+    /// Returns error if the plan address is invalid
+    pub fn analytics(plan: &str, context: &RuntimeContext) -> Result<Self> {
+        let merchant = configured_merchant(context);
+        Ok(Self::Analytics {
+            plan: parse_plan_pda(plan, merchant.as_ref())?,
+        })
+    }
+
+    /// Build an events request, defaulting `since` to the configured lookback window
+    ///
+    /// # Errors
+    /// Returns error if no merchant is available or the address is invalid
+    pub fn events(
+        merchant: Option<&str>,
+        since: Option<i64>,
+        context: &RuntimeContext,
+        config: &TallyCliConfig,
+    ) -> Result<Self> {
+        let since =
+            since.unwrap_or_else(|| config.default_events_since_timestamp(current_timestamp()));
+        Ok(Self::Events {
+            merchant: resolve_merchant(merchant, context)?,
+            since,
+        })
+    }
+
+    /// Build a subscriptions request, falling back to the configured payee
+    ///
+    /// # Errors
+    /// Returns error if no merchant is available or the address is invalid
+    pub fn subscriptions(
+        merchant: Option<&str>,
+        active_only: bool,
+        context: &RuntimeContext,
+    ) -> Result<Self> {
+        Ok(Self::Subscriptions {
+            merchant: resolve_merchant(merchant, context)?,
+            active_only,
+        })
+    }
 }
 
-/// Execute dashboard command with proper routing
+/// Execute a dashboard request
 ///
 /// # Errors
 /// Returns error if dashboard operation fails or merchant/plan not found
-pub fn execute_dashboard_command<T: std::fmt::Debug + Send + Sync>(
-    command: &T,
-    output_format: &OutputFormat,
-    rpc_url: &str,
-) -> Result<String> {
-    // Create dashboard client
+pub fn execute(request: &DashboardRequest, rpc_url: &str) -> Result<Output> {
     let dashboard_client =
         DashboardClient::new(rpc_url).context("Failed to create dashboard client")?;
 
-    // Route to appropriate handler based on command type
-    // Since we don't have access to the actual DashboardCommands type here,
-    // we'll use the command debug string to route
-    let command_str = format!("{command:?}");
-
-    if command_str.contains("Overview") {
-        // Extract merchant address from command
-        extract_and_execute_overview(&dashboard_client, &command_str, output_format)
-    } else if command_str.contains("Analytics") {
-        // Extract plan address from command
-        extract_and_execute_analytics(&dashboard_client, &command_str, output_format)
-    } else if command_str.contains("Events") {
-        // Extract merchant address and optional since timestamp
-        extract_and_execute_events(&dashboard_client, &command_str, output_format)
-    } else if command_str.contains("Subscriptions") {
-        // Extract merchant address and active_only flag
-        extract_and_execute_subscriptions(&dashboard_client, &command_str, output_format)
-    } else {
-        Err(anyhow::anyhow!("Unknown dashboard command"))
+    Ok(match request {
+        DashboardRequest::Overview { merchant } => {
+            execute_overview(&dashboard_client, merchant)?.into()
+        }
+        DashboardRequest::Analytics { plan } => execute_analytics(&dashboard_client, plan)?.into(),
+        DashboardRequest::Events { merchant, since } => {
+            execute_events(&dashboard_client, merchant, *since)?.into()
+        }
+        DashboardRequest::Subscriptions {
+            merchant,
+            active_only,
+        } => execute_subscriptions(&dashboard_client, merchant, *active_only)?.into(),
+    })
+}
+
+/// Resolve the merchant address from the CLI argument or the configured payee
+///
+/// The fallback is [`RuntimeContext::payee`], so `TALLY_PAYEE` and the active
+/// profile apply with the usual precedence.
+///
+/// # Errors
+/// Returns error if no merchant is available or the address is invalid
+pub fn resolve_merchant(merchant: Option<&str>, context: &RuntimeContext) -> Result<Pubkey> {
+    if let Some(merchant) = merchant {
+        return parse_merchant_pda(merchant, configured_merchant(context).as_ref());
     }
+
+    let saved = context.payee.as_ref().ok_or_else(|| {
+        CliError::new(
+            ErrorCode::ConfigMissing,
+            "Merchant not provided and not configured in TALLY_PAYEE or the active profile.",
+        )
+        .with_suggestion(
+            "You can fix this by:\n\
+             \n\
+             1. Pass the merchant as an argument:\n\
+                tally-merchant dashboard overview --merchant <MERCHANT_ADDRESS>\n\
+             \n\
+             2. Or configure it in your profile:\n\
+                tally-merchant config set merchant <MERCHANT_ADDRESS>\n\
+             \n\
+             If you haven't created a merchant yet, run:\n\
+                tally-merchant init",
+        )
+    })?;
+
+    parse_merchant_pda(&saved.value, None)
 }
 
-/// Execute the Overview command
-fn extract_and_execute_overview(
-    dashboard_client: &DashboardClient,
-    command_str: &str,
-    output_format: &OutputFormat,
-) -> Result<String> {
-    // Parse merchant address from command string
-    let merchant_str = command_str
-        .split("merchant:")
-        .nth(1)
-        .and_then(|s| s.split_whitespace().next())
-        .and_then(|s| s.trim_matches(|c| c == '"' || c == ',').split(',').next())
-        // Strip Option wrapper if present (e.g., "Some(\"address\")" -> "address")
-        .map(|s| {
-            s.trim_start_matches("Some(")
-                .trim_end_matches(')')
-                .trim_matches('"')
-        })
-        .context("Failed to extract merchant address from command")?;
+/// Configured payee, if it is a valid address
+fn configured_merchant(context: &RuntimeContext) -> Option<Pubkey> {
+    context
+        .payee
+        .as_ref()
+        .and_then(|payee| Pubkey::from_str(&payee.value).ok())
+}
 
-    let merchant = Pubkey::from_str(merchant_str)
-        .context(format!("Invalid merchant address: {merchant_str}"))?;
+/// Current unix timestamp in seconds
+fn current_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(0))
+}
 
-    // Get overview data
-    let overview = dashboard_client
-        .get_payee_overview(&merchant)
-        .context("Failed to fetch merchant overview")?;
+/// Payee statistics shown by `dashboard overview`
+///
+/// Amounts are in USDC micro-units.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PayeeOverviewReport {
+    pub payee: String,
+    pub payee_authority: String,
+    pub usdc_mint: String,
+    pub total_revenue: u64,
+    pub monthly_revenue: u64,
+    pub average_revenue_per_payer: u64,
+    pub total_payment_terms: u32,
+    pub active_agreements: u32,
+    pub inactive_agreements: u32,
+    pub churn_rate_percent: f64,
+    pub monthly_new_agreements: u32,
+    pub monthly_paused_agreements: u32,
+}
 
-    // Format output
-    match output_format {
-        OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&overview)?;
-            Ok(json)
-        }
-        OutputFormat::Csv => {
-            // CSV output for overview statistics
-            let mut wtr = csv::Writer::from_writer(vec![]);
-
-            // Write header
-            wtr.write_record(["metric", "value"])?;
-
-            // Write metrics
-            wtr.write_record(["merchant_address", &merchant.to_string()])?;
-            wtr.write_record(["merchant_authority", &overview.payee_authority.to_string()])?;
-            wtr.write_record(["usdc_mint", &overview.usdc_mint.to_string()])?;
-            wtr.write_record([
-                "total_revenue_usdc",
-                &UsdcAmount::from_microlamports(overview.total_revenue).to_string(),
-            ])?;
-            wtr.write_record([
-                "monthly_revenue_usdc",
-                &UsdcAmount::from_microlamports(overview.monthly_revenue).to_string(),
-            ])?;
-            wtr.write_record([
+impl CommandOutput for PayeeOverviewReport {
+    const KIND: &'static str = "dashboard.overview";
+
+    fn human(&self) -> Result<String> {
+        let mut output = format!("\nMerchant Dashboard Overview - {}\n", self.payee);
+        output.push_str(&"=".repeat(70));
+        output.push('\n');
+
+        output.push_str("\nRevenue Statistics:\n");
+        writeln!(
+            output,
+            "  Total Revenue:        {} USDC",
+            UsdcAmount::from_microlamports(self.total_revenue)
+        )?;
+        writeln!(
+            output,
+            "  Monthly Revenue:      {} USDC",
+            UsdcAmount::from_microlamports(self.monthly_revenue)
+        )?;
+        writeln!(
+            output,
+            "  Avg Revenue per User: {} USDC",
+            UsdcAmount::from_microlamports(self.average_revenue_per_payer)
+        )?;
+
+        output.push_str("\nSubscription Statistics:\n");
+        writeln!(
+            output,
+            "  Total Plans:          {}",
+            self.total_payment_terms
+        )?;
+        writeln!(output, "  Active Subscriptions: {}", self.active_agreements)?;
+        writeln!(
+            output,
+            "  Inactive Subscriptions: {}",
+            self.inactive_agreements
+        )?;
+        writeln!(
+            output,
+            "  Churn Rate:           {:.2}%",
+            self.churn_rate_percent
+        )?;
+
+        output.push_str("\nMonthly Growth:\n");
+        writeln!(
+            output,
+            "  New Subscriptions:    {}",
+            self.monthly_new_agreements
+        )?;
+        writeln!(
+            output,
+            "  PaymentAgreementPaused Subscriptions: {}",
+            self.monthly_paused_agreements
+        )?;
+
+        output.push_str("\nConfiguration:\n");
+        writeln!(output, "  Merchant Authority:   {}", self.payee_authority)?;
+        writeln!(output, "  USDC Mint:            {}", self.usdc_mint)?;
+
+        Ok(output)
+    }
+
+    fn csv(&self) -> Option<Result<String>> {
+        Some(format_metrics(&[
+            ("merchant_address", self.payee.clone()),
+            ("merchant_authority", self.payee_authority.clone()),
+            ("usdc_mint", self.usdc_mint.clone()),
+            ("total_revenue_usdc", usdc(self.total_revenue)),
+            ("monthly_revenue_usdc", usdc(self.monthly_revenue)),
+            (
                 "average_revenue_per_user_usdc",
-                &UsdcAmount::from_microlamports(overview.average_revenue_per_payer).to_string(),
-            ])?;
-            wtr.write_record(["total_plans", &overview.total_payment_terms.to_string()])?;
-            wtr.write_record([
-                "active_subscriptions",
-                &overview.active_agreements.to_string(),
-            ])?;
-            wtr.write_record([
+                usdc(self.average_revenue_per_payer),
+            ),
+            ("total_plans", self.total_payment_terms.to_string()),
+            ("active_subscriptions", self.active_agreements.to_string()),
+            (
                 "inactive_subscriptions",
-                &overview.inactive_agreements.to_string(),
-            ])?;
-            wtr.write_record([
+                self.inactive_agreements.to_string(),
+            ),
+            (
                 "churn_rate_percent",
-                &format!("{:.2}", overview.churn_rate()),
-            ])?;
-            wtr.write_record([
+                format!("{:.2}", self.churn_rate_percent),
+            ),
+            (
                 "monthly_new_subscriptions",
-                &overview.monthly_new_agreements.to_string(),
-            ])?;
-            wtr.write_record([
+                self.monthly_new_agreements.to_string(),
+            ),
+            (
                 "monthly_canceled_subscriptions",
-                &overview.monthly_paused_agreements.to_string(),
-            ])?;
+                self.monthly_paused_agreements.to_string(),
+            ),
+        ]))
+    }
+}
 
-            let data = String::from_utf8(wtr.into_inner()?)?;
-            Ok(data)
-        }
-        OutputFormat::Human => {
-            let mut output = format!("\nMerchant Dashboard Overview - {merchant}\n");
-            output.push_str(&"=".repeat(70));
-            output.push('\n');
+/// Payment terms statistics shown by `dashboard analytics`
+///
+/// Amounts are in USDC micro-units.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PaymentTermsAnalyticsReport {
+    pub payment_terms: String,
+    pub terms_id: String,
+    pub amount_microlamports: u64,
+    pub period_seconds: u64,
+    pub total_revenue: u64,
+    pub monthly_revenue: u64,
+    pub active_count: u32,
+    pub inactive_count: u32,
+    pub total_agreements: u32,
+    pub churn_rate_percent: f64,
+    pub average_duration_days: f64,
+    pub monthly_new_agreements: u32,
+    pub monthly_paused_agreements: u32,
+    pub monthly_growth_rate_percent: f64,
+    pub conversion_rate_percent: Option<f64>,
+}
 
-            output.push_str("\nRevenue Statistics:\n");
-            writeln!(
-                output,
-                "  Total Revenue:        {} USDC",
-                UsdcAmount::from_microlamports(overview.total_revenue)
-            )?;
-            writeln!(
-                output,
-                "  Monthly Revenue:      {} USDC",
-                UsdcAmount::from_microlamports(overview.monthly_revenue)
-            )?;
-            writeln!(
-                output,
-                "  Avg Revenue per User: {} USDC",
-                UsdcAmount::from_microlamports(overview.average_revenue_per_payer)
-            )?;
+impl CommandOutput for PaymentTermsAnalyticsReport {
+    const KIND: &'static str = "dashboard.analytics";
 
-            output.push_str("\nSubscription Statistics:\n");
-            writeln!(
-                output,
-                "  Total Plans:          {}",
-                overview.total_payment_terms
-            )?;
-            writeln!(
-                output,
-                "  Active Subscriptions: {}",
-                overview.active_agreements
-            )?;
-            writeln!(
-                output,
-                "  Inactive Subscriptions: {}",
-                overview.inactive_agreements
-            )?;
-            writeln!(
-                output,
-                "  Churn Rate:           {:.2}%",
-                overview.churn_rate()
-            )?;
+    fn human(&self) -> Result<String> {
+        let mut output = format!("\nPlan Analytics - {}\n", self.terms_id);
+        output.push_str(&"=".repeat(70));
+        output.push('\n');
 
-            output.push_str("\nMonthly Growth:\n");
-            writeln!(
-                output,
-                "  New Subscriptions:    {}",
-                overview.monthly_new_agreements
-            )?;
-            writeln!(
-                output,
-                "  PaymentAgreementPaused Subscriptions: {}",
-                overview.monthly_paused_agreements
-            )?;
+        output.push_str("\nPlan Information:\n");
+        writeln!(output, "  Plan ID:              {}", self.terms_id)?;
+        writeln!(output, "  Plan Address:         {}", self.payment_terms)?;
+        writeln!(
+            output,
+            "  Price:                {} USDC",
+            UsdcAmount::from_microlamports(self.amount_microlamports)
+        )?;
+        writeln!(
+            output,
+            "  Period:               {}",
+            format_period(self.period_seconds)
+        )?;
+        writeln!(output, "  Active:               true")?;
 
-            output.push_str("\nConfiguration:\n");
-            writeln!(
-                output,
-                "  Merchant Authority:   {}",
-                overview.payee_authority
-            )?;
-            writeln!(output, "  USDC Mint:            {}", overview.usdc_mint)?;
+        output.push_str("\nRevenue Statistics:\n");
+        writeln!(
+            output,
+            "  Total Revenue:        {} USDC",
+            UsdcAmount::from_microlamports(self.total_revenue)
+        )?;
+        writeln!(
+            output,
+            "  Monthly Revenue:      {} USDC",
+            UsdcAmount::from_microlamports(self.monthly_revenue)
+        )?;
 
-            Ok(output)
-        }
-    }
-}
+        output.push_str("\nSubscription Statistics:\n");
+        writeln!(output, "  Active Count:         {}", self.active_count)?;
+        writeln!(output, "  Inactive Count:       {}", self.inactive_count)?;
+        writeln!(output, "  Total Subscriptions:  {}", self.total_agreements)?;
+        writeln!(
+            output,
+            "  Churn Rate:           {:.2}%",
+            self.churn_rate_percent
+        )?;
+        writeln!(
+            output,
+            "  Avg Duration:         {:.1} days",
+            self.average_duration_days
+        )?;
 
-/// Execute the Analytics command
-fn extract_and_execute_analytics(
-    dashboard_client: &DashboardClient,
-    command_str: &str,
-    output_format: &OutputFormat,
-) -> Result<String> {
-    // Parse plan address from command string
-    let plan_str = command_str
-        .split("plan:")
-        .nth(1)
-        .and_then(|s| s.split_whitespace().next())
-        .and_then(|s| s.trim_matches(|c| c == '"' || c == ',').split(',').next())
-        .context("Failed to extract plan address from command")?;
-
-    let plan = Pubkey::from_str(plan_str).context(format!("Invalid plan address: {plan_str}"))?;
-
-    // Get plan analytics
-    let analytics = dashboard_client
-        .get_payment_terms_analytics(&plan)
-        .context("Failed to fetch plan analytics")?;
+        output.push_str("\nMonthly Growth:\n");
+        writeln!(
+            output,
+            "  New Subscriptions:    {}",
+            self.monthly_new_agreements
+        )?;
+        writeln!(
+            output,
+            "  PaymentAgreementPaused Subscriptions: {}",
+            self.monthly_paused_agreements
+        )?;
+        writeln!(
+            output,
+            "  Growth Rate:          {:.2}%",
+            self.monthly_growth_rate_percent
+        )?;
 
-    // Format output
-    match output_format {
-        OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&analytics)?;
-            Ok(json)
+        if let Some(conversion_rate) = self.conversion_rate_percent {
+            writeln!(output, "  Conversion Rate:      {conversion_rate:.2}%")?;
         }
-        OutputFormat::Csv => {
-            // CSV output for plan analytics
-            let plan_id_str = String::from_utf8(analytics.payment_terms.terms_id.to_vec())
-                .unwrap_or_else(|_| format!("{:?}", analytics.payment_terms.terms_id));
 
-            let mut wtr = csv::Writer::from_writer(vec![]);
-
-            // Write header
-            wtr.write_record(["metric", "value"])?;
+        Ok(output)
+    }
 
-            // Write metrics
-            wtr.write_record(["plan_id", &plan_id_str])?;
-            wtr.write_record(["plan_address", &analytics.payment_terms_address.to_string()])?;
-            wtr.write_record([
-                "price_usdc",
-                &UsdcAmount::from_microlamports(analytics.payment_terms.amount_usdc).to_string(),
-            ])?;
-            wtr.write_record([
-                "period_seconds",
-                &analytics.payment_terms.period_secs.to_string(),
-            ])?;
-            wtr.write_record(["active", "true"])?;
-            wtr.write_record([
-                "total_revenue_usdc",
-                &UsdcAmount::from_microlamports(analytics.total_revenue).to_string(),
-            ])?;
-            wtr.write_record([
-                "monthly_revenue_usdc",
-                &UsdcAmount::from_microlamports(analytics.monthly_revenue).to_string(),
-            ])?;
-            wtr.write_record(["active_count", &analytics.active_count.to_string()])?;
-            wtr.write_record(["inactive_count", &analytics.inactive_count.to_string()])?;
-            wtr.write_record([
-                "total_subscriptions",
-                &analytics.total_agreements().to_string(),
-            ])?;
-            wtr.write_record([
+    fn csv(&self) -> Option<Result<String>> {
+        let mut metrics = vec![
+            ("plan_id", self.terms_id.clone()),
+            ("plan_address", self.payment_terms.clone()),
+            ("price_usdc", usdc(self.amount_microlamports)),
+            ("period_seconds", self.period_seconds.to_string()),
+            ("active", "true".to_string()),
+            ("total_revenue_usdc", usdc(self.total_revenue)),
+            ("monthly_revenue_usdc", usdc(self.monthly_revenue)),
+            ("active_count", self.active_count.to_string()),
+            ("inactive_count", self.inactive_count.to_string()),
+            ("total_subscriptions", self.total_agreements.to_string()),
+            (
                 "churn_rate_percent",
-                &format!("{:.2}", analytics.churn_rate()),
-            ])?;
-            wtr.write_record([
+                format!("{:.2}", self.churn_rate_percent),
+            ),
+            (
                 "average_duration_days",
-                &format!("{:.1}", analytics.average_duration_days),
-            ])?;
-            wtr.write_record([
+                format!("{:.1}", self.average_duration_days),
+            ),
+            (
                 "monthly_new_subscriptions",
-                &analytics.monthly_new_agreements.to_string(),
-            ])?;
-            wtr.write_record([
+                self.monthly_new_agreements.to_string(),
+            ),
+            (
                 "monthly_canceled_subscriptions",
-                &analytics.monthly_paused_agreements.to_string(),
-            ])?;
-            wtr.write_record([
+                self.monthly_paused_agreements.to_string(),
+            ),
+            (
                 "monthly_growth_rate_percent",
-                &format!("{:.2}", analytics.monthly_growth_rate()),
-            ])?;
-            if let Some(conversion_rate) = analytics.conversion_rate {
-                wtr.write_record(["conversion_rate_percent", &format!("{conversion_rate:.2}")])?;
-            }
+                format!("{:.2}", self.monthly_growth_rate_percent),
+            ),
+        ];
+        if let Some(conversion_rate) = self.conversion_rate_percent {
+            metrics.push(("conversion_rate_percent", format!("{conversion_rate:.2}")));
+        }
+        Some(format_metrics(&metrics))
+    }
+}
+
+/// Recent events shown by `dashboard events`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EventList {
+    pub payee: String,
+    /// Unix timestamp the events start at
+    pub since: i64,
+    pub count: usize,
+    pub events: Vec<EventEntry>,
+}
+
+impl CommandOutput for EventList {
+    const KIND: &'static str = "dashboard.events";
 
-            let data = String::from_utf8(wtr.into_inner()?)?;
-            Ok(data)
+    fn human(&self) -> Result<String> {
+        let mut output = format!("\nRecent Events for Merchant: {}\n", self.payee);
+        output.push_str(&"=".repeat(70));
+        output.push('\n');
+        writeln!(output, "\nShowing events since timestamp: {}\n", self.since)?;
+
+        if self.events.is_empty() {
+            output.push_str("No events found in the specified time period.\n");
+        } else {
+            writeln!(output, "Total Events: {}\n", self.count)?;
+
+            for event in &self.events {
+                output.push_str(&format_event_human(event));
+                output.push('\n');
+            }
         }
-        OutputFormat::Human => {
-            // Convert plan_id bytes to string
-            let plan_id_str = String::from_utf8(analytics.payment_terms.terms_id.to_vec())
-                .unwrap_or_else(|_| format!("{:?}", analytics.payment_terms.terms_id));
 
-            let mut output = format!("\nPlan Analytics - {plan_id_str}\n");
-            output.push_str(&"=".repeat(70));
-            output.push('\n');
+        Ok(output)
+    }
 
-            output.push_str("\nPlan Information:\n");
-            writeln!(output, "  Plan ID:              {plan_id_str}")?;
-            writeln!(
-                output,
-                "  Plan Address:         {}",
-                analytics.payment_terms_address
-            )?;
-            writeln!(
-                output,
-                "  Price:                {} USDC",
-                UsdcAmount::from_microlamports(analytics.payment_terms.amount_usdc)
-            )?;
-            writeln!(
-                output,
-                "  Period:               {} seconds",
-                analytics.payment_terms.period_secs
-            )?;
-            writeln!(output, "  Active:               true")?;
+    fn csv(&self) -> Option<Result<String>> {
+        Some(format_events_csv(&self.events, true))
+    }
 
-            output.push_str("\nRevenue Statistics:\n");
-            writeln!(
-                output,
-                "  Total Revenue:        {} USDC",
-                UsdcAmount::from_microlamports(analytics.total_revenue)
-            )?;
-            writeln!(
-                output,
-                "  Monthly Revenue:      {} USDC",
-                UsdcAmount::from_microlamports(analytics.monthly_revenue)
-            )?;
+    /// One event per line, like `dashboard events --follow`
+    fn ndjson(&self) -> Option<Result<String>> {
+        Some(ndjson_records(&self.events))
+    }
+}
 
-            output.push_str("\nSubscription Statistics:\n");
-            writeln!(output, "  Active Count:         {}", analytics.active_count)?;
-            writeln!(
-                output,
-                "  Inactive Count:       {}",
-                analytics.inactive_count
-            )?;
-            writeln!(
-                output,
-                "  Total Subscriptions:  {}",
-                analytics.total_agreements()
-            )?;
-            writeln!(
-                output,
-                "  Churn Rate:           {:.2}%",
-                analytics.churn_rate()
-            )?;
-            writeln!(
-                output,
-                "  Avg Duration:         {:.1} days",
-                analytics.average_duration_days
-            )?;
+/// A payment agreement shown by `dashboard subscriptions`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SubscriptionEntry {
+    pub payer: String,
+    pub terms_id: String,
+    pub payment_terms: String,
+    pub status: String,
+    pub active: bool,
+    pub payment_count: u32,
+    pub total_paid_microlamports: u64,
+    pub amount_microlamports: u64,
+    pub period_seconds: u64,
+    pub next_payment_ts: i64,
+}
 
-            output.push_str("\nMonthly Growth:\n");
-            writeln!(
-                output,
-                "  New Subscriptions:    {}",
-                analytics.monthly_new_agreements
-            )?;
-            writeln!(
-                output,
-                "  PaymentAgreementPaused Subscriptions: {}",
-                analytics.monthly_paused_agreements
-            )?;
+/// Payment agreements shown by `dashboard subscriptions`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SubscriptionList {
+    pub payee: String,
+    pub active_only: bool,
+    pub count: usize,
+    pub subscriptions: Vec<SubscriptionEntry>,
+}
+
+impl CommandOutput for SubscriptionList {
+    const KIND: &'static str = "dashboard.subscriptions";
+
+    fn human(&self) -> Result<String> {
+        let mut output = format!("\nSubscriptions for Merchant: {}\n", self.payee);
+        output.push_str(&"=".repeat(100));
+        output.push('\n');
+        write!(output, "\n{} subscriptions found", self.count)?;
+        if self.active_only {
+            output.push_str(" (active only)");
+        }
+        output.push_str("\n\n");
+
+        if self.subscriptions.is_empty() {
+            output.push_str("No subscriptions found.\n");
+        } else {
+            // Header
             writeln!(
                 output,
-                "  Growth Rate:          {:.2}%",
-                analytics.monthly_growth_rate()
+                "{:<45} {:<30} {:<14} {:<12} {:<12} {:<12}",
+                "Subscriber", "Plan", "Period", "Status", "Renewals", "Total Paid"
             )?;
+            output.push_str(&"-".repeat(100));
+            output.push('\n');
 
-            if let Some(conversion_rate) = analytics.conversion_rate {
-                writeln!(output, "  Conversion Rate:      {conversion_rate:.2}%")?;
-            }
+            // Data rows
+            for sub in &self.subscriptions {
+                let total_paid_str = format!(
+                    "{} USDC",
+                    UsdcAmount::from_microlamports(sub.total_paid_microlamports)
+                );
 
-            Ok(output)
+                writeln!(
+                    output,
+                    "{:<45} {:<30} {:<14} {:<12} {:<12} {:<12}",
+                    truncate_string(&sub.payer, 44),
+                    truncate_string(&sub.terms_id, 29),
+                    format_period(sub.period_seconds),
+                    sub.status,
+                    sub.payment_count,
+                    total_paid_str
+                )?;
+            }
         }
+
+        Ok(output)
+    }
+
+    fn csv(&self) -> Option<Result<String>> {
+        let rows: Vec<_> = self
+            .subscriptions
+            .iter()
+            .map(|sub| {
+                (
+                    &sub.payer,
+                    &sub.terms_id,
+                    &sub.payment_terms,
+                    &sub.status,
+                    sub.active,
+                    sub.payment_count,
+                    usdc(sub.total_paid_microlamports),
+                    usdc(sub.amount_microlamports),
+                    sub.period_seconds,
+                    sub.next_payment_ts,
+                )
+            })
+            .collect();
+        Some(format_csv(
+            &[
+                "subscriber",
+                "plan_id",
+                "plan_address",
+                "status",
+                "active",
+                "renewals",
+                "total_paid_usdc",
+                "price_usdc",
+                "period_seconds",
+                "next_renewal_timestamp",
+            ],
+            &rows,
+        ))
     }
 }
 
-/// Execute the Events command
-fn extract_and_execute_events(
+/// Execute the Overview command
+fn execute_overview(
     dashboard_client: &DashboardClient,
-    command_str: &str,
-    _output_format: &OutputFormat,
-) -> Result<String> {
-    // Parse merchant address from command string
-    let merchant_str = command_str
-        .split("merchant:")
-        .nth(1)
-        .and_then(|s| s.split_whitespace().next())
-        .and_then(|s| s.trim_matches(|c| c == '"' || c == ',').split(',').next())
-        // Strip Option wrapper if present (e.g., "Some(\"address\")" -> "address")
-        .map(|s| {
-            s.trim_start_matches("Some(")
-                .trim_end_matches(')')
-                .trim_matches('"')
-        })
-        .context("Failed to extract merchant address from command")?;
-
-    let merchant = Pubkey::from_str(merchant_str)
-        .context(format!("Invalid merchant address: {merchant_str}"))?;
-
-    // Parse optional since timestamp (defaults to 1 hour ago)
-    let current_timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| i64::try_from(d.as_secs()).unwrap_or(0))
-        .unwrap_or(0);
-
-    // Default lookback: 1 hour (3600 seconds)
-    const DEFAULT_EVENTS_LOOKBACK_SECS: i64 = 3600;
-
-    let since_timestamp = if command_str.contains("since: Some(") {
-        command_str
-            .split("since: Some(")
-            .nth(1)
-            .and_then(|s| s.split(')').next())
-            .and_then(|s| s.parse::<i64>().ok())
-            .unwrap_or(current_timestamp - DEFAULT_EVENTS_LOOKBACK_SECS)
-    } else {
-        current_timestamp - DEFAULT_EVENTS_LOOKBACK_SECS
-    };
-
-    // Get recent events
-    let events = dashboard_client
-        .poll_recent_events(&merchant, since_timestamp)
-        .context("Failed to fetch recent events")?;
-
-    // Format output
-    let mut output = format!("\nRecent Events for Merchant: {merchant}\n");
-    output.push_str(&"=".repeat(70));
-    output.push('\n');
-    writeln!(
-        output,
-        "\nShowing events since timestamp: {since_timestamp}\n"
-    )?;
-
-    if events.is_empty() {
-        output.push_str("No events found in the specified time period.\n");
-    } else {
-        writeln!(output, "Total Events: {}\n", events.len())?;
-
-        for event in &events {
-            writeln!(output, "Event: {:?}", event.event_type)?;
-            writeln!(output, "  Timestamp: {}", event.timestamp)?;
+    merchant: &Pubkey,
+) -> Result<PayeeOverviewReport> {
+    let overview = dashboard_client
+        .get_payee_overview(merchant)
+        .context("Failed to fetch merchant overview")?;
 
-            if let Some(subscriber) = event.payer {
-                writeln!(output, "  Subscriber: {subscriber}")?;
-            }
-            if let Some(plan) = event.payment_terms_address {
-                writeln!(output, "  Plan: {plan}")?;
-            }
-            if let Some(amount) = event.amount {
-                writeln!(output, "  Amount: {} USDC", UsdcAmount::from_microlamports(amount))?;
-            }
-            if let Some(sig) = &event.transaction_signature {
-                writeln!(output, "  Signature: {sig}")?;
-            }
+    Ok(PayeeOverviewReport {
+        payee: merchant.to_string(),
+        payee_authority: overview.payee_authority.to_string(),
+        usdc_mint: overview.usdc_mint.to_string(),
+        total_revenue: overview.total_revenue,
+        monthly_revenue: overview.monthly_revenue,
+        average_revenue_per_payer: overview.average_revenue_per_payer,
+        total_payment_terms: overview.total_payment_terms,
+        active_agreements: overview.active_agreements,
+        inactive_agreements: overview.inactive_agreements,
+        churn_rate_percent: overview.churn_rate(),
+        monthly_new_agreements: overview.monthly_new_agreements,
+        monthly_paused_agreements: overview.monthly_paused_agreements,
+    })
+}
 
-            output.push('\n');
-        }
-    }
+/// Execute the Analytics command
+fn execute_analytics(
+    dashboard_client: &DashboardClient,
+    plan: &Pubkey,
+) -> Result<PaymentTermsAnalyticsReport> {
+    let analytics = dashboard_client
+        .get_payment_terms_analytics(plan)
+        .context("Failed to fetch plan analytics")?;
 
-    Ok(output)
+    Ok(PaymentTermsAnalyticsReport {
+        payment_terms: analytics.payment_terms_address.to_string(),
+        terms_id: terms_id_string(&analytics.payment_terms.terms_id),
+        amount_microlamports: analytics.payment_terms.amount_usdc,
+        period_seconds: analytics.payment_terms.period_secs,
+        total_revenue: analytics.total_revenue,
+        monthly_revenue: analytics.monthly_revenue,
+        active_count: analytics.active_count,
+        inactive_count: analytics.inactive_count,
+        total_agreements: analytics.total_agreements(),
+        churn_rate_percent: analytics.churn_rate(),
+        average_duration_days: analytics.average_duration_days,
+        monthly_new_agreements: analytics.monthly_new_agreements,
+        monthly_paused_agreements: analytics.monthly_paused_agreements,
+        monthly_growth_rate_percent: analytics.monthly_growth_rate(),
+        conversion_rate_percent: analytics.conversion_rate,
+    })
 }
 
-/// Execute the Subscriptions command
-fn extract_and_execute_subscriptions(
+/// Execute the Events command
+fn execute_events(
     dashboard_client: &DashboardClient,
-    command_str: &str,
-    output_format: &OutputFormat,
-) -> Result<String> {
-    // Parse merchant address from command string
-    let merchant_str = command_str
-        .split("merchant:")
-        .nth(1)
-        .and_then(|s| s.split_whitespace().next())
-        .and_then(|s| s.trim_matches(|c| c == '"' || c == ',').split(',').next())
-        // Strip Option wrapper if present (e.g., "Some(\"address\")" -> "address")
-        .map(|s| {
-            s.trim_start_matches("Some(")
-                .trim_end_matches(')')
-                .trim_matches('"')
+    merchant: &Pubkey,
+    since_timestamp: i64,
+) -> Result<EventList> {
+    let events: Vec<EventEntry> = dashboard_client
+        .poll_recent_events(merchant, since_timestamp)
+        .context("Failed to fetch recent events")?
+        .into_iter()
+        .map(|event| {
+            EventEntry::from(&EventInfo {
+                event_type: format!("{:?}", event.event_type),
+                timestamp: event.timestamp,
+                payee: *merchant,
+                payer: event.payer,
+                payment_terms: event.payment_terms_address,
+                amount: event.amount,
+                signature: event.transaction_signature,
+            })
         })
-        .context("Failed to extract merchant address from command")?;
+        .collect();
 
-    let merchant = Pubkey::from_str(merchant_str)
-        .context(format!("Invalid merchant address: {merchant_str}"))?;
-
-    // Parse active_only flag
-    let active_only = command_str.contains("active_only: true");
+    Ok(EventList {
+        payee: merchant.to_string(),
+        since: since_timestamp,
+        count: events.len(),
+        events,
+    })
+}
 
-    // Get subscriptions
+/// Execute the Subscriptions command
+fn execute_subscriptions(
+    dashboard_client: &DashboardClient,
+    merchant: &Pubkey,
+    active_only: bool,
+) -> Result<SubscriptionList> {
     let mut subscriptions = dashboard_client
-        .get_live_agreements(&merchant)
+        .get_live_agreements(merchant)
         .context("Failed to fetch subscriptions")?;
 
     // Filter if active_only
@@ -514,97 +677,43 @@ fn extract_and_execute_subscriptions(
         subscriptions.retain(|sub| sub.payment_agreement.active);
     }
 
-    // Format output
-    match output_format {
-        OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&subscriptions)?;
-            Ok(json)
-        }
-        OutputFormat::Csv => {
-            // CSV output for subscriptions
-            let mut wtr = csv::Writer::from_writer(vec![]);
-
-            // Write header
-            wtr.write_record([
-                "subscriber",
-                "plan_id",
-                "plan_address",
-                "status",
-                "active",
-                "renewals",
-                "total_paid_usdc",
-                "price_usdc",
-                "period_seconds",
-                "next_renewal_timestamp",
-            ])?;
-
-            // Write data rows
-            for sub in &subscriptions {
-                let plan_id_str = String::from_utf8(sub.payment_terms.terms_id.to_vec())
-                    .unwrap_or_else(|_| format!("{:?}", sub.payment_terms.terms_id));
-
-                wtr.write_record([
-                    sub.payment_agreement.payer.to_string(),
-                    plan_id_str,
-                    sub.payment_agreement.payment_terms.to_string(),
-                    format!("{:?}", sub.status),
-                    sub.payment_agreement.active.to_string(),
-                    sub.payment_agreement.payment_count.to_string(),
-                    UsdcAmount::from_microlamports(sub.total_paid).to_string(),
-                    UsdcAmount::from_microlamports(sub.payment_terms.amount_usdc).to_string(),
-                    sub.payment_terms.period_secs.to_string(),
-                    sub.payment_agreement.next_payment_ts.to_string(),
-                ])?;
-            }
+    let subscriptions: Vec<SubscriptionEntry> = subscriptions
+        .iter()
+        .map(|sub| SubscriptionEntry {
+            payer: sub.payment_agreement.payer.to_string(),
+            terms_id: terms_id_string(&sub.payment_terms.terms_id),
+            payment_terms: sub.payment_agreement.payment_terms.to_string(),
+            status: format!("{:?}", sub.status),
+            active: sub.payment_agreement.active,
+            payment_count: sub.payment_agreement.payment_count,
+            total_paid_microlamports: sub.total_paid,
+            amount_microlamports: sub.payment_terms.amount_usdc,
+            period_seconds: sub.payment_terms.period_secs,
+            next_payment_ts: sub.payment_agreement.next_payment_ts,
+        })
+        .collect();
 
-            let data = String::from_utf8(wtr.into_inner()?)?;
-            Ok(data)
-        }
-        OutputFormat::Human => {
-            let mut output = format!("\nSubscriptions for Merchant: {merchant}\n");
-            output.push_str(&"=".repeat(100));
-            output.push('\n');
-            write!(output, "\n{} subscriptions found", subscriptions.len())?;
-            if active_only {
-                output.push_str(" (active only)");
-            }
-            output.push_str("\n\n");
+    Ok(SubscriptionList {
+        payee: merchant.to_string(),
+        active_only,
+        count: subscriptions.len(),
+        subscriptions,
+    })
+}
 
-            if subscriptions.is_empty() {
-                output.push_str("No subscriptions found.\n");
-            } else {
-                // Header
-                writeln!(
-                    output,
-                    "{:<45} {:<45} {:<12} {:<12} {:<12}",
-                    "Subscriber", "Plan", "Status", "Renewals", "Total Paid"
-                )?;
-                output.push_str(&"-".repeat(100));
-                output.push('\n');
+/// Terms ID bytes as text, or their debug form if they are not UTF-8
+fn terms_id_string(terms_id: &[u8]) -> String {
+    String::from_utf8(terms_id.to_vec()).unwrap_or_else(|_| format!("{terms_id:?}"))
+}
 
-                // Data rows
-                for sub in &subscriptions {
-                    let subscriber_str = sub.payment_agreement.payer.to_string();
-                    let plan_id_str = String::from_utf8(sub.payment_terms.terms_id.to_vec())
-                        .unwrap_or_else(|_| format!("{:?}", sub.payment_terms.terms_id));
-                    let status_str = format!("{:?}", sub.status);
-                    let total_paid_str = format!("{} USDC", UsdcAmount::from_microlamports(sub.total_paid));
-
-                    writeln!(
-                        output,
-                        "{:<45} {:<45} {:<12} {:<12} {:<12}",
-                        truncate_string(&subscriber_str, 44),
-                        truncate_string(&plan_id_str, 44),
-                        status_str,
-                        sub.payment_agreement.payment_count,
-                        total_paid_str
-                    )?;
-                }
-            }
+/// Micro-units as a USDC amount string
+fn usdc(microlamports: u64) -> String {
+    UsdcAmount::from_microlamports(microlamports).to_string()
+}
 
-            Ok(output)
-        }
-    }
+/// Format `(metric, value)` pairs as a two-column CSV
+fn format_metrics(metrics: &[(&str, String)]) -> Result<String> {
+    format_csv(&["metric", "value"], metrics)
 }
 
 /// Truncate string to max length with ellipsis if needed
@@ -619,6 +728,131 @@ fn truncate_string(s: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config_file::ConfigFile;
+    use crate::context::ContextFlags;
+    use crate::output::OutputFormat;
+    use crate::utils::formatting::EVENT_CSV_HEADER;
+
+    const MERCHANT: &str = "HkDq7K2RRStvPrXw6U3YPJrPU2dYbvGj8Y5z8VQmKR8C";
+    const PLAN: &str = "8rPqJKt2fT9xYw5zR3vN8mPdLkQcXnU1wVbHjGaFsYe4";
+
+    fn context_with_merchant(merchant: Option<&str>) -> RuntimeContext {
+        let mut config_file = ConfigFile::new();
+        config_file.set_active_profile("devnet".to_string());
+        if let Some(merchant) = merchant {
+            config_file
+                .set_merchant(merchant.to_string())
+                .expect("Should set merchant");
+        }
+        RuntimeContext::resolve_with(
+            &ContextFlags::default(),
+            &TallyCliConfig::new(),
+            &config_file,
+            |_| None,
+        )
+    }
+
+    #[test]
+    fn test_overview_request_uses_explicit_merchant() {
+        let context = context_with_merchant(None);
+        let request = DashboardRequest::overview(Some(MERCHANT), &context).unwrap();
+        assert_eq!(
+            request,
+            DashboardRequest::Overview {
+                merchant: Pubkey::from_str(MERCHANT).unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn test_overview_request_falls_back_to_profile_merchant() {
+        let context = context_with_merchant(Some(MERCHANT));
+        let request = DashboardRequest::overview(None, &context).unwrap();
+        assert_eq!(
+            request,
+            DashboardRequest::Overview {
+                merchant: Pubkey::from_str(MERCHANT).unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn test_overview_request_without_merchant_fails() {
+        let context = context_with_merchant(None);
+        let error = DashboardRequest::overview(None, &context).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Merchant not provided and not configured"));
+    }
+
+    #[test]
+    fn test_overview_request_rejects_invalid_merchant() {
+        let context = context_with_merchant(None);
+        let error = DashboardRequest::overview(Some("not-an-address"), &context).unwrap_err();
+        assert!(error.to_string().contains("Invalid merchant address"));
+    }
+
+    #[test]
+    fn test_analytics_request_parses_plan() {
+        let context = context_with_merchant(None);
+        let request = DashboardRequest::analytics(PLAN, &context).unwrap();
+        assert_eq!(
+            request,
+            DashboardRequest::Analytics {
+                plan: Pubkey::from_str(PLAN).unwrap()
+            }
+        );
+    }
+
+    #[test]
+    fn test_analytics_request_rejects_invalid_plan() {
+        let context = context_with_merchant(Some(MERCHANT));
+        let error = DashboardRequest::analytics("bogus", &context).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("Invalid plan address"));
+        assert!(message.contains(MERCHANT));
+    }
+
+    #[test]
+    fn test_events_request_keeps_explicit_since() {
+        let context = context_with_merchant(Some(MERCHANT));
+        let config = TallyCliConfig::new();
+        let request =
+            DashboardRequest::events(None, Some(1_700_000_000), &context, &config).unwrap();
+        assert_eq!(
+            request,
+            DashboardRequest::Events {
+                merchant: Pubkey::from_str(MERCHANT).unwrap(),
+                since: 1_700_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_events_request_defaults_since_to_lookback_window() {
+        let context = context_with_merchant(Some(MERCHANT));
+        let config = TallyCliConfig::new();
+        let before = current_timestamp() - config.default_events_lookback_secs;
+        let request = DashboardRequest::events(None, None, &context, &config).unwrap();
+        let DashboardRequest::Events { since, .. } = request else {
+            panic!("Expected events request");
+        };
+        assert!(since >= before);
+        assert!(since <= current_timestamp());
+    }
+
+    #[test]
+    fn test_subscriptions_request_carries_active_only() {
+        let context = context_with_merchant(None);
+        let request = DashboardRequest::subscriptions(Some(MERCHANT), true, &context).unwrap();
+        assert_eq!(
+            request,
+            DashboardRequest::Subscriptions {
+                merchant: Pubkey::from_str(MERCHANT).unwrap(),
+                active_only: true,
+            }
+        );
+    }
 
     #[test]
     fn test_truncate_string() {
@@ -629,4 +863,171 @@ mod tests {
         );
         assert_eq!(truncate_string("exactly10!", 10), "exactly10!");
     }
+
+    fn overview_report() -> PayeeOverviewReport {
+        PayeeOverviewReport {
+            payee: MERCHANT.to_string(),
+            payee_authority: PLAN.to_string(),
+            usdc_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            total_revenue: 125_500_000,
+            monthly_revenue: 20_000_000,
+            average_revenue_per_payer: 10_458_333,
+            total_payment_terms: 3,
+            active_agreements: 12,
+            inactive_agreements: 4,
+            churn_rate_percent: 25.0,
+            monthly_new_agreements: 5,
+            monthly_paused_agreements: 1,
+        }
+    }
+
+    #[test]
+    fn test_overview_report_renders_every_format() {
+        let output = Output::from(overview_report());
+
+        let human = output.render(&OutputFormat::Human).unwrap();
+        assert!(human.contains(&format!("Merchant Dashboard Overview - {MERCHANT}")));
+        assert!(human.contains("Total Revenue:        125.500000 USDC"));
+        assert!(human.contains("Active Subscriptions: 12"));
+        assert!(human.contains("Churn Rate:           25.00%"));
+
+        let csv = output.render(&OutputFormat::Csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("metric,value"));
+        assert!(csv.contains(&format!("merchant_address,{MERCHANT}")));
+        assert!(csv.contains("total_revenue_usdc,125.500000"));
+        assert!(csv.contains("churn_rate_percent,25.00"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&output.render(&OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["kind"], "dashboard.overview");
+        assert_eq!(json["data"]["total_revenue"], 125_500_000);
+        assert_eq!(json["data"]["active_agreements"], 12);
+    }
+
+    #[test]
+    fn test_analytics_report_lists_conversion_rate_only_when_known() {
+        let mut report = PaymentTermsAnalyticsReport {
+            payment_terms: PLAN.to_string(),
+            terms_id: "pro".to_string(),
+            amount_microlamports: 9_990_000,
+            period_seconds: crate::utils::duration::SECONDS_PER_MONTH,
+            total_revenue: 99_900_000,
+            monthly_revenue: 29_970_000,
+            active_count: 3,
+            inactive_count: 1,
+            total_agreements: 4,
+            churn_rate_percent: 25.0,
+            average_duration_days: 45.5,
+            monthly_new_agreements: 2,
+            monthly_paused_agreements: 0,
+            monthly_growth_rate_percent: 50.0,
+            conversion_rate_percent: None,
+        };
+        let human = report.human().unwrap();
+        assert!(human.contains("Plan Analytics - pro"));
+        assert!(human.contains("Price:                9.990000 USDC"));
+        assert!(human.contains("Period:               1 month"));
+        assert!(!human.contains("Conversion Rate"));
+        assert!(!report
+            .csv()
+            .unwrap()
+            .unwrap()
+            .contains("conversion_rate_percent"));
+
+        report.conversion_rate_percent = Some(12.345);
+        assert!(report
+            .human()
+            .unwrap()
+            .contains("Conversion Rate:      12.35%"));
+        let csv = report.csv().unwrap().unwrap();
+        assert!(csv.contains("price_usdc,9.990000"));
+        assert!(csv.ends_with("conversion_rate_percent,12.35\n"));
+    }
+
+    #[test]
+    fn test_event_list_renders_events_and_empty_period() {
+        let payer = Pubkey::from_str(PLAN).unwrap();
+        let event = EventEntry::from(&EventInfo {
+            event_type: "PaymentExecuted".to_string(),
+            timestamp: 1_700_000_000,
+            payee: Pubkey::from_str(MERCHANT).unwrap(),
+            payer: Some(payer),
+            payment_terms: None,
+            amount: Some(10_500_000),
+            signature: Some("5sig".to_string()),
+        });
+        let list = EventList {
+            payee: MERCHANT.to_string(),
+            since: 1_699_000_000,
+            count: 2,
+            events: vec![event.clone(), event],
+        };
+        let human = list.human().unwrap();
+        assert!(human.contains("Total Events: 2"));
+        assert!(human.contains("PaymentExecuted"));
+        assert!(human.contains("10.500000 USDC"));
+
+        let csv = list.csv().unwrap().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], EVENT_CSV_HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            format!("PaymentExecuted,1700000000,{payer},,10500000,10.500000,5sig")
+        );
+
+        // NDJSON is one event envelope per line, as streamed by `dashboard events --follow`
+        let ndjson = list.ndjson().unwrap().unwrap();
+        assert_eq!(ndjson.lines().count(), 2);
+        for line in ndjson.lines() {
+            let envelope: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(envelope["kind"], "event");
+            assert_eq!(envelope["data"]["amount_usdc"], "10.500000");
+        }
+
+        let empty = EventList {
+            events: Vec::new(),
+            count: 0,
+            ..list
+        };
+        assert!(empty
+            .human()
+            .unwrap()
+            .contains("No events found in the specified time period."));
+        assert_eq!(empty.csv().unwrap().unwrap().lines().count(), 1);
+        assert!(empty.ndjson().unwrap().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_subscription_list_renders_rows() {
+        let list = SubscriptionList {
+            payee: MERCHANT.to_string(),
+            active_only: true,
+            count: 1,
+            subscriptions: vec![SubscriptionEntry {
+                payer: PLAN.to_string(),
+                terms_id: "pro".to_string(),
+                payment_terms: MERCHANT.to_string(),
+                status: "Active".to_string(),
+                active: true,
+                payment_count: 3,
+                total_paid_microlamports: 29_970_000,
+                amount_microlamports: 9_990_000,
+                period_seconds: crate::utils::duration::SECONDS_PER_MONTH,
+                next_payment_ts: 1_700_000_000,
+            }],
+        };
+        let human = list.human().unwrap();
+        assert!(human.contains("1 subscriptions found (active only)"));
+        assert!(human.contains("29.970000 USDC"));
+        assert!(human.contains("1 month"));
+
+        let csv = list.csv().unwrap().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("subscriber,plan_id,plan_address,status,active,renewals"));
+        assert_eq!(
+            lines[1],
+            format!("{PLAN},pro,{MERCHANT},Active,true,3,29.970000,9.990000,2629746,1700000000")
+        );
+    }
 }
@@ -0,0 +1,171 @@
+//! Deactivate payment terms command implementation
+
+use crate::config::TallyCliConfig;
+use crate::output::CommandOutput;
+use crate::utils::authority::verify_payee_authority;
+use crate::utils::colors::Theme;
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::SimpleTallyClient;
+use tracing::info;
+
+/// Arguments for deactivating payment terms
+pub struct DeactivatePaymentTermsRequest<'a> {
+    pub payment_terms_str: &'a str,
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Payment terms deactivated by `payment-terms deactivate`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PaymentTermsDeactivated {
+    pub payment_terms: String,
+    pub terms_id: String,
+    pub signature: String,
+}
+
+impl CommandOutput for PaymentTermsDeactivated {
+    const KIND: &'static str = "payment-terms.deactivate";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Payment terms deactivated successfully!")
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payment Terms PDA:"),
+            Theme::highlight(&self.payment_terms)
+        )?;
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::dim(
+                "New agreements can no longer be started; existing agreements are unaffected."
+            )
+        )?;
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
+}
+
+/// Execute the deactivate payment terms command
+///
+/// Deactivated terms no longer accept new agreements; existing agreements are
+/// left untouched.
+///
+/// # Errors
+/// Returns error if the payment terms account cannot be fetched, the authority
+/// does not own the payee, or the transaction fails
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &DeactivatePaymentTermsRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<PaymentTermsDeactivated>> {
+    info!("Starting payment terms deactivation");
+
+    let payment_terms_pda = Pubkey::from_str(request.payment_terms_str).map_err(|e| {
+        anyhow!(
+            "Invalid payment terms address '{}': {e}",
+            request.payment_terms_str
+        )
+    })?;
+
+    let terms = tally_client
+        .get_payment_terms(&payment_terms_pda)
+        .context("Failed to fetch payment terms account - check RPC connection and account state")?
+        .ok_or_else(|| anyhow!("Payment terms account not found: {payment_terms_pda}"))?;
+
+    // Load authority keypair and validate it owns the payee of these terms
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    info!("Using authority: {}", authority.pubkey());
+    verify_payee_authority(tally_client, &terms.payee, &authority.pubkey())?;
+
+    let authority_pubkey = authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .deactivate_payment_terms_instructions(&authority_pubkey, &payment_terms_pda)
+            .map_err(|e| anyhow!("Failed to build deactivation transaction: {e}"))?;
+        Ok(TxPlan::new(
+            format!("Deactivate payment terms '{}'", terms.terms_id_str()),
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        );
+    }
+
+    if !request.skip_confirmation {
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Deactivate payment terms '{}' ({payment_terms_pda})? New agreements will be rejected.",
+                terms.terms_id_str()
+            ))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Deactivation canceled by user"));
+        }
+    }
+
+    let deactivated = |signature: String| PaymentTermsDeactivated {
+        payment_terms: payment_terms_pda.to_string(),
+        terms_id: terms.terms_id_str(),
+        signature,
+    };
+    if let TxSigner::External(signer) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(deactivated(signature.to_string())));
+    }
+    let authority = authority.into_keypair()?;
+
+    let spinner =
+        progress::create_spinner("Deactivating payment terms and submitting transaction...");
+    let result = tally_client
+        .deactivate_payment_terms(&authority, &payment_terms_pda)
+        .map_err(|e| {
+            transaction::explain_failure(
+                tally_client,
+                plan,
+                "Failed to deactivate payment terms",
+                e,
+            )
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Payment terms deactivated successfully");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to deactivate payment terms");
+        }
+    }
+
+    Ok(TxOutcome::Sent(deactivated(result?)))
+}
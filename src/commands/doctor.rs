@@ -0,0 +1,755 @@
+//! Doctor command implementation
+//!
+//! Walks the same path a real command takes - config file, profile, RPC,
+//! program, global config, wallet and payee - and reports each step as pass,
+//! warn or fail with a hint on how to fix it, so a misconfiguration can be
+//! found without bouncing between `config list`, `config show`, `payee show`
+//! and `solana balance`.
+
+use crate::commands::init_wizard::MIN_SOL_BALANCE_LAMPORTS;
+use crate::commands::payee_treasury::check_treasury_mint;
+use crate::config_file::ConfigFile;
+use crate::context::{program_id_missing, RuntimeContext};
+use crate::errors::{
+    enhance_account_not_found_error, enhance_insufficient_balance_error, enhance_rpc_error,
+    parse_merchant_pda,
+};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::formatting::{detect_network, lamports_to_sol};
+use crate::utils::signer::stored_signer_pubkey;
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::program_types::Config;
+use tally_sdk::solana_client::rpc_client::RpcClient;
+use tally_sdk::solana_sdk::commitment_config::CommitmentConfig;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{get_usdc_mint, SimpleTallyClient};
+
+/// Genesis hashes of the public Solana clusters
+const KNOWN_GENESIS_HASHES: [(&str, &str); 3] = [
+    ("mainnet", "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d"),
+    ("devnet", "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG"),
+    ("testnet", "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY"),
+];
+
+/// Arguments for the doctor command
+pub struct DoctorRequest<'a> {
+    pub context: &'a RuntimeContext,
+    /// Config file as loaded by `main` (falls back to defaults if unreadable)
+    pub config_file: &'a ConfigFile,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run because an earlier check failed
+    Skip,
+}
+
+/// Result of one diagnostic check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skip(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skip,
+            message: format!("Skipped - {reason}"),
+            hint: None,
+        }
+    }
+}
+
+/// Results of every check
+///
+/// A failing check marks the report unsuccessful, so scripts and CI see a
+/// non-zero exit status.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DoctorReport {
+    /// `true` if no check failed
+    pub healthy: bool,
+    pub checks: Vec<CheckResult>,
+    pub summary: CheckSummary,
+}
+
+/// Number of checks with each outcome
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CheckSummary {
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+}
+
+impl DoctorReport {
+    #[must_use]
+    pub fn new(checks: Vec<CheckResult>) -> Self {
+        let count = |status| checks.iter().filter(|c| c.status == status).count();
+        let summary = CheckSummary {
+            passed: count(CheckStatus::Pass),
+            warnings: count(CheckStatus::Warn),
+            failed: count(CheckStatus::Fail),
+        };
+        Self {
+            healthy: summary.failed == 0,
+            checks,
+            summary,
+        }
+    }
+}
+
+impl CommandOutput for DoctorReport {
+    const KIND: &'static str = "doctor";
+
+    fn human(&self) -> Result<String> {
+        format_checks_human(&self.checks)
+    }
+
+    fn success(&self) -> bool {
+        self.healthy
+    }
+}
+
+/// Execute the doctor command
+#[must_use]
+pub fn execute(request: &DoctorRequest<'_>) -> DoctorReport {
+    DoctorReport::new(run_checks(request.context, request.config_file))
+}
+
+/// Run every check in order, skipping those whose prerequisites failed
+fn run_checks(context: &RuntimeContext, config_file: &ConfigFile) -> Vec<CheckResult> {
+    let mut checks = vec![
+        check_config_file(ConfigFile::load()),
+        check_profile(context, config_file),
+    ];
+
+    let rpc_url = context.rpc_url.value.as_str();
+    let rpc = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+    let genesis_hash = match rpc.get_genesis_hash() {
+        Ok(hash) => {
+            checks.push(CheckResult::pass(
+                "RPC",
+                format!("Reachable at {rpc_url} ({})", context.rpc_url.source),
+            ));
+            checks.push(check_network(rpc_url, &hash.to_string()));
+            Some(hash)
+        }
+        Err(e) => {
+            let error = anyhow!("RPC endpoint {rpc_url} is unreachable: {e}");
+            checks.push(CheckResult::fail(
+                "RPC",
+                error.to_string(),
+                enhance_rpc_error(&error, rpc_url).to_string(),
+            ));
+            None
+        }
+    };
+    if genesis_hash.is_none() {
+        for name in ["Program", "Global config", "Wallet", "Payee", "Treasury"] {
+            checks.push(CheckResult::skip(name, "RPC is unreachable"));
+        }
+        return checks;
+    }
+
+    let program = check_program(&rpc, context);
+    let program_ok = program.status == CheckStatus::Pass;
+    checks.push(program);
+
+    let client = if program_ok {
+        context.tally_client().ok()
+    } else {
+        None
+    };
+    let config = if let Some(client) = &client {
+        let (check, config) = check_global_config(client);
+        checks.push(check);
+        config
+    } else {
+        checks.push(CheckResult::skip("Global config", "program check failed"));
+        None
+    };
+
+    checks.push(check_wallet(&rpc, context));
+
+    if let Some(client) = &client {
+        checks.extend(check_payee(&rpc, client, context, config.as_ref()));
+    } else {
+        checks.push(CheckResult::skip("Payee", "program check failed"));
+        checks.push(CheckResult::skip("Treasury", "program check failed"));
+    }
+    checks
+}
+
+/// The config file must parse
+fn check_config_file(loaded: Result<ConfigFile>) -> CheckResult {
+    let path = ConfigFile::config_file_path().map_or_else(
+        |_| "config file".to_string(),
+        |path| path.display().to_string(),
+    );
+    match loaded {
+        Err(e) => CheckResult::fail(
+            "Config file",
+            format!("{e:#}"),
+            format!(
+                "Fix the TOML in {path}, or recreate it with: tally-merchant config init --force"
+            ),
+        ),
+        Ok(_) if !std::path::Path::new(&path).exists() => CheckResult::warn(
+            "Config file",
+            format!("No config file at {path} - using built-in profiles"),
+            "Create one with: tally-merchant config init",
+        ),
+        Ok(_) => CheckResult::pass("Config file", format!("{path} parses")),
+    }
+}
+
+/// The active profile must exist
+fn check_profile(context: &RuntimeContext, config_file: &ConfigFile) -> CheckResult {
+    match &context.profile {
+        None => CheckResult::warn(
+            "Profile",
+            "No active profile - using flags, environment and built-in defaults",
+            "Select one with: tally-merchant config profile use <NAME>",
+        ),
+        Some(profile) if config_file.get_profile(&profile.value).is_none() => CheckResult::fail(
+            "Profile",
+            format!("Active profile '{}' does not exist", profile.value),
+            "List profiles with 'tally-merchant config profile list', then run \
+             'tally-merchant config profile use <NAME>'",
+        ),
+        Some(profile) => CheckResult::pass(
+            "Profile",
+            format!("Using '{}' ({})", profile.value, profile.source),
+        ),
+    }
+}
+
+/// The cluster behind the RPC URL should be the one its name suggests
+fn check_network(rpc_url: &str, genesis_hash: &str) -> CheckResult {
+    let guess = detect_network(rpc_url);
+    let actual = KNOWN_GENESIS_HASHES
+        .iter()
+        .find(|(_, hash)| *hash == genesis_hash)
+        .map(|(network, _)| *network);
+
+    let mismatch_hint = "Point the profile at the intended cluster with: \
+                         tally-merchant config set rpc-url <URL> (or pass --rpc-url)";
+    match (guess.as_str(), actual) {
+        (guess, Some(actual)) if guess == actual => {
+            CheckResult::pass("Network", format!("Connected to {actual}"))
+        }
+        ("custom", Some(actual)) => {
+            CheckResult::pass("Network", format!("Custom RPC serves {actual}"))
+        }
+        ("localnet", None) => CheckResult::pass(
+            "Network",
+            format!("Connected to a local validator (genesis {genesis_hash})"),
+        ),
+        ("custom", None) => CheckResult::warn(
+            "Network",
+            format!("Unknown cluster (genesis {genesis_hash})"),
+            "Make sure this is the cluster your program is deployed to",
+        ),
+        (guess, Some(actual)) => CheckResult::fail(
+            "Network",
+            format!("RPC URL looks like {guess}, but the cluster is {actual}"),
+            mismatch_hint,
+        ),
+        (guess, None) => CheckResult::fail(
+            "Network",
+            format!(
+                "RPC URL looks like {guess}, but its genesis hash {genesis_hash} is not {guess}'s"
+            ),
+            mismatch_hint,
+        ),
+    }
+}
+
+/// The program ID must be an executable account on this cluster
+fn check_program(rpc: &RpcClient, context: &RuntimeContext) -> CheckResult {
+    let Some(program_id) = &context.program_id else {
+        return CheckResult::fail(
+            "Program",
+            "Program ID not configured",
+            program_id_missing().to_string(),
+        );
+    };
+    let Ok(address) = Pubkey::from_str(&program_id.value) else {
+        return CheckResult::fail(
+            "Program",
+            format!(
+                "Program ID '{}' ({}) is not a valid address",
+                program_id.value, program_id.source
+            ),
+            "Set a base58 program ID with: tally-merchant config set program-id <PROGRAM_ID>",
+        );
+    };
+
+    match rpc.get_account_with_commitment(&address, CommitmentConfig::confirmed()) {
+        Err(e) => CheckResult::fail(
+            "Program",
+            format!("Failed to fetch program account {address}: {e}"),
+            enhance_rpc_error(&anyhow!("{e}"), &rpc.url()).to_string(),
+        ),
+        Ok(response) => match response.value {
+            None => CheckResult::fail(
+                "Program",
+                format!("Program {address} is not deployed on this cluster"),
+                "Check you're on the correct network (--rpc-url) and that the program ID \
+                 matches it (tally-merchant config get program-id)",
+            ),
+            Some(account) if !account.executable => CheckResult::fail(
+                "Program",
+                format!("Account {address} exists but is not an executable program"),
+                "The program ID probably points at a data account - check it with: \
+                 tally-merchant config get program-id",
+            ),
+            Some(_) => CheckResult::pass(
+                "Program",
+                format!("{address} is deployed ({})", program_id.source),
+            ),
+        },
+    }
+}
+
+/// The global config account must exist and not be paused
+fn check_global_config(client: &SimpleTallyClient) -> (CheckResult, Option<Config>) {
+    match client.get_config() {
+        Err(e) => (
+            CheckResult::fail(
+                "Global config",
+                format!("Failed to fetch config account: {e}"),
+                "Check the RPC connection and program ID",
+            ),
+            None,
+        ),
+        Ok(None) => (
+            CheckResult::fail(
+                "Global config",
+                "Config account not found",
+                "The platform authority must run: tally-merchant admin init-config",
+            ),
+            None,
+        ),
+        Ok(Some(config)) if config.paused => (
+            CheckResult::fail(
+                "Global config",
+                "Program is paused - new agreements and payments are rejected",
+                "The platform authority can lift the pause with: tally-merchant admin unpause",
+            ),
+            Some(config),
+        ),
+        Ok(Some(config)) => (
+            CheckResult::pass("Global config", "Config account exists and is not paused"),
+            Some(config),
+        ),
+    }
+}
+
+/// The wallet must load and hold enough SOL for fees and rent
+///
+/// Signers that would prompt, read stdin, run a program or use a hardware
+/// wallet are reported as configured without being probed.
+fn check_wallet(rpc: &RpcClient, context: &RuntimeContext) -> CheckResult {
+    let path = &context.wallet_path.value;
+    let wallet = match stored_signer_pubkey(path) {
+        Ok(Some(wallet)) => wallet,
+        Ok(None) => {
+            return CheckResult::pass(
+                "Wallet",
+                format!(
+                    "{path} ({}) is configured, not probed - its balance is not checked",
+                    context.wallet_path.source
+                ),
+            );
+        }
+        Err(e) => {
+            return CheckResult::fail(
+                "Wallet",
+                format!(
+                    "Failed to load {path} ({}): {e}",
+                    context.wallet_path.source
+                ),
+                "Create a wallet with 'tally-merchant wallet new <NAME>', or point to yours \
+                 with: tally-merchant config set wallet-path <PATH>",
+            );
+        }
+    };
+
+    match rpc.get_balance(&wallet) {
+        Err(e) => CheckResult::fail(
+            "Wallet",
+            format!("Failed to fetch balance of {wallet}: {e}"),
+            enhance_rpc_error(&anyhow!("{e}"), &rpc.url()).to_string(),
+        ),
+        Ok(balance) if balance < MIN_SOL_BALANCE_LAMPORTS => {
+            let hint = enhance_insufficient_balance_error(
+                low_balance_sol(balance),
+                low_balance_sol(MIN_SOL_BALANCE_LAMPORTS),
+                &detect_network(&rpc.url()),
+            );
+            CheckResult::warn(
+                "Wallet",
+                format!(
+                    "{wallet} has {} SOL, below the recommended {} SOL",
+                    lamports_to_sol(balance),
+                    lamports_to_sol(MIN_SOL_BALANCE_LAMPORTS)
+                ),
+                hint.to_string(),
+            )
+        }
+        Ok(balance) => CheckResult::pass(
+            "Wallet",
+            format!("{wallet} has {} SOL", lamports_to_sol(balance)),
+        ),
+    }
+}
+
+/// The profile's payee must exist and its treasury must hold the expected mint
+fn check_payee(
+    rpc: &RpcClient,
+    client: &SimpleTallyClient,
+    context: &RuntimeContext,
+    config: Option<&Config>,
+) -> Vec<CheckResult> {
+    let Some(saved) = &context.payee else {
+        return vec![
+            CheckResult::warn(
+                "Payee",
+                "No payee saved in the active profile",
+                "Run 'tally-merchant init', or save an existing one with: \
+                 tally-merchant config set payee <PAYEE_PDA>",
+            ),
+            CheckResult::skip("Treasury", "no payee configured"),
+        ];
+    };
+    let address = match parse_merchant_pda(&saved.value, None) {
+        Ok(address) => address,
+        Err(e) => {
+            return vec![
+                CheckResult::fail("Payee", "Saved payee is not a valid address", e.to_string()),
+                CheckResult::skip("Treasury", "payee check failed"),
+            ];
+        }
+    };
+
+    let payee = match client.get_payee(&address) {
+        Err(e) => {
+            return vec![
+                CheckResult::fail(
+                    "Payee",
+                    format!("Failed to fetch payee {address}: {e}"),
+                    "Check the RPC connection and program ID",
+                ),
+                CheckResult::skip("Treasury", "payee check failed"),
+            ];
+        }
+        Ok(None) => {
+            return vec![
+                CheckResult::fail(
+                    "Payee",
+                    format!("Payee account {address} not found"),
+                    enhance_account_not_found_error("merchant", &address).to_string(),
+                ),
+                CheckResult::skip("Treasury", "payee check failed"),
+            ];
+        }
+        Ok(Some(payee)) => payee,
+    };
+    let payee_check = CheckResult::pass(
+        "Payee",
+        format!("{address} exists (authority {})", payee.authority),
+    );
+
+    let treasury = payee.treasury_ata;
+    let treasury_check = match rpc.get_token_account(&treasury) {
+        Err(e) => CheckResult::fail(
+            "Treasury",
+            format!("Failed to fetch treasury {treasury}: {e}"),
+            "Check the RPC connection",
+        ),
+        Ok(None) => CheckResult::fail(
+            "Treasury",
+            format!("Treasury {treasury} does not exist or is not a token account"),
+            "Point the payee at a USDC token account with: \
+             tally-merchant payee update-treasury --treasury <ATA>",
+        ),
+        Ok(Some(account)) => {
+            let expected_mint = get_usdc_mint(context.usdc_mint()).ok();
+            check_treasury(
+                &treasury,
+                &account.mint,
+                &payee.usdc_mint,
+                expected_mint.as_ref(),
+                config.map(|config| &config.allowed_mint),
+            )
+        }
+    };
+    vec![payee_check, treasury_check]
+}
+
+/// Compare the treasury's mint with the payee's, the profile's and the program's
+fn check_treasury(
+    treasury: &Pubkey,
+    treasury_mint: &str,
+    payee_mint: &Pubkey,
+    expected_mint: Option<&Pubkey>,
+    allowed_mint: Option<&Pubkey>,
+) -> CheckResult {
+    if let Err(e) = check_treasury_mint(treasury, treasury_mint, payee_mint) {
+        return CheckResult::fail(
+            "Treasury",
+            e.to_string(),
+            format!(
+                "Point the payee at a token account for {payee_mint} with: \
+                 tally-merchant payee update-treasury --treasury <ATA>"
+            ),
+        );
+    }
+    if let Some(allowed) = allowed_mint.filter(|allowed| *allowed != payee_mint) {
+        return CheckResult::fail(
+            "Treasury",
+            format!("Payee uses mint {payee_mint}, but the program only allows {allowed}"),
+            "Create a new payee for the allowed mint with: tally-merchant init",
+        );
+    }
+    if let Some(expected) = expected_mint.filter(|expected| *expected != payee_mint) {
+        return CheckResult::warn(
+            "Treasury",
+            format!("Payee uses mint {payee_mint}, but the profile expects {expected}"),
+            format!("Update the profile with: tally-merchant config set usdc-mint {payee_mint}"),
+        );
+    }
+    CheckResult::pass("Treasury", format!("{treasury} holds {payee_mint}"))
+}
+
+/// Format the checks for human-readable output
+///
+/// # Errors
+/// Returns error if string formatting fails
+pub fn format_checks_human(checks: &[CheckResult]) -> Result<String> {
+    let mut output = String::new();
+    writeln!(&mut output, "{}", Theme::header("Tally doctor"))?;
+    writeln!(&mut output, "{}", Theme::dim(&"=".repeat(50)))?;
+    for check in checks {
+        let marker = match check.status {
+            CheckStatus::Pass => Theme::success("✓"),
+            CheckStatus::Warn => Theme::warning("⚠"),
+            CheckStatus::Fail => Theme::error("✗"),
+            CheckStatus::Skip => Theme::dim("-"),
+        };
+        writeln!(
+            &mut output,
+            "{marker} {:<14} {}",
+            Theme::info(check.name),
+            check.message
+        )?;
+        if let Some(hint) = &check.hint {
+            for line in hint.lines() {
+                writeln!(&mut output, "    {}", Theme::dim(line))?;
+            }
+        }
+    }
+
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    write!(
+        &mut output,
+        "\n{} passed, {} warnings, {} failed",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail)
+    )?;
+    Ok(output)
+}
+
+/// SOL value of a balance at or below [`MIN_SOL_BALANCE_LAMPORTS`]
+///
+/// Such balances fit in a `u32`, so the conversion to `f64` is exact.
+fn low_balance_sol(lamports: u64) -> f64 {
+    f64::from(u32::try_from(lamports).unwrap_or(u32::MAX)) / 1e9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TallyCliConfig;
+    use crate::context::ContextFlags;
+
+    const DEVNET_GENESIS: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+    const MAINNET_GENESIS: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+    fn context(config_file: &ConfigFile) -> RuntimeContext {
+        RuntimeContext::resolve_with(
+            &ContextFlags::default(),
+            &TallyCliConfig::new(),
+            config_file,
+            |_| None,
+        )
+    }
+
+    #[test]
+    fn test_check_network() {
+        let check = check_network("https://api.devnet.solana.com", DEVNET_GENESIS);
+        assert_eq!(check.status, CheckStatus::Pass);
+
+        let check = check_network("https://api.devnet.solana.com", MAINNET_GENESIS);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check
+            .message
+            .contains("looks like devnet, but the cluster is mainnet"));
+
+        let check = check_network("https://rpc.example.com", MAINNET_GENESIS);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.message.contains("mainnet"));
+
+        let check = check_network("http://127.0.0.1:8899", "LocalGenesis111");
+        assert_eq!(check.status, CheckStatus::Pass);
+
+        let check = check_network("https://rpc.example.com", "Unknown111");
+        assert_eq!(check.status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_check_profile() {
+        let config_file = ConfigFile::new();
+        let check = check_profile(&context(&config_file), &config_file);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.message.contains("devnet"));
+
+        let mut missing = ConfigFile::new();
+        missing.set_active_profile("staging".to_string());
+        let check = check_profile(&context(&missing), &missing);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.hint.unwrap().contains("config profile use"));
+
+        let mut none = ConfigFile::new();
+        none.defaults.active_profile = None;
+        assert_eq!(
+            check_profile(&context(&none), &none).status,
+            CheckStatus::Warn
+        );
+    }
+
+    #[test]
+    fn test_check_profile_missing_env_profile() {
+        let config_file = ConfigFile::new();
+        let context = RuntimeContext::resolve_with(
+            &ContextFlags::default(),
+            &TallyCliConfig::new(),
+            &config_file,
+            |var| (var == "TALLY_PROFILE").then(|| "staging".to_string()),
+        );
+        let check = check_profile(&context, &config_file);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.message.contains("'staging' does not exist"));
+    }
+
+    #[test]
+    fn test_check_config_file_parse_error() {
+        let check = check_config_file(Err(anyhow!("Failed to parse config file")));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.hint.unwrap().contains("config init --force"));
+    }
+
+    #[test]
+    fn test_check_treasury() {
+        let treasury = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let check = check_treasury(
+            &treasury,
+            &mint.to_string(),
+            &mint,
+            Some(&mint),
+            Some(&mint),
+        );
+        assert_eq!(check.status, CheckStatus::Pass);
+
+        let check = check_treasury(&treasury, &other.to_string(), &mint, None, None);
+        assert_eq!(check.status, CheckStatus::Fail);
+
+        let check = check_treasury(&treasury, &mint.to_string(), &mint, None, Some(&other));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.message.contains("only allows"));
+
+        let check = check_treasury(&treasury, &mint.to_string(), &mint, Some(&other), None);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.hint.unwrap().contains("config set usdc-mint"));
+    }
+
+    #[test]
+    fn test_doctor_report_json() {
+        let checks = vec![
+            CheckResult::pass("RPC", "Reachable"),
+            CheckResult::warn("Payee", "No payee", "Run init"),
+            CheckResult::fail("Program", "Missing", "Set program-id"),
+            CheckResult::skip("Treasury", "payee check failed"),
+        ];
+        let report = DoctorReport::new(checks);
+        assert!(!report.success());
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["healthy"], false);
+        assert_eq!(json["summary"]["passed"], 1);
+        assert_eq!(json["summary"]["warnings"], 1);
+        assert_eq!(json["summary"]["failed"], 1);
+        assert_eq!(json["checks"][2]["status"], "fail");
+        assert_eq!(json["checks"][2]["hint"], "Set program-id");
+        assert!(json["checks"][0].get("hint").is_none());
+    }
+
+    #[test]
+    fn test_format_checks_human() {
+        let checks = vec![
+            CheckResult::pass("RPC", "Reachable"),
+            CheckResult::fail("Program", "Missing", "Set program-id\nor pass --program-id"),
+        ];
+        let output = format_checks_human(&checks).unwrap();
+        assert!(output.contains("Reachable"));
+        assert!(output.contains("or pass --program-id"));
+        assert!(output.ends_with("1 passed, 0 warnings, 1 failed"));
+    }
+}
@@ -0,0 +1,532 @@
+//! Live event streaming over the Solana websocket `logsSubscribe` API
+//!
+//! Subscribes to program logs that mention a payee, decodes Tally events as
+//! they land and prints them until the user presses Ctrl-C. Dropped
+//! connections are re-established with exponential backoff.
+//!
+//! Machine-readable formats are written one record per line so the stream
+//! can be tailed into log shippers: JSON and NDJSON both emit one compact
+//! `event` envelope per event, and CSV emits the header once followed by one
+//! row per event. The stream ends with an `events.follow` result.
+
+use crate::output::{CommandOutput, Output, OutputFormat};
+use crate::utils::colors::Theme;
+use crate::utils::formatting::{
+    format_event_human, format_events_csv, EventEntry, EventInfo, EVENT_CSV_HEADER,
+};
+use anyhow::{anyhow, Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Duration;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{parse_events_from_logs, TallyEvent};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+use url::Url;
+
+/// Initial delay before reconnecting after a dropped connection
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound for the reconnect delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Request to follow live events for a payee
+pub struct FollowEventsRequest<'a> {
+    /// Payee (merchant) account address
+    pub merchant: Pubkey,
+    /// HTTP RPC URL, used to derive the websocket URL when none is given
+    pub rpc_url: &'a str,
+    /// Explicit websocket URL override
+    pub ws_url: Option<&'a str>,
+    /// Format used for each streamed event
+    pub output_format: &'a OutputFormat,
+}
+
+/// A `logsNotification` payload received from the websocket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogsNotification {
+    /// Transaction signature the logs belong to
+    pub signature: String,
+    /// Whether the transaction failed
+    pub failed: bool,
+    /// Raw program log lines
+    pub logs: Vec<String>,
+}
+
+/// Summary of a `dashboard events --follow` session
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EventsFollowed {
+    /// Number of events printed
+    pub received: usize,
+}
+
+impl CommandOutput for EventsFollowed {
+    const KIND: &'static str = "events.follow";
+
+    fn human(&self) -> Result<String> {
+        Ok(format!(
+            "\nStopped following events ({} received)",
+            self.received
+        ))
+    }
+
+    /// Nothing, so the CSV stream stays a single table
+    fn csv(&self) -> Option<Result<String>> {
+        Some(Ok(String::new()))
+    }
+}
+
+/// Follow events until Ctrl-C is pressed
+///
+/// Events are printed as they arrive; the returned summary is shown after the
+/// stream stops.
+///
+/// # Errors
+/// Returns error if the websocket URL cannot be determined
+pub async fn follow(request: &FollowEventsRequest<'_>) -> Result<EventsFollowed> {
+    let ws_url = match request.ws_url {
+        Some(url) => url.to_string(),
+        None => websocket_url(request.rpc_url)?,
+    };
+
+    eprintln!(
+        "{} {} {}",
+        Theme::info("Following events for"),
+        Theme::highlight(&request.merchant.to_string()),
+        Theme::dim(&format!("via {ws_url} (Ctrl-C to stop)"))
+    );
+
+    if matches!(request.output_format, OutputFormat::Csv) {
+        println!("{}", EVENT_CSV_HEADER.join(","));
+    }
+
+    let mut received = 0usize;
+    let stream = stream_with_reconnect(&ws_url, &request.merchant, |notification| {
+        for event in decode_notification(&notification, &request.merchant) {
+            received += 1;
+            match format_streamed_event(&event, request.output_format) {
+                Ok(line) => println!("{line}"),
+                Err(e) => warn!("Failed to format event: {e:#}"),
+            }
+        }
+    });
+
+    tokio::select! {
+        result = stream => result?,
+        _ = tokio::signal::ctrl_c() => {
+            info!("Received Ctrl-C, stopping event stream");
+        }
+    }
+
+    Ok(EventsFollowed { received })
+}
+
+/// Keep a subscription alive, reconnecting with exponential backoff
+///
+/// Only returns on unrecoverable errors; callers are expected to race it
+/// against a shutdown signal.
+async fn stream_with_reconnect<F>(ws_url: &str, merchant: &Pubkey, mut on_logs: F) -> Result<()>
+where
+    F: FnMut(LogsNotification),
+{
+    let mut backoff = INITIAL_BACKOFF;
+
+    loop {
+        match run_subscription(ws_url, merchant, &mut on_logs, &mut backoff).await {
+            Ok(()) => warn!("Websocket closed by server"),
+            Err(e) => warn!("Websocket error: {e:#}"),
+        }
+
+        eprintln!(
+            "{} reconnecting in {}s...",
+            Theme::warning("Connection lost,"),
+            backoff.as_secs()
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = next_backoff(backoff);
+    }
+}
+
+/// Open one websocket connection and forward notifications until it closes
+///
+/// `backoff` is reset once the subscription is confirmed so that a healthy
+/// connection which later drops reconnects quickly.
+///
+/// # Errors
+/// Returns error if the connection, subscription or message parsing fails
+pub async fn run_subscription<F>(
+    ws_url: &str,
+    merchant: &Pubkey,
+    on_logs: &mut F,
+    backoff: &mut Duration,
+) -> Result<()>
+where
+    F: FnMut(LogsNotification),
+{
+    let (mut socket, _) = connect_async(ws_url)
+        .await
+        .with_context(|| format!("Failed to connect to websocket endpoint {ws_url}"))?;
+
+    socket
+        .send(Message::Text(subscribe_request(merchant).to_string()))
+        .await
+        .context("Failed to send logsSubscribe request")?;
+
+    while let Some(message) = socket.next().await {
+        let text = match message.context("Websocket read failed")? {
+            Message::Text(text) => text,
+            Message::Ping(payload) => {
+                socket.send(Message::Pong(payload)).await?;
+                continue;
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let value: serde_json::Value =
+            serde_json::from_str(&text).context("Invalid JSON from websocket")?;
+
+        if let Some(error) = value.get("error") {
+            return Err(anyhow!("logsSubscribe rejected by RPC node: {error}"));
+        }
+
+        if value.get("id").is_some() && value.get("result").is_some() {
+            info!("Subscribed to logs (subscription {})", value["result"]);
+            *backoff = INITIAL_BACKOFF;
+            continue;
+        }
+
+        if let Some(notification) = parse_logs_notification(&value) {
+            on_logs(notification);
+        }
+    }
+
+    Ok(())
+}
+
+/// Build the JSON-RPC `logsSubscribe` request for transactions mentioning `merchant`
+#[must_use]
+pub fn subscribe_request(merchant: &Pubkey) -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "logsSubscribe",
+        "params": [
+            { "mentions": [merchant.to_string()] },
+            { "commitment": "confirmed" }
+        ]
+    })
+}
+
+/// Extract the signature and logs from a `logsNotification` message
+#[must_use]
+pub fn parse_logs_notification(value: &serde_json::Value) -> Option<LogsNotification> {
+    if value.get("method")?.as_str()? != "logsNotification" {
+        return None;
+    }
+
+    let result = value.get("params")?.get("result")?.get("value")?;
+    let signature = result.get("signature")?.as_str()?.to_string();
+    let failed = result.get("err").is_some_and(|err| !err.is_null());
+    let logs = result
+        .get("logs")?
+        .as_array()?
+        .iter()
+        .filter_map(|line| line.as_str().map(String::from))
+        .collect();
+
+    Some(LogsNotification {
+        signature,
+        failed,
+        logs,
+    })
+}
+
+/// Derive the websocket URL from an HTTP RPC URL
+///
+/// Follows the Solana CLI convention: `http` becomes `ws`, `https` becomes
+/// `wss`, and the local test validator port 8899 maps to 8900.
+///
+/// # Errors
+/// Returns error if the RPC URL cannot be parsed or uses an unknown scheme
+pub fn websocket_url(rpc_url: &str) -> Result<String> {
+    let mut url = Url::parse(rpc_url).with_context(|| format!("Invalid RPC URL: {rpc_url}"))?;
+
+    let scheme = match url.scheme() {
+        "http" | "ws" => "ws",
+        "https" | "wss" => "wss",
+        other => return Err(anyhow!("Unsupported RPC URL scheme '{other}': {rpc_url}")),
+    };
+    url.set_scheme(scheme)
+        .map_err(|()| anyhow!("Failed to derive websocket URL from {rpc_url}"))?;
+
+    if url.port() == Some(8899) {
+        url.set_port(Some(8900))
+            .map_err(|()| anyhow!("Failed to derive websocket URL from {rpc_url}"))?;
+    }
+
+    Ok(url.to_string())
+}
+
+/// Decode the Tally events for `merchant` from a logs notification
+///
+/// Websocket notifications carry no block time, so events are stamped with
+/// the time they were received.
+fn decode_notification(notification: &LogsNotification, merchant: &Pubkey) -> Vec<EventInfo> {
+    if notification.failed {
+        return Vec::new();
+    }
+
+    let parsed = parse_events_from_logs(&notification.logs);
+    for error in &parsed.errors {
+        warn!(
+            "Failed to decode event in {} (log line {}): {}",
+            notification.signature, error.log_index, error.error
+        );
+    }
+
+    let received_at = current_timestamp();
+    parsed
+        .events
+        .iter()
+        .filter_map(|event| event_info(event, &notification.signature, received_at))
+        .filter(|event| event.payee == *merchant)
+        .collect()
+}
+
+/// Convert a decoded Tally event into display form
+fn event_info(event: &TallyEvent, signature: &str, timestamp: i64) -> Option<EventInfo> {
+    let (event_type, payee, payment_terms, subscriber, amount) = match event {
+        TallyEvent::PaymentAgreementStarted(e) => (
+            "PaymentAgreementStarted",
+            e.payee,
+            e.payment_terms,
+            e.payer,
+            Some(e.amount),
+        ),
+        TallyEvent::PaymentExecuted(e) => (
+            "PaymentExecuted",
+            e.payee,
+            e.payment_terms,
+            e.payer,
+            Some(e.amount),
+        ),
+        TallyEvent::PaymentAgreementPaused(e) => (
+            "PaymentAgreementPaused",
+            e.payee,
+            e.payment_terms,
+            e.payer,
+            None,
+        ),
+        TallyEvent::PaymentFailed(e) => ("PaymentFailed", e.payee, e.payment_terms, e.payer, None),
+        TallyEvent::LowAllowanceWarning(e) => (
+            "LowAllowanceWarning",
+            e.payee,
+            e.payment_terms,
+            e.payer,
+            None,
+        ),
+        _ => return None,
+    };
+
+    Some(EventInfo {
+        event_type: event_type.to_string(),
+        timestamp,
+        payee,
+        payer: Some(subscriber),
+        payment_terms: Some(payment_terms),
+        amount,
+        signature: Some(signature.to_string()),
+    })
+}
+
+/// Format one event for the live stream
+///
+/// # Errors
+/// Returns error if JSON or CSV serialization fails
+pub fn format_streamed_event(event: &EventInfo, output_format: &OutputFormat) -> Result<String> {
+    match output_format {
+        OutputFormat::Human => Ok(format_event_human(&EventEntry::from(event))),
+        // One compact envelope per line, even for `--output json`
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            Output::from(EventEntry::from(event)).render(&OutputFormat::Ndjson)
+        }
+        OutputFormat::Csv => Ok(format_events_csv(&[EventEntry::from(event)], false)?
+            .trim_end()
+            .to_string()),
+    }
+}
+
+/// Double the backoff, capped at `MAX_BACKOFF`
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(MAX_BACKOFF)
+}
+
+/// Current unix timestamp in seconds
+fn current_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    #[test]
+    fn test_websocket_url_devnet() {
+        assert_eq!(
+            websocket_url("https://api.devnet.solana.com").unwrap(),
+            "wss://api.devnet.solana.com/"
+        );
+    }
+
+    #[test]
+    fn test_websocket_url_local_validator() {
+        assert_eq!(
+            websocket_url("http://127.0.0.1:8899").unwrap(),
+            "ws://127.0.0.1:8900/"
+        );
+    }
+
+    #[test]
+    fn test_websocket_url_rejects_unknown_scheme() {
+        assert!(websocket_url("ftp://example.com").is_err());
+    }
+
+    fn sample_event() -> EventInfo {
+        EventInfo {
+            event_type: "PaymentExecuted".to_string(),
+            timestamp: 1_700_000_000,
+            payee: Pubkey::new_unique(),
+            payer: Some(Pubkey::new_unique()),
+            payment_terms: Some(Pubkey::new_unique()),
+            amount: Some(5_000_000),
+            signature: Some("sig".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_format_streamed_event_json_is_single_line() {
+        let line = format_streamed_event(&sample_event(), &OutputFormat::Json).unwrap();
+        assert!(!line.contains('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["kind"], "event");
+        assert_eq!(value["data"]["event_type"], "PaymentExecuted");
+        assert_eq!(value["data"]["amount_microlamports"], 5_000_000);
+    }
+
+    #[test]
+    fn test_format_streamed_event_csv_has_no_header() {
+        let line = format_streamed_event(&sample_event(), &OutputFormat::Csv).unwrap();
+        assert!(line.starts_with("PaymentExecuted,1700000000,"));
+        assert!(!line.contains('\n'));
+    }
+
+    #[test]
+    fn test_next_backoff_is_capped() {
+        assert_eq!(next_backoff(Duration::from_secs(1)), Duration::from_secs(2));
+        assert_eq!(next_backoff(Duration::from_secs(20)), MAX_BACKOFF);
+        assert_eq!(next_backoff(MAX_BACKOFF), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_parse_logs_notification() {
+        let value = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "logsNotification",
+            "params": {
+                "result": {
+                    "context": { "slot": 5 },
+                    "value": {
+                        "signature": "sig123",
+                        "err": null,
+                        "logs": ["Program log: hello", "Program data: AAAA"]
+                    }
+                },
+                "subscription": 7
+            }
+        });
+
+        let notification = parse_logs_notification(&value).unwrap();
+        assert_eq!(notification.signature, "sig123");
+        assert!(!notification.failed);
+        assert_eq!(notification.logs.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_logs_notification_ignores_other_messages() {
+        let value = serde_json::json!({ "jsonrpc": "2.0", "result": 7, "id": 1 });
+        assert!(parse_logs_notification(&value).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_subscription_against_mock_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let merchant = Pubkey::new_unique();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+
+            let Some(Ok(Message::Text(request))) = socket.next().await else {
+                panic!("Expected subscribe request");
+            };
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+
+            socket
+                .send(Message::Text(
+                    serde_json::json!({ "jsonrpc": "2.0", "result": 42, "id": 1 }).to_string(),
+                ))
+                .await
+                .unwrap();
+            socket
+                .send(Message::Text(
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "method": "logsNotification",
+                        "params": {
+                            "result": {
+                                "context": { "slot": 1 },
+                                "value": {
+                                    "signature": "5Qx",
+                                    "err": null,
+                                    "logs": ["Program log: Instruction: ExecutePayment"]
+                                }
+                            },
+                            "subscription": 42
+                        }
+                    })
+                    .to_string(),
+                ))
+                .await
+                .unwrap();
+            socket.close(None).await.unwrap();
+            request
+        });
+
+        let mut received = Vec::new();
+        let mut backoff = Duration::from_secs(8);
+        run_subscription(
+            &format!("ws://{addr}"),
+            &merchant,
+            &mut |notification| received.push(notification),
+            &mut backoff,
+        )
+        .await
+        .unwrap();
+
+        let request = server.await.unwrap();
+        assert_eq!(request["method"], "logsSubscribe");
+        assert_eq!(request["params"][0]["mentions"][0], merchant.to_string());
+        assert_eq!(backoff, INITIAL_BACKOFF);
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].signature, "5Qx");
+    }
+}
@@ -0,0 +1,236 @@
+//! Explain-error command implementation
+//!
+//! Turns what a failed command or explorer shows - a custom program error
+//! code such as `0x1771`, an error name, or the signature of a failed
+//! transaction - into the decoded error with what it means and how to fix it.
+
+use crate::context::RuntimeContext;
+use crate::errors::{CliError, ErrorCode, ProgramErrorCode};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::program_errors;
+use crate::utils::tx_decode;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::solana_client::rpc_client::RpcClient;
+use tally_sdk::solana_sdk::commitment_config::CommitmentConfig;
+use tally_sdk::solana_sdk::signature::Signature;
+
+/// Arguments for the explain-error command
+pub struct ExplainErrorRequest<'a> {
+    pub context: &'a RuntimeContext,
+    /// Error code (`0x1771` or `6001`), error name, or transaction signature
+    pub query: &'a str,
+}
+
+/// What `explain-error` found for its query
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ErrorExplanation {
+    pub query: String,
+    /// Signature of the transaction looked up, if the query was one
+    pub signature: Option<String>,
+    /// The transaction's error as reported by the RPC, `None` if it succeeded
+    pub transaction_error: Option<String>,
+    /// Errors the query may refer to; a bare code can match several programs
+    pub matches: Vec<ProgramErrorCode>,
+    /// Program logs of the transaction
+    pub logs: Vec<String>,
+}
+
+impl CommandOutput for ErrorExplanation {
+    const KIND: &'static str = "error.explain";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        if let Some(signature) = &self.signature {
+            writeln!(
+                &mut output,
+                "{} {}",
+                Theme::header("Transaction"),
+                Theme::highlight(signature)
+            )?;
+            match &self.transaction_error {
+                Some(error) => writeln!(&mut output, "Status: {}", Theme::error(error))?,
+                None => writeln!(&mut output, "Status: {}", Theme::success("Succeeded"))?,
+            }
+            writeln!(&mut output)?;
+        }
+
+        if self.matches.is_empty() && self.transaction_error.is_some() {
+            writeln!(
+                &mut output,
+                "{}",
+                Theme::dim("The error is not a custom program error.")
+            )?;
+        } else if self.matches.is_empty() && self.signature.is_none() {
+            writeln!(
+                &mut output,
+                "{} No known program error matches '{}'",
+                Theme::warning("⚠"),
+                self.query
+            )?;
+            writeln!(
+                &mut output,
+                "{}",
+                Theme::dim(
+                    "Tally errors start at 6000 (0x1770), Anchor errors at 100, and SPL Token \
+                     and System program errors below 100."
+                )
+            )?;
+        }
+        if self.matches.len() > 1 {
+            writeln!(
+                &mut output,
+                "{}",
+                Theme::dim("The code is used by several programs; the failing program decides:")
+            )?;
+            writeln!(&mut output)?;
+        }
+        for error in &self.matches {
+            writeln!(&mut output, "{}", Theme::highlight(&error.to_string()))?;
+            if let Some(program_id) = &error.program_id {
+                writeln!(&mut output, "  Program: {}", Theme::dim(program_id))?;
+            }
+            if let Some(explanation) = &error.explanation {
+                writeln!(&mut output, "  {explanation}")?;
+            }
+            if let Some(fix) = &error.fix {
+                writeln!(&mut output, "  To fix this: {fix}")?;
+            }
+            writeln!(&mut output)?;
+        }
+
+        if !self.logs.is_empty() {
+            writeln!(&mut output, "{}", Theme::info("Program logs:"))?;
+            for line in &self.logs {
+                writeln!(&mut output, "  {}", Theme::dim(line))?;
+            }
+        }
+        Ok(output.trim_end().to_string())
+    }
+
+    fn success(&self) -> bool {
+        self.signature.is_some() || !self.matches.is_empty()
+    }
+}
+
+/// How the query was read
+#[derive(Debug, PartialEq, Eq)]
+enum Query {
+    Code(u32),
+    Name(String),
+    Signature(Signature),
+}
+
+/// Execute the explain-error command
+///
+/// # Errors
+/// Returns error if the query is an unknown error name, or the transaction
+/// cannot be fetched
+pub fn execute(request: &ExplainErrorRequest) -> Result<ErrorExplanation> {
+    let query = request.query.trim();
+    let mut explanation = ErrorExplanation {
+        query: query.to_string(),
+        signature: None,
+        transaction_error: None,
+        matches: Vec::new(),
+        logs: Vec::new(),
+    };
+
+    match parse_query(query) {
+        Query::Code(code) => {
+            explanation.matches = program_errors::candidates(code)
+                .into_iter()
+                .map(|decoded| ProgramErrorCode::with_info(None, code, None, Some(decoded)))
+                .collect();
+        }
+        Query::Name(name) => {
+            let found = program_errors::find_by_name(&name);
+            if found.is_empty() {
+                return Err(
+                    CliError::new(ErrorCode::General, format!("Unknown error '{name}'"))
+                        .with_suggestion(
+                            "Pass an error code (e.g. 0x1771 or 6001), an error name \
+                     (e.g. InsufficientAllowance), or a transaction signature.",
+                        )
+                        .into(),
+                );
+            }
+            explanation.matches = found
+                .into_iter()
+                .map(|(source, info)| {
+                    ProgramErrorCode::with_info(None, info.code, None, Some((source, info)))
+                })
+                .collect();
+        }
+        Query::Signature(signature) => {
+            let rpc_url = request.context.rpc_url.value.clone();
+            let rpc = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+            let transaction = tx_decode::fetch_transaction(&rpc, &signature)?;
+            explanation.signature = Some(signature.to_string());
+            if let Some((error, program_error)) = tx_decode::transaction_error(&transaction.meta) {
+                explanation.transaction_error = Some(error);
+                explanation.matches = program_error.into_iter().collect();
+                explanation.logs = program_errors::relevant_logs(&transaction.logs());
+            }
+        }
+    }
+    Ok(explanation)
+}
+
+fn parse_query(query: &str) -> Query {
+    if let Some(hex) = query
+        .strip_prefix("0x")
+        .or_else(|| query.strip_prefix("0X"))
+    {
+        if let Ok(code) = u32::from_str_radix(hex, 16) {
+            return Query::Code(code);
+        }
+    }
+    if let Ok(code) = query.parse() {
+        return Query::Code(code);
+    }
+    if let Ok(signature) = Signature::from_str(query) {
+        return Query::Signature(signature);
+    }
+    Query::Name(query.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query("0x1771"), Query::Code(6001));
+        assert_eq!(parse_query("6001"), Query::Code(6001));
+        assert_eq!(
+            parse_query("InsufficientAllowance"),
+            Query::Name("InsufficientAllowance".to_string())
+        );
+        let signature = Signature::new_unique();
+        assert_eq!(
+            parse_query(&signature.to_string()),
+            Query::Signature(signature)
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_code_lists_every_program() {
+        let explanation = ErrorExplanation {
+            query: "1".to_string(),
+            signature: None,
+            transaction_error: None,
+            matches: program_errors::candidates(1)
+                .into_iter()
+                .map(|decoded| ProgramErrorCode::with_info(None, 1, None, Some(decoded)))
+                .collect(),
+            logs: Vec::new(),
+        };
+        assert!(explanation.matches.len() > 1);
+        assert!(explanation.human().unwrap().contains("several programs"));
+    }
+}
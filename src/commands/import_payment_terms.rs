@@ -0,0 +1,713 @@
+//! Import payment terms command implementation
+//!
+//! Creates payment terms in bulk from a CSV file with the columns
+//! `id,amount_usdc,period_days` (or `period_months`, or `period` with a
+//! duration like `1mo`). Every row is validated
+//! before anything is sent, so a typo on line 40 does not leave a half-imported
+//! catalog behind.
+
+use crate::commands::apply_payment_terms::{CurrentTerms, DesiredTerms};
+use crate::config::TallyCliConfig;
+use crate::output::CommandOutput;
+use crate::utils::amount::parse_usdc_amount;
+use crate::utils::authority::verify_payee_authority;
+use crate::utils::colors::Theme;
+use crate::utils::duration::{format_period, period_from_fields, warn_calendar_drift};
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::hash::BuildHasher;
+use std::io::Read;
+use std::path::Path;
+use tally_sdk::program_types::CreatePaymentTermsArgs;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{SimpleTallyClient, UsdcAmount};
+use tracing::info;
+
+/// Arguments for importing payment terms from CSV
+pub struct ImportPaymentTermsRequest<'a> {
+    pub csv_path: &'a Path,
+    pub payee: Pubkey,
+    pub authority_path: Option<&'a str>,
+    /// Skip rows whose payment terms already exist instead of failing
+    pub resume: bool,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// One CSV record
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    id: String,
+    amount_usdc: String,
+    #[serde(default)]
+    period: Option<String>,
+    #[serde(default)]
+    period_days: Option<u64>,
+    #[serde(default)]
+    period_months: Option<u64>,
+}
+
+/// A validated CSV row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportRow {
+    /// Line number in the CSV file (the header is line 1)
+    pub line: u64,
+    pub terms: DesiredTerms,
+}
+
+/// A row that failed validation or could not be created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct RowError {
+    pub line: u64,
+    pub message: String,
+}
+
+/// Outcome of `payment-terms import`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TermsImport {
+    pub payee: String,
+    /// Rows skipped with `--resume` because their terms already exist
+    pub skipped: Vec<SkippedTerms>,
+    /// What was done with the remaining rows: one `sent` entry, or one
+    /// simulated, exported or proposed transaction per row; empty when every
+    /// row already existed
+    pub outcomes: Vec<TxOutcome<TermsCreated>>,
+    /// Rows whose transaction failed; the other rows were still sent
+    pub failed: Vec<RowError>,
+}
+
+/// A row skipped with `--resume`, and how the existing terms differ from it
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SkippedTerms {
+    #[serde(flatten)]
+    pub terms: ImportedTerms,
+    /// On-chain amount in micro-units, if it differs from the CSV
+    pub existing_amount_microlamports: Option<u64>,
+    /// On-chain period, if it differs from the CSV
+    pub existing_period_seconds: Option<u64>,
+}
+
+impl SkippedTerms {
+    fn new(row: &ImportRow, existing: &CurrentTerms) -> Self {
+        Self {
+            terms: ImportedTerms::new(row, Some(existing.address)),
+            existing_amount_microlamports: (existing.amount_usdc != row.terms.amount_usdc)
+                .then_some(existing.amount_usdc),
+            existing_period_seconds: (existing.period_secs != row.terms.period_secs)
+                .then_some(existing.period_secs),
+        }
+    }
+
+    /// What differs between the CSV row and the existing terms, if anything
+    fn mismatch(&self) -> Option<String> {
+        let mut differences = Vec::new();
+        if let Some(existing) = self.existing_amount_microlamports {
+            differences.push(format!(
+                "amount is {} USDC on-chain, {} USDC in the CSV",
+                UsdcAmount::from_microlamports(existing),
+                UsdcAmount::from_microlamports(self.terms.amount_microlamports)
+            ));
+        }
+        if let Some(existing) = self.existing_period_seconds {
+            differences.push(format!(
+                "period is {} on-chain, {} in the CSV",
+                format_period(existing),
+                format_period(self.terms.period_seconds)
+            ));
+        }
+        (!differences.is_empty()).then(|| differences.join("; "))
+    }
+}
+
+/// Payment terms created from the CSV, one transaction per row
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TermsCreated {
+    pub created: Vec<ImportedTerms>,
+}
+
+/// Payment terms from one CSV row
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ImportedTerms {
+    /// Line number in the CSV file
+    pub line: u64,
+    pub terms_id: String,
+    pub amount_microlamports: u64,
+    pub period_seconds: u64,
+    /// Payment terms PDA, if known
+    pub payment_terms: Option<String>,
+}
+
+impl ImportedTerms {
+    fn new(row: &ImportRow, address: Option<Pubkey>) -> Self {
+        Self {
+            line: row.line,
+            terms_id: row.terms.id.clone(),
+            amount_microlamports: row.terms.amount_usdc,
+            period_seconds: row.terms.period_secs,
+            payment_terms: address.map(|address| address.to_string()),
+        }
+    }
+}
+
+impl CommandOutput for TermsCreated {
+    const KIND: &'static str = "payment-terms.import.created";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        for terms in &self.created {
+            writeln!(
+                &mut output,
+                "{} line {}: '{}' {} USDC every {} -> {}",
+                Theme::success("created"),
+                terms.line,
+                terms.terms_id,
+                UsdcAmount::from_microlamports(terms.amount_microlamports),
+                format_period(terms.period_seconds),
+                terms
+                    .payment_terms
+                    .as_deref()
+                    .unwrap_or("(address not fetched)")
+            )?;
+        }
+        Ok(output)
+    }
+}
+
+impl CommandOutput for TermsImport {
+    const KIND: &'static str = "payment-terms.import";
+
+    fn human(&self) -> Result<String> {
+        let mut output = format_skipped(&self.skipped)?;
+        match self.outcomes.as_slice() {
+            [] => write!(
+                &mut output,
+                "{} All {} rows already exist - nothing to import",
+                Theme::success("✓"),
+                self.skipped.len()
+            )?,
+            [outcome @ TxOutcome::Sent(terms)] => {
+                write!(
+                    &mut output,
+                    "{}{} created, {} skipped, {} failed",
+                    outcome.human()?,
+                    terms.created.len(),
+                    self.skipped.len(),
+                    self.failed.len()
+                )?;
+                if !self.failed.is_empty() {
+                    write!(
+                        &mut output,
+                        "\n\nFailed rows:\n{}\n\n\
+                         Fix the failures and re-run with --resume to skip the terms already created.",
+                        format_row_errors(&self.failed)
+                    )?;
+                }
+            }
+            outcomes => {
+                let previews = outcomes
+                    .iter()
+                    .map(CommandOutput::human)
+                    .collect::<Result<Vec<_>>>()?;
+                output.push_str(&previews.join("\n\n"));
+            }
+        }
+        Ok(output)
+    }
+
+    fn success(&self) -> bool {
+        self.failed.is_empty() && self.outcomes.iter().all(CommandOutput::success)
+    }
+}
+
+fn format_skipped(skipped: &[SkippedTerms]) -> Result<String> {
+    let mut output = String::new();
+    for skipped in skipped {
+        let terms = &skipped.terms;
+        write!(
+            &mut output,
+            "{} line {}: '{}' already exists at {}",
+            Theme::dim("skip"),
+            terms.line,
+            terms.terms_id,
+            terms.payment_terms.as_deref().unwrap_or_default()
+        )?;
+        match skipped.mismatch() {
+            Some(mismatch) => writeln!(
+                &mut output,
+                " {}",
+                Theme::warning(&format!("but its {mismatch} - left unchanged"))
+            )?,
+            None => writeln!(&mut output)?,
+        }
+    }
+    Ok(output)
+}
+
+/// Parse and validate every row, collecting all row-level errors
+///
+/// # Errors
+/// Returns the errors of every invalid row, or a single line 1 error if the
+/// CSV header cannot be read
+pub fn parse_rows(reader: impl Read) -> std::result::Result<Vec<ImportRow>, Vec<RowError>> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = csv_reader.headers().cloned().map_err(|e| {
+        vec![RowError {
+            line: 1,
+            message: format!("Invalid CSV header: {e}"),
+        }]
+    })?;
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut first_line_by_id: HashMap<String, u64> = HashMap::new();
+    for record in csv_reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(RowError {
+                    line: e.position().map_or(0, csv::Position::line),
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, csv::Position::line);
+
+        match parse_record(&record, &headers) {
+            Ok(terms) => {
+                if let Some(first) = first_line_by_id.get(&terms.id) {
+                    errors.push(RowError {
+                        line,
+                        message: format!("Terms id '{}' already used on line {first}", terms.id),
+                    });
+                } else {
+                    first_line_by_id.insert(terms.id.clone(), line);
+                    rows.push(ImportRow { line, terms });
+                }
+            }
+            Err(e) => errors.push(RowError {
+                line,
+                message: format!("{e:#}"),
+            }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(rows)
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_record(record: &csv::StringRecord, headers: &csv::StringRecord) -> Result<DesiredTerms> {
+    let row: CsvRow = record
+        .deserialize(Some(headers))
+        .map_err(|e| anyhow!("{}", csv_error_message(&e)))?;
+    let period_secs =
+        period_from_fields(row.period.as_deref(), row.period_days, row.period_months)?;
+    let amount = parse_usdc_amount(&row.amount_usdc)?;
+    DesiredTerms::new(&row.id, amount, period_secs)
+}
+
+/// Strip the position prefix csv adds, since errors are reported per line
+fn csv_error_message(error: &csv::Error) -> String {
+    match error.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => err.field().map_or_else(
+            || err.kind().to_string(),
+            |field| format!("column {}: {}", field + 1, err.kind()),
+        ),
+        _ => error.to_string(),
+    }
+}
+
+/// Format row errors as "line N: message"
+fn format_row_errors(errors: &[RowError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("  line {}: {}", e.line, e.message))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Execute the import payment terms command
+///
+/// # Errors
+/// Returns error if any row is invalid, a row's terms already exist without
+/// `--resume`, or the authority does not own the payee. Rows that fail to be
+/// created are reported in [`TermsImport::failed`] after the remaining rows
+/// have been attempted.
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &ImportPaymentTermsRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TermsImport> {
+    info!(
+        "Importing payment terms from {}",
+        request.csv_path.display()
+    );
+
+    let file = std::fs::File::open(request.csv_path)
+        .with_context(|| format!("Failed to open {}", request.csv_path.display()))?;
+    let rows = parse_rows(file).map_err(|errors| {
+        anyhow!(
+            "{} invalid rows in {} - nothing was sent:\n{}",
+            errors.len(),
+            request.csv_path.display(),
+            format_row_errors(&errors)
+        )
+    })?;
+    if rows.is_empty() {
+        return Err(anyhow!(
+            "{} has no rows - expected a header 'id,amount_usdc,period_days' followed by one row per terms",
+            request.csv_path.display()
+        ));
+    }
+    let periods: BTreeSet<u64> = rows.iter().map(|row| row.terms.period_secs).collect();
+    periods.into_iter().for_each(warn_calendar_drift);
+
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    info!("Using authority: {}", authority.pubkey());
+    verify_payee_authority(tally_client, &request.payee, &authority.pubkey())?;
+
+    let existing: HashMap<String, CurrentTerms> = tally_client
+        .list_payment_terms(&request.payee)
+        .context("Failed to fetch payment terms - check RPC connection and payee account state")?
+        .into_iter()
+        .map(|(address, terms)| {
+            let current = CurrentTerms {
+                address,
+                id: terms.terms_id_str(),
+                amount_usdc: terms.amount_usdc,
+                period_secs: terms.period_secs,
+                active: terms.active,
+            };
+            (current.id.clone(), current)
+        })
+        .collect();
+    let (pending, skipped) = split_existing(rows, &existing, request.resume)?;
+
+    let skipped: Vec<SkippedTerms> = skipped
+        .iter()
+        .map(|(row, existing)| SkippedTerms::new(row, existing))
+        .collect();
+    for skipped in &skipped {
+        if let Some(mismatch) = skipped.mismatch() {
+            eprintln!(
+                "{} line {}: '{}' already exists, but its {mismatch} - --resume leaves it unchanged",
+                Theme::warning("⚠"),
+                skipped.terms.line,
+                skipped.terms.terms_id
+            );
+        }
+    }
+    let report = |outcomes, failed| TermsImport {
+        payee: request.payee.to_string(),
+        skipped: skipped.clone(),
+        outcomes,
+        failed,
+    };
+    if pending.is_empty() {
+        return Ok(report(Vec::new(), Vec::new()));
+    }
+
+    if !request.tx_mode.is_send() {
+        // One transaction per row, exactly as they would be sent
+        let plans = pending
+            .iter()
+            .map(|row| {
+                let instructions = tally_client
+                    .create_payment_terms_instructions(
+                        &authority.pubkey(),
+                        row.terms.create_args()?,
+                    )
+                    .map_err(|e| {
+                        anyhow!(
+                            "Line {}: failed to build payment terms '{}' transaction: {e}",
+                            row.line,
+                            row.terms.id
+                        )
+                    })?;
+                Ok(TxPlan::new(
+                    format!("Create payment terms '{}'", row.terms.id),
+                    authority.pubkey(),
+                    instructions,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let outcomes = transaction::simulate_or_export_each(
+            tally_client,
+            &plans,
+            request.tx_mode,
+            &authority,
+        )?;
+        return Ok(report(outcomes, Vec::new()));
+    }
+
+    if !request.skip_confirmation {
+        eprint!("{}", format_skipped(&skipped)?);
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Create {} payment terms for payee {}?",
+                pending.len(),
+                request.payee
+            ))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Import canceled by user"));
+        }
+    }
+
+    // Keep going after a failure so one bad row does not block the rest
+    let bar = progress::create_progress_bar(pending.len() as u64, "Creating payment terms");
+    let mut created = Vec::new();
+    let mut failed = Vec::new();
+    for row in &pending {
+        bar.set_message(format!("Creating '{}'", row.terms.id));
+        let result = row
+            .terms
+            .create_args()
+            .and_then(|args| create_terms(tally_client, &authority, args));
+        match result {
+            Ok((address, signature)) => {
+                info!("Created '{}': {signature}", row.terms.id);
+                created.push((row, address));
+            }
+            Err(e) => failed.push(RowError {
+                line: row.line,
+                message: format!("'{}': {e:#}", row.terms.id),
+            }),
+        }
+        bar.inc(1);
+    }
+    if failed.is_empty() {
+        progress::finish_progress_success(&bar, "Import complete");
+    } else {
+        progress::finish_progress_error(&bar, "Import finished with errors");
+    }
+
+    // External signers send through a plan, which does not report the address
+    let listed: HashMap<String, Pubkey> = if created.iter().any(|(_, address)| address.is_none()) {
+        tally_client
+            .list_payment_terms(&request.payee)
+            .map(|terms| {
+                terms
+                    .into_iter()
+                    .map(|(address, terms)| (terms.terms_id_str(), address))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        HashMap::new()
+    };
+    let created = TermsCreated {
+        created: created
+            .iter()
+            .map(|(row, address)| {
+                let address = address.or_else(|| listed.get(&row.terms.id).copied());
+                ImportedTerms::new(row, address)
+            })
+            .collect(),
+    };
+
+    Ok(report(vec![TxOutcome::Sent(created)], failed))
+}
+
+/// Create one row's payment terms, returning their address if known and the signature
+fn create_terms(
+    tally_client: &SimpleTallyClient,
+    authority: &TxSigner,
+    args: CreatePaymentTermsArgs,
+) -> Result<(Option<Pubkey>, String)> {
+    if let TxSigner::External(signer) = authority {
+        let plan = TxPlan::new(
+            format!("Create payment terms '{}'", args.terms_id),
+            signer.pubkey(),
+            tally_client
+                .create_payment_terms_instructions(&signer.pubkey(), args)
+                .map_err(|e| anyhow!("{e}"))?,
+        );
+        let signature = transaction::send(tally_client, &plan, signer.as_ref())?;
+        return Ok((None, signature.to_string()));
+    }
+    let authority = authority
+        .keypair()
+        .ok_or_else(|| anyhow!("Creating payment terms requires the authority keypair"))?;
+    let (address, signature) = tally_client
+        .create_payment_terms(authority, args)
+        .map_err(|e| anyhow!("{e}"))?;
+    Ok((Some(address), signature))
+}
+
+/// Rows still to create, and rows skipped with their existing terms
+pub type SplitRows = (Vec<ImportRow>, Vec<(ImportRow, CurrentTerms)>);
+
+/// Separate rows whose terms already exist on-chain
+///
+/// # Errors
+/// Returns error listing the existing rows unless `resume` is set
+pub fn split_existing<S: BuildHasher>(
+    rows: Vec<ImportRow>,
+    existing: &HashMap<String, CurrentTerms, S>,
+    resume: bool,
+) -> Result<SplitRows> {
+    let mut pending = Vec::new();
+    let mut skipped = Vec::new();
+    for row in rows {
+        match existing.get(&row.terms.id) {
+            Some(current) => skipped.push((row, current.clone())),
+            None => pending.push(row),
+        }
+    }
+
+    if !resume && !skipped.is_empty() {
+        let errors: Vec<RowError> = skipped
+            .iter()
+            .map(|(row, current)| RowError {
+                line: row.line,
+                message: format!("'{}' already exists at {}", row.terms.id, current.address),
+            })
+            .collect();
+        return Err(anyhow!(
+            "{} rows already exist on-chain - nothing was sent:\n{}\n\n\
+             Pass --resume to skip existing terms and import the rest.",
+            errors.len(),
+            format_row_errors(&errors)
+        ));
+    }
+
+    Ok((pending, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::duration::SECONDS_PER_YEAR;
+
+    fn row(line: u64, id: &str) -> ImportRow {
+        ImportRow {
+            line,
+            terms: DesiredTerms::new(id, UsdcAmount::from_microlamports(10_000_000), 30 * 86_400)
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_parse_rows() {
+        let csv = "id,amount_usdc,period_days,period_months,period\n\
+                   basic, 9.99, 30,,\n\
+                   annual,99,,12,\n\
+                   enterprise,\"$1,250.50\",30,,\n\
+                   weekly,2,,,1w\n";
+        let rows = parse_rows(csv.as_bytes()).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].terms.amount_usdc, 9_990_000);
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].terms.period_secs, SECONDS_PER_YEAR);
+        assert_eq!(rows[2].terms.amount_usdc, 1_250_500_000);
+        assert_eq!(rows[3].terms.period_secs, 7 * 86_400);
+    }
+
+    #[test]
+    fn test_parse_rows_without_months_column() {
+        let rows = parse_rows("id,amount_usdc,period_days\nbasic,5,7\n".as_bytes()).unwrap();
+        assert_eq!(rows[0].terms.period_secs, 7 * 86_400);
+    }
+
+    #[test]
+    fn test_parse_rows_reports_every_bad_line() {
+        let csv = "id,amount_usdc,period_days\n\
+                   ok,5,30\n\
+                   free,0,30\n\
+                   weekly,5,0\n\
+                   price,abc,30\n\
+                   ok,6,30\n";
+        let errors = parse_rows(csv.as_bytes()).unwrap_err();
+        let lines: Vec<u64> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4, 5, 6]);
+        assert!(errors[0].message.contains("greater than 0"));
+        assert!(errors[1].message.contains("at least 1 day"));
+        assert!(errors[2].message.contains("Invalid amount"));
+        assert!(errors[3].message.contains("already used on line 2"));
+    }
+
+    #[test]
+    fn test_parse_rows_rejects_sub_micro_amount() {
+        let errors =
+            parse_rows("id,amount_usdc,period_days\ntiny,0.0000001,30\n".as_bytes()).unwrap_err();
+        assert!(errors[0].message.contains("more than 6 decimal places"));
+    }
+
+    fn current(id: &str, amount_usdc: u64, period_secs: u64) -> CurrentTerms {
+        CurrentTerms {
+            address: Pubkey::new_unique(),
+            id: id.to_string(),
+            amount_usdc,
+            period_secs,
+            active: true,
+        }
+    }
+
+    #[test]
+    fn test_split_existing_requires_resume() {
+        let existing = HashMap::from([(
+            "basic".to_string(),
+            current("basic", 10_000_000, 30 * 86_400),
+        )]);
+        let rows = vec![row(2, "basic"), row(3, "pro")];
+
+        let err = split_existing(rows.clone(), &existing, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 2"));
+        assert!(err.contains("--resume"));
+
+        let (pending, skipped) = split_existing(rows, &existing, true).unwrap();
+        assert_eq!(pending, vec![row(3, "pro")]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0.line, 2);
+    }
+    #[test]
+    fn test_skipped_terms_report_mismatch() {
+        let same = SkippedTerms::new(&row(2, "basic"), &current("basic", 10_000_000, 30 * 86_400));
+        assert_eq!(same.mismatch(), None);
+
+        let changed = SkippedTerms::new(&row(3, "pro"), &current("pro", 5_000_000, 30 * 86_400));
+        assert_eq!(changed.existing_amount_microlamports, Some(5_000_000));
+        assert_eq!(changed.existing_period_seconds, None);
+        let mismatch = changed.mismatch().unwrap();
+        assert!(mismatch.contains("5.000000 USDC on-chain"));
+        assert!(mismatch.contains("10.000000 USDC in the CSV"));
+    }
+
+    #[test]
+    fn test_partial_failure_is_reported_as_unsuccessful() {
+        let report = TermsImport {
+            payee: Pubkey::new_unique().to_string(),
+            skipped: Vec::new(),
+            outcomes: vec![TxOutcome::Sent(TermsCreated {
+                created: vec![ImportedTerms::new(&row(2, "basic"), None)],
+            })],
+            failed: vec![RowError {
+                line: 3,
+                message: "'pro': simulation failed".to_string(),
+            }],
+        };
+        assert!(!report.success());
+        let human = report.human().unwrap();
+        assert!(human.contains("1 created, 0 skipped, 1 failed"));
+        assert!(human.contains("line 3: 'pro': simulation failed"));
+    }
+}
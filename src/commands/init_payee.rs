@@ -3,17 +3,137 @@
 use crate::config::TallyCliConfig;
 use crate::config_file::ConfigFile;
 use crate::errors::enhance_payee_init_error;
+use crate::output::CommandOutput;
 use crate::utils::colors::Theme;
-use anyhow::{anyhow, Result};
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::fmt::Write as _;
 use std::str::FromStr;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
-use tally_sdk::solana_sdk::signature::Signer;
-use tally_sdk::{get_usdc_mint, load_keypair, SimpleTallyClient};
+use tally_sdk::{get_usdc_mint, pda_v2, SimpleTallyClient};
 use tracing::info;
 
+/// A payee initialized by `payee init`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PayeeInitialized {
+    pub payee: String,
+    pub authority: String,
+    pub treasury: String,
+    pub usdc_mint: String,
+    /// Whether the treasury token account was created by this transaction
+    pub treasury_created: bool,
+    pub signature: String,
+    /// Profile the payee address was saved to
+    pub saved_to_profile: Option<String>,
+    /// Why the payee address could not be saved to the config file
+    pub save_error: Option<String>,
+}
+
+impl CommandOutput for PayeeInitialized {
+    const KIND: &'static str = "payee.init";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Payee initialization successful!")
+        )?;
+
+        // Show ATA creation status
+        if self.treasury_created {
+            writeln!(
+                &mut output,
+                "{}",
+                Theme::info("Treasury ATA created and payee initialized")
+            )?;
+        } else {
+            writeln!(
+                &mut output,
+                "{}",
+                Theme::info("Payee initialized with existing treasury ATA")
+            )?;
+        }
+
+        writeln!(&mut output)?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payee PDA:"),
+            Theme::highlight(&self.payee)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            Theme::dim(&self.signature)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Authority:"),
+            Theme::value(&self.authority)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Treasury ATA:"),
+            Theme::value(&self.treasury)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {} (platform fee: 25 bps / 0.25%)",
+            Theme::info("Volume Tier:"),
+            Theme::active("Standard")
+        )?;
+
+        // Config save message
+        if let Some(profile_name) = &self.saved_to_profile {
+            writeln!(&mut output)?;
+            writeln!(
+                &mut output,
+                "{} Payee PDA saved to config (profile: {})",
+                Theme::success("✓"),
+                Theme::value(profile_name)
+            )?;
+        }
+        if let Some(error) = &self.save_error {
+            writeln!(&mut output)?;
+            writeln!(
+                &mut output,
+                "{} Could not save payee PDA to config: {}",
+                Theme::warning("⚠"),
+                Theme::dim(error)
+            )?;
+        }
+
+        // Note about volume tiers
+        writeln!(&mut output)?;
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::dim("Note: All payees start at Standard tier (0.25% platform fee).")
+        )?;
+        write!(
+            &mut output,
+            "{}",
+            Theme::dim(
+                "Volume tiers auto-upgrade based on monthly payment volume \
+                 (Growth: 0.20%, Scale: 0.15%)."
+            )
+        )?;
+
+        Ok(output)
+    }
+}
+
 /// Execute the init payee command
 ///
+/// Unless `tx_mode` is [`TxMode::Send`] the transaction is simulated or
+/// exported, and nothing is saved to the config file.
+///
 /// # Errors
 /// Returns error if payee initialization fails due to invalid parameters, network issues, or Solana program errors
 pub async fn execute(
@@ -21,13 +141,15 @@ pub async fn execute(
     authority_path: Option<&str>,
     treasury_str: &str,
     usdc_mint_str: Option<&str>,
+    tx_mode: &TxMode,
     _config: &TallyCliConfig,
-) -> Result<String> {
+) -> Result<TxOutcome<PayeeInitialized>> {
     info!("Starting payee initialization");
 
     // Load authority keypair
-    let authority = load_keypair(authority_path)?;
-    info!("Using authority: {}", authority.pubkey());
+    let authority = TxSigner::load(authority_path, tx_mode)?;
+    let authority_pubkey = authority.pubkey();
+    info!("Using authority: {}", authority_pubkey);
 
     // Parse USDC mint
     let usdc_mint =
@@ -39,107 +161,59 @@ pub async fn execute(
         .map_err(|e| anyhow!("Invalid treasury ATA address '{treasury_str}': {e}"))?;
     info!("Using treasury ATA: {}", treasury_ata);
 
-    // Use the new unified method that handles both ATA existence scenarios
-    // Volume tier is automatically set to Standard by the program
-    let (payee_pda, signature, created_ata) = tally_client
-        .init_payee_with_treasury(&authority, &treasury_ata, &usdc_mint)
-        .map_err(|e| enhance_payee_init_error(&e, &authority.pubkey(), &treasury_ata))?;
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .init_payee_with_treasury_instructions(&authority_pubkey, &treasury_ata, &usdc_mint)
+            .map_err(|e| anyhow!("Failed to build payee initialization transaction: {e}"))?;
+        Ok(TxPlan::new(
+            "Initialize payee",
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !tx_mode.is_send() {
+        return transaction::simulate_or_export(tally_client, &plan()?, tx_mode, &authority);
+    }
+
+    let (payee_pda, signature, created_ata) = if let TxSigner::External(signer) = &authority {
+        let treasury_existed = tally_client
+            .account_exists(&treasury_ata)
+            .context("Failed to check treasury account - check RPC connection")?;
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        let payee_pda: Pubkey = pda_v2::payee(&authority_pubkey)?.into();
+        (payee_pda, signature.to_string(), !treasury_existed)
+    } else {
+        let authority = authority.into_keypair()?;
+
+        // Use the new unified method that handles both ATA existence scenarios
+        // Volume tier is automatically set to Standard by the program
+        tally_client
+            .init_payee_with_treasury(&authority, &treasury_ata, &usdc_mint)
+            .map_err(|e| {
+                let logs = transaction::failure_logs(tally_client, plan, &e.to_string());
+                enhance_payee_init_error(&e, &authority_pubkey, &treasury_ata, &logs)
+            })?
+    };
 
     info!(
         "Transaction confirmed: {}, created_ata: {}",
-        signature.as_str(), created_ata
+        signature.as_str(),
+        created_ata
     );
 
     // Save payee PDA to config file for future use
     let config_save_result = save_payee_to_config(&payee_pda);
 
-    // Build colored output
-    let mut output = String::new();
-    writeln!(
-        &mut output,
-        "{}",
-        Theme::success("Payee initialization successful!")
-    )?;
-
-    // Show ATA creation status
-    if created_ata {
-        writeln!(
-            &mut output,
-            "{}",
-            Theme::info("Treasury ATA created and payee initialized")
-        )?;
-    } else {
-        writeln!(
-            &mut output,
-            "{}",
-            Theme::info("Payee initialized with existing treasury ATA")
-        )?;
-    }
-
-    writeln!(&mut output)?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Payee PDA:"),
-        Theme::highlight(&payee_pda.to_string())
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Transaction signature:"),
-        Theme::dim(&signature)
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Authority:"),
-        Theme::value(&authority.pubkey().to_string())
-    )?;
-    writeln!(
-        &mut output,
-        "{} {}",
-        Theme::info("Treasury ATA:"),
-        Theme::value(&treasury_ata.to_string())
-    )?;
-    writeln!(
-        &mut output,
-        "{} {} (platform fee: 25 bps / 0.25%)",
-        Theme::info("Volume Tier:"),
-        Theme::active("Standard")
-    )?;
-
-    // Config save message
-    match config_save_result {
-        Ok(profile_name) => {
-            writeln!(&mut output)?;
-            writeln!(
-                &mut output,
-                "{} Payee PDA saved to config (profile: {})",
-                Theme::success("✓"),
-                Theme::value(&profile_name)
-            )?;
-        }
-        Err(e) => {
-            writeln!(&mut output)?;
-            writeln!(
-                &mut output,
-                "{} Could not save payee PDA to config: {}",
-                Theme::warning("⚠"),
-                Theme::dim(&e.to_string())
-            )?;
-        }
-    }
-
-    // Note about volume tiers
-    writeln!(&mut output)?;
-    writeln!(
-        &mut output,
-        "{}",
-        Theme::dim("Note: All payees start at Standard tier (0.25% platform fee).")
-    )?;
-    write!(&mut output, "{}", Theme::dim("Volume tiers auto-upgrade based on monthly payment volume (Growth: 0.20%, Scale: 0.15%)."))?;
-
-    Ok(output)
+    Ok(TxOutcome::Sent(PayeeInitialized {
+        payee: payee_pda.to_string(),
+        authority: authority_pubkey.to_string(),
+        treasury: treasury_ata.to_string(),
+        usdc_mint: usdc_mint.to_string(),
+        treasury_created: created_ata,
+        signature,
+        saved_to_profile: config_save_result.as_ref().ok().cloned(),
+        save_error: config_save_result.err().map(|e| e.to_string()),
+    }))
 }
 
 /// Save payee PDA to config file
@@ -1,53 +1,134 @@
 //! Interactive initialization wizard for first-time setup
 //!
 //! Provides a guided setup experience for new merchants, including:
-//! - Interactive wallet selection with balance display
+//! - Interactive wallet selection with balance display, or creation of an
+//!   encrypted keystore wallet when none is found
 //! - Pre-flight checks (RPC connectivity, SOL balance)
 //! - Interactive prompts for treasury and fee setup
 //! - Payee initialization
 //! - Optional first plan creation
 
 use crate::config::TallyCliConfig;
+use crate::config_file::ConfigFile;
 use crate::errors::enhance_payee_init_error;
-use crate::utils::formatting::detect_network;
+use crate::keystore::{read_passphrase, validate_name, KdfParams, Keystore};
+use crate::output::CommandOutput;
+use crate::utils::formatting::{detect_network, lamports_to_sol};
 use crate::utils::progress;
+use crate::utils::signer::{load_signer_keypair, signer_exists};
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan};
 use anyhow::{anyhow, Context, Result};
 use dialoguer::{Confirm, Input, Select};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
 use std::str::FromStr;
 use tally_sdk::solana_sdk::commitment_config::CommitmentConfig;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
 use tally_sdk::solana_sdk::signature::{Keypair, Signer};
-use tally_sdk::{get_usdc_mint, load_keypair, SimpleTallyClient};
+use tally_sdk::{get_usdc_mint, SimpleTallyClient};
 
 /// Minimum SOL balance required for merchant initialization (0.01 SOL for rent + fees)
-const MIN_SOL_BALANCE_LAMPORTS: u64 = 10_000_000; // 0.01 SOL in lamports
+pub const MIN_SOL_BALANCE_LAMPORTS: u64 = 10_000_000; // 0.01 SOL in lamports
+
+/// A payee set up by the wizard
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SetupComplete {
+    pub payee: String,
+    pub authority: String,
+    pub treasury: String,
+    /// Whether the wizard created the treasury ATA
+    pub treasury_created: bool,
+    pub signature: String,
+    /// Whether the user asked how to create a first plan
+    #[serde(skip)]
+    show_plan_guide: bool,
+}
 
-/// Lamports per SOL
-const LAMPORTS_PER_SOL_U64: u64 = 1_000_000_000;
+impl CommandOutput for SetupComplete {
+    const KIND: &'static str = "init";
 
-/// Convert lamports to SOL for display purposes
-///
-/// Uses integer arithmetic to avoid f64 precision loss.
-/// Returns formatted string with 9 decimal places.
-fn lamports_to_sol(lamports: u64) -> String {
-    let whole = lamports / LAMPORTS_PER_SOL_U64;
-    let fractional = lamports % LAMPORTS_PER_SOL_U64;
-    format!("{whole}.{fractional:09}")
+    fn human(&self) -> Result<String> {
+        let ata_message = if self.treasury_created {
+            "Treasury ATA created"
+        } else {
+            "Using existing treasury ATA"
+        };
+
+        let mut output = format!(
+            "Merchant Setup Complete!\n\
+             ═══════════════════════════════════════════════════\n\
+             \n\
+             Payee PDA:       {}\n\
+             Authority:          {}\n\
+             Treasury ATA:       {}\n\
+             Tier:               Free (platform fee: 200 bps / 2.0%)\n\
+             Transaction:        {}\n\
+             Status:             {}\n\
+             \n\
+             ✓ Payee PDA saved to config file\n\
+             \n\
+             Note: New merchants start on the Free tier.\n\
+             Contact the platform authority to upgrade tiers.\n\
+             \n\
+             Next Steps:\n\
+             • Create your first subscription plan\n\
+             • Monitor with: tally-merchant dashboard overview\n\
+             • View plans with: tally-merchant plan list\n",
+            self.payee, self.authority, self.treasury, self.signature, ata_message
+        );
+
+        if self.show_plan_guide {
+            output.push_str("\n\nTo create a plan, run:\n\n");
+            write!(
+                output,
+                "  tally-merchant plan create \\\n\
+                 --merchant {} \\\n\
+                 --id premium \\\n\
+                 --name \"Premium Plan\" \\\n\
+                 --price-usdc 10.0 \\\n\
+                 --period-days 30\n",
+                self.payee
+            )?;
+            output.push_str(
+                "\nOr use interactive mode:\n\n  tally-merchant plan create --merchant <merchant> --interactive\n",
+            );
+        }
+
+        output.push_str("\n💡 Run 'tally-merchant --help' to see all available commands.\n");
+        Ok(output)
+    }
 }
 
 /// Execute the interactive initialization wizard
 ///
+/// With `--dry-run` the wizard stops after simulating the payee initialization.
+/// Offline export is not supported because the wizard needs the wallet up front.
+///
 /// # Errors
 /// Returns error if any step fails (wallet selection, RPC connectivity, merchant initialization)
 pub async fn execute(
     tally_client: &SimpleTallyClient,
     _config: &TallyCliConfig,
     skip_plan: bool,
-) -> Result<String> {
+    usdc_mint: Option<&str>,
+    wallet_path: &str,
+    tx_mode: &TxMode,
+) -> Result<TxOutcome<SetupComplete>> {
+    if matches!(tx_mode, TxMode::Export(_)) {
+        return Err(anyhow!(
+            "The init wizard cannot export transactions.\n\
+             Use 'tally-merchant payee init --export-tx <FILE>' for offline signing."
+        ));
+    }
+
+    let usdc_mint =
+        get_usdc_mint(usdc_mint).map_err(|e| anyhow!("Failed to parse USDC mint: {e}"))?;
+
     println!("\n🚀 Welcome to Tally! Let's set up your payee account.\n");
 
     // Step 1: Wallet selection (with info display and progressive disclosure)
-    let wallet = prompt_wallet_selection(tally_client)?;
+    let wallet = prompt_wallet_selection(tally_client, wallet_path)?;
 
     // Step 2: Pre-flight checks with selected wallet
     println!("\nRunning pre-flight checks...\n");
@@ -78,20 +159,38 @@ pub async fn execute(
     println!("\n✅ All pre-flight checks passed!\n");
 
     // Step 3: Treasury setup
-    let treasury_ata = prompt_treasury_setup(tally_client, &wallet)?;
+    let treasury_ata = prompt_treasury_setup(tally_client, &wallet, &usdc_mint)?;
 
     // Step 4: Initialize merchant
     println!("\nInitializing payee account...");
     println!("   • Your merchant will be created on the Free tier (2.0% platform fee)");
     println!("   • Contact platform authority to upgrade to Pro (1.5%) or Enterprise (1.0%)\n");
 
-    let usdc_mint = get_usdc_mint(None)?;
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .init_payee_with_treasury_instructions(&wallet.pubkey(), &treasury_ata, &usdc_mint)
+            .map_err(|e| anyhow!("Failed to build payee initialization transaction: {e}"))?;
+        Ok(TxPlan::new(
+            "Initialize payee",
+            wallet.pubkey(),
+            instructions,
+        ))
+    };
+    if !tx_mode.is_send() {
+        return Ok(TxOutcome::Simulated(transaction::dry_run(
+            tally_client,
+            &plan()?,
+        )?));
+    }
 
     // Use progress spinner for transaction
     let spinner = progress::create_spinner("Submitting merchant initialization transaction...");
     let result = tally_client
-        .init_payee_with_treasury(&wallet, &usdc_mint, &treasury_ata)
-        .map_err(|e| enhance_payee_init_error(&e, &wallet.pubkey(), &treasury_ata));
+        .init_payee_with_treasury(&wallet, &treasury_ata, &usdc_mint)
+        .map_err(|e| {
+            let logs = transaction::failure_logs(tally_client, plan, &e.to_string());
+            enhance_payee_init_error(&e, &wallet.pubkey(), &treasury_ata, &logs)
+        });
 
     match &result {
         Ok(_) => progress::finish_progress_success(&spinner, "Merchant account created"),
@@ -101,71 +200,26 @@ pub async fn execute(
     let (merchant_pda, signature, created_ata) = result?;
     println!();
 
-    // Step 5: Display results
-    let ata_message = if created_ata {
-        "Treasury ATA created"
+    // Step 5: Optional plan creation guidance
+    let show_plan_guide = if skip_plan {
+        false
     } else {
-        "Using existing treasury ATA"
-    };
-
-    let mut output = format!(
-        "Merchant Setup Complete!\n\
-         ═══════════════════════════════════════════════════\n\
-         \n\
-         Payee PDA:       {}\n\
-         Authority:          {}\n\
-         Treasury ATA:       {}\n\
-         Tier:               Free (platform fee: 200 bps / 2.0%)\n\
-         Transaction:        {}\n\
-         Status:             {}\n\
-         \n\
-         ✓ Payee PDA saved to config file\n\
-         \n\
-         Note: New merchants start on the Free tier.\n\
-         Contact the platform authority to upgrade tiers.\n\
-         \n\
-         Next Steps:\n\
-         • Create your first subscription plan\n\
-         • Monitor with: tally-merchant dashboard overview\n\
-         • View plans with: tally-merchant plan list\n",
-        merchant_pda,
-        wallet.pubkey(),
-        treasury_ata,
-        signature,
-        ata_message
-    );
-
-    // Step 7: Optional plan creation guidance
-    if !skip_plan {
         println!();
-        let create_plan = Confirm::new()
+        Confirm::new()
             .with_prompt("Would you like to create your first subscription plan now?")
             .default(true)
             .interact()
-            .context("Failed to read user input")?;
-
-        if create_plan {
-            use std::fmt::Write;
-            output.push_str("\n\nTo create a plan, run:\n\n");
-            write!(
-                output,
-                "  tally-merchant plan create \\\n\
-                 --merchant {merchant_pda} \\\n\
-                 --id premium \\\n\
-                 --name \"Premium Plan\" \\\n\
-                 --price-usdc 10.0 \\\n\
-                 --period-days 30\n"
-            )
-            .expect("Writing to String should not fail");
-            output.push_str(
-                "\nOr use interactive mode:\n\n  tally-merchant plan create --merchant <merchant> --interactive\n",
-            );
-        }
-    }
-
-    output.push_str("\n💡 Run 'tally-merchant --help' to see all available commands.\n");
+            .context("Failed to read user input")?
+    };
 
-    Ok(output)
+    Ok(TxOutcome::Sent(SetupComplete {
+        payee: merchant_pda.to_string(),
+        authority: wallet.pubkey().to_string(),
+        treasury: treasury_ata.to_string(),
+        treasury_created: created_ata,
+        signature,
+        show_plan_guide,
+    }))
 }
 
 /// Prompt for wallet selection with info display and progressive disclosure
@@ -175,7 +229,7 @@ pub async fn execute(
 ///
 /// # Errors
 /// Returns error if wallet cannot be loaded or RPC calls fail
-fn prompt_wallet_selection(tally_client: &SimpleTallyClient) -> Result<Keypair> {
+fn prompt_wallet_selection(tally_client: &SimpleTallyClient, wallet_path: &str) -> Result<Keypair> {
     println!("Wallet Setup");
     println!("──────────────────────────────────────────────────");
     println!(
@@ -185,10 +239,17 @@ fn prompt_wallet_selection(tally_client: &SimpleTallyClient) -> Result<Keypair>
          • Withdraw merchant fees\n"
     );
 
-    // Try to load default wallet
-    let default_path = "~/.config/solana/id.json";
-
-    if let Ok(wallet) = load_keypair(None) {
+    // Offer a new wallet only when none exists; any other load failure (a
+    // wrong passphrase, a corrupt file, a bad URI) is reported as is
+    let wallet = if signer_exists(wallet_path)? {
+        Some(
+            load_signer_keypair(wallet_path)
+                .with_context(|| format!("Failed to load wallet {wallet_path}"))?,
+        )
+    } else {
+        None
+    };
+    if let Some(wallet) = wallet {
         // Get balance for display
         let balance = tally_client
             .rpc_client
@@ -198,7 +259,7 @@ fn prompt_wallet_selection(tally_client: &SimpleTallyClient) -> Result<Keypair>
         let balance_sol = lamports_to_sol(balance);
 
         // Show wallet info
-        println!("Found wallet: {default_path}");
+        println!("Found wallet: {wallet_path}");
         println!("Address: {}", wallet.pubkey());
         println!("Balance: {balance_sol} SOL\n");
 
@@ -216,10 +277,60 @@ fn prompt_wallet_selection(tally_client: &SimpleTallyClient) -> Result<Keypair>
             prompt_custom_wallet_path()
         }
     } else {
-        // No default wallet found
-        println!("⚠️  No default Solana wallet found at {default_path}\n");
-        prompt_custom_wallet_path()
+        // No default wallet found - offer to create one in the keystore
+        println!("⚠️  No Solana wallet found at {wallet_path}\n");
+        let choices = [
+            "Create a new encrypted keystore wallet",
+            "Enter the path to an existing keypair",
+        ];
+        let selection = Select::new()
+            .with_prompt("How would you like to continue?")
+            .items(choices)
+            .default(0)
+            .interact()
+            .context("Failed to read user input")?;
+
+        if selection == 0 {
+            create_keystore_wallet()
+        } else {
+            prompt_custom_wallet_path()
+        }
+    }
+}
+
+/// Create a keystore wallet and make it the active profile's wallet
+///
+/// # Errors
+/// Returns error if user input fails or the wallet cannot be written
+fn create_keystore_wallet() -> Result<Keypair> {
+    let keystore = Keystore::open_default()?;
+    let name: String = Input::new()
+        .with_prompt("Wallet name")
+        .default("merchant".to_string())
+        .validate_with(|name: &String| validate_name(name).map_err(|e| e.to_string()))
+        .interact_text()
+        .context("Failed to read user input")?;
+    let passphrase = read_passphrase(&format!("Passphrase for new wallet '{name}'"), true)?;
+
+    let wallet = Keypair::new();
+    let path = keystore.create(&name, &wallet, &passphrase, KdfParams::default())?;
+    println!("✓ Wallet created: {}", path.display());
+    println!("   Address: {}", wallet.pubkey());
+
+    // Point the profile at the new wallet so later commands sign with it
+    let uri = format!("keystore:{name}");
+    let saved = ConfigFile::load().and_then(|mut config_file| {
+        config_file.set_profile_value("wallet-path", uri.clone())?;
+        config_file.save()
+    });
+    match saved {
+        Ok(()) => println!("   Saved as the active profile's wallet-path ({uri})\n"),
+        Err(e) => println!(
+            "⚠️  Could not save wallet-path: {e}\n   \
+             Run: tally-merchant config set wallet-path {uri}\n"
+        ),
     }
+    Ok(wallet)
 }
 
 /// Prompt for custom wallet path with validation loop
@@ -233,10 +344,7 @@ fn prompt_custom_wallet_path() -> Result<Keypair> {
             .interact_text()
             .context("Failed to read user input")?;
 
-        // Expand tilde to home directory if needed
-        let expanded_path = expand_tilde(&path);
-
-        match load_keypair(Some(&expanded_path)) {
+        match load_signer_keypair(&path) {
             Ok(wallet) => {
                 println!("✓ Wallet loaded successfully");
                 println!("   Address: {}\n", wallet.pubkey());
@@ -250,18 +358,6 @@ fn prompt_custom_wallet_path() -> Result<Keypair> {
     }
 }
 
-/// Expand tilde (~) to home directory path
-///
-/// Converts paths like `~/foo` to `/home/user/foo`
-fn expand_tilde(path: &str) -> String {
-    if path.starts_with("~/") {
-        if let Some(home) = dirs::home_dir() {
-            return path.replacen('~', &home.to_string_lossy(), 1);
-        }
-    }
-    path.to_string()
-}
-
 /// Handle insufficient balance with recovery options
 ///
 /// Displays error message and offers actionable choices to the user.
@@ -327,7 +423,11 @@ fn handle_insufficient_balance(balance_lamports: u64) -> Result<()> {
 ///
 /// # Errors
 /// Returns error if user input fails or pubkey parsing fails
-fn prompt_treasury_setup(tally_client: &SimpleTallyClient, wallet: &Keypair) -> Result<Pubkey> {
+fn prompt_treasury_setup(
+    tally_client: &SimpleTallyClient,
+    wallet: &Keypair,
+    usdc_mint: &Pubkey,
+) -> Result<Pubkey> {
     println!("Treasury Setup");
     println!("──────────────────────────────────────────────────");
     println!(
@@ -361,9 +461,8 @@ fn prompt_treasury_setup(tally_client: &SimpleTallyClient, wallet: &Keypair) ->
         Ok(treasury)
     } else {
         // Calculate the default ATA address upfront
-        let usdc_mint = get_usdc_mint(None)?;
         let default_ata =
-            tally_sdk::ata::get_associated_token_address_for_mint(&wallet.pubkey(), &usdc_mint)?;
+            tally_sdk::ata::get_associated_token_address_for_mint(&wallet.pubkey(), usdc_mint)?;
 
         // Display the default ATA that will be created
         println!("\n💡 The CLI will automatically create a USDC treasury (ATA) for you.\n");
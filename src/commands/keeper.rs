@@ -0,0 +1,576 @@
+//! Keeper mode: execute payments for agreements that are due
+//!
+//! Scans the live agreements of a payee, submits `execute_payment` for every
+//! active agreement whose `next_payment_ts` has passed and earns the
+//! configured keeper fee. Runs until Ctrl-C unless `--once` is given.
+
+use crate::config::TallyCliConfig;
+use crate::context::SOLANA_CLI_DEFAULT_WALLET;
+use crate::errors::{config_account_not_found, program_paused, ErrorDetails};
+use crate::output::{serialize_display, CommandOutput};
+use crate::utils::colors::Theme;
+use crate::utils::formatting::format_timestamp;
+use crate::utils::signer::load_signer_keypair;
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::{Keypair, Signer};
+use tally_sdk::{pda_v2, DashboardAgreement, DashboardClient, SimpleTallyClient, UsdcAmount};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+/// Exit status after a second Ctrl-C (128 + SIGINT, as shells report it)
+const FORCED_SHUTDOWN_EXIT_CODE: i32 = 130;
+
+/// Request to run the keeper for a payee
+pub struct KeeperRunRequest<'a> {
+    /// Payee (merchant) whose agreements are scanned
+    pub payee: Pubkey,
+    /// RPC URL used for the dashboard scan
+    pub rpc_url: &'a str,
+    /// Keeper keypair path or URI, from `--keeper` or else
+    /// [`RuntimeContext::signer_path`](crate::context::RuntimeContext::signer_path);
+    /// the Solana CLI keypair when unset
+    pub keeper_path: Option<&'a str>,
+    /// Run a single scan and exit
+    pub once: bool,
+    /// List due payments without submitting transactions
+    pub dry_run: bool,
+    /// Maximum number of payments submitted in parallel
+    pub concurrency: usize,
+    /// Delay between scans
+    pub interval: Duration,
+}
+
+/// An agreement whose next payment is due
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct DuePayment {
+    /// Payment agreement PDA
+    #[serde(serialize_with = "serialize_display")]
+    #[schemars(with = "String")]
+    pub agreement: Pubkey,
+    /// Payer of the agreement
+    #[serde(serialize_with = "serialize_display")]
+    #[schemars(with = "String")]
+    pub payer: Pubkey,
+    /// Payment terms the agreement follows
+    #[serde(serialize_with = "serialize_display")]
+    #[schemars(with = "String")]
+    pub payment_terms: Pubkey,
+    /// Amount charged per period in micro-units
+    pub amount: u64,
+    /// When the payment became due
+    pub next_payment_ts: i64,
+}
+
+/// Totals accumulated across keeper cycles
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct KeeperStats {
+    pub cycles: u64,
+    pub executed: u64,
+    pub failed: u64,
+    /// Scans that could not fetch the payee's agreements
+    pub scan_failures: u64,
+    /// Keeper fees earned in micro-units
+    pub fees_earned: u64,
+}
+
+/// Outcome of `keeper run`
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum KeeperReport {
+    /// Payments a `--dry-run` would submit
+    DryRun {
+        #[serde(serialize_with = "serialize_display")]
+        #[schemars(with = "String")]
+        payee: Pubkey,
+        fee_bps: u16,
+        due: Vec<DuePayment>,
+    },
+    /// Totals when the keeper stopped
+    Stopped(KeeperStats),
+}
+
+impl CommandOutput for KeeperReport {
+    const KIND: &'static str = "keeper.run";
+
+    fn human(&self) -> Result<String> {
+        Ok(match self {
+            Self::DryRun {
+                payee,
+                fee_bps,
+                due,
+            } => format_dry_run(payee, due, *fee_bps),
+            Self::Stopped(stats) => format_summary(stats),
+        })
+    }
+}
+
+/// Run the keeper
+///
+/// RPC calls block, so they run on tokio's blocking thread pool and the
+/// Ctrl-C handler stays responsive while a batch is in flight.
+///
+/// # Errors
+/// Returns error if the keeper keypair cannot be loaded, the config account
+/// cannot be fetched, the program is paused (except in dry-run mode), or (in
+/// `--once`/dry-run mode) the agreement scan fails
+pub async fn run(
+    tally_client: Arc<SimpleTallyClient>,
+    request: &KeeperRunRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<KeeperReport> {
+    if request.concurrency == 0 {
+        return Err(anyhow!("--concurrency must be at least 1"));
+    }
+
+    let keeper = Arc::new(
+        load_signer_keypair(request.keeper_path.unwrap_or(SOLANA_CLI_DEFAULT_WALLET))
+            .context("Failed to load keeper keypair")?,
+    );
+    let global_config = {
+        let tally_client = Arc::clone(&tally_client);
+        blocking(move || {
+            tally_client
+                .get_config()
+                .context("Failed to fetch config account - check RPC connection and account state")
+        })
+        .await?
+        .ok_or_else(config_account_not_found)?
+    };
+    let fee_bps = global_config.keeper_fee_bps;
+    if global_config.paused && !request.dry_run {
+        return Err(program_paused());
+    }
+    let dashboard_client = Arc::new(
+        DashboardClient::new(request.rpc_url).context("Failed to create dashboard client")?,
+    );
+
+    if request.dry_run {
+        let due = scan(&dashboard_client, request.payee).await?;
+        return Ok(KeeperReport::DryRun {
+            payee: request.payee,
+            fee_bps,
+            due,
+        });
+    }
+
+    info!(
+        "Keeper {} running for payee {} (fee {fee_bps} bps, concurrency {})",
+        keeper.pubkey(),
+        request.payee,
+        request.concurrency
+    );
+
+    // The first Ctrl-C lets the in-flight batch finish, the second exits at once
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let wake = Arc::new(Notify::new());
+    {
+        let shutdown = Arc::clone(&shutdown);
+        let wake = Arc::clone(&wake);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            eprintln!(
+                "{}",
+                Theme::warning("Shutting down after the current batch (Ctrl-C again to force)...")
+            );
+            shutdown.store(true, Ordering::SeqCst);
+            wake.notify_one();
+
+            if tokio::signal::ctrl_c().await.is_ok() {
+                eprintln!("{}", Theme::error("Forced shutdown"));
+                std::process::exit(FORCED_SHUTDOWN_EXIT_CODE);
+            }
+        });
+    }
+
+    let mut stats = KeeperStats::default();
+    loop {
+        stats.cycles += 1;
+        match scan(&dashboard_client, request.payee).await {
+            Ok(due) => {
+                info!("Cycle {}: {} payment(s) due", stats.cycles, due.len());
+                execute_due(
+                    &tally_client,
+                    &keeper,
+                    &due,
+                    fee_bps,
+                    request.concurrency,
+                    &shutdown,
+                    &mut stats,
+                )
+                .await;
+            }
+            Err(e) if request.once => return Err(e),
+            Err(e) => {
+                stats.scan_failures += 1;
+                warn!("Cycle {} scan failed: {e:#}", stats.cycles);
+                eprintln!(
+                    "{} Cycle {} scan failed: {e:#}",
+                    Theme::error("✗"),
+                    stats.cycles
+                );
+            }
+        }
+
+        if request.once || shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+
+        tokio::select! {
+            () = tokio::time::sleep(request.interval) => {}
+            () = wake.notified() => {}
+        }
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+    }
+
+    Ok(KeeperReport::Stopped(stats))
+}
+
+/// Run blocking RPC work on tokio's blocking thread pool
+async fn blocking<T, F>(work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .context("Keeper worker panicked")?
+}
+
+/// Fetch live agreements and select the ones that are due now
+async fn scan(dashboard_client: &Arc<DashboardClient>, payee: Pubkey) -> Result<Vec<DuePayment>> {
+    let dashboard_client = Arc::clone(dashboard_client);
+    let agreements = blocking(move || {
+        dashboard_client
+            .get_live_agreements(&payee)
+            .context("Failed to fetch payment agreements")
+    })
+    .await?;
+    due_payments(&agreements, current_timestamp())
+}
+
+/// Select active agreements whose next payment is due at `now`, oldest first
+///
+/// # Errors
+/// Returns error if an agreement PDA cannot be derived
+pub fn due_payments(agreements: &[DashboardAgreement], now: i64) -> Result<Vec<DuePayment>> {
+    let candidates = agreements
+        .iter()
+        .map(|a| {
+            let payment_terms = a.payment_agreement.payment_terms;
+            let payer = a.payment_agreement.payer;
+            let payment = DuePayment {
+                agreement: pda_v2::payment_agreement(&payment_terms, &payer)?.into(),
+                payer,
+                payment_terms,
+                amount: a.payment_terms.amount_usdc,
+                next_payment_ts: a.payment_agreement.next_payment_ts,
+            };
+            Ok((a.payment_agreement.active, payment))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(select_due(candidates, now))
+}
+
+/// Keep the active payments whose due time is at or before `now`, oldest first
+fn select_due(candidates: Vec<(bool, DuePayment)>, now: i64) -> Vec<DuePayment> {
+    let mut due: Vec<DuePayment> = candidates
+        .into_iter()
+        .filter(|(active, payment)| *active && payment.next_payment_ts <= now)
+        .map(|(_, payment)| payment)
+        .collect();
+    due.sort_by_key(|p| p.next_payment_ts);
+    due
+}
+
+/// Keeper fee earned for a payment of `amount` at `fee_bps`
+#[must_use]
+pub fn keeper_fee(amount: u64, fee_bps: u16) -> u64 {
+    let fee = u128::from(amount) * u128::from(fee_bps) / 10_000;
+    u64::try_from(fee).unwrap_or(u64::MAX)
+}
+
+/// Submit due payments in batches of at most `concurrency` parallel transactions
+///
+/// Each executed or failed payment is reported on stderr as progress; the
+/// totals end up in the returned [`KeeperReport`].
+async fn execute_due(
+    tally_client: &Arc<SimpleTallyClient>,
+    keeper: &Arc<Keypair>,
+    due: &[DuePayment],
+    fee_bps: u16,
+    concurrency: usize,
+    shutdown: &AtomicBool,
+    stats: &mut KeeperStats,
+) {
+    for batch in due.chunks(concurrency) {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let agreements: Vec<Pubkey> = batch.iter().map(|payment| payment.agreement).collect();
+        let results = {
+            let tally_client = Arc::clone(tally_client);
+            let keeper = Arc::clone(keeper);
+            blocking(move || Ok(execute_batch(&tally_client, &keeper, &agreements))).await
+        };
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => batch.iter().map(|_| Err(anyhow!("{e:#}"))).collect(),
+        };
+
+        for (payment, result) in batch.iter().zip(results) {
+            match result {
+                Ok(signature) => {
+                    let fee = keeper_fee(payment.amount, fee_bps);
+                    stats.executed += 1;
+                    stats.fees_earned = stats.fees_earned.saturating_add(fee);
+                    info!(
+                        "Executed payment for agreement {}: {signature}",
+                        payment.agreement
+                    );
+                    eprintln!(
+                        "{} {} charged {} USDC (fee {} USDC) {}",
+                        Theme::success("✓"),
+                        payment.agreement,
+                        UsdcAmount::from_microlamports(payment.amount),
+                        UsdcAmount::from_microlamports(fee),
+                        Theme::dim(&signature)
+                    );
+                }
+                Err(e) => {
+                    stats.failed += 1;
+                    warn!("Payment for agreement {} failed: {e:#}", payment.agreement);
+                    eprintln!("{} {} {e}", Theme::error("✗"), payment.agreement);
+                    let decoded = ErrorDetails::new(&e).program_error;
+                    if let Some(explanation) = decoded.and_then(|p| p.explanation) {
+                        eprintln!("    {}", Theme::dim(&explanation));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Execute one batch of payments on parallel threads, returning results in batch order
+fn execute_batch(
+    tally_client: &SimpleTallyClient,
+    keeper: &Keypair,
+    agreements: &[Pubkey],
+) -> Vec<Result<String>> {
+    std::thread::scope(|scope| {
+        // Spawn the whole batch before joining so the payments run in parallel
+        let mut handles = Vec::with_capacity(agreements.len());
+        for agreement in agreements {
+            handles.push(scope.spawn(move || {
+                tally_client
+                    .execute_payment(keeper, agreement)
+                    .map_err(|e| anyhow!("{e}"))
+            }));
+        }
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("Payment worker panicked")))
+            })
+            .collect()
+    })
+}
+
+/// Format the list of payments a dry run would submit
+fn format_dry_run(payee: &Pubkey, due: &[DuePayment], fee_bps: u16) -> String {
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "{} {}",
+        Theme::header("Dry run - payments due for payee"),
+        Theme::highlight(&payee.to_string())
+    );
+
+    if due.is_empty() {
+        output.push_str("\nNo payments are due.\n");
+        return output;
+    }
+
+    let mut total = 0u64;
+    let mut fees = 0u64;
+    for payment in due {
+        let fee = keeper_fee(payment.amount, fee_bps);
+        total = total.saturating_add(payment.amount);
+        fees = fees.saturating_add(fee);
+        let _ = write!(
+            output,
+            "\n{} {}\n  {} {}\n  {} {}\n  {} {} USDC (fee {} USDC)\n",
+            Theme::info("Agreement:"),
+            payment.agreement,
+            Theme::dim("Payer:"),
+            payment.payer,
+            Theme::dim("Due since:"),
+            format_timestamp(payment.next_payment_ts),
+            Theme::dim("Charge:"),
+            UsdcAmount::from_microlamports(payment.amount),
+            UsdcAmount::from_microlamports(fee)
+        );
+    }
+
+    let _ = write!(
+        output,
+        "\n{} payment(s) totalling {} USDC would be charged, earning {} USDC in keeper fees",
+        due.len(),
+        Theme::value(&UsdcAmount::from_microlamports(total).to_string()),
+        Theme::value(&UsdcAmount::from_microlamports(fees).to_string())
+    );
+    output
+}
+
+/// Format the totals printed when the keeper stops
+fn format_summary(stats: &KeeperStats) -> String {
+    format!(
+        "\n{} {} cycle(s), {} failed scan(s), {} payment(s) executed, {} failed, {} USDC earned in keeper fees",
+        Theme::info("Keeper stopped:"),
+        stats.cycles,
+        stats.scan_failures,
+        stats.executed,
+        stats.failed,
+        UsdcAmount::from_microlamports(stats.fees_earned)
+    )
+}
+
+/// Current unix timestamp in seconds
+fn current_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment(next_payment_ts: i64) -> DuePayment {
+        DuePayment {
+            agreement: Pubkey::new_unique(),
+            payer: Pubkey::new_unique(),
+            payment_terms: Pubkey::new_unique(),
+            amount: 10_000_000,
+            next_payment_ts,
+        }
+    }
+
+    #[test]
+    fn test_select_due_skips_payments_not_yet_due() {
+        let now = 1_700_000_000;
+        let due = select_due(
+            vec![(true, payment(now + 1)), (true, payment(now + 3600))],
+            now,
+        );
+        assert!(due.is_empty());
+    }
+
+    #[test]
+    fn test_select_due_skips_inactive_agreements() {
+        let now = 1_700_000_000;
+        let overdue = payment(now - 3600);
+        let due = select_due(
+            vec![(false, payment(now - 7200)), (true, overdue.clone())],
+            now,
+        );
+        assert_eq!(due, vec![overdue]);
+    }
+
+    #[test]
+    fn test_select_due_includes_payment_due_exactly_now() {
+        let now = 1_700_000_000;
+        let at_boundary = payment(now);
+        let due = select_due(
+            vec![(true, payment(now + 1)), (true, at_boundary.clone())],
+            now,
+        );
+        assert_eq!(due, vec![at_boundary]);
+    }
+
+    #[test]
+    fn test_select_due_orders_oldest_first() {
+        let now = 1_700_000_000;
+        let older = payment(now - 7200);
+        let newer = payment(now - 60);
+        let due = select_due(vec![(true, newer.clone()), (true, older.clone())], now);
+        assert_eq!(due, vec![older, newer]);
+    }
+
+    #[test]
+    fn test_keeper_fee() {
+        assert_eq!(keeper_fee(10_000_000, 50), 50_000);
+        assert_eq!(keeper_fee(10_000_000, 0), 0);
+        assert_eq!(keeper_fee(1, 50), 0);
+        assert_eq!(keeper_fee(u64::MAX, 10_000), u64::MAX);
+    }
+
+    #[test]
+    fn test_format_summary_counts() {
+        let stats = KeeperStats {
+            cycles: 3,
+            executed: 2,
+            failed: 1,
+            scan_failures: 1,
+            fees_earned: 100_000,
+        };
+        let summary = format_summary(&stats);
+        assert!(summary.contains("3 cycle(s)"));
+        assert!(summary.contains("1 failed scan(s)"));
+        assert!(summary.contains("2 payment(s) executed"));
+        assert!(summary.contains("1 failed"));
+    }
+
+    #[test]
+    fn test_format_dry_run_empty() {
+        let output = format_dry_run(&Pubkey::new_unique(), &[], 50);
+        assert!(output.contains("No payments are due"));
+    }
+
+    #[test]
+    fn test_format_dry_run_totals() {
+        let due = vec![
+            DuePayment {
+                agreement: Pubkey::new_unique(),
+                payer: Pubkey::new_unique(),
+                payment_terms: Pubkey::new_unique(),
+                amount: 10_000_000,
+                next_payment_ts: 1_700_000_000,
+            },
+            DuePayment {
+                agreement: Pubkey::new_unique(),
+                payer: Pubkey::new_unique(),
+                payment_terms: Pubkey::new_unique(),
+                amount: 5_000_000,
+                next_payment_ts: 1_700_000_100,
+            },
+        ];
+        let output = format_dry_run(&Pubkey::new_unique(), &due, 100);
+        assert!(output.contains("2 payment(s)"));
+        assert!(output.contains(&due[0].agreement.to_string()));
+        assert!(output.contains(&due[1].payer.to_string()));
+    }
+
+    #[test]
+    fn test_format_dry_run_total_saturates() {
+        let mut first = payment(1_700_000_000);
+        first.amount = u64::MAX;
+        let mut second = payment(1_700_000_100);
+        second.amount = u64::MAX;
+        let output = format_dry_run(&Pubkey::new_unique(), &[first, second], 100);
+        assert!(output.contains("2 payment(s)"));
+    }
+}
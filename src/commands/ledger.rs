@@ -0,0 +1,992 @@
+//! Revenue ledger built from a payee's transaction history
+//!
+//! `dashboard ledger` walks `getSignaturesForAddress` for the payee account
+//! and its treasury, fetches every transaction in the requested period and
+//! turns each `PaymentExecuted` event for the payee into a ledger row: gross
+//! amount, platform fee, keeper fee and the net amount the treasury received.
+//!
+//! The net amount is read from the treasury's token balance change when the
+//! transaction holds a single payment to the payee. Otherwise the platform
+//! fee is estimated from the payee's current volume tier and the row is
+//! marked `estimated`. The keeper fee is taken out of the gross amount.
+//!
+//! Signature pages and the payments found in each transaction are cached in
+//! `ledger/<network>/<payee>.json` next to the config file, so a re-run only
+//! fetches signatures newer than the cache, plus older ones when `--from`
+//! reaches further back than any earlier run.
+
+use crate::commands::show_payee::volume_tier_fee_bps;
+use crate::config_file::ConfigFile;
+use crate::errors::{enhance_account_not_found_error, CliError, ErrorCode};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::formatting::{detect_network, format_csv};
+use crate::utils::progress;
+use crate::utils::tx_decode;
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tally_sdk::solana_client::rpc_client::RpcClient;
+use tally_sdk::solana_client::rpc_request::RpcRequest;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::Signature;
+use tally_sdk::{parse_events_from_logs, SimpleTallyClient, TallyEvent, UsdcAmount};
+use tracing::warn;
+
+/// Most signatures `getSignaturesForAddress` returns per request
+const PAGE_LIMIT: usize = 1000;
+
+/// Version of the cache file layout; other versions are discarded
+const CACHE_VERSION: u32 = 1;
+
+/// Transactions fetched between cache writes, so an interrupted run resumes
+const CACHE_CHECKPOINT: usize = 100;
+
+/// Column names of the ledger CSV, in row order
+pub const LEDGER_CSV_HEADER: [&str; 11] = [
+    "date_utc",
+    "timestamp",
+    "payer",
+    "payment_terms",
+    "payment_number",
+    "gross_usdc",
+    "platform_fee_usdc",
+    "keeper_fee_usdc",
+    "net_usdc",
+    "fee_source",
+    "signature",
+];
+
+/// Arguments for `dashboard ledger`
+pub struct LedgerRequest<'a> {
+    /// Payee (merchant) account address
+    pub merchant: Pubkey,
+    /// Only include payments at or after this unix timestamp
+    pub from: Option<i64>,
+    /// Only include payments at or before this unix timestamp
+    pub to: Option<i64>,
+    /// RPC URL, used to keep the cache of each network apart
+    pub rpc_url: &'a str,
+}
+
+/// How a ledger row's platform fee was determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeSource {
+    /// From the treasury's balance change in the transaction
+    Observed,
+    /// From the payee's current volume tier
+    Estimated,
+}
+
+/// One payment received by the payee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct LedgerEntry {
+    /// Block time of the payment transaction
+    pub timestamp: i64,
+    pub date_utc: String,
+    pub payer: String,
+    pub payment_terms: String,
+    pub payment_number: u32,
+    /// Amount charged to the payer
+    pub gross_microlamports: u64,
+    pub platform_fee_microlamports: u64,
+    pub keeper_fee_microlamports: u64,
+    /// Amount credited to the payee's treasury
+    pub net_microlamports: u64,
+    pub fee_source: FeeSource,
+    pub signature: String,
+}
+
+/// Sums over every row of the ledger
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct LedgerTotals {
+    pub payments: usize,
+    pub gross_microlamports: u64,
+    pub platform_fee_microlamports: u64,
+    pub keeper_fee_microlamports: u64,
+    pub net_microlamports: u64,
+}
+
+/// Per-payment revenue ledger shown by `dashboard ledger`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RevenueLedger {
+    pub payee: String,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Payments in chronological order
+    pub payments: Vec<LedgerEntry>,
+    pub totals: LedgerTotals,
+    /// Transactions fetched from the RPC during this run
+    pub transactions_fetched: usize,
+    /// Transactions in the period read from the local cache
+    pub transactions_cached: usize,
+    /// Transactions left out because the RPC reported no block time, so
+    /// they cannot be placed in a bounded period
+    pub transactions_without_time: usize,
+    /// Cache file, `None` if it could not be located
+    pub cache_file: Option<String>,
+}
+
+impl CommandOutput for RevenueLedger {
+    const KIND: &'static str = "dashboard.ledger";
+
+    fn human(&self) -> Result<String> {
+        let mut output = format!("\nRevenue Ledger for Merchant: {}\n", self.payee);
+        output.push_str(&"=".repeat(100));
+        output.push('\n');
+        writeln!(
+            output,
+            "Period: {} to {}\n",
+            self.from
+                .map_or_else(|| "first payment".to_string(), format_utc),
+            self.to.map_or_else(|| "now".to_string(), format_utc)
+        )?;
+
+        if self.payments.is_empty() {
+            output.push_str("No payments found.\n");
+        } else {
+            writeln!(
+                output,
+                "{:<20} {:<14} {:>14} {:>12} {:>12} {:>14}  {:<14}",
+                "Date (UTC)", "Payer", "Gross", "Platform", "Keeper", "Net", "Signature"
+            )?;
+            output.push_str(&"-".repeat(100));
+            output.push('\n');
+
+            for entry in &self.payments {
+                let marker = if entry.fee_source == FeeSource::Estimated {
+                    "*"
+                } else {
+                    ""
+                };
+                writeln!(
+                    output,
+                    "{:<20} {:<14} {:>14} {:>12} {:>12} {:>14}  {:<14}",
+                    entry.date_utc,
+                    short(&entry.payer),
+                    usdc(entry.gross_microlamports),
+                    format!("{}{marker}", usdc(entry.platform_fee_microlamports)),
+                    usdc(entry.keeper_fee_microlamports),
+                    usdc(entry.net_microlamports),
+                    short(&entry.signature)
+                )?;
+            }
+            output.push_str(&"-".repeat(100));
+            output.push('\n');
+            writeln!(
+                output,
+                "{:<35} {:>14} {:>12} {:>12} {:>14}",
+                format!("Total ({} payments)", self.totals.payments),
+                usdc(self.totals.gross_microlamports),
+                usdc(self.totals.platform_fee_microlamports),
+                usdc(self.totals.keeper_fee_microlamports),
+                usdc(self.totals.net_microlamports)
+            )?;
+
+            if self
+                .payments
+                .iter()
+                .any(|entry| entry.fee_source == FeeSource::Estimated)
+            {
+                writeln!(
+                    output,
+                    "\n{}",
+                    Theme::warning("* Platform fee estimated from the current volume tier")
+                )?;
+            }
+        }
+
+        write!(
+            output,
+            "\n{}",
+            Theme::dim(&format!(
+                "{} transactions fetched, {} read from cache",
+                self.transactions_fetched, self.transactions_cached
+            ))
+        )?;
+        if self.transactions_without_time > 0 {
+            write!(
+                output,
+                "\n{}",
+                Theme::warning(&format!(
+                    "{} transactions without a block time were left out - run without \
+                     --from/--to to include them",
+                    self.transactions_without_time
+                ))
+            )?;
+        }
+        if let Some(cache_file) = &self.cache_file {
+            write!(output, "\n{}", Theme::dim(&format!("Cache: {cache_file}")))?;
+        }
+        Ok(output)
+    }
+
+    fn csv(&self) -> Option<Result<String>> {
+        let rows: Vec<_> = self
+            .payments
+            .iter()
+            .map(|entry| {
+                (
+                    &entry.date_utc,
+                    entry.timestamp,
+                    &entry.payer,
+                    &entry.payment_terms,
+                    entry.payment_number,
+                    usdc(entry.gross_microlamports),
+                    usdc(entry.platform_fee_microlamports),
+                    usdc(entry.keeper_fee_microlamports),
+                    usdc(entry.net_microlamports),
+                    entry.fee_source,
+                    &entry.signature,
+                )
+            })
+            .collect();
+        Some(format_csv(&LEDGER_CSV_HEADER, &rows))
+    }
+}
+
+/// Fetched history, stored as JSON between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LedgerCache {
+    version: u32,
+    /// Signature history of each walked address
+    addresses: BTreeMap<String, AddressHistory>,
+    /// What each fetched transaction held for the payee, by signature
+    transactions: BTreeMap<String, CachedTransaction>,
+}
+
+/// Signatures of one address, newest first as the RPC returns them
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct AddressHistory {
+    signatures: Vec<SignatureInfo>,
+    /// Whether the walk reached the address's first transaction
+    complete: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SignatureInfo {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    failed: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedTransaction {
+    block_time: Option<i64>,
+    payments: Vec<CachedPayment>,
+    /// Token balance changes by account; only kept when there are payments
+    token_changes: Vec<(String, i64)>,
+}
+
+/// The fields of a `PaymentExecuted` event the ledger uses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedPayment {
+    payment_terms: String,
+    payer: String,
+    amount: u64,
+    keeper_fee: u64,
+    payment_number: u32,
+}
+
+/// Build the revenue ledger for a payee
+///
+/// # Errors
+/// Returns error if the period is empty, the payee account is missing, or
+/// an RPC request fails
+pub fn execute(tally_client: &SimpleTallyClient, request: &LedgerRequest) -> Result<RevenueLedger> {
+    if let (Some(from), Some(to)) = (request.from, request.to) {
+        if from > to {
+            return Err(CliError::new(
+                ErrorCode::General,
+                format!(
+                    "--from ({}) is after --to ({})",
+                    format_utc(from),
+                    format_utc(to)
+                ),
+            )
+            .into());
+        }
+    }
+
+    let payee = tally_client
+        .get_payee(&request.merchant)
+        .context("Failed to fetch payee account - check RPC connection and account state")?
+        .ok_or_else(|| enhance_account_not_found_error("payee", &request.merchant))?;
+    let treasury = payee.treasury_ata.to_string();
+    let fee_bps = volume_tier_fee_bps(payee.volume_tier);
+
+    let cache_path = cache_path(request.rpc_url, &request.merchant)
+        .map_err(|e| warn!("Ledger cache disabled: {e:#}"))
+        .ok();
+    let mut cache = cache_path.as_deref().map(load_cache).unwrap_or_default();
+
+    // Walk the signature history of the payee and its treasury
+    let rpc = &tally_client.rpc_client;
+    for address in [request.merchant, payee.treasury_ata] {
+        let history = cache.addresses.entry(address.to_string()).or_default();
+        let walked = walk_address(history, request.from, PAGE_LIMIT, |before, until| {
+            signatures_page(rpc, &address, before, until)
+        });
+        if let Err(e) = walked {
+            // Keep the pages walked so far for the next run
+            if let Some(path) = &cache_path {
+                save_cache(path, &cache);
+            }
+            return Err(e);
+        }
+    }
+    if let Some(path) = &cache_path {
+        save_cache(path, &cache);
+    }
+
+    // Fetch the transactions in the period that are not cached yet
+    let signatures = signatures_in_period(&cache, request.from, request.to);
+    let transactions_without_time = signatures_without_time(&cache, request.from, request.to);
+    if transactions_without_time > 0 {
+        warn!(
+            "{transactions_without_time} transactions have no block time and were left out of the period"
+        );
+    }
+    let missing: Vec<String> = signatures
+        .iter()
+        .filter(|signature| !cache.transactions.contains_key(*signature))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        let bar = progress::create_progress_bar(missing.len() as u64, "Fetching transactions");
+        for (done, signature) in missing.iter().enumerate() {
+            match fetch_payments(rpc, &request.merchant, signature) {
+                Ok(transaction) => {
+                    cache.transactions.insert(signature.clone(), transaction);
+                }
+                Err(e) => {
+                    progress::finish_progress_error(&bar, "Fetching transactions");
+                    if let Some(path) = &cache_path {
+                        save_cache(path, &cache);
+                    }
+                    return Err(e);
+                }
+            }
+            bar.inc(1);
+            if (done + 1) % CACHE_CHECKPOINT == 0 {
+                if let Some(path) = &cache_path {
+                    save_cache(path, &cache);
+                }
+            }
+        }
+        progress::finish_progress_success(&bar, "Fetched transactions");
+        if let Some(path) = &cache_path {
+            save_cache(path, &cache);
+        }
+    }
+
+    let mut payments: Vec<LedgerEntry> = signatures
+        .iter()
+        .filter_map(|signature| {
+            let transaction = cache.transactions.get(signature)?;
+            Some(ledger_entries(signature, transaction, &treasury, fee_bps))
+        })
+        .flatten()
+        .collect();
+    payments.sort_by(|a, b| {
+        (a.timestamp, &a.signature, a.payment_number).cmp(&(
+            b.timestamp,
+            &b.signature,
+            b.payment_number,
+        ))
+    });
+
+    Ok(RevenueLedger {
+        payee: request.merchant.to_string(),
+        from: request.from,
+        to: request.to,
+        totals: totals(&payments),
+        payments,
+        transactions_fetched: missing.len(),
+        transactions_cached: signatures.len() - missing.len(),
+        transactions_without_time,
+        cache_file: cache_path.map(|path| path.display().to_string()),
+    })
+}
+
+/// Parse a `--from`/`--to` bound: a `YYYY-MM-DD` date (UTC) or a unix
+/// timestamp
+///
+/// A date used as the end of the period includes the whole day.
+///
+/// # Errors
+/// Returns error if the value is neither
+pub fn parse_period_bound(value: &str, end_of_day: bool) -> Result<i64> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(timestamp);
+    }
+    let date = value
+        .split('-')
+        .map(str::parse::<u32>)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()
+        .and_then(|parts| match parts[..] {
+            [year, month, day] => {
+                let days = days_from_civil(i64::from(year), month, day);
+                (civil_from_days(days) == (i64::from(year), month, day)).then_some(days)
+            }
+            _ => None,
+        });
+    let Some(days) = date else {
+        return Err(
+            CliError::new(ErrorCode::General, format!("Invalid date '{value}'"))
+                .with_suggestion("Use a UTC date such as 2025-01-31, or a unix timestamp.")
+                .into(),
+        );
+    };
+    let start = days * 86_400;
+    Ok(if end_of_day { start + 86_399 } else { start })
+}
+
+/// Walk an address's signatures, extending what `history` already holds
+///
+/// Signatures newer than the cached ones are always fetched. Older pages are
+/// fetched until the walk passes `from` or reaches the first transaction.
+/// `fetch_page(before, until)` returns at most `page_limit` signatures,
+/// newest first.
+fn walk_address(
+    history: &mut AddressHistory,
+    from: Option<i64>,
+    page_limit: usize,
+    mut fetch_page: impl FnMut(Option<&str>, Option<&str>) -> Result<Vec<SignatureInfo>>,
+) -> Result<()> {
+    if let Some(until) = history.signatures.first().map(|s| s.signature.clone()) {
+        let mut newer: Vec<SignatureInfo> = Vec::new();
+        loop {
+            let before = newer.last().map(|s| s.signature.clone());
+            let page = fetch_page(before.as_deref(), Some(&until))?;
+            let done = page.len() < page_limit;
+            newer.extend(page);
+            if done {
+                break;
+            }
+        }
+        newer.append(&mut history.signatures);
+        history.signatures = newer;
+    }
+
+    while !history.complete && !walked_past(history, from) {
+        let before = history.signatures.last().map(|s| s.signature.clone());
+        let page = fetch_page(before.as_deref(), None)?;
+        history.complete = page.len() < page_limit;
+        history.signatures.extend(page);
+    }
+    Ok(())
+}
+
+/// Whether the oldest walked signature is older than `from`
+fn walked_past(history: &AddressHistory, from: Option<i64>) -> bool {
+    match (history.signatures.last().and_then(|s| s.block_time), from) {
+        (Some(block_time), Some(from)) => block_time < from,
+        _ => false,
+    }
+}
+
+/// One page of `getSignaturesForAddress`
+fn signatures_page(
+    rpc: &RpcClient,
+    address: &Pubkey,
+    before: Option<&str>,
+    until: Option<&str>,
+) -> Result<Vec<SignatureInfo>> {
+    let mut config = json!({"limit": PAGE_LIMIT, "commitment": "confirmed"});
+    if let Some(before) = before {
+        config["before"] = json!(before);
+    }
+    if let Some(until) = until {
+        config["until"] = json!(until);
+    }
+    let response: Value = rpc
+        .send(
+            RpcRequest::GetSignaturesForAddress,
+            json!([address.to_string(), config]),
+        )
+        .map_err(|e| anyhow!("Failed to fetch signatures for {address}: {e}"))?;
+    Ok(response
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            Some(SignatureInfo {
+                signature: entry["signature"].as_str()?.to_string(),
+                slot: entry["slot"].as_u64().unwrap_or_default(),
+                block_time: entry["blockTime"].as_i64(),
+                failed: !entry["err"].is_null(),
+            })
+        })
+        .collect())
+}
+
+/// Successful signatures of every walked address within the period, deduped
+fn signatures_in_period(cache: &LedgerCache, from: Option<i64>, to: Option<i64>) -> Vec<String> {
+    let signatures: BTreeSet<&String> = cache
+        .addresses
+        .values()
+        .flat_map(|history| &history.signatures)
+        .filter(|info| !info.failed && in_period(info.block_time, from, to))
+        .map(|info| &info.signature)
+        .collect();
+    signatures.into_iter().cloned().collect()
+}
+
+/// Successful signatures without a block time, which a bounded period excludes
+fn signatures_without_time(cache: &LedgerCache, from: Option<i64>, to: Option<i64>) -> usize {
+    if from.is_none() && to.is_none() {
+        return 0;
+    }
+    cache
+        .addresses
+        .values()
+        .flat_map(|history| &history.signatures)
+        .filter(|info| !info.failed && info.block_time.is_none())
+        .map(|info| &info.signature)
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+/// Whether a block time falls within the period; an unknown time only
+/// matches an unbounded period
+fn in_period(block_time: Option<i64>, from: Option<i64>, to: Option<i64>) -> bool {
+    block_time.map_or_else(
+        || from.is_none() && to.is_none(),
+        |time| from.is_none_or(|from| time >= from) && to.is_none_or(|to| time <= to),
+    )
+}
+
+/// Fetch a transaction and keep its payments to `payee`
+fn fetch_payments(rpc: &RpcClient, payee: &Pubkey, signature: &str) -> Result<CachedTransaction> {
+    let signature = Signature::from_str(signature)
+        .with_context(|| format!("Invalid signature '{signature}' in ledger cache"))?;
+    let fetched = tx_decode::fetch_transaction(rpc, &signature)?;
+    let parsed = parse_events_from_logs(&fetched.logs());
+    let payments: Vec<CachedPayment> = parsed
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            TallyEvent::PaymentExecuted(e) if e.payee == *payee => Some(CachedPayment {
+                payment_terms: e.payment_terms.to_string(),
+                payer: e.payer.to_string(),
+                amount: e.amount,
+                keeper_fee: e.keeper_fee,
+                payment_number: e.payment_number,
+            }),
+            _ => None,
+        })
+        .collect();
+    let token_changes = if payments.is_empty() {
+        Vec::new()
+    } else {
+        tx_decode::token_balance_changes(&fetched.meta, &fetched.account_keys)
+            .into_iter()
+            .map(|change| (change.account, change.change))
+            .collect()
+    };
+    Ok(CachedTransaction {
+        block_time: fetched.block_time,
+        payments,
+        token_changes,
+    })
+}
+
+/// Ledger rows for the payments in one transaction
+fn ledger_entries(
+    signature: &str,
+    transaction: &CachedTransaction,
+    treasury: &str,
+    fee_bps: u16,
+) -> Vec<LedgerEntry> {
+    // The treasury credit is only attributable when there is one payment
+    let observed_net = match transaction.payments[..] {
+        [_] => transaction
+            .token_changes
+            .iter()
+            .find(|(account, _)| account == treasury)
+            .and_then(|(_, change)| u64::try_from(*change).ok()),
+        _ => None,
+    };
+    let timestamp = transaction.block_time.unwrap_or_default();
+
+    transaction
+        .payments
+        .iter()
+        .map(|payment| {
+            let after_keeper = payment.amount.saturating_sub(payment.keeper_fee);
+            let (platform_fee, net, fee_source) = observed_net.map_or_else(
+                || {
+                    let fee =
+                        u64::try_from(u128::from(payment.amount) * u128::from(fee_bps) / 10_000)
+                            .unwrap_or(u64::MAX);
+                    (fee, after_keeper.saturating_sub(fee), FeeSource::Estimated)
+                },
+                |net| (after_keeper.saturating_sub(net), net, FeeSource::Observed),
+            );
+            LedgerEntry {
+                timestamp,
+                date_utc: format_utc(timestamp),
+                payer: payment.payer.clone(),
+                payment_terms: payment.payment_terms.clone(),
+                payment_number: payment.payment_number,
+                gross_microlamports: payment.amount,
+                platform_fee_microlamports: platform_fee,
+                keeper_fee_microlamports: payment.keeper_fee,
+                net_microlamports: net,
+                fee_source,
+                signature: signature.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn totals(payments: &[LedgerEntry]) -> LedgerTotals {
+    payments
+        .iter()
+        .fold(LedgerTotals::default(), |mut totals, entry| {
+            totals.payments += 1;
+            totals.gross_microlamports += entry.gross_microlamports;
+            totals.platform_fee_microlamports += entry.platform_fee_microlamports;
+            totals.keeper_fee_microlamports += entry.keeper_fee_microlamports;
+            totals.net_microlamports += entry.net_microlamports;
+            totals
+        })
+}
+
+/// `ledger/<network>/<payee>.json` next to the config file
+fn cache_path(rpc_url: &str, payee: &Pubkey) -> Result<PathBuf> {
+    let config_path = ConfigFile::config_file_path()?;
+    Ok(config_path
+        .parent()
+        .context("Config file path has no parent directory")?
+        .join("ledger")
+        .join(detect_network(rpc_url))
+        .join(format!("{payee}.json")))
+}
+
+/// Read the cache, starting over if it is missing, unreadable or outdated
+fn load_cache(path: &Path) -> LedgerCache {
+    let cache = fs::read_to_string(path)
+        .ok()
+        .and_then(
+            |contents| match serde_json::from_str::<LedgerCache>(&contents) {
+                Ok(cache) => Some(cache),
+                Err(e) => {
+                    warn!("Ignoring unreadable ledger cache {}: {e}", path.display());
+                    None
+                }
+            },
+        )
+        .filter(|cache| cache.version == CACHE_VERSION);
+    cache.unwrap_or_else(|| LedgerCache {
+        version: CACHE_VERSION,
+        ..LedgerCache::default()
+    })
+}
+
+/// Write the cache; a failure only costs the next run a refetch
+fn save_cache(path: &Path, cache: &LedgerCache) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .map_err(anyhow::Error::from)
+        .and_then(|()| Ok(serde_json::to_string(cache)?))
+        .and_then(|contents| Ok(fs::write(path, contents)?));
+    if let Err(e) = result {
+        warn!("Failed to write ledger cache {}: {e}", path.display());
+    }
+}
+
+/// Unix timestamp as `YYYY-MM-DD HH:MM:SS` in UTC
+fn format_utc(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86_400));
+    let seconds = timestamp.rem_euclid(86_400);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian `(year, month, day)` of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (
+        year,
+        u32::try_from(month).unwrap_or_default(),
+        u32::try_from(day).unwrap_or_default(),
+    )
+}
+
+/// Micro-units as a USDC amount string
+fn usdc(microlamports: u64) -> String {
+    UsdcAmount::from_microlamports(microlamports).to_string()
+}
+
+/// First characters of an address or signature
+fn short(value: &str) -> String {
+    if value.len() > 12 {
+        format!("{}...", &value[..9])
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signature `sig<n>` with block time `n * 100`
+    fn signature_info(n: u32) -> SignatureInfo {
+        SignatureInfo {
+            signature: format!("sig{n}"),
+            slot: u64::from(n),
+            block_time: Some(i64::from(n) * 100),
+            failed: false,
+        }
+    }
+
+    /// Serve `chain` (newest first) the way `getSignaturesForAddress` pages it
+    fn serve<'a>(
+        chain: &'a [SignatureInfo],
+        limit: usize,
+        requests: &'a mut usize,
+    ) -> impl FnMut(Option<&str>, Option<&str>) -> Result<Vec<SignatureInfo>> + 'a {
+        move |before, until| {
+            *requests += 1;
+            let start = before.map_or(0, |before| {
+                chain.iter().position(|s| s.signature == before).unwrap() + 1
+            });
+            Ok(chain[start..]
+                .iter()
+                .take_while(|s| Some(s.signature.as_str()) != until)
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_walk_address_pages_to_first_transaction() {
+        let chain: Vec<_> = (1..=5).rev().map(signature_info).collect();
+        let mut history = AddressHistory::default();
+        let mut requests = 0;
+        walk_address(&mut history, None, 3, serve(&chain, 3, &mut requests)).unwrap();
+        assert_eq!(requests, 2);
+        assert_eq!(history.signatures, chain);
+        assert!(history.complete);
+    }
+
+    #[test]
+    fn test_walk_address_keeps_pages_before_failure() {
+        let chain: Vec<_> = (1..=5).rev().map(signature_info).collect();
+        let mut history = AddressHistory::default();
+        let mut requests = 0;
+        let mut pages = serve(&chain, 3, &mut requests);
+        let mut calls = 0;
+        let result = walk_address(&mut history, None, 3, |before, until| {
+            calls += 1;
+            if calls == 2 {
+                return Err(anyhow!("RPC unavailable"));
+            }
+            pages(before, until)
+        });
+        assert!(result.is_err());
+        assert_eq!(history.signatures, chain[..3]);
+        assert!(!history.complete);
+    }
+
+    #[test]
+    fn test_walk_address_resumes_from_cache() {
+        // sig10 (newest, t=1000) down to sig1 (oldest, t=100)
+        let chain: Vec<_> = (1..=10).rev().map(signature_info).collect();
+
+        // The first run stops once a page passes --from; sig9 and sig10 land later
+        let mut history = AddressHistory::default();
+        let mut requests = 0;
+        walk_address(
+            &mut history,
+            Some(700),
+            3,
+            serve(&chain[2..], 3, &mut requests),
+        )
+        .unwrap();
+        assert_eq!(requests, 1);
+        assert_eq!(history.signatures, chain[2..5]);
+        assert!(!history.complete);
+
+        // A re-run with an earlier --from fetches the newer signatures, then older pages
+        let mut requests = 0;
+        walk_address(&mut history, Some(400), 3, serve(&chain, 3, &mut requests)).unwrap();
+        assert_eq!(requests, 2);
+        assert_eq!(history.signatures, chain[..8]);
+        assert!(!history.complete);
+
+        // Nothing new: a single request for newer signatures
+        let mut requests = 0;
+        walk_address(&mut history, Some(400), 3, serve(&chain, 3, &mut requests)).unwrap();
+        assert_eq!(requests, 1);
+        assert_eq!(history.signatures, chain[..8]);
+    }
+
+    #[test]
+    fn test_signatures_in_period_dedupes_and_skips_failed() {
+        let mut failed = signature_info(3);
+        failed.failed = true;
+        let mut cache = LedgerCache::default();
+        cache.addresses.insert(
+            "payee".to_string(),
+            AddressHistory {
+                signatures: vec![failed, signature_info(2), signature_info(1)],
+                complete: true,
+            },
+        );
+        cache.addresses.insert(
+            "treasury".to_string(),
+            AddressHistory {
+                signatures: vec![signature_info(2)],
+                complete: true,
+            },
+        );
+        assert_eq!(signatures_in_period(&cache, None, None), ["sig1", "sig2"]);
+        assert_eq!(signatures_in_period(&cache, Some(150), Some(400)), ["sig2"]);
+    }
+
+    #[test]
+    fn test_signatures_without_time_counted_for_bounded_period() {
+        let mut untimed = signature_info(4);
+        untimed.block_time = None;
+        let mut cache = LedgerCache::default();
+        cache.addresses.insert(
+            "payee".to_string(),
+            AddressHistory {
+                signatures: vec![untimed.clone(), signature_info(1)],
+                complete: true,
+            },
+        );
+        cache.addresses.insert(
+            "treasury".to_string(),
+            AddressHistory {
+                signatures: vec![untimed],
+                complete: true,
+            },
+        );
+        assert_eq!(signatures_in_period(&cache, None, None), ["sig1", "sig4"]);
+        assert_eq!(signatures_without_time(&cache, None, None), 0);
+        assert_eq!(signatures_in_period(&cache, Some(50), None), ["sig1"]);
+        assert_eq!(signatures_without_time(&cache, Some(50), None), 1);
+    }
+
+    fn payment(amount: u64, keeper_fee: u64) -> CachedPayment {
+        CachedPayment {
+            payment_terms: "terms".to_string(),
+            payer: "payer".to_string(),
+            amount,
+            keeper_fee,
+            payment_number: 1,
+        }
+    }
+
+    #[test]
+    fn test_ledger_entries_observed_and_estimated_fees() {
+        let single = CachedTransaction {
+            block_time: Some(1_700_000_000),
+            payments: vec![payment(10_000_000, 50_000)],
+            token_changes: vec![
+                ("payer_ata".to_string(), -10_000_000),
+                ("treasury".to_string(), 9_925_000),
+            ],
+        };
+        let entries = ledger_entries("sig", &single, "treasury", 25);
+        assert_eq!(entries[0].fee_source, FeeSource::Observed);
+        assert_eq!(entries[0].platform_fee_microlamports, 25_000);
+        assert_eq!(entries[0].net_microlamports, 9_925_000);
+        assert_eq!(entries[0].date_utc, "2023-11-14 22:13:20");
+
+        let batch = CachedTransaction {
+            payments: vec![payment(10_000_000, 50_000), payment(4_000_000, 0)],
+            ..single
+        };
+        let entries = ledger_entries("sig", &batch, "treasury", 25);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].fee_source, FeeSource::Estimated);
+        assert_eq!(entries[1].platform_fee_microlamports, 10_000);
+        assert_eq!(entries[1].net_microlamports, 3_990_000);
+
+        let ledger = RevenueLedger {
+            payee: "payee".to_string(),
+            from: None,
+            to: None,
+            totals: totals(&entries),
+            payments: entries,
+            transactions_fetched: 1,
+            transactions_cached: 0,
+            transactions_without_time: 0,
+            cache_file: None,
+        };
+        assert_eq!(ledger.totals.gross_microlamports, 14_000_000);
+        let csv = ledger.csv().unwrap().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], LEDGER_CSV_HEADER.join(","));
+        assert_eq!(
+            lines[2],
+            "2023-11-14 22:13:20,1700000000,payer,terms,1,4.000000,0.010000,0.000000,3.990000,\
+             estimated,sig"
+        );
+    }
+
+    #[test]
+    fn test_parse_period_bound() {
+        assert_eq!(
+            parse_period_bound("1700000000", false).unwrap(),
+            1_700_000_000
+        );
+        assert_eq!(
+            parse_period_bound("2024-02-29", false).unwrap(),
+            1_709_164_800
+        );
+        assert_eq!(
+            parse_period_bound("2024-02-29", true).unwrap(),
+            1_709_251_199
+        );
+        assert!(parse_period_bound("2023-02-29", false).is_err());
+        assert!(parse_period_bound("last week", false).is_err());
+        assert_eq!(format_utc(1_709_251_199), "2024-02-29 23:59:59");
+    }
+}
@@ -2,19 +2,40 @@
 
 use crate::{
     config::TallyCliConfig,
-    utils::formatting::{format_agreements_human, format_agreements_json, AgreementInfo},
+    output::CommandOutput,
+    utils::formatting::{
+        format_agreements_human, format_csv, AgreementEntry, AgreementInfo, AGREEMENT_CSV_HEADER,
+    },
 };
 use anyhow::{anyhow, Context, Result};
-use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::str::FromStr;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
 use tally_sdk::SimpleTallyClient;
 use tracing::info;
 
-#[derive(Clone, Debug, ValueEnum)]
-pub enum OutputFormat {
-    Human,
-    Json,
+/// Payment agreements under one set of payment terms, oldest first
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AgreementList {
+    pub payment_terms: String,
+    pub count: usize,
+    pub agreements: Vec<AgreementEntry>,
+}
+
+impl CommandOutput for AgreementList {
+    const KIND: &'static str = "agreement.list";
+
+    fn human(&self) -> Result<String> {
+        Ok(format_agreements_human(
+            &self.agreements,
+            &self.payment_terms,
+        ))
+    }
+
+    fn csv(&self) -> Option<Result<String>> {
+        Some(format_csv(&AGREEMENT_CSV_HEADER, &self.agreements))
+    }
 }
 
 /// Execute the list agreements command
@@ -24,9 +45,8 @@ pub enum OutputFormat {
 pub async fn execute(
     tally_client: &SimpleTallyClient,
     payment_terms_str: &str,
-    output_format: &OutputFormat,
-    config: &TallyCliConfig,
-) -> Result<String> {
+    _config: &TallyCliConfig,
+) -> Result<AgreementList> {
     info!(
         "Starting payment agreement listing for payment terms: {}",
         payment_terms_str
@@ -84,13 +104,9 @@ pub async fn execute(
     // Sort agreements by created timestamp for consistent output
     agreements.sort_by(|a, b| a.created_ts.cmp(&b.created_ts));
 
-    // Format output based on requested format
-    match output_format {
-        OutputFormat::Human => Ok(format_agreements_human(
-            &agreements,
-            &payment_terms_pda,
-            config,
-        )),
-        OutputFormat::Json => format_agreements_json(&agreements, config),
-    }
+    Ok(AgreementList {
+        payment_terms: payment_terms_pda.to_string(),
+        count: agreements.len(),
+        agreements: agreements.iter().map(AgreementEntry::from).collect(),
+    })
 }
@@ -1,19 +1,36 @@
 //! List payment terms command implementation
 
+use crate::output::CommandOutput;
 use crate::utils::formatting::{
-    format_payment_terms_human, format_payment_terms_json, PaymentTermsInfo,
+    format_csv, format_payment_terms_human, PaymentTermsEntry, PaymentTermsInfo,
+    PAYMENT_TERMS_CSV_HEADER,
 };
 use anyhow::{anyhow, Context, Result};
-use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::str::FromStr;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
 use tally_sdk::SimpleTallyClient;
 use tracing::info;
 
-#[derive(Clone, Debug, ValueEnum)]
-pub enum OutputFormat {
-    Human,
-    Json,
+/// Payment terms of a payee, sorted by terms ID
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PaymentTermsList {
+    pub payee: String,
+    pub count: usize,
+    pub payment_terms: Vec<PaymentTermsEntry>,
+}
+
+impl CommandOutput for PaymentTermsList {
+    const KIND: &'static str = "payment-terms.list";
+
+    fn human(&self) -> Result<String> {
+        Ok(format_payment_terms_human(&self.payment_terms, &self.payee))
+    }
+
+    fn csv(&self) -> Option<Result<String>> {
+        Some(format_csv(&PAYMENT_TERMS_CSV_HEADER, &self.payment_terms))
+    }
 }
 
 /// Execute the list payment terms command
@@ -27,8 +44,7 @@ pub enum OutputFormat {
 pub async fn execute(
     tally_client: &SimpleTallyClient,
     payee_str: &str,
-    output_format: &OutputFormat,
-) -> Result<String> {
+) -> Result<PaymentTermsList> {
     info!("Starting payment terms listing for payee: {}", payee_str);
 
     // Parse payee PDA address
@@ -75,9 +91,9 @@ pub async fn execute(
     // Sort payment terms by terms_id for consistent output
     terms_list.sort_by(|a, b| a.terms_id.cmp(&b.terms_id));
 
-    // Format output based on requested format
-    match output_format {
-        OutputFormat::Human => Ok(format_payment_terms_human(&terms_list, &payee_pda)),
-        OutputFormat::Json => format_payment_terms_json(&terms_list),
-    }
+    Ok(PaymentTermsList {
+        payee: payee_pda.to_string(),
+        count: terms_list.len(),
+        payment_terms: terms_list.iter().map(PaymentTermsEntry::from).collect(),
+    })
 }
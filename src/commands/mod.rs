@@ -3,24 +3,57 @@
 //! This module contains the individual command implementations, each in their own file
 //! for better organization and maintainability.
 
+pub mod admin;
+pub mod agreement_lifecycle;
+pub mod apply_payment_terms;
 pub mod completions;
 pub mod config_file_ops;
 pub mod create_payment_terms;
 pub mod dashboard;
+pub mod deactivate_payment_terms;
+pub mod doctor;
+pub mod event_stream;
+pub mod explain_error;
+pub mod import_payment_terms;
 pub mod init_payee;
 pub mod init_wizard;
+pub mod keeper;
+pub mod ledger;
 pub mod list_agreements;
 pub mod list_payment_terms;
+pub mod payee_authority;
+pub mod payee_treasury;
+pub mod schema;
 pub mod show_agreement;
 pub mod show_config;
 pub mod show_payee;
+pub mod tx;
+pub mod update_payment_terms;
+pub mod wallet;
 
 // Re-export command execution functions for easy access
+pub use admin::execute as execute_admin;
+pub use agreement_lifecycle::execute as execute_agreement_lifecycle;
+pub use apply_payment_terms::execute as execute_apply_payment_terms;
 pub use create_payment_terms::execute as execute_create_payment_terms;
+pub use deactivate_payment_terms::execute as execute_deactivate_payment_terms;
+pub use doctor::execute as execute_doctor;
+pub use explain_error::execute as execute_explain_error;
+pub use import_payment_terms::execute as execute_import_payment_terms;
 pub use init_payee::execute as execute_init_payee;
 pub use init_wizard::execute as execute_init_wizard;
+pub use keeper::run as execute_keeper_run;
 pub use list_agreements::execute as execute_list_agreements;
 pub use list_payment_terms::execute as execute_list_payment_terms;
+pub use payee_authority::{
+    accept_authority as execute_accept_payee_authority,
+    set_authority as execute_set_payee_authority,
+};
+pub use payee_treasury::{
+    update_treasury as execute_update_payee_treasury, withdraw as execute_payee_withdraw,
+};
 pub use show_agreement::execute as execute_show_agreement;
 pub use show_config::execute as execute_show_config;
 pub use show_payee::execute as execute_show_payee;
+pub use tx::{show as execute_tx_show, sign as execute_tx_sign, submit as execute_tx_submit};
+pub use update_payment_terms::execute as execute_update_payment_terms;
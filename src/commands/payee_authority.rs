@@ -0,0 +1,387 @@
+//! Two-step payee authority transfer: propose a new authority, then accept it
+
+use crate::config::TallyCliConfig;
+use crate::errors::{enhance_account_not_found_error, CliError, ErrorCode};
+use crate::output::CommandOutput;
+use crate::utils::authority::ensure_current_authority;
+use crate::utils::colors::Theme;
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::Signer;
+use tally_sdk::SimpleTallyClient;
+use tracing::info;
+
+/// Arguments for proposing a new payee authority
+pub struct SetAuthorityRequest<'a> {
+    pub payee: Pubkey,
+    pub new_authority_str: &'a str,
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Arguments for accepting a pending payee authority
+pub struct AcceptAuthorityRequest<'a> {
+    pub payee: Pubkey,
+    /// Keypair of the pending (new) authority
+    pub authority_path: Option<&'a str>,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// A pending authority recorded by `payee set-authority`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PendingAuthoritySet {
+    pub payee: String,
+    pub pending_authority: String,
+    pub signature: String,
+}
+
+impl CommandOutput for PendingAuthoritySet {
+    const KIND: &'static str = "payee.set-authority";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Pending authority set successfully!")
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payee PDA:"),
+            Theme::highlight(&self.payee)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Pending authority:"),
+            Theme::value(&self.pending_authority)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        write!(
+            &mut output,
+            "\n{}\n  tally-merchant payee accept-authority --payee {} --authority <NEW_AUTHORITY_KEYPAIR>",
+            Theme::info("To complete the transfer, the new authority must run:"),
+            self.payee
+        )?;
+        Ok(output)
+    }
+}
+
+/// A payee authority transfer completed by `payee accept-authority`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AuthorityAccepted {
+    pub payee: String,
+    pub previous_authority: String,
+    pub new_authority: String,
+    pub signature: String,
+}
+
+impl CommandOutput for AuthorityAccepted {
+    const KIND: &'static str = "payee.accept-authority";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Payee authority transferred successfully!")
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payee PDA:"),
+            Theme::highlight(&self.payee)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Previous authority:"),
+            Theme::dim(&self.previous_authority)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("New authority:"),
+            Theme::value(&self.new_authority)
+        )?;
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
+}
+
+/// Execute the set-authority command (step 1 of 2)
+///
+/// Records `new_authority` as the payee's pending authority. Control does not
+/// change until the new authority runs `payee accept-authority`.
+///
+/// # Errors
+/// Returns error if the signer is not the current authority, the new authority
+/// is invalid or unchanged, or the transaction fails
+pub async fn set_authority(
+    tally_client: &SimpleTallyClient,
+    request: &SetAuthorityRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<PendingAuthoritySet>> {
+    info!("Starting payee authority transfer");
+
+    let new_authority = Pubkey::from_str(request.new_authority_str).map_err(|e| {
+        anyhow!(
+            "Invalid new authority address '{}': {e}",
+            request.new_authority_str
+        )
+    })?;
+
+    let payee = tally_client
+        .get_payee(&request.payee)
+        .context("Failed to fetch payee account - check RPC connection and account state")?
+        .ok_or_else(|| enhance_account_not_found_error("payee", &request.payee))?;
+
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    ensure_current_authority(&request.payee, &payee.authority, &authority.pubkey())?;
+
+    if new_authority == payee.authority {
+        return Err(anyhow!(
+            "{new_authority} is already the authority for payee {}",
+            request.payee
+        ));
+    }
+
+    let authority_pubkey = authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .set_payee_pending_authority_instructions(
+                &authority_pubkey,
+                &request.payee,
+                &new_authority,
+            )
+            .map_err(|e| anyhow!("Failed to build authority transfer transaction: {e}"))?;
+        Ok(TxPlan::new(
+            "Propose new payee authority",
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        );
+    }
+
+    if !request.skip_confirmation {
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Propose {new_authority} as the new authority for payee {}? They must accept before control changes.",
+                request.payee
+            ))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Authority transfer canceled by user"));
+        }
+    }
+
+    if let TxSigner::External(signer) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(PendingAuthoritySet {
+            payee: request.payee.to_string(),
+            pending_authority: new_authority.to_string(),
+            signature: signature.to_string(),
+        }));
+    }
+    let authority = authority.into_keypair()?;
+
+    let spinner =
+        progress::create_spinner("Setting pending authority and submitting transaction...");
+    let result = tally_client
+        .set_payee_pending_authority(&authority, &request.payee, &new_authority)
+        .map_err(|e| {
+            transaction::explain_failure(
+                tally_client,
+                plan,
+                "Failed to set pending payee authority",
+                e,
+            )
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Pending authority set");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to set pending authority");
+        }
+    }
+
+    Ok(TxOutcome::Sent(PendingAuthoritySet {
+        payee: request.payee.to_string(),
+        pending_authority: new_authority.to_string(),
+        signature: result?,
+    }))
+}
+
+/// Execute the accept-authority command (step 2 of 2)
+///
+/// # Errors
+/// Returns error if there is no pending authority, the signer is not the
+/// pending authority, or the transaction fails
+pub async fn accept_authority(
+    tally_client: &SimpleTallyClient,
+    request: &AcceptAuthorityRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<AuthorityAccepted>> {
+    info!("Accepting payee authority transfer");
+
+    let payee = tally_client
+        .get_payee(&request.payee)
+        .context("Failed to fetch payee account - check RPC connection and account state")?
+        .ok_or_else(|| enhance_account_not_found_error("payee", &request.payee))?;
+
+    let new_authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load new authority keypair")?;
+    check_pending_authority(
+        &request.payee,
+        payee.pending_authority.as_ref(),
+        &new_authority.pubkey(),
+    )?;
+
+    let new_authority_pubkey = new_authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .accept_payee_authority_instructions(&new_authority_pubkey, &request.payee)
+            .map_err(|e| anyhow!("Failed to build accept-authority transaction: {e}"))?;
+        Ok(TxPlan::new(
+            "Accept payee authority",
+            new_authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &new_authority,
+        );
+    }
+
+    if let TxSigner::External(signer) = &new_authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(AuthorityAccepted {
+            payee: request.payee.to_string(),
+            previous_authority: payee.authority.to_string(),
+            new_authority: new_authority.pubkey().to_string(),
+            signature: signature.to_string(),
+        }));
+    }
+    let new_authority = new_authority.into_keypair()?;
+
+    let spinner = progress::create_spinner("Accepting authority and submitting transaction...");
+    let result = tally_client
+        .accept_payee_authority(&new_authority, &request.payee)
+        .map_err(|e| {
+            transaction::explain_failure(tally_client, plan, "Failed to accept payee authority", e)
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Payee authority transferred");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to accept payee authority");
+        }
+    }
+
+    Ok(TxOutcome::Sent(AuthorityAccepted {
+        payee: request.payee.to_string(),
+        previous_authority: payee.authority.to_string(),
+        new_authority: new_authority.pubkey().to_string(),
+        signature: result?,
+    }))
+}
+
+/// Check that `signer` is the payee's pending authority
+///
+/// # Errors
+/// Returns error if no transfer is pending or it names a different key
+pub fn check_pending_authority(
+    payee: &Pubkey,
+    pending: Option<&Pubkey>,
+    signer: &Pubkey,
+) -> Result<()> {
+    match pending {
+        None => Err(CliError::new(
+            ErrorCode::AuthorityMismatch,
+            format!("Payee {payee} has no pending authority transfer."),
+        )
+        .with_suggestion(
+            "The current authority must first run 'tally-merchant payee set-authority'.",
+        )
+        .into()),
+        Some(pending) if pending != signer => Err(CliError::new(
+            ErrorCode::AuthorityMismatch,
+            format!(
+                "Payee {payee} is pending transfer to {pending}, but the loaded keypair is {signer}"
+            ),
+        )
+        .with_suggestion("Pass --authority <KEYPAIR> with the pending authority keypair.")
+        .into()),
+        Some(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_pending_authority_none() {
+        let err = check_pending_authority(&Pubkey::new_unique(), None, &Pubkey::new_unique())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no pending authority"));
+    }
+
+    #[test]
+    fn test_check_pending_authority_wrong_signer() {
+        let pending = Pubkey::new_unique();
+        let err =
+            check_pending_authority(&Pubkey::new_unique(), Some(&pending), &Pubkey::new_unique())
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains(&pending.to_string()));
+    }
+
+    #[test]
+    fn test_check_pending_authority_matches() {
+        let pending = Pubkey::new_unique();
+        assert!(check_pending_authority(&Pubkey::new_unique(), Some(&pending), &pending).is_ok());
+    }
+}
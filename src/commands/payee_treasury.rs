@@ -0,0 +1,467 @@
+//! Payee treasury commands: rotate the treasury ATA and withdraw funds
+
+use crate::config::TallyCliConfig;
+use crate::errors::{config_account_not_found, enhance_account_not_found_error};
+use crate::output::CommandOutput;
+use crate::utils::authority::ensure_current_authority;
+use crate::utils::colors::Theme;
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{ata, SimpleTallyClient, UsdcAmount};
+use tracing::info;
+
+/// Arguments for rotating the payee treasury
+pub struct UpdateTreasuryRequest<'a> {
+    pub payee: Pubkey,
+    pub treasury_str: &'a str,
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Arguments for withdrawing from the payee treasury
+pub struct WithdrawRequest<'a> {
+    pub payee: Pubkey,
+    pub amount: UsdcAmount,
+    pub destination_str: Option<&'a str>,
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// A treasury rotation made by `payee update-treasury`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TreasuryUpdated {
+    pub payee: String,
+    pub previous_treasury: String,
+    pub new_treasury: String,
+    pub signature: String,
+}
+
+impl CommandOutput for TreasuryUpdated {
+    const KIND: &'static str = "payee.update-treasury";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Payee treasury updated successfully!")
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payee PDA:"),
+            Theme::highlight(&self.payee)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Previous treasury:"),
+            Theme::dim(&self.previous_treasury)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("New treasury:"),
+            Theme::value(&self.new_treasury)
+        )?;
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
+}
+
+/// A withdrawal made by `payee withdraw`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Withdrawal {
+    pub payee: String,
+    pub amount_usdc: String,
+    pub amount_microlamports: u64,
+    pub destination: String,
+    /// Treasury balance after the withdrawal, in USDC micro-units
+    pub remaining_balance_microlamports: u64,
+    pub signature: String,
+}
+
+impl CommandOutput for Withdrawal {
+    const KIND: &'static str = "payee.withdraw";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Withdrawal completed successfully!")
+        )?;
+        writeln!(
+            &mut output,
+            "{} {} USDC",
+            Theme::info("Amount:"),
+            Theme::value(&self.amount_usdc)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Destination:"),
+            Theme::highlight(&self.destination)
+        )?;
+        writeln!(
+            &mut output,
+            "{} {} USDC",
+            Theme::info("Remaining treasury balance:"),
+            Theme::value(
+                &UsdcAmount::from_microlamports(self.remaining_balance_microlamports).to_string()
+            )
+        )?;
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
+}
+
+/// Execute the update-treasury command
+///
+/// # Errors
+/// Returns error if the payee cannot be fetched, the signer is not the payee
+/// authority, the new treasury is not a token account for the payee's mint,
+/// or the transaction fails
+pub async fn update_treasury(
+    tally_client: &SimpleTallyClient,
+    request: &UpdateTreasuryRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<TreasuryUpdated>> {
+    info!("Starting payee treasury update");
+
+    let new_treasury = Pubkey::from_str(request.treasury_str).map_err(|e| {
+        anyhow!(
+            "Invalid treasury ATA address '{}': {e}",
+            request.treasury_str
+        )
+    })?;
+
+    let payee = tally_client
+        .get_payee(&request.payee)
+        .context("Failed to fetch payee account - check RPC connection and account state")?
+        .ok_or_else(|| enhance_account_not_found_error("payee", &request.payee))?;
+
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    ensure_current_authority(&request.payee, &payee.authority, &authority.pubkey())?;
+
+    if new_treasury == payee.treasury_ata {
+        return Err(anyhow!(
+            "{new_treasury} is already the treasury for payee {}",
+            request.payee
+        ));
+    }
+
+    // The program only accepts a token account for the payee's USDC mint
+    let token_account = tally_client
+        .rpc_client
+        .get_token_account(&new_treasury)
+        .context("Failed to fetch treasury token account - check RPC connection")?
+        .ok_or_else(|| {
+            anyhow!(
+                "Treasury account {new_treasury} does not exist or is not a token account.\n\
+                 Create the USDC associated token account first (e.g. 'spl-token create-account {}').",
+                payee.usdc_mint
+            )
+        })?;
+    check_treasury_mint(&new_treasury, &token_account.mint, &payee.usdc_mint)?;
+
+    let authority_pubkey = authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .update_payee_treasury_instructions(&authority_pubkey, &request.payee, &new_treasury)
+            .map_err(|e| anyhow!("Failed to build treasury update transaction: {e}"))?;
+        Ok(TxPlan::new(
+            "Update payee treasury",
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        );
+    }
+
+    if !request.skip_confirmation {
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Move payee treasury from {} to {new_treasury}? Future payments will be sent to the new account.",
+                payee.treasury_ata
+            ))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Treasury update canceled by user"));
+        }
+    }
+
+    let updated = |signature: String| TreasuryUpdated {
+        payee: request.payee.to_string(),
+        previous_treasury: payee.treasury_ata.to_string(),
+        new_treasury: new_treasury.to_string(),
+        signature,
+    };
+    if let TxSigner::External(signer) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(updated(signature.to_string())));
+    }
+    let authority = authority.into_keypair()?;
+
+    let spinner = progress::create_spinner("Updating payee treasury and submitting transaction...");
+    let result = tally_client
+        .update_payee_treasury(&authority, &request.payee, &new_treasury)
+        .map_err(|e| {
+            transaction::explain_failure(tally_client, plan, "Failed to update payee treasury", e)
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Payee treasury updated successfully");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to update payee treasury");
+        }
+    }
+
+    Ok(TxOutcome::Sent(updated(result?)))
+}
+
+/// Execute the withdraw command
+///
+/// # Errors
+/// Returns error if the amount is invalid, exceeds the global
+/// `max_withdrawal_amount` or the treasury balance, the signer is not the
+/// payee authority, or the transaction fails
+pub async fn withdraw(
+    tally_client: &SimpleTallyClient,
+    request: &WithdrawRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<Withdrawal>> {
+    info!("Starting payee withdrawal");
+
+    let amount = request.amount;
+    if amount.microlamports() == 0 {
+        return Err(anyhow!("Withdrawal amount must be greater than 0 USDC"));
+    }
+
+    let payee = tally_client
+        .get_payee(&request.payee)
+        .context("Failed to fetch payee account - check RPC connection and account state")?
+        .ok_or_else(|| enhance_account_not_found_error("payee", &request.payee))?;
+
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    ensure_current_authority(&request.payee, &payee.authority, &authority.pubkey())?;
+
+    let max_withdrawal = tally_client
+        .get_config()
+        .context("Failed to fetch config account - check RPC connection and account state")?
+        .ok_or_else(config_account_not_found)?
+        .max_withdrawal_amount;
+
+    let balance = tally_client
+        .rpc_client
+        .get_token_account_balance(&payee.treasury_ata)
+        .context("Failed to fetch treasury balance - check RPC connection")?;
+    let balance: u64 = balance
+        .amount
+        .parse()
+        .with_context(|| format!("Invalid treasury balance '{}'", balance.amount))?;
+
+    check_withdrawal(amount.microlamports(), max_withdrawal, balance)?;
+
+    // Default to the authority's own USDC account
+    let destination = if let Some(destination) = request.destination_str {
+        Pubkey::from_str(destination)
+            .map_err(|e| anyhow!("Invalid destination address '{destination}': {e}"))?
+    } else {
+        let authority_pubkey = Pubkey::from(authority.pubkey().to_bytes());
+        ata::get_associated_token_address_for_mint(&authority_pubkey, &payee.usdc_mint)?
+    };
+
+    let authority_pubkey = authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .withdraw_payee_treasury_instructions(
+                &authority_pubkey,
+                &request.payee,
+                &destination,
+                amount.microlamports(),
+            )
+            .map_err(|e| anyhow!("Failed to build withdrawal transaction: {e}"))?;
+        Ok(TxPlan::new(
+            format!("Withdraw {amount} USDC from payee treasury"),
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        );
+    }
+
+    if !request.skip_confirmation {
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Withdraw {amount} USDC from treasury {} to {destination}?",
+                payee.treasury_ata
+            ))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Withdrawal canceled by user"));
+        }
+    }
+
+    let withdrawal = |signature: String| Withdrawal {
+        payee: request.payee.to_string(),
+        amount_usdc: amount.to_string(),
+        amount_microlamports: amount.microlamports(),
+        destination: destination.to_string(),
+        remaining_balance_microlamports: balance - amount.microlamports(),
+        signature,
+    };
+    if let TxSigner::External(signer) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(withdrawal(signature.to_string())));
+    }
+    let authority = authority.into_keypair()?;
+
+    let spinner =
+        progress::create_spinner("Withdrawing from treasury and submitting transaction...");
+    let result = tally_client
+        .withdraw_payee_treasury(
+            &authority,
+            &request.payee,
+            &destination,
+            amount.microlamports(),
+        )
+        .map_err(|e| {
+            transaction::explain_failure(
+                tally_client,
+                plan,
+                "Failed to withdraw from payee treasury",
+                e,
+            )
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Withdrawal completed successfully");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to withdraw from payee treasury");
+        }
+    }
+
+    Ok(TxOutcome::Sent(withdrawal(result?)))
+}
+
+/// Check that a withdrawal respects the global limit and the available balance
+///
+/// # Errors
+/// Returns error if `amount` exceeds `max_withdrawal` or `balance`
+pub fn check_withdrawal(amount: u64, max_withdrawal: u64, balance: u64) -> Result<()> {
+    if amount > max_withdrawal {
+        return Err(anyhow!(
+            "Withdrawal of {} USDC exceeds the platform maximum of {} USDC per withdrawal.\n\
+             Split the withdrawal into smaller amounts.",
+            UsdcAmount::from_microlamports(amount),
+            UsdcAmount::from_microlamports(max_withdrawal)
+        ));
+    }
+    if amount > balance {
+        return Err(anyhow!(
+            "Insufficient treasury balance: requested {} USDC, available {} USDC",
+            UsdcAmount::from_microlamports(amount),
+            UsdcAmount::from_microlamports(balance)
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a prospective treasury holds the payee's mint
+///
+/// # Errors
+/// Returns error if the token account mint differs from `expected_mint`
+pub fn check_treasury_mint(treasury: &Pubkey, mint: &str, expected_mint: &Pubkey) -> Result<()> {
+    if mint != expected_mint.to_string() {
+        return Err(anyhow!(
+            "Treasury {treasury} holds mint {mint}, but this payee uses {expected_mint}"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_withdrawal_within_limits() {
+        assert!(check_withdrawal(5_000_000, 10_000_000, 20_000_000).is_ok());
+        assert!(check_withdrawal(10_000_000, 10_000_000, 10_000_000).is_ok());
+    }
+
+    #[test]
+    fn test_check_withdrawal_exceeds_max() {
+        let err = check_withdrawal(11_000_000, 10_000_000, 50_000_000)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("platform maximum"));
+    }
+
+    #[test]
+    fn test_check_withdrawal_exceeds_balance() {
+        let err = check_withdrawal(5_000_000, 10_000_000, 1_000_000)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Insufficient treasury balance"));
+    }
+
+    #[test]
+    fn test_check_treasury_mint() {
+        let treasury = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        assert!(check_treasury_mint(&treasury, &mint.to_string(), &mint).is_ok());
+
+        let other = Pubkey::new_unique();
+        let err = check_treasury_mint(&treasury, &other.to_string(), &mint)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&other.to_string()));
+    }
+}
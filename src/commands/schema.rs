@@ -0,0 +1,244 @@
+//! JSON Schemas of command results
+//!
+//! `tally-merchant schema` lists every result kind, and
+//! `tally-merchant schema <KIND>` prints the schema of the `--output json`
+//! envelope carrying that kind, so integrations can validate what they parse.
+
+use crate::commands::{
+    admin, agreement_lifecycle, apply_payment_terms, create_payment_terms, dashboard,
+    deactivate_payment_terms, doctor, event_stream, explain_error, import_payment_terms,
+    init_payee, init_wizard, keeper, ledger, list_agreements, list_payment_terms, payee_authority,
+    payee_treasury, show_agreement, show_config, show_payee, tx, update_payment_terms, wallet,
+};
+use crate::context::EffectiveConfig;
+use crate::output::{error_schema, schema, CommandOutput, Message, Output, SCHEMA_VERSION};
+use crate::utils::formatting::EventEntry;
+use crate::utils::transaction::TxOutcome;
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write as _;
+
+/// Kind of the error envelope
+const ERROR_KIND: &str = "error";
+
+/// Produces the JSON Schema of one result kind
+type SchemaFn = fn() -> Value;
+
+/// Every result kind with the function producing its schema
+const SCHEMAS: &[(&str, SchemaFn)] = &[
+    (
+        init_wizard::SetupComplete::KIND,
+        schema::<TxOutcome<init_wizard::SetupComplete>>,
+    ),
+    (
+        show_config::ConfigDetails::KIND,
+        schema::<show_config::ConfigDetails>,
+    ),
+    (EffectiveConfig::KIND, schema::<EffectiveConfig>),
+    (
+        init_payee::PayeeInitialized::KIND,
+        schema::<TxOutcome<init_payee::PayeeInitialized>>,
+    ),
+    (
+        show_payee::PayeeDetails::KIND,
+        schema::<show_payee::PayeeDetails>,
+    ),
+    (
+        payee_treasury::TreasuryUpdated::KIND,
+        schema::<TxOutcome<payee_treasury::TreasuryUpdated>>,
+    ),
+    (
+        payee_treasury::Withdrawal::KIND,
+        schema::<TxOutcome<payee_treasury::Withdrawal>>,
+    ),
+    (
+        payee_authority::PendingAuthoritySet::KIND,
+        schema::<TxOutcome<payee_authority::PendingAuthoritySet>>,
+    ),
+    (
+        payee_authority::AuthorityAccepted::KIND,
+        schema::<TxOutcome<payee_authority::AuthorityAccepted>>,
+    ),
+    (
+        create_payment_terms::PaymentTermsCreated::KIND,
+        schema::<TxOutcome<create_payment_terms::PaymentTermsCreated>>,
+    ),
+    (
+        list_payment_terms::PaymentTermsList::KIND,
+        schema::<list_payment_terms::PaymentTermsList>,
+    ),
+    (
+        update_payment_terms::PaymentTermsUpdated::KIND,
+        schema::<TxOutcome<update_payment_terms::PaymentTermsUpdated>>,
+    ),
+    (
+        deactivate_payment_terms::PaymentTermsDeactivated::KIND,
+        schema::<TxOutcome<deactivate_payment_terms::PaymentTermsDeactivated>>,
+    ),
+    (
+        apply_payment_terms::CatalogApplied::KIND,
+        schema::<apply_payment_terms::CatalogApplied>,
+    ),
+    (
+        import_payment_terms::TermsImport::KIND,
+        schema::<import_payment_terms::TermsImport>,
+    ),
+    (
+        list_agreements::AgreementList::KIND,
+        schema::<list_agreements::AgreementList>,
+    ),
+    (
+        show_agreement::AgreementDetails::KIND,
+        schema::<show_agreement::AgreementDetails>,
+    ),
+    (
+        agreement_lifecycle::AgreementLifecycleResult::KIND,
+        schema::<TxOutcome<agreement_lifecycle::AgreementLifecycleResult>>,
+    ),
+    (
+        dashboard::PayeeOverviewReport::KIND,
+        schema::<dashboard::PayeeOverviewReport>,
+    ),
+    (
+        dashboard::PaymentTermsAnalyticsReport::KIND,
+        schema::<dashboard::PaymentTermsAnalyticsReport>,
+    ),
+    (dashboard::EventList::KIND, schema::<dashboard::EventList>),
+    (
+        dashboard::SubscriptionList::KIND,
+        schema::<dashboard::SubscriptionList>,
+    ),
+    (EventEntry::KIND, schema::<EventEntry>),
+    (
+        event_stream::EventsFollowed::KIND,
+        schema::<event_stream::EventsFollowed>,
+    ),
+    (ledger::RevenueLedger::KIND, schema::<ledger::RevenueLedger>),
+    (admin::AdminResult::KIND, schema::<admin::AdminResult>),
+    (keeper::KeeperReport::KIND, schema::<keeper::KeeperReport>),
+    (tx::TxSigned::KIND, schema::<tx::TxSigned>),
+    (tx::TxSubmitted::KIND, schema::<tx::TxSubmitted>),
+    (tx::TxDetails::KIND, schema::<tx::TxDetails>),
+    (wallet::WalletStored::KIND, schema::<wallet::WalletStored>),
+    (wallet::WalletList::KIND, schema::<wallet::WalletList>),
+    (
+        wallet::WalletExported::KIND,
+        schema::<wallet::WalletExported>,
+    ),
+    (doctor::DoctorReport::KIND, schema::<doctor::DoctorReport>),
+    (
+        explain_error::ErrorExplanation::KIND,
+        schema::<explain_error::ErrorExplanation>,
+    ),
+    (SchemaList::KIND, schema::<SchemaList>),
+    (SchemaDocument::KIND, schema::<SchemaDocument>),
+    (Message::KIND, schema::<Message>),
+    (ERROR_KIND, error_schema),
+];
+
+/// Result kinds listed by `schema`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SchemaList {
+    pub schema_version: u32,
+    pub kinds: Vec<String>,
+}
+
+impl CommandOutput for SchemaList {
+    const KIND: &'static str = "schema.list";
+
+    fn human(&self) -> Result<String> {
+        let mut output = format!("Result kinds (schema version {}):\n", self.schema_version);
+        for kind in &self.kinds {
+            writeln!(output, "  {kind}")?;
+        }
+        write!(
+            output,
+            "\nPrint a schema with: tally-merchant schema <KIND>"
+        )?;
+        Ok(output)
+    }
+}
+
+/// A JSON Schema printed by `schema <KIND>`
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(transparent)]
+pub struct SchemaDocument(pub Value);
+
+impl CommandOutput for SchemaDocument {
+    const KIND: &'static str = "schema";
+
+    fn human(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.0)?)
+    }
+}
+
+/// Execute the schema command
+///
+/// # Errors
+/// Returns error if `kind` is not a known result kind
+pub fn execute(kind: Option<&str>) -> Result<Output> {
+    let Some(kind) = kind else {
+        return Ok(SchemaList {
+            schema_version: SCHEMA_VERSION,
+            kinds: SCHEMAS
+                .iter()
+                .map(|(kind, _)| (*kind).to_string())
+                .collect(),
+        }
+        .into());
+    };
+
+    let (_, schema) = SCHEMAS
+        .iter()
+        .find(|(name, _)| *name == kind)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown result kind '{kind}'.\n\
+                 Run 'tally-merchant schema' to list every kind."
+            )
+        })?;
+    Ok(SchemaDocument(schema()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_kinds_are_unique_and_schemas_pin_them() {
+        let mut seen = HashSet::new();
+        for (kind, schema) in SCHEMAS {
+            assert!(seen.insert(*kind), "duplicate kind {kind}");
+            let schema = schema();
+            if *kind != ERROR_KIND {
+                assert_eq!(schema["properties"]["kind"]["const"], *kind);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nested_result_kinds_differ_from_listed_kinds() {
+        // Only ever emitted inside the listed results, but a shared kind would
+        // still make two payload shapes indistinguishable
+        let nested = [
+            admin::AdminSubmitted::KIND,
+            apply_payment_terms::ChangesApplied::KIND,
+            import_payment_terms::TermsCreated::KIND,
+        ];
+        for kind in nested {
+            assert!(
+                SCHEMAS.iter().all(|(listed, _)| *listed != kind),
+                "kind {kind} is shared with a listed result"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_kind() {
+        let err = execute(Some("nope")).err().unwrap().to_string();
+        assert!(err.contains("Unknown result kind 'nope'"));
+    }
+}
@@ -1,9 +1,14 @@
 //! Show payment agreement account details
 
 use crate::config::TallyCliConfig;
+use crate::errors::enhance_account_not_found_error;
+use crate::output::CommandOutput;
+use crate::utils::amount::format_usdc_micro_units;
 use crate::utils::colors::Theme;
 use crate::utils::formatting;
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::fmt::Write as _;
 use std::str::FromStr;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
@@ -13,78 +18,40 @@ use tally_sdk::SimpleTallyClient;
 pub struct ShowAgreementRequest<'a> {
     /// Payment Agreement PDA address
     pub agreement: &'a str,
-    /// Output format
-    pub output_format: &'a str,
 }
 
-/// Execute the show-agreement command
-///
-/// # Arguments
-/// * `tally_client` - The Tally SDK client
-/// * `request` - The show agreement request parameters
-/// * `config` - CLI configuration
-///
-/// # Returns
-/// * `Ok(String)` - Formatted agreement details
-///
-/// # Errors
-/// Returns an error if:
-/// * Agreement public key cannot be parsed
-/// * Failed to fetch agreement account from RPC
-/// * Agreement account not found
-/// * JSON serialization fails
-///
-/// # Panics
-/// This function does not panic under normal operation.
-pub async fn execute(
-    tally_client: &SimpleTallyClient,
-    request: &ShowAgreementRequest<'_>,
-    _config: &TallyCliConfig,
-) -> Result<String> {
-    // Parse agreement address
-    let agreement_address = Pubkey::from_str(request.agreement)
-        .context("Failed to parse payment agreement public key")?;
-
-    // Fetch payment agreement account
-    let agreement = tally_client
-        .get_payment_agreement(&agreement_address)
-        .context(
-            "Failed to fetch payment agreement account - check RPC connection and account state",
-        )?
-        .context("Payment agreement account not found")?;
+/// A payment agreement as shown by `agreement show`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AgreementDetails {
+    pub agreement: String,
+    pub payment_terms: String,
+    pub payer: String,
+    pub next_payment_ts: i64,
+    pub next_payment_human: String,
+    pub active: bool,
+    pub payment_count: u32,
+    pub created_ts: i64,
+    pub created_human: String,
+    pub last_amount_microlamports: u64,
+    /// Exact decimal amount, e.g. "10.500000"
+    pub last_amount_usdc: String,
+    pub last_amount_display: String,
+    pub last_payment_ts: i64,
+    pub last_payment_human: String,
+    pub bump: u8,
+}
 
-    // Create type-safe amount
-    let last_amount = tally_sdk::UsdcAmount::from_microlamports(agreement.last_amount);
+impl CommandOutput for AgreementDetails {
+    const KIND: &'static str = "agreement.show";
 
-    // Format output based on requested format
-    if request.output_format == "json" {
-        let json_output = serde_json::json!({
-            "agreement": request.agreement,
-            "payment_terms": agreement.payment_terms.to_string(),
-            "payer": agreement.payer.to_string(),
-            "next_payment_ts": agreement.next_payment_ts,
-            "next_payment_human": formatting::format_timestamp(agreement.next_payment_ts),
-            "active": agreement.active,
-            "payment_count": agreement.payment_count,
-            "created_ts": agreement.created_ts,
-            "created_human": formatting::format_timestamp(agreement.created_ts),
-            "last_amount_microlamports": last_amount.microlamports(),
-            "last_amount_usdc": last_amount.usdc(),
-            "last_amount_display": last_amount.to_string(),
-            "last_payment_ts": agreement.last_payment_ts,
-            "last_payment_human": formatting::format_timestamp(agreement.last_payment_ts),
-            "bump": agreement.bump,
-        });
-        Ok(serde_json::to_string_pretty(&json_output)?)
-    } else {
-        // Human-readable output with colors
-        let status_text = if agreement.active {
+    fn human(&self) -> Result<String> {
+        let status_text = if self.active {
             Theme::active("Active")
         } else {
             Theme::inactive("Paused")
         };
 
-        let active_status = if agreement.active {
+        let active_status = if self.active {
             Theme::active("Yes")
         } else {
             Theme::inactive("No")
@@ -101,19 +68,19 @@ pub async fn execute(
             &mut output,
             "{:<22} {}",
             Theme::info("Agreement PDA:"),
-            Theme::highlight(request.agreement)
+            Theme::highlight(&self.agreement)
         )?;
         writeln!(
             &mut output,
             "{:<22} {}",
             Theme::info("Payment Terms:"),
-            Theme::dim(&agreement.payment_terms.to_string())
+            Theme::dim(&self.payment_terms)
         )?;
         writeln!(
             &mut output,
             "{:<22} {}",
             Theme::info("Payer:"),
-            Theme::value(&agreement.payer.to_string())
+            Theme::value(&self.payer)
         )?;
         writeln!(
             &mut output,
@@ -131,45 +98,102 @@ pub async fn execute(
             &mut output,
             "{:<22} {} ({})",
             Theme::info("Next Payment:"),
-            agreement.next_payment_ts,
-            Theme::dim(&formatting::format_timestamp(agreement.next_payment_ts))
+            self.next_payment_ts,
+            Theme::dim(&self.next_payment_human)
         )?;
         writeln!(
             &mut output,
             "{:<22} {} ({})",
             Theme::info("Created:"),
-            agreement.created_ts,
-            Theme::dim(&formatting::format_timestamp(agreement.created_ts))
+            self.created_ts,
+            Theme::dim(&self.created_human)
         )?;
         writeln!(
             &mut output,
             "{:<22} {}",
             Theme::info("Last Amount:"),
-            Theme::value(&last_amount.to_string())
+            Theme::value(&self.last_amount_display)
         )?;
         writeln!(
             &mut output,
             "{:<22} {} ({})",
             Theme::info("Last Payment:"),
-            agreement.last_payment_ts,
-            Theme::dim(&formatting::format_timestamp(agreement.last_payment_ts))
+            self.last_payment_ts,
+            Theme::dim(&self.last_payment_human)
         )?;
         writeln!(
             &mut output,
             "{:<22} {}",
             Theme::info("Payment Count:"),
-            Theme::value(&agreement.payment_count.to_string())
+            Theme::value(&self.payment_count.to_string())
         )?;
         write!(
             &mut output,
             "{:<22} {}",
             Theme::info("Bump:"),
-            Theme::dim(&agreement.bump.to_string())
+            Theme::dim(&self.bump.to_string())
         )?;
         Ok(output)
     }
 }
 
+/// Execute the show-agreement command
+///
+/// # Arguments
+/// * `tally_client` - The Tally SDK client
+/// * `request` - The show agreement request parameters
+/// * `config` - CLI configuration
+///
+/// # Returns
+/// * `Ok(AgreementDetails)` - Agreement details
+///
+/// # Errors
+/// Returns an error if:
+/// * Agreement public key cannot be parsed
+/// * Failed to fetch agreement account from RPC
+/// * Agreement account not found
+///
+/// # Panics
+/// This function does not panic under normal operation.
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &ShowAgreementRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<AgreementDetails> {
+    // Parse agreement address
+    let agreement_address = Pubkey::from_str(request.agreement)
+        .context("Failed to parse payment agreement public key")?;
+
+    // Fetch payment agreement account
+    let agreement = tally_client
+        .get_payment_agreement(&agreement_address)
+        .context(
+            "Failed to fetch payment agreement account - check RPC connection and account state",
+        )?
+        .ok_or_else(|| enhance_account_not_found_error("payment agreement", &agreement_address))?;
+
+    // Create type-safe amount
+    let last_amount = tally_sdk::UsdcAmount::from_microlamports(agreement.last_amount);
+
+    Ok(AgreementDetails {
+        agreement: request.agreement.to_string(),
+        payment_terms: agreement.payment_terms.to_string(),
+        payer: agreement.payer.to_string(),
+        next_payment_ts: agreement.next_payment_ts,
+        next_payment_human: formatting::format_timestamp(agreement.next_payment_ts),
+        active: agreement.active,
+        payment_count: agreement.payment_count,
+        created_ts: agreement.created_ts,
+        created_human: formatting::format_timestamp(agreement.created_ts),
+        last_amount_microlamports: last_amount.microlamports(),
+        last_amount_usdc: format_usdc_micro_units(last_amount.microlamports()),
+        last_amount_display: last_amount.to_string(),
+        last_payment_ts: agreement.last_payment_ts,
+        last_payment_human: formatting::format_timestamp(agreement.last_payment_ts),
+        bump: agreement.bump,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,9 +202,7 @@ mod tests {
     fn test_request_creation() {
         let request = ShowAgreementRequest {
             agreement: "11111111111111111111111111111111",
-            output_format: "human",
         };
         assert_eq!(request.agreement, "11111111111111111111111111111111");
-        assert_eq!(request.output_format, "human");
     }
 }
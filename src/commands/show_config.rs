@@ -1,73 +1,72 @@
 //! Show global configuration account details
 
+use crate::errors::config_account_not_found;
+use crate::output::CommandOutput;
+use crate::utils::amount::format_usdc_micro_units;
 use crate::utils::colors::Theme;
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::fmt::Write as _;
+use tally_sdk::program_types::Config;
 use tally_sdk::{BasisPoints, SimpleTallyClient, UsdcAmount};
 
-/// Request to show config details
-pub struct ShowConfigRequest<'a> {
-    /// Output format
-    pub output_format: &'a str,
+/// The global config account as shown by `config show`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ConfigDetails {
+    pub platform_authority: String,
+    pub pending_authority: Option<String>,
+    pub max_platform_fee_bps: u16,
+    pub max_platform_fee_pct: f64,
+    pub min_platform_fee_bps: u16,
+    pub min_platform_fee_pct: f64,
+    pub min_period_seconds: u64,
+    pub default_allowance_periods: u8,
+    pub allowed_mint: String,
+    pub max_withdrawal_amount: u64,
+    /// Exact decimal amount, e.g. "10.500000"
+    pub max_withdrawal_amount_usdc: String,
+    pub max_grace_period_seconds: u64,
+    pub paused: bool,
+    pub keeper_fee_bps: u16,
+    pub keeper_fee_pct: f64,
+    pub bump: u8,
 }
 
-/// Execute the show-config command
-///
-/// # Arguments
-/// * `tally_client` - The Tally SDK client
-/// * `request` - The show config request parameters
-/// * `config` - CLI configuration
-///
-/// # Returns
-/// * `Ok(String)` - Formatted config details
-///
-/// # Errors
-/// Returns an error if:
-/// * Failed to fetch config account from RPC
-/// * Config account not found
-/// * JSON serialization fails
-///
-/// # Panics
-/// May panic if platform fee basis points exceed maximum allowed value (10000).
-/// This should not occur under normal operation as values are validated on-chain.
-pub async fn execute(
-    tally_client: &SimpleTallyClient,
-    request: &ShowConfigRequest<'_>,
-) -> Result<String> {
-    // Fetch config account
-    let cfg = tally_client
-        .get_config()
-        .context("Failed to fetch config account - check RPC connection and account state")?
-        .context("Config account not found - has init-config been run?")?;
+impl ConfigDetails {
+    /// Describe a config account
+    ///
+    /// # Errors
+    /// Returns error if a fee exceeds 10000 basis points
+    pub fn new(cfg: &Config) -> Result<Self> {
+        Ok(Self {
+            platform_authority: cfg.platform_authority.to_string(),
+            pending_authority: cfg.pending_authority.map(|p| p.to_string()),
+            max_platform_fee_bps: cfg.max_platform_fee_bps,
+            max_platform_fee_pct: BasisPoints::new(cfg.max_platform_fee_bps)?.percentage(),
+            min_platform_fee_bps: cfg.min_platform_fee_bps,
+            min_platform_fee_pct: BasisPoints::new(cfg.min_platform_fee_bps)?.percentage(),
+            min_period_seconds: cfg.min_period_seconds,
+            default_allowance_periods: cfg.default_allowance_periods,
+            allowed_mint: cfg.allowed_mint.to_string(),
+            max_withdrawal_amount: cfg.max_withdrawal_amount,
+            max_withdrawal_amount_usdc: format_usdc_micro_units(cfg.max_withdrawal_amount),
+            max_grace_period_seconds: cfg.max_grace_period_seconds,
+            paused: cfg.paused,
+            keeper_fee_bps: cfg.keeper_fee_bps,
+            keeper_fee_pct: BasisPoints::new(cfg.keeper_fee_bps)?.percentage(),
+            bump: cfg.bump,
+        })
+    }
+}
+
+impl CommandOutput for ConfigDetails {
+    const KIND: &'static str = "config.show";
 
-    // Format output based on requested format
-    if request.output_format == "json" {
-        let json_output = serde_json::json!({
-            "platform_authority": cfg.platform_authority.to_string(),
-            "pending_authority": cfg.pending_authority.map(|p| p.to_string()),
-            "max_platform_fee_bps": cfg.max_platform_fee_bps,
-            "max_platform_fee_pct": BasisPoints::new(cfg.max_platform_fee_bps).expect("Valid fee").percentage(),
-            "min_platform_fee_bps": cfg.min_platform_fee_bps,
-            "min_platform_fee_pct": BasisPoints::new(cfg.min_platform_fee_bps).expect("Valid fee").percentage(),
-            "min_period_seconds": cfg.min_period_seconds,
-            "default_allowance_periods": cfg.default_allowance_periods,
-            "allowed_mint": cfg.allowed_mint.to_string(),
-            "max_withdrawal_amount": cfg.max_withdrawal_amount,
-            "max_withdrawal_amount_usdc": UsdcAmount::from_microlamports(cfg.max_withdrawal_amount).usdc(),
-            "max_grace_period_seconds": cfg.max_grace_period_seconds,
-            "paused": cfg.paused,
-            "keeper_fee_bps": cfg.keeper_fee_bps,
-            "keeper_fee_pct": BasisPoints::new(cfg.keeper_fee_bps).expect("Valid fee").percentage(),
-            "bump": cfg.bump,
-        });
-        Ok(serde_json::to_string_pretty(&json_output)?)
-    } else {
-        // Human-readable output with colors
-        let pending_auth = cfg
-            .pending_authority
-            .map_or_else(|| "None".to_string(), |p| p.to_string());
+    fn human(&self) -> Result<String> {
+        let pending_auth = self.pending_authority.as_deref().unwrap_or("None");
 
-        let paused_status = if cfg.paused {
+        let paused_status = if self.paused {
             Theme::warning("Yes")
         } else {
             Theme::active("No")
@@ -80,71 +79,63 @@ pub async fn execute(
             &mut output,
             "{:<26} {}",
             Theme::info("Platform Authority:"),
-            Theme::highlight(&cfg.platform_authority.to_string())
+            Theme::highlight(&self.platform_authority)
         )?;
         writeln!(
             &mut output,
             "{:<26} {}",
             Theme::info("Pending Authority:"),
-            Theme::dim(&pending_auth)
+            Theme::dim(pending_auth)
         )?;
         writeln!(
             &mut output,
             "{:<26} {} bps ({}%)",
             Theme::info("Max Platform Fee:"),
-            cfg.max_platform_fee_bps,
-            Theme::value(
-                &BasisPoints::new(cfg.max_platform_fee_bps)?
-                    .percentage()
-                    .to_string()
-            )
+            self.max_platform_fee_bps,
+            Theme::value(&self.max_platform_fee_pct.to_string())
         )?;
         writeln!(
             &mut output,
             "{:<26} {} bps ({}%)",
             Theme::info("Min Platform Fee:"),
-            cfg.min_platform_fee_bps,
-            Theme::value(
-                &BasisPoints::new(cfg.min_platform_fee_bps)?
-                    .percentage()
-                    .to_string()
-            )
+            self.min_platform_fee_bps,
+            Theme::value(&self.min_platform_fee_pct.to_string())
         )?;
         writeln!(
             &mut output,
             "{:<26} {} seconds ({} days)",
             Theme::info("Min Period:"),
-            cfg.min_period_seconds,
-            cfg.min_period_seconds / 86400
+            self.min_period_seconds,
+            self.min_period_seconds / 86400
         )?;
         writeln!(
             &mut output,
             "{:<26} {}",
             Theme::info("Default Allowance Periods:"),
-            Theme::value(&cfg.default_allowance_periods.to_string())
+            Theme::value(&self.default_allowance_periods.to_string())
         )?;
         writeln!(
             &mut output,
             "{:<26} {}",
             Theme::info("Allowed Mint (USDC):"),
-            Theme::dim(&cfg.allowed_mint.to_string())
+            Theme::dim(&self.allowed_mint)
         )?;
         writeln!(
             &mut output,
             "{:<26} {} micro-units ({} USDC)",
             Theme::info("Max Withdrawal Amount:"),
-            cfg.max_withdrawal_amount,
+            self.max_withdrawal_amount,
             Theme::value(&format!(
                 "{:.6}",
-                UsdcAmount::from_microlamports(cfg.max_withdrawal_amount)
+                UsdcAmount::from_microlamports(self.max_withdrawal_amount)
             ))
         )?;
         writeln!(
             &mut output,
             "{:<26} {} seconds ({} days)",
             Theme::info("Max Grace Period:"),
-            cfg.max_grace_period_seconds,
-            cfg.max_grace_period_seconds / 86400
+            self.max_grace_period_seconds,
+            self.max_grace_period_seconds / 86400
         )?;
         writeln!(
             &mut output,
@@ -156,33 +147,70 @@ pub async fn execute(
             &mut output,
             "{:<26} {} bps ({}%)",
             Theme::info("Keeper Fee:"),
-            cfg.keeper_fee_bps,
-            Theme::value(&BasisPoints::new(cfg.keeper_fee_bps).expect("Valid fee").to_string())
+            self.keeper_fee_bps,
+            Theme::value(&BasisPoints::new(self.keeper_fee_bps)?.to_string())
         )?;
         write!(
             &mut output,
             "{:<26} {}",
             Theme::info("Bump:"),
-            Theme::dim(&cfg.bump.to_string())
+            Theme::dim(&self.bump.to_string())
         )?;
         Ok(output)
     }
 }
 
+/// Execute the show-config command
+///
+/// # Arguments
+/// * `tally_client` - The Tally SDK client
+///
+/// # Returns
+/// * `Ok(ConfigDetails)` - Config details
+///
+/// # Errors
+/// Returns an error if:
+/// * Failed to fetch config account from RPC
+/// * Config account not found
+/// * A fee exceeds the maximum allowed value (10000 bps)
+pub async fn execute(tally_client: &SimpleTallyClient) -> Result<ConfigDetails> {
+    // Fetch config account
+    let cfg = tally_client
+        .get_config()
+        .context("Failed to fetch config account - check RPC connection and account state")?
+        .ok_or_else(config_account_not_found)?;
+
+    ConfigDetails::new(&cfg)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tally_sdk::solana_sdk::pubkey::Pubkey;
 
     #[test]
-    fn test_request_creation() {
-        let request = ShowConfigRequest {
-            output_format: "human",
+    fn test_config_details() {
+        let authority = Pubkey::new_unique();
+        let cfg = Config {
+            platform_authority: authority,
+            pending_authority: None,
+            max_platform_fee_bps: 100,
+            min_platform_fee_bps: 10,
+            min_period_seconds: 86_400,
+            default_allowance_periods: 3,
+            allowed_mint: Pubkey::new_unique(),
+            max_withdrawal_amount: 1_000_000_000,
+            max_grace_period_seconds: 604_800,
+            paused: true,
+            keeper_fee_bps: 25,
+            bump: 255,
         };
-        assert_eq!(request.output_format, "human");
+        let details = ConfigDetails::new(&cfg).unwrap();
+        assert_eq!(details.platform_authority, authority.to_string());
+        assert_eq!(details.pending_authority, None);
 
-        let request_json = ShowConfigRequest {
-            output_format: "json",
-        };
-        assert_eq!(request_json.output_format, "json");
+        let human = details.human().unwrap();
+        assert!(human.contains("Global Configuration"));
+        assert!(human.contains("None"));
     }
 }
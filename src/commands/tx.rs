@@ -0,0 +1,727 @@
+//! Transaction commands: sign an exported transaction, submit it, and
+//! inspect a sent one
+//!
+//! `tx sign` never touches the network, so it can run on an air-gapped
+//! machine that holds the authority key. `tx show` decodes a confirmed
+//! transaction with [`crate::utils::tx_decode`].
+
+use crate::config::TallyCliConfig;
+use crate::context::{program_id_missing, RuntimeContext, SOLANA_CLI_DEFAULT_WALLET};
+use crate::errors::{
+    enhance_transaction_error, preflight_logs, CliError, ErrorCode, ProgramErrorCode,
+};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::formatting::{format_timestamp, lamports_to_sol};
+use crate::utils::offline::{
+    format_signer_status, read_transaction_file, sign_transaction, signer_status,
+    write_transaction_file, SignerStatus,
+};
+use crate::utils::program_errors::relevant_logs;
+use crate::utils::progress;
+use crate::utils::signer::load_signer;
+use crate::utils::transaction::is_program_failure;
+use crate::utils::tx_decode::{
+    self, DecodedEvent, DecodedInstruction, FetchedTransaction, TallyAccount, TokenBalanceChange,
+};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::Signature;
+use tally_sdk::solana_sdk::transaction::Transaction;
+use tally_sdk::{SimpleTallyClient, UsdcAmount};
+use tracing::{info, warn};
+
+/// Arguments for signing an exported transaction
+pub struct SignTxRequest<'a> {
+    /// Transaction file written by `--export-tx`
+    pub input: &'a Path,
+    /// Where to write the signed transaction (defaults to `input`)
+    pub output: Option<&'a Path>,
+    pub keypair_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+}
+
+/// Arguments for submitting a signed transaction
+pub struct SubmitTxRequest<'a> {
+    pub input: &'a Path,
+}
+
+/// Arguments for inspecting a sent transaction
+pub struct ShowTxRequest<'a> {
+    pub context: &'a RuntimeContext,
+    pub signature: &'a str,
+}
+
+/// A sent transaction as decoded by `tx show`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TxDetails {
+    pub signature: String,
+    pub slot: u64,
+    /// Unix timestamp, when the RPC knows it
+    pub block_time: Option<i64>,
+    pub fee_payer: String,
+    pub fee_lamports: u64,
+    /// The transaction's error as reported by the RPC, `None` if it succeeded
+    pub error: Option<String>,
+    /// Decoded program error behind a failure
+    pub program_error: Option<ProgramErrorCode>,
+    pub instructions: Vec<DecodedInstruction>,
+    /// Tally accounts the transaction touched
+    pub tally_accounts: Vec<TallyAccount>,
+    pub events: Vec<DecodedEvent>,
+    pub token_balance_changes: Vec<TokenBalanceChange>,
+    pub logs: Vec<String>,
+}
+
+impl CommandOutput for TxDetails {
+    const KIND: &'static str = "tx.show";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::header("Transaction"),
+            Theme::highlight(&self.signature)
+        )?;
+        match &self.error {
+            Some(error) => writeln!(&mut output, "  Status:    {}", Theme::error(error))?,
+            None => writeln!(&mut output, "  Status:    {}", Theme::success("Succeeded"))?,
+        }
+        writeln!(&mut output, "  Slot:      {}", self.slot)?;
+        if let Some(block_time) = self.block_time {
+            writeln!(&mut output, "  Time:      {}", format_timestamp(block_time))?;
+        }
+        writeln!(&mut output, "  Fee payer: {}", self.fee_payer)?;
+        writeln!(
+            &mut output,
+            "  Fee:       {} SOL",
+            lamports_to_sol(self.fee_lamports)
+        )?;
+        if let Some(program_error) = &self.program_error {
+            writeln!(&mut output, "  Cause:     {program_error}")?;
+            if let Some(explanation) = &program_error.explanation {
+                writeln!(&mut output, "             {}", Theme::dim(explanation))?;
+            }
+        }
+
+        writeln!(&mut output, "\n{}", Theme::header("Instructions"))?;
+        for instruction in &self.instructions {
+            format_instruction(&mut output, instruction)?;
+        }
+
+        if !self.tally_accounts.is_empty() {
+            writeln!(&mut output, "\n{}", Theme::header("Tally accounts"))?;
+            for account in &self.tally_accounts {
+                writeln!(
+                    &mut output,
+                    "  {:<18} {}",
+                    Theme::info(&account.kind),
+                    account.address
+                )?;
+            }
+        }
+
+        if !self.events.is_empty() {
+            writeln!(&mut output, "\n{}", Theme::header("Events"))?;
+            for event in &self.events {
+                format_event(&mut output, event)?;
+            }
+        }
+
+        if !self.token_balance_changes.is_empty() {
+            writeln!(&mut output, "\n{}", Theme::header("Token balance changes"))?;
+            for change in &self.token_balance_changes {
+                let amount = format_token_change(change.change, change.decimals);
+                let amount = if change.change < 0 {
+                    Theme::error(&amount)
+                } else {
+                    Theme::success(&amount)
+                };
+                let role = change
+                    .role
+                    .as_deref()
+                    .map_or_else(String::new, |role| format!(" ({role})"));
+                writeln!(&mut output, "  {}{role}  {amount}", change.account)?;
+            }
+        }
+
+        if self.error.is_some() && !self.logs.is_empty() {
+            writeln!(&mut output, "\n{}", Theme::header("Program logs"))?;
+            for line in relevant_logs(&self.logs) {
+                writeln!(&mut output, "  {}", Theme::dim(&line))?;
+            }
+        }
+        Ok(output.trim_end().to_string())
+    }
+}
+
+/// A transaction signed by `tx sign`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TxSigned {
+    pub signer: String,
+    /// File the signed transaction was written to
+    pub file: String,
+    pub signers: Vec<SignerStatus>,
+    /// Whether every required signature is present
+    pub complete: bool,
+}
+
+impl CommandOutput for TxSigned {
+    const KIND: &'static str = "tx.sign";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::success("Signed as"),
+            Theme::highlight(&self.signer)
+        )?;
+        writeln!(&mut output, "{} {}", Theme::info("File:"), self.file)?;
+        write!(&mut output, "{}", format_signer_status(&self.signers)?)?;
+        if self.complete {
+            write!(
+                &mut output,
+                "\n{}\n  tally-merchant tx submit {}",
+                Theme::info("All signatures present. Submit from an online machine with:"),
+                self.file
+            )?;
+        }
+        Ok(output)
+    }
+}
+
+/// A transaction submitted by `tx submit`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TxSubmitted {
+    pub signature: String,
+}
+
+impl CommandOutput for TxSubmitted {
+    const KIND: &'static str = "tx.submit";
+
+    fn human(&self) -> Result<String> {
+        Ok(format!(
+            "{}\n{} {}",
+            Theme::success("Transaction submitted successfully!"),
+            Theme::info("Transaction signature:"),
+            self.signature
+        ))
+    }
+}
+
+/// Execute the tx sign command
+///
+/// # Errors
+/// Returns error if the file cannot be read or written, the keypair is not a
+/// required signer, or the user declines the confirmation prompt
+pub fn sign(request: &SignTxRequest<'_>) -> Result<TxSigned> {
+    let mut transaction = read_transaction_file(request.input)?;
+    let keypair = load_signer(request.keypair_path.unwrap_or(SOLANA_CLI_DEFAULT_WALLET))
+        .context("Failed to load signing keypair")?;
+
+    if !request.skip_confirmation {
+        eprintln!("{}", describe_transaction(&transaction)?);
+        let confirmed = Confirm::new()
+            .with_prompt(format!("Sign this transaction as {}?", keypair.pubkey()))
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Signing canceled by user"));
+        }
+    }
+
+    sign_transaction(&mut transaction, keypair.as_ref())?;
+    let output_path = request.output.unwrap_or(request.input);
+    write_transaction_file(output_path, &transaction)?;
+    info!("Signed transaction as {}", keypair.pubkey());
+
+    Ok(TxSigned {
+        signer: keypair.pubkey().to_string(),
+        file: output_path.display().to_string(),
+        signers: signer_status(&transaction),
+        complete: transaction.is_signed(),
+    })
+}
+
+/// Execute the tx submit command
+///
+/// # Errors
+/// Returns error if the file is invalid, signatures are missing or invalid,
+/// or the transaction fails
+pub async fn submit(
+    tally_client: &SimpleTallyClient,
+    request: &SubmitTxRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxSubmitted> {
+    let transaction = read_transaction_file(request.input)?;
+    ensure_fully_signed(&transaction)?;
+
+    let spinner = progress::create_spinner("Submitting signed transaction...");
+    let result = tally_client
+        .rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .map_err(|e| {
+            let mut logs = preflight_logs(&e);
+            if logs.is_empty() && is_program_failure(&e.to_string()) {
+                // Preflight was skipped or passed; replay the transaction for its logs
+                logs = tally_client
+                    .rpc_client
+                    .simulate_transaction(&transaction)
+                    .ok()
+                    .and_then(|response| response.value.logs)
+                    .unwrap_or_default();
+            }
+            enhance_transaction_error("Failed to submit transaction", &e, &logs)
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Transaction confirmed");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to submit transaction");
+        }
+    }
+
+    Ok(TxSubmitted {
+        signature: result?.to_string(),
+    })
+}
+
+/// Execute the tx show command
+///
+/// # Errors
+/// Returns error if the signature or program ID is invalid, or the
+/// transaction cannot be fetched
+pub fn show(tally_client: &SimpleTallyClient, request: &ShowTxRequest<'_>) -> Result<TxDetails> {
+    let signature = Signature::from_str(request.signature).map_err(|e| {
+        CliError::new(
+            ErrorCode::InvalidAddress,
+            format!("Invalid transaction signature '{}': {e}", request.signature),
+        )
+    })?;
+    let program_id = request
+        .context
+        .program_id
+        .as_ref()
+        .ok_or_else(program_id_missing)?;
+    let program_id = Pubkey::from_str(&program_id.value)
+        .map_err(|e| anyhow!("Invalid program ID '{}': {e}", program_id.value))?;
+
+    let fetched = tx_decode::fetch_transaction(&tally_client.rpc_client, &signature)?;
+    let logs = fetched.logs();
+    let instructions = tx_decode::decode_instructions(&fetched, &program_id);
+    let events = tx_decode::decode_events(&logs);
+    let tally_accounts = tally_accounts(tally_client, &program_id, &instructions, &events);
+
+    let mut token_balance_changes =
+        tx_decode::token_balance_changes(&fetched.meta, &fetched.account_keys);
+    let parties = parties(tally_client, &tally_accounts, &events);
+    for change in &mut token_balance_changes {
+        change.role = token_role(change, &parties).map(str::to_string);
+    }
+
+    let (error, program_error) = tx_decode::transaction_error(&fetched.meta)
+        .map_or((None, None), |(error, program_error)| {
+            (Some(error), program_error)
+        });
+    Ok(TxDetails {
+        signature: signature.to_string(),
+        slot: fetched.slot,
+        block_time: fetched.block_time,
+        fee_payer: fee_payer(&fetched),
+        fee_lamports: fetched.fee(),
+        error,
+        program_error,
+        instructions,
+        tally_accounts,
+        events,
+        token_balance_changes,
+        logs,
+    })
+}
+
+fn fee_payer(fetched: &FetchedTransaction) -> String {
+    fetched
+        .account_keys
+        .first()
+        .map_or_else(String::new, ToString::to_string)
+}
+
+/// Tally accounts passed to Tally instructions or named by events
+///
+/// Accounts still open are recognized by their data; those closed by the
+/// transaction are only known from its events.
+fn tally_accounts(
+    tally_client: &SimpleTallyClient,
+    program_id: &Pubkey,
+    instructions: &[DecodedInstruction],
+    events: &[DecodedEvent],
+) -> Vec<TallyAccount> {
+    let mut addresses: Vec<Pubkey> = Vec::new();
+    for instruction in instructions.iter().filter(|i| i.name.is_some()) {
+        for address in instruction.accounts.iter().filter_map(|a| a.parse().ok()) {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+    }
+
+    let mut accounts = Vec::new();
+    match tally_client.rpc_client.get_multiple_accounts(&addresses) {
+        Ok(fetched) => {
+            for (address, account) in addresses.iter().zip(fetched) {
+                let kind = account
+                    .filter(|account| account.owner == *program_id)
+                    .and_then(|account| tx_decode::account_kind(&account.data));
+                if let Some(kind) = kind {
+                    accounts.push(TallyAccount {
+                        address: address.to_string(),
+                        kind: kind.to_string(),
+                    });
+                }
+            }
+        }
+        Err(e) => warn!("Failed to fetch the transaction's accounts: {e}"),
+    }
+
+    for event in events {
+        for (address, kind) in [
+            (&event.payee, "payee"),
+            (&event.payment_terms, "payment-terms"),
+        ] {
+            let Some(address) = address else { continue };
+            if !accounts.iter().any(|account| account.address == *address) {
+                accounts.push(TallyAccount {
+                    address: address.clone(),
+                    kind: kind.to_string(),
+                });
+            }
+        }
+    }
+    accounts
+}
+
+/// Owners and accounts whose token balance changes are labelled, by role
+fn parties(
+    tally_client: &SimpleTallyClient,
+    tally_accounts: &[TallyAccount],
+    events: &[DecodedEvent],
+) -> Vec<(String, &'static str)> {
+    let mut parties = Vec::new();
+    for account in tally_accounts.iter().filter(|a| a.kind == "payee") {
+        let payee = Pubkey::from_str(&account.address)
+            .ok()
+            .and_then(|address| tally_client.get_payee(&address).ok().flatten());
+        if let Some(payee) = payee {
+            parties.push((payee.treasury_ata.to_string(), "treasury"));
+            parties.push((payee.authority.to_string(), "payee-authority"));
+        }
+    }
+    if let Ok(Some(config)) = tally_client.get_config() {
+        parties.push((config.platform_authority.to_string(), "platform"));
+    }
+    for event in events {
+        if let Some(keeper) = &event.keeper {
+            parties.push((keeper.clone(), "keeper"));
+        }
+        if let Some(payer) = &event.payer {
+            parties.push((payer.clone(), "payer"));
+        }
+    }
+    parties
+}
+
+/// Role of a token account, matched on its address or owner
+fn token_role(
+    change: &TokenBalanceChange,
+    parties: &[(String, &'static str)],
+) -> Option<&'static str> {
+    parties
+        .iter()
+        .find(|(address, _)| *address == change.account || change.owner.as_ref() == Some(address))
+        .map(|(_, role)| *role)
+}
+
+fn format_instruction(output: &mut String, instruction: &DecodedInstruction) -> Result<()> {
+    let program = instruction
+        .program
+        .clone()
+        .unwrap_or_else(|| instruction.program_id.clone());
+    match &instruction.name {
+        Some(name) => writeln!(
+            output,
+            "  #{} {} {}",
+            instruction.index,
+            program,
+            Theme::highlight(name)
+        )?,
+        None => writeln!(output, "  #{} {program}", instruction.index)?,
+    }
+    if let Some(args) = instruction.args.as_ref().and_then(|args| args.as_object()) {
+        for (name, value) in args {
+            writeln!(output, "       {} {value}", Theme::dim(&format!("{name}:")))?;
+        }
+    }
+    if instruction.name.is_some() {
+        if let Some(data) = &instruction.data {
+            writeln!(output, "       {} {data}", Theme::dim("undecoded args:"))?;
+        }
+    }
+    Ok(())
+}
+
+fn format_event(output: &mut String, event: &DecodedEvent) -> Result<()> {
+    writeln!(output, "  {}", Theme::highlight(&event.name))?;
+    let usdc = |amount: u64| format!("{} USDC", UsdcAmount::from_microlamports(amount));
+    let fields = [
+        ("payer", event.payer.clone()),
+        ("payment terms", event.payment_terms.clone()),
+        ("amount", event.amount.map(usdc)),
+        ("keeper", event.keeper.clone()),
+        ("keeper fee", event.keeper_fee.map(usdc)),
+        ("payment #", event.payment_number.map(|n| n.to_string())),
+        ("allowance", event.current_allowance.map(usdc)),
+        ("reason", event.reason.clone()),
+        ("data", event.data.clone()),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            writeln!(output, "       {} {value}", Theme::dim(&format!("{name}:")))?;
+        }
+    }
+    Ok(())
+}
+
+/// Format a change in base units with its sign, e.g. `-5.000000`
+fn format_token_change(change: i64, decimals: u8) -> String {
+    let sign = if change < 0 { "-" } else { "+" };
+    let decimals = usize::from(decimals);
+    let digits = format!("{:0width$}", change.unsigned_abs(), width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    if fraction.is_empty() {
+        format!("{sign}{whole}")
+    } else {
+        format!("{sign}{whole}.{fraction}")
+    }
+}
+
+/// Check that every required signer has signed and the signatures verify
+///
+/// # Errors
+/// Returns error naming the missing signers, or if a signature is invalid
+pub fn ensure_fully_signed(transaction: &Transaction) -> Result<()> {
+    let missing: Vec<String> = signer_status(transaction)
+        .into_iter()
+        .filter(|s| !s.signed)
+        .map(|s| s.pubkey.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "Transaction is missing signatures from: {}\n\
+             Run 'tally-merchant tx sign <FILE> --keypair <KEYPAIR>' for each missing signer.",
+            missing.join(", ")
+        ));
+    }
+    transaction.verify().map_err(|e| {
+        anyhow!("Transaction signatures do not verify ({e}) - was it modified after signing?")
+    })
+}
+
+/// Summarize a transaction for review before signing
+fn describe_transaction(transaction: &Transaction) -> Result<String> {
+    let message = &transaction.message;
+    let mut output = String::new();
+    writeln!(&mut output, "{}", Theme::header("Transaction to sign"))?;
+    if let Some(fee_payer) = message.account_keys.first() {
+        writeln!(&mut output, "{} {fee_payer}", Theme::info("Fee payer:"))?;
+    }
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::info("Blockhash:"),
+        message.recent_blockhash
+    )?;
+    writeln!(&mut output, "{}", Theme::info("Instructions:"))?;
+    for (i, instruction) in message.instructions.iter().enumerate() {
+        let program = message
+            .account_keys
+            .get(usize::from(instruction.program_id_index))
+            .map_or_else(|| "<invalid>".to_string(), ToString::to_string);
+        writeln!(
+            &mut output,
+            "  {}. program {program} ({} accounts, {} bytes of data)",
+            i + 1,
+            instruction.accounts.len(),
+            instruction.data.len()
+        )?;
+    }
+    write!(
+        &mut output,
+        "{}",
+        format_signer_status(&signer_status(transaction))?
+    )?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::offline::build_transaction;
+    use crate::utils::transaction::TxPlan;
+    use tally_sdk::solana_sdk::hash::Hash;
+    use tally_sdk::solana_sdk::instruction::{AccountMeta, Instruction};
+    use tally_sdk::solana_sdk::pubkey::Pubkey;
+    use tally_sdk::solana_sdk::signature::{Keypair, Signer};
+
+    fn unsigned(payer: &Keypair) -> Transaction {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[7],
+            vec![AccountMeta::new(payer.pubkey(), true)],
+        );
+        let plan = TxPlan::new("Test", payer.pubkey(), vec![instruction]);
+        build_transaction(&plan, &Hash::new_unique(), None)
+    }
+
+    #[test]
+    fn test_ensure_fully_signed_missing() {
+        let payer = Keypair::new();
+        let err = ensure_fully_signed(&unsigned(&payer))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&payer.pubkey().to_string()));
+    }
+
+    #[test]
+    fn test_ensure_fully_signed_ok() {
+        let payer = Keypair::new();
+        let mut transaction = unsigned(&payer);
+        sign_transaction(&mut transaction, &payer).unwrap();
+        assert!(ensure_fully_signed(&transaction).is_ok());
+    }
+
+    #[test]
+    fn test_ensure_fully_signed_detects_tampering() {
+        let payer = Keypair::new();
+        let mut transaction = unsigned(&payer);
+        sign_transaction(&mut transaction, &payer).unwrap();
+        transaction.message.instructions[0].data = vec![8];
+        assert!(ensure_fully_signed(&transaction).is_err());
+    }
+
+    #[test]
+    fn test_format_token_change() {
+        assert_eq!(format_token_change(-5_000_000, 6), "-5.000000");
+        assert_eq!(format_token_change(25, 6), "+0.000025");
+        assert_eq!(format_token_change(7, 0), "+7");
+    }
+
+    #[test]
+    fn test_token_role_matches_account_or_owner() {
+        let change = |account: &str, owner: &str| TokenBalanceChange {
+            account: account.to_string(),
+            owner: Some(owner.to_string()),
+            mint: "USDC".to_string(),
+            role: None,
+            pre_amount: 0,
+            post_amount: 1,
+            change: 1,
+            decimals: 6,
+        };
+        let parties = vec![
+            ("TreasuryAta".to_string(), "treasury"),
+            ("Platform".to_string(), "platform"),
+            ("Keeper".to_string(), "keeper"),
+        ];
+        assert_eq!(
+            token_role(&change("TreasuryAta", "Payee"), &parties),
+            Some("treasury")
+        );
+        assert_eq!(
+            token_role(&change("FeeAta", "Platform"), &parties),
+            Some("platform")
+        );
+        assert_eq!(token_role(&change("Other", "Someone"), &parties), None);
+    }
+
+    #[test]
+    fn test_token_role_withdraw_from_treasury() {
+        let change = |account: &str| TokenBalanceChange {
+            account: account.to_string(),
+            owner: Some("Authority".to_string()),
+            mint: "USDC".to_string(),
+            role: None,
+            pre_amount: 0,
+            post_amount: 1,
+            change: 1,
+            decimals: 6,
+        };
+        // The treasury is owned by the payee authority, like the destination
+        let parties = vec![
+            ("TreasuryAta".to_string(), "treasury"),
+            ("Authority".to_string(), "payee-authority"),
+        ];
+        assert_eq!(
+            token_role(&change("TreasuryAta"), &parties),
+            Some("treasury")
+        );
+        assert_eq!(
+            token_role(&change("DestinationAta"), &parties),
+            Some("payee-authority")
+        );
+    }
+
+    #[test]
+    fn test_tx_details_human() {
+        let details = TxDetails {
+            signature: "Sig".to_string(),
+            slot: 7,
+            block_time: None,
+            fee_payer: "Payer".to_string(),
+            fee_lamports: 5000,
+            error: None,
+            program_error: None,
+            instructions: vec![DecodedInstruction {
+                index: 0,
+                program_id: "Tally".to_string(),
+                program: Some("Tally".to_string()),
+                name: Some("update_payment_terms".to_string()),
+                args: Some(serde_json::json!({"amount_usdc": 5_000_000, "period_secs": null})),
+                accounts: Vec::new(),
+                data: None,
+            }],
+            tally_accounts: vec![TallyAccount {
+                address: "Terms".to_string(),
+                kind: "payment-terms".to_string(),
+            }],
+            events: Vec::new(),
+            token_balance_changes: Vec::new(),
+            logs: vec!["Program log: hidden on success".to_string()],
+        };
+        let human = details.human().unwrap();
+        assert!(human.contains("update_payment_terms"));
+        assert!(human.contains("5000000"));
+        assert!(human.contains("payment-terms"));
+        assert!(!human.contains("hidden on success"));
+    }
+
+    #[test]
+    fn test_describe_transaction() {
+        let payer = Keypair::new();
+        let description = describe_transaction(&unsigned(&payer)).unwrap();
+        assert!(description.contains(&payer.pubkey().to_string()));
+        assert!(description.contains("1 accounts, 1 bytes of data"));
+        assert!(description.contains("missing"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Update payment terms command implementation
+
+use crate::config::TallyCliConfig;
+use crate::output::CommandOutput;
+use crate::utils::authority::verify_payee_authority;
+use crate::utils::colors::Theme;
+use crate::utils::duration::format_period;
+use crate::utils::progress;
+use crate::utils::transaction::{self, TxMode, TxOutcome, TxPlan, TxSigner};
+use anyhow::{anyhow, Context, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::{
+    program_types::UpdatePaymentTermsArgs, PaymentPeriod, SimpleTallyClient, UsdcAmount,
+};
+use tracing::info;
+
+/// Arguments for updating payment terms
+pub struct UpdatePaymentTermsRequest<'a> {
+    pub payment_terms_str: &'a str,
+    pub amount: Option<UsdcAmount>,
+    /// New billing period in seconds (see [`crate::utils::duration`])
+    pub period_secs: Option<u64>,
+    pub authority_path: Option<&'a str>,
+    pub skip_confirmation: bool,
+    /// Send, simulate or export the transaction
+    pub tx_mode: &'a TxMode,
+}
+
+/// Payment terms changed by `payment-terms update`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PaymentTermsUpdated {
+    pub payment_terms: String,
+    /// Changed fields, as "field: old -> new" lines
+    pub changes: Vec<String>,
+    pub signature: String,
+}
+
+impl CommandOutput for PaymentTermsUpdated {
+    const KIND: &'static str = "payment-terms.update";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::success("Payment terms updated successfully!")
+        )?;
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Payment Terms PDA:"),
+            Theme::highlight(&self.payment_terms)
+        )?;
+        writeln!(&mut output, "{}", Theme::info("Updated fields:"))?;
+        for change in &self.changes {
+            writeln!(&mut output, "  {change}")?;
+        }
+        write!(
+            &mut output,
+            "{} {}",
+            Theme::info("Transaction signature:"),
+            self.signature
+        )?;
+        Ok(output)
+    }
+}
+
+/// Execute the update payment terms command
+///
+/// # Errors
+/// Returns error if no field is being changed, the payment terms account cannot be
+/// fetched, the authority does not own the payee, or the transaction fails
+pub async fn execute(
+    tally_client: &SimpleTallyClient,
+    request: &UpdatePaymentTermsRequest<'_>,
+    _config: &TallyCliConfig,
+) -> Result<TxOutcome<PaymentTermsUpdated>> {
+    info!("Starting payment terms update");
+
+    // Validate requested changes before touching the network
+    let args = build_update_args(request.amount, request.period_secs)?;
+
+    let payment_terms_pda = Pubkey::from_str(request.payment_terms_str).map_err(|e| {
+        anyhow!(
+            "Invalid payment terms address '{}': {e}",
+            request.payment_terms_str
+        )
+    })?;
+
+    let terms = tally_client
+        .get_payment_terms(&payment_terms_pda)
+        .context("Failed to fetch payment terms account - check RPC connection and account state")?
+        .ok_or_else(|| anyhow!("Payment terms account not found: {payment_terms_pda}"))?;
+
+    // Load authority keypair and validate it owns the payee of these terms
+    let authority = TxSigner::load(request.authority_path, request.tx_mode)
+        .context("Failed to load authority keypair")?;
+    info!("Using authority: {}", authority.pubkey());
+    verify_payee_authority(tally_client, &terms.payee, &authority.pubkey())?;
+
+    let current_amount = UsdcAmount::from_microlamports(terms.amount_usdc);
+    let changes = describe_changes(&args, current_amount, terms.period_secs);
+
+    let authority_pubkey = authority.pubkey();
+    let plan = || -> Result<TxPlan> {
+        let instructions = tally_client
+            .update_payment_terms_instructions(&authority_pubkey, &payment_terms_pda, args.clone())
+            .map_err(|e| anyhow!("Failed to build payment terms update transaction: {e}"))?;
+        Ok(TxPlan::new(
+            format!("Update payment terms '{}'", terms.terms_id_str()),
+            authority_pubkey,
+            instructions,
+        ))
+    };
+    if !request.tx_mode.is_send() {
+        return transaction::simulate_or_export(
+            tally_client,
+            &plan()?,
+            request.tx_mode,
+            &authority,
+        );
+    }
+
+    if !request.skip_confirmation {
+        eprintln!(
+            "{} {}",
+            Theme::info("Updating payment terms:"),
+            Theme::highlight(&terms.terms_id_str())
+        );
+        for change in &changes {
+            eprintln!("  {change}");
+        }
+
+        let confirmed = Confirm::new()
+            .with_prompt("Apply these changes?")
+            .default(false)
+            .interact()
+            .context("Confirmation prompt failed - pass --yes to run non-interactively")?;
+        if !confirmed {
+            return Err(anyhow!("Payment terms update canceled by user"));
+        }
+    }
+
+    let updated = |signature: String| PaymentTermsUpdated {
+        payment_terms: payment_terms_pda.to_string(),
+        changes: changes.clone(),
+        signature,
+    };
+    if let TxSigner::External(signer) = &authority {
+        let signature = transaction::send_plan(tally_client, &plan()?, signer.as_ref())?;
+        return Ok(TxOutcome::Sent(updated(signature.to_string())));
+    }
+    let authority = authority.into_keypair()?;
+
+    let spinner = progress::create_spinner("Updating payment terms and submitting transaction...");
+    let result = tally_client
+        .update_payment_terms(&authority, &payment_terms_pda, args.clone())
+        .map_err(|e| {
+            transaction::explain_failure(tally_client, plan, "Failed to update payment terms", e)
+        });
+
+    match &result {
+        Ok(signature) => {
+            progress::finish_progress_success(&spinner, "Payment terms updated successfully");
+            info!("Transaction confirmed: {}", signature);
+        }
+        Err(_) => {
+            progress::finish_progress_error(&spinner, "Failed to update payment terms");
+        }
+    }
+
+    Ok(TxOutcome::Sent(updated(result?)))
+}
+
+/// Build the on-chain update arguments from the requested changes
+///
+/// # Errors
+/// Returns error if no field is being changed or a value is out of range
+pub fn build_update_args(
+    amount: Option<UsdcAmount>,
+    period_secs: Option<u64>,
+) -> Result<UpdatePaymentTermsArgs> {
+    if amount.is_none() && period_secs.is_none() {
+        return Err(anyhow!(
+            "Nothing to update - pass --amount-usdc and/or --period (or --period-days, --period-months)"
+        ));
+    }
+
+    let amount_usdc = amount.map(|amount| amount.microlamports());
+    if amount_usdc == Some(0) {
+        return Err(anyhow!("Amount must be greater than 0 USDC"));
+    }
+
+    let period_secs = period_secs
+        .map(|secs| {
+            PaymentPeriod::from_seconds(secs).context("Invalid period - must be at least 1 day")
+        })
+        .transpose()?
+        .map(|period| period.seconds());
+
+    Ok(UpdatePaymentTermsArgs {
+        amount_usdc,
+        period_secs,
+    })
+}
+
+/// Describe each changed field as "old -> new"
+fn describe_changes(
+    args: &UpdatePaymentTermsArgs,
+    current_amount: UsdcAmount,
+    current_period_secs: u64,
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    if let Some(amount) = args.amount_usdc {
+        changes.push(format!(
+            "Amount: {current_amount} -> {}",
+            UsdcAmount::from_microlamports(amount)
+        ));
+    }
+    if let Some(period_secs) = args.period_secs {
+        changes.push(format!(
+            "Period: {} -> {}",
+            format_period(current_period_secs),
+            format_period(period_secs)
+        ));
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::duration::{SECONDS_PER_DAY, SECONDS_PER_MONTH};
+
+    #[test]
+    fn test_at_least_one_field_required() {
+        let err = build_update_args(None, None).unwrap_err().to_string();
+        assert!(err.contains("Nothing to update"));
+    }
+
+    #[test]
+    fn test_valid_amount_update() {
+        let args =
+            build_update_args(Some(UsdcAmount::from_microlamports(15_000_000)), None).unwrap();
+        assert_eq!(args.amount_usdc, Some(15_000_000));
+        assert_eq!(args.period_secs, None);
+    }
+
+    #[test]
+    fn test_valid_period_update() {
+        let args = build_update_args(None, Some(30 * SECONDS_PER_DAY)).unwrap();
+        assert_eq!(args.amount_usdc, None);
+        assert_eq!(args.period_secs, Some(30 * 86_400));
+    }
+
+    #[test]
+    fn test_valid_multiple_updates() {
+        let args = build_update_args(
+            Some(UsdcAmount::from_microlamports(2_500_000)),
+            Some(7 * SECONDS_PER_DAY),
+        )
+        .unwrap();
+        assert_eq!(args.amount_usdc, Some(2_500_000));
+        assert_eq!(args.period_secs, Some(7 * 86_400));
+    }
+
+    #[test]
+    fn test_rejects_zero_amount() {
+        assert!(build_update_args(Some(UsdcAmount::from_microlamports(0)), None).is_err());
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        let args = build_update_args(
+            Some(UsdcAmount::from_microlamports(15_000_000)),
+            Some(SECONDS_PER_MONTH),
+        )
+        .unwrap();
+        let changes = describe_changes(
+            &args,
+            UsdcAmount::from_microlamports(10_000_000),
+            30 * SECONDS_PER_DAY,
+        );
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].starts_with("Amount:"));
+        assert_eq!(changes[1], "Period: 30 days -> 1 month");
+    }
+}
@@ -0,0 +1,309 @@
+//! Wallet command implementations
+//!
+//! Manage keypairs in the encrypted keystore (see [`crate::keystore`]) for
+//! teams that don't use the Solana CLI. Stored wallets are used by setting a
+//! profile's `wallet-path` (or any keypair flag) to `keystore:<name>`.
+
+use crate::keystore::{read_passphrase, write_private_file, KdfParams, Keystore};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use crate::utils::signer::load_signer_keypair;
+use anyhow::{anyhow, Result};
+use dialoguer::Confirm;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+use tally_sdk::solana_sdk::signature::{Keypair, Signer};
+
+/// A wallet written by `wallet new` or `wallet import`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletStored {
+    pub name: String,
+    pub pubkey: String,
+    /// Signer URI to use in keypair flags and `wallet-path`
+    pub uri: String,
+    pub path: String,
+    /// `created` or `imported`
+    pub action: String,
+}
+
+impl CommandOutput for WalletStored {
+    const KIND: &'static str = "wallet.store";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{} Wallet '{}' {}",
+            Theme::success("✓"),
+            Theme::highlight(&self.name),
+            self.action
+        )?;
+        writeln!(&mut output, "  Address: {}", Theme::value(&self.pubkey))?;
+        writeln!(&mut output, "  File:    {}", self.path)?;
+        writeln!(&mut output)?;
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::dim(&format!(
+                "Use it for the active profile with: \
+                 tally-merchant config set wallet-path {}",
+                self.uri
+            ))
+        )?;
+        write!(
+            &mut output,
+            "{}",
+            Theme::dim(&format!(
+                "Back up the secret key with: tally-merchant wallet export {} --output <PATH>",
+                self.name
+            ))
+        )?;
+        Ok(output)
+    }
+}
+
+/// A keystore wallet as listed by `wallet list`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletEntry {
+    pub name: String,
+    pub pubkey: String,
+    pub uri: String,
+    /// Unix timestamp
+    pub created_at: i64,
+    pub path: String,
+}
+
+/// Wallets listed by `wallet list`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletList {
+    /// Keystore directory
+    pub keystore: String,
+    pub wallets: Vec<WalletEntry>,
+}
+
+impl CommandOutput for WalletList {
+    const KIND: &'static str = "wallet.list";
+
+    fn human(&self) -> Result<String> {
+        if self.wallets.is_empty() {
+            return Ok(format!(
+                "No wallets in {}\n{}",
+                self.keystore,
+                Theme::dim("Create one with: tally-merchant wallet new <NAME>")
+            ));
+        }
+
+        let width = self
+            .wallets
+            .iter()
+            .map(|wallet| wallet.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Name".len());
+        let mut output = String::new();
+        writeln!(
+            &mut output,
+            "{}",
+            Theme::header(&format!("{:<width$}  Address", "Name"))
+        )?;
+        for wallet in &self.wallets {
+            writeln!(&mut output, "{:<width$}  {}", wallet.name, wallet.pubkey)?;
+        }
+        writeln!(&mut output)?;
+        write!(
+            &mut output,
+            "{}",
+            Theme::dim(&format!(
+                "{} wallet(s) in {}",
+                self.wallets.len(),
+                self.keystore
+            ))
+        )?;
+        Ok(output)
+    }
+}
+
+/// A wallet decrypted by `wallet export`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletExported {
+    pub pubkey: String,
+    /// File the keypair was written to
+    pub path: Option<String>,
+    /// Secret key bytes in Solana CLI format, when not written to a file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keypair: Option<Vec<u8>>,
+}
+
+impl CommandOutput for WalletExported {
+    const KIND: &'static str = "wallet.export";
+
+    fn human(&self) -> Result<String> {
+        match (&self.path, &self.keypair) {
+            (Some(path), _) => Ok(format!(
+                "{} Wrote keypair for {} to {path}",
+                Theme::success("✓"),
+                self.pubkey
+            )),
+            (None, keypair) => Ok(serde_json::to_string(keypair)?),
+        }
+    }
+}
+
+/// Generate a new keypair and store it encrypted
+///
+/// # Errors
+/// Returns error if the name is invalid or taken, or the wallet cannot be written
+pub fn new_wallet(name: &str) -> Result<WalletStored> {
+    let keystore = Keystore::open_default()?;
+    let passphrase = read_passphrase(&format!("Passphrase for new wallet '{name}'"), true)?;
+    store(&keystore, name, &Keypair::new(), &passphrase, "created")
+}
+
+/// Encrypt an existing keypair or signer URI into the keystore
+///
+/// # Errors
+/// Returns error if the source cannot be loaded, or the wallet cannot be written
+pub fn import(name: &str, from: &str) -> Result<WalletStored> {
+    let keystore = Keystore::open_default()?;
+    let keypair = load_signer_keypair(from)?;
+    let passphrase = read_passphrase(&format!("Passphrase for wallet '{name}'"), true)?;
+    store(&keystore, name, &keypair, &passphrase, "imported")
+}
+
+/// List keystore wallets
+///
+/// # Errors
+/// Returns error if the keystore cannot be read
+pub fn list() -> Result<WalletList> {
+    list_in(&Keystore::open_default()?)
+}
+
+/// Decrypt a wallet and write it as a Solana CLI keypair file, or print it
+///
+/// # Errors
+/// Returns error if the export is not confirmed, the passphrase is wrong, or
+/// the output file exists
+pub fn export(name: &str, output: Option<&Path>, yes: bool) -> Result<WalletExported> {
+    let keystore = Keystore::open_default()?;
+    let entry = keystore.read(name)?;
+
+    if !yes {
+        eprintln!(
+            "{} This writes the unencrypted secret key of {} ({}). Anyone holding it controls \
+             the wallet.",
+            Theme::warning("⚠"),
+            Theme::highlight(name),
+            entry.pubkey
+        );
+        let confirmed = Confirm::new()
+            .with_prompt("Export the secret key?")
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            return Err(anyhow!("Export cancelled"));
+        }
+    }
+
+    let passphrase = read_passphrase(&format!("Passphrase for wallet '{name}'"), false)?;
+    let keypair = keystore.unlock(name, &passphrase)?;
+    export_keypair(&keypair, output)
+}
+
+fn store(
+    keystore: &Keystore,
+    name: &str,
+    keypair: &Keypair,
+    passphrase: &str,
+    action: &str,
+) -> Result<WalletStored> {
+    let path = keystore.create(name, keypair, passphrase, KdfParams::default())?;
+    Ok(WalletStored {
+        name: name.to_string(),
+        pubkey: keypair.pubkey().to_string(),
+        uri: format!("keystore:{name}"),
+        path: path.display().to_string(),
+        action: action.to_string(),
+    })
+}
+
+fn list_in(keystore: &Keystore) -> Result<WalletList> {
+    let wallets = keystore
+        .list()?
+        .into_iter()
+        .map(|entry| WalletEntry {
+            uri: format!("keystore:{}", entry.name),
+            path: keystore.path(&entry.name).display().to_string(),
+            name: entry.name,
+            pubkey: entry.pubkey,
+            created_at: entry.created_at,
+        })
+        .collect();
+    Ok(WalletList {
+        keystore: keystore.dir().display().to_string(),
+        wallets,
+    })
+}
+
+fn export_keypair(keypair: &Keypair, output: Option<&Path>) -> Result<WalletExported> {
+    let bytes = keypair.to_bytes().to_vec();
+    let Some(path) = output else {
+        return Ok(WalletExported {
+            pubkey: keypair.pubkey().to_string(),
+            path: None,
+            keypair: Some(bytes),
+        });
+    };
+    if path.exists() {
+        return Err(anyhow!(
+            "{} already exists - choose another --output path",
+            path.display()
+        ));
+    }
+    write_private_file(path, serde_json::to_string(&bytes)?.as_bytes())?;
+    Ok(WalletExported {
+        pubkey: keypair.pubkey().to_string(),
+        path: Some(path.display().to_string()),
+        keypair: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tally_sdk::solana_sdk::signature::read_keypair_file;
+
+    #[test]
+    fn test_list_json_and_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::at(dir.path().join("keystore"));
+        assert!(list_in(&keystore)
+            .unwrap()
+            .human()
+            .unwrap()
+            .contains("No wallets"));
+
+        let keypair = Keypair::new();
+        let params = KdfParams {
+            memory_kib: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        keystore
+            .create("merchant", &keypair, "passphrase", params)
+            .unwrap();
+
+        let json = serde_json::to_value(list_in(&keystore).unwrap()).unwrap();
+        let wallet = &json["wallets"][0];
+        assert_eq!(wallet["name"], "merchant");
+        assert_eq!(wallet["uri"], "keystore:merchant");
+        assert_eq!(wallet["pubkey"], keypair.pubkey().to_string());
+
+        let unlocked = keystore.unlock("merchant", "passphrase").unwrap();
+        let path = dir.path().join("merchant.json");
+        export_keypair(&unlocked, Some(&path)).unwrap();
+        assert_eq!(read_keypair_file(&path).unwrap().pubkey(), keypair.pubkey());
+        assert!(export_keypair(&unlocked, Some(&path)).is_err());
+    }
+}
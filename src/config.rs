@@ -7,6 +7,7 @@ use std::env;
 
 /// Centralized configuration for the Tally CLI
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_field_names)] // Every field is a default value
 pub struct TallyCliConfig {
     /// Default RPC URL for Solana connections
     pub default_rpc_url: String,
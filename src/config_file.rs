@@ -48,6 +48,12 @@ pub struct DefaultConfig {
 
     /// Wallet path override
     pub wallet_path: Option<String>,
+
+    /// Program ID used when the active profile does not set one
+    pub program_id: Option<String>,
+
+    /// USDC mint used when the active profile does not set one
+    pub usdc_mint: Option<String>,
 }
 
 /// Profile-specific configuration
@@ -117,6 +123,8 @@ impl ConfigFile {
                 active_profile: Some("devnet".to_string()),
                 output_format: Some("human".to_string()),
                 wallet_path: None,
+                program_id: None,
+                usdc_mint: None,
             },
             profiles,
         }
@@ -249,7 +257,7 @@ impl ConfigFile {
             "rpc-url" | "rpc_url" => profile.rpc_url = value,
             "program-id" | "program_id" => profile.program_id = Some(value),
             "usdc-mint" | "usdc_mint" => profile.usdc_mint = Some(value),
-            "merchant" => profile.merchant = Some(value),
+            "merchant" | "payee" => profile.merchant = Some(value),
             "wallet-path" | "wallet_path" => profile.wallet_path = Some(value),
             _ => anyhow::bail!("Unknown config key: {key}"),
         }
@@ -278,7 +286,7 @@ impl ConfigFile {
             "rpc-url" | "rpc_url" => Some(profile.rpc_url.clone()),
             "program-id" | "program_id" => profile.program_id.clone(),
             "usdc-mint" | "usdc_mint" => profile.usdc_mint.clone(),
-            "merchant" => profile.merchant.clone(),
+            "merchant" | "payee" => profile.merchant.clone(),
             "wallet-path" | "wallet_path" => profile.wallet_path.clone(),
             _ => anyhow::bail!("Unknown config key: {key}"),
         };
@@ -381,6 +389,25 @@ mod tests {
         assert_eq!(value, Some(merchant_pda.to_string()));
     }
 
+    #[test]
+    fn test_set_payee_aliases_merchant() {
+        let mut config = ConfigFile::new();
+        let payee_pda = "HkDq7K2RRStvPrXw6U3YPJrPU2dYbvGj8Y5z8VQmKR8C";
+
+        config
+            .set_payee(payee_pda.to_string())
+            .expect("Should set payee");
+
+        assert_eq!(
+            config.get_profile_value("merchant").unwrap(),
+            Some(payee_pda.to_string())
+        );
+        assert_eq!(
+            config.get_profile_value("payee").unwrap(),
+            Some(payee_pda.to_string())
+        );
+    }
+
     #[test]
     fn test_save_and_load() {
         let _temp_dir = TempDir::new().expect("Failed to create temp dir");
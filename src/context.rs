@@ -0,0 +1,637 @@
+//! Resolved runtime context for a CLI invocation
+//!
+//! The RPC URL, program ID, USDC mint, wallet path and payee are resolved once
+//! in `main` with the precedence documented on [`ConfigFile`]:
+//!
+//! 1. CLI flags (`--rpc-url`, `--program-id`, `--usdc-mint`, a command's `--authority`)
+//! 2. Environment variables (`TALLY_RPC_URL`, `TALLY_PROGRAM_ID`, `TALLY_USDC_MINT`,
+//!    `TALLY_WALLET`, `TALLY_PAYEE`)
+//! 3. Active profile (`TALLY_PROFILE` or `active_profile`)
+//! 4. Config file defaults
+//! 5. Built-in defaults
+//!
+//! Each value remembers where it came from so `config effective` can explain it.
+//! A `TALLY_PROFILE` naming a profile that does not exist is reported by
+//! [`RuntimeContext::check_profile`], an error for commands that read profile
+//! values rather than a silent fallback to another network.
+
+use crate::config::TallyCliConfig;
+use crate::config_file::ConfigFile;
+use crate::errors::{CliError, ErrorCode};
+use crate::output::CommandOutput;
+use crate::utils::colors::Theme;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::{self, Write as _};
+use tally_sdk::SimpleTallyClient;
+
+/// Wallet used by the Solana CLI when nothing else is configured
+pub const SOLANA_CLI_DEFAULT_WALLET: &str = "~/.config/solana/id.json";
+
+/// Where a resolved value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// A command-line flag, e.g. `--rpc-url`
+    Flag(&'static str),
+    /// An environment variable, e.g. `TALLY_RPC_URL`
+    Env(&'static str),
+    /// The named config file profile
+    Profile(String),
+    /// The `[defaults]` section of the config file
+    ConfigDefaults,
+    /// Compiled-in default
+    BuiltIn,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flag(flag) => write!(f, "{flag} flag"),
+            Self::Env(var) => write!(f, "{var} environment variable"),
+            Self::Profile(name) => write!(f, "profile '{name}'"),
+            Self::ConfigDefaults => write!(f, "config file defaults"),
+            Self::BuiltIn => write!(f, "built-in default"),
+        }
+    }
+}
+
+/// A value together with its [`ValueSource`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: ValueSource,
+}
+
+impl<T> Resolved<T> {
+    const fn new(value: T, source: ValueSource) -> Self {
+        Self { value, source }
+    }
+}
+
+/// Global flags that take part in context resolution
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContextFlags<'a> {
+    pub rpc_url: Option<&'a str>,
+    pub program_id: Option<&'a str>,
+    pub usdc_mint: Option<&'a str>,
+}
+
+/// Settings shared by every command of one invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeContext {
+    /// Active config file profile, if any
+    pub profile: Option<Resolved<String>>,
+    pub rpc_url: Resolved<String>,
+    /// `None` when no program ID is configured anywhere
+    pub program_id: Option<Resolved<String>>,
+    /// `None` to use the SDK's default USDC mint
+    pub usdc_mint: Option<Resolved<String>>,
+    /// Signing wallet path, tilde-expanded
+    pub wallet_path: Resolved<String>,
+    /// Payee (merchant) PDA from `TALLY_PAYEE` or the active profile
+    pub payee: Option<Resolved<String>>,
+}
+
+impl RuntimeContext {
+    /// Resolve the context from flags, the process environment and the config file
+    #[must_use]
+    pub fn resolve(
+        flags: &ContextFlags<'_>,
+        config: &TallyCliConfig,
+        config_file: &ConfigFile,
+    ) -> Self {
+        Self::resolve_with(flags, config, config_file, |var| std::env::var(var).ok())
+    }
+
+    /// Resolve the context reading environment variables through `env`
+    ///
+    /// A missing profile contributes no values; see [`Self::check_profile`].
+    #[must_use]
+    pub fn resolve_with(
+        flags: &ContextFlags<'_>,
+        config: &TallyCliConfig,
+        config_file: &ConfigFile,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let env = |var: &'static str| {
+            env(var)
+                .filter(|value| !value.is_empty())
+                .map(|value| Resolved::new(value, ValueSource::Env(var)))
+        };
+        let flag = |value: Option<&str>, name: &'static str| {
+            value.map(|value| Resolved::new(value.to_string(), ValueSource::Flag(name)))
+        };
+
+        let profile = env("TALLY_PROFILE").or_else(|| {
+            config_file
+                .defaults
+                .active_profile
+                .clone()
+                .map(|name| Resolved::new(name, ValueSource::ConfigDefaults))
+        });
+        let active = profile.as_ref().and_then(|name| {
+            config_file
+                .get_profile(&name.value)
+                .map(|p| (&name.value, p))
+        });
+        let from_profile = |field: fn(&crate::config_file::ProfileConfig) -> Option<&String>| {
+            active.and_then(|(name, profile)| {
+                field(profile)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| Resolved::new(value.clone(), ValueSource::Profile(name.clone())))
+            })
+        };
+
+        let rpc_url = flag(flags.rpc_url, "--rpc-url")
+            .or_else(|| env("TALLY_RPC_URL"))
+            .or_else(|| from_profile(|p| Some(&p.rpc_url)))
+            .unwrap_or_else(|| Resolved::new(config.default_rpc_url.clone(), ValueSource::BuiltIn));
+
+        let from_defaults = |value: &Option<String>| {
+            value
+                .clone()
+                .filter(|value| !value.is_empty())
+                .map(|value| Resolved::new(value, ValueSource::ConfigDefaults))
+        };
+
+        let program_id = flag(flags.program_id, "--program-id")
+            .or_else(|| env("TALLY_PROGRAM_ID"))
+            .or_else(|| from_profile(|p| p.program_id.as_ref()))
+            .or_else(|| from_defaults(&config_file.defaults.program_id));
+
+        let usdc_mint = flag(flags.usdc_mint, "--usdc-mint")
+            .or_else(|| env("TALLY_USDC_MINT"))
+            .or_else(|| from_profile(|p| p.usdc_mint.as_ref()))
+            .or_else(|| from_defaults(&config_file.defaults.usdc_mint));
+
+        let mut wallet_path = env("TALLY_WALLET")
+            .or_else(|| from_profile(|p| p.wallet_path.as_ref()))
+            .or_else(|| from_defaults(&config_file.defaults.wallet_path))
+            .unwrap_or_else(|| {
+                Resolved::new(SOLANA_CLI_DEFAULT_WALLET.to_string(), ValueSource::BuiltIn)
+            });
+        wallet_path.value = expand_tilde(&wallet_path.value);
+
+        let payee = env("TALLY_PAYEE").or_else(|| from_profile(|p| p.merchant.as_ref()));
+
+        Self {
+            profile,
+            rpc_url,
+            program_id,
+            usdc_mint,
+            wallet_path,
+            payee,
+        }
+    }
+
+    /// Check that a profile selected by `TALLY_PROFILE` exists
+    ///
+    /// # Errors
+    /// Returns error if `TALLY_PROFILE` names a profile that does not exist
+    pub fn check_profile(&self, config_file: &ConfigFile) -> Result<()> {
+        match &self.profile {
+            Some(name)
+                if name.source == ValueSource::Env("TALLY_PROFILE")
+                    && !config_file.profiles.contains_key(&name.value) =>
+            {
+                Err(unknown_profile(&name.value, config_file))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The configured USDC mint, or `None` for the SDK default
+    #[must_use]
+    pub fn usdc_mint(&self) -> Option<&str> {
+        self.usdc_mint.as_ref().map(|mint| mint.value.as_str())
+    }
+
+    /// Keypair path a command signs with: its `--authority` flag, else the resolved wallet
+    #[must_use]
+    pub fn signer_path<'a>(&'a self, flag: Option<&'a str>) -> &'a str {
+        flag.unwrap_or(&self.wallet_path.value)
+    }
+
+    /// Connect a client to the resolved RPC URL and program
+    ///
+    /// # Errors
+    /// Returns error if no program ID is configured or the client cannot be created
+    pub fn tally_client(&self) -> Result<SimpleTallyClient> {
+        let program_id = self.program_id.as_ref().ok_or_else(program_id_missing)?;
+        Ok(SimpleTallyClient::new_with_program_id(
+            &self.rpc_url.value,
+            &program_id.value,
+        )?)
+    }
+}
+
+/// Expand tilde (~) to home directory path
+///
+/// Converts paths like `~/foo` to `/home/user/foo`
+#[must_use]
+pub fn expand_tilde(path: &str) -> String {
+    if path.starts_with("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return path.replacen('~', &home.to_string_lossy(), 1);
+        }
+    }
+    path.to_string()
+}
+
+/// Error for commands that need the program but none is configured
+#[must_use]
+pub fn program_id_missing() -> anyhow::Error {
+    CliError::new(
+        ErrorCode::ConfigMissing,
+        "This command requires connection to Solana, but no program ID is configured.",
+    )
+    .with_suggestion(
+        "You can fix this by:\n\
+         \n\
+         1. Set the TALLY_PROGRAM_ID environment variable:\n\
+            export TALLY_PROGRAM_ID=<your-program-id>\n\
+         \n\
+         2. Or configure it in your profile:\n\
+            tally-merchant config init\n\
+            tally-merchant config set program-id <your-program-id>\n\
+         \n\
+         3. Or pass it as a CLI flag:\n\
+            tally-merchant --program-id <your-program-id> <command>\n\
+         \n\
+         See https://github.com/Tally-Pay/tally-cli for program IDs",
+    )
+    .into()
+}
+
+/// Error for a `TALLY_PROFILE` that names no configured profile
+fn unknown_profile(name: &str, config_file: &ConfigFile) -> anyhow::Error {
+    let mut available: Vec<&str> = config_file.profiles.keys().map(String::as_str).collect();
+    available.sort_unstable();
+    let available = if available.is_empty() {
+        "(none - run 'tally-merchant config init')".to_string()
+    } else {
+        available.join(", ")
+    };
+    CliError::new(
+        ErrorCode::ConfigMissing,
+        format!("Profile '{name}' selected by TALLY_PROFILE does not exist."),
+    )
+    .with_suggestion(format!(
+        "Available profiles: {available}\n\
+         \n\
+         Set TALLY_PROFILE to one of them, unset it to use the active profile, or create it:\n\
+            tally-merchant config profile create {name} --rpc-url <RPC_URL>"
+    ))
+    .into()
+}
+
+/// A resolved setting as shown by `config effective`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SourcedValue {
+    pub value: String,
+    /// Where the value came from, e.g. `TALLY_RPC_URL environment variable`
+    pub source: String,
+}
+
+impl From<&Resolved<String>> for SourcedValue {
+    fn from(resolved: &Resolved<String>) -> Self {
+        Self {
+            value: resolved.value.clone(),
+            source: resolved.source.to_string(),
+        }
+    }
+}
+
+/// The effective settings and where each one came from
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EffectiveConfig {
+    pub profile: Option<SourcedValue>,
+    pub rpc_url: SourcedValue,
+    pub program_id: Option<SourcedValue>,
+    pub usdc_mint: Option<SourcedValue>,
+    pub wallet_path: SourcedValue,
+    pub payee: Option<SourcedValue>,
+}
+
+impl From<&RuntimeContext> for EffectiveConfig {
+    fn from(context: &RuntimeContext) -> Self {
+        Self {
+            profile: context.profile.as_ref().map(SourcedValue::from),
+            rpc_url: SourcedValue::from(&context.rpc_url),
+            program_id: context.program_id.as_ref().map(SourcedValue::from),
+            usdc_mint: context.usdc_mint.as_ref().map(SourcedValue::from),
+            wallet_path: SourcedValue::from(&context.wallet_path),
+            payee: context.payee.as_ref().map(SourcedValue::from),
+        }
+    }
+}
+
+impl CommandOutput for EffectiveConfig {
+    const KIND: &'static str = "config.effective";
+
+    fn human(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(&mut output, "{}", Theme::header("Effective configuration"))?;
+        writeln!(&mut output, "{}", Theme::dim(&"=".repeat(50)))?;
+
+        let rows: [(&str, Option<&SourcedValue>, &str); 6] = [
+            ("Profile:", self.profile.as_ref(), "(none)"),
+            ("RPC URL:", Some(&self.rpc_url), ""),
+            ("Program ID:", self.program_id.as_ref(), "(not set)"),
+            ("USDC Mint:", self.usdc_mint.as_ref(), "(SDK default)"),
+            ("Wallet Path:", Some(&self.wallet_path), ""),
+            ("Payee:", self.payee.as_ref(), "(not set)"),
+        ];
+        for (label, resolved, unset) in rows {
+            match resolved {
+                Some(resolved) => writeln!(
+                    &mut output,
+                    "{:<15} {}  {}",
+                    Theme::info(label),
+                    Theme::value(&resolved.value),
+                    Theme::dim(&format!("({})", resolved.source))
+                )?,
+                None => writeln!(
+                    &mut output,
+                    "{:<15} {}",
+                    Theme::info(label),
+                    Theme::dim(unset)
+                )?,
+            }
+        }
+        write!(
+            &mut output,
+            "{}",
+            Theme::dim("Precedence: flags > environment > profile > config defaults > built-in")
+        )?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const MINT: &str = "Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr";
+
+    fn config() -> TallyCliConfig {
+        TallyCliConfig {
+            default_rpc_url: "https://api.devnet.solana.com".to_string(),
+            default_output_format: "human".to_string(),
+            default_events_lookback_secs: 3600,
+        }
+    }
+
+    fn resolve(
+        flags: &ContextFlags<'_>,
+        config_file: &ConfigFile,
+        env: &[(&str, &str)],
+    ) -> RuntimeContext {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        RuntimeContext::resolve_with(flags, &config(), config_file, |var| env.get(var).cloned())
+    }
+
+    #[test]
+    fn test_profile_values_used_by_default() {
+        let context = resolve(&ContextFlags::default(), &ConfigFile::new(), &[]);
+
+        assert_eq!(
+            context.profile,
+            Some(Resolved::new(
+                "devnet".to_string(),
+                ValueSource::ConfigDefaults
+            ))
+        );
+        assert_eq!(context.rpc_url.value, "https://api.devnet.solana.com");
+        assert_eq!(
+            context.rpc_url.source,
+            ValueSource::Profile("devnet".to_string())
+        );
+        assert_eq!(context.usdc_mint(), Some(MINT));
+        assert_eq!(context.program_id, None);
+        assert_eq!(
+            context.wallet_path.value,
+            expand_tilde(SOLANA_CLI_DEFAULT_WALLET)
+        );
+        assert!(!context.wallet_path.value.starts_with('~'));
+        assert_eq!(context.wallet_path.source, ValueSource::BuiltIn);
+    }
+
+    #[test]
+    fn test_env_overrides_profile() {
+        let context = resolve(
+            &ContextFlags::default(),
+            &ConfigFile::new(),
+            &[
+                ("TALLY_RPC_URL", "http://env.rpc"),
+                ("TALLY_PROGRAM_ID", "EnvProgram"),
+                ("TALLY_USDC_MINT", "EnvMint"),
+            ],
+        );
+
+        assert_eq!(
+            context.rpc_url,
+            Resolved::new(
+                "http://env.rpc".to_string(),
+                ValueSource::Env("TALLY_RPC_URL")
+            )
+        );
+        assert_eq!(
+            context.program_id.unwrap().source,
+            ValueSource::Env("TALLY_PROGRAM_ID")
+        );
+        assert_eq!(context.usdc_mint.unwrap().value, "EnvMint");
+    }
+
+    #[test]
+    fn test_flags_override_env() {
+        let flags = ContextFlags {
+            rpc_url: Some("http://flag.rpc"),
+            program_id: Some("FlagProgram"),
+            usdc_mint: Some("FlagMint"),
+        };
+        let context = resolve(
+            &flags,
+            &ConfigFile::new(),
+            &[
+                ("TALLY_RPC_URL", "http://env.rpc"),
+                ("TALLY_USDC_MINT", "EnvMint"),
+            ],
+        );
+
+        assert_eq!(context.rpc_url.source, ValueSource::Flag("--rpc-url"));
+        assert_eq!(context.rpc_url.value, "http://flag.rpc");
+        assert_eq!(context.usdc_mint(), Some("FlagMint"));
+        assert_eq!(context.program_id.unwrap().value, "FlagProgram");
+    }
+
+    #[test]
+    fn test_tally_profile_env_selects_profile() {
+        let context = resolve(
+            &ContextFlags::default(),
+            &ConfigFile::new(),
+            &[("TALLY_PROFILE", "mainnet")],
+        );
+
+        assert_eq!(
+            context.profile.as_ref().unwrap().source,
+            ValueSource::Env("TALLY_PROFILE")
+        );
+        assert_eq!(context.rpc_url.value, "https://api.mainnet-beta.solana.com");
+        assert_eq!(
+            context.usdc_mint(),
+            Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
+        );
+    }
+
+    #[test]
+    fn test_unknown_tally_profile_fails() {
+        let config_file = ConfigFile::new();
+        let context = resolve(
+            &ContextFlags::default(),
+            &config_file,
+            &[("TALLY_PROFILE", "nope")],
+        );
+        assert_eq!(context.rpc_url.source, ValueSource::BuiltIn);
+        let error = context.check_profile(&config_file).unwrap_err();
+
+        let details = crate::errors::ErrorDetails::new(&error);
+        assert_eq!(details.code, ErrorCode::ConfigMissing);
+        assert!(details.message.contains("'nope'"));
+        let suggestion = details.suggestion.unwrap();
+        assert!(
+            suggestion.contains("devnet, localnet, mainnet"),
+            "{suggestion}"
+        );
+    }
+
+    #[test]
+    fn test_config_defaults_supply_program_id_and_mint() {
+        let mut config_file = ConfigFile::new();
+        config_file.defaults.program_id = Some("DefaultProgram".to_string());
+        config_file.defaults.usdc_mint = Some(MINT.to_string());
+        config_file.profiles.get_mut("devnet").unwrap().usdc_mint = None;
+
+        let context = resolve(&ContextFlags::default(), &config_file, &[]);
+        assert_eq!(
+            context.program_id,
+            Some(Resolved::new(
+                "DefaultProgram".to_string(),
+                ValueSource::ConfigDefaults
+            ))
+        );
+        assert_eq!(
+            context.usdc_mint,
+            Some(Resolved::new(MINT.to_string(), ValueSource::ConfigDefaults))
+        );
+
+        // The active profile still wins over the defaults
+        config_file.profiles.get_mut("devnet").unwrap().program_id =
+            Some("ProfileProgram".to_string());
+        let context = resolve(&ContextFlags::default(), &config_file, &[]);
+        assert_eq!(context.program_id.unwrap().value, "ProfileProgram");
+    }
+
+    #[test]
+    fn test_builtin_defaults_without_profile() {
+        let mut config_file = ConfigFile::new();
+        config_file.defaults.active_profile = None;
+        config_file.defaults.wallet_path = Some("~/keys/payee.json".to_string());
+
+        let context = resolve(&ContextFlags::default(), &config_file, &[]);
+        assert_eq!(context.profile, None);
+        assert_eq!(context.rpc_url.source, ValueSource::BuiltIn);
+        assert_eq!(context.usdc_mint, None);
+        assert_eq!(
+            context.wallet_path,
+            Resolved::new(
+                expand_tilde("~/keys/payee.json"),
+                ValueSource::ConfigDefaults
+            )
+        );
+    }
+
+    #[test]
+    fn test_wallet_path_precedence() {
+        let mut config_file = ConfigFile::new();
+        config_file.defaults.wallet_path = Some("/keys/default.json".to_string());
+        config_file
+            .set_profile_value("wallet-path", "/keys/devnet.json".to_string())
+            .unwrap();
+        config_file.set_active_profile("mainnet".to_string());
+        config_file
+            .set_profile_value("wallet-path", "/keys/mainnet.json".to_string())
+            .unwrap();
+
+        // Switching profiles switches the signing wallet
+        let context = resolve(&ContextFlags::default(), &config_file, &[]);
+        assert_eq!(context.signer_path(None), "/keys/mainnet.json");
+        let context = resolve(
+            &ContextFlags::default(),
+            &config_file,
+            &[("TALLY_PROFILE", "devnet")],
+        );
+        assert_eq!(context.signer_path(None), "/keys/devnet.json");
+
+        let context = resolve(
+            &ContextFlags::default(),
+            &config_file,
+            &[("TALLY_WALLET", "/keys/env.json")],
+        );
+        assert_eq!(context.wallet_path.source, ValueSource::Env("TALLY_WALLET"));
+        assert_eq!(context.signer_path(None), "/keys/env.json");
+        assert_eq!(
+            context.signer_path(Some("/keys/flag.json")),
+            "/keys/flag.json"
+        );
+
+        config_file.profiles.get_mut("mainnet").unwrap().wallet_path = None;
+        let context = resolve(&ContextFlags::default(), &config_file, &[]);
+        assert_eq!(context.wallet_path.source, ValueSource::ConfigDefaults);
+        assert_eq!(context.signer_path(None), "/keys/default.json");
+    }
+
+    #[test]
+    fn test_payee_from_profile_merchant() {
+        let mut config_file = ConfigFile::new();
+        config_file
+            .set_payee("HkDq7K2RRStvPrXw6U3YPJrPU2dYbvGj8Y5z8VQmKR8C".to_string())
+            .unwrap();
+
+        let context = resolve(&ContextFlags::default(), &config_file, &[]);
+        let payee = context.payee.unwrap();
+        assert_eq!(payee.value, "HkDq7K2RRStvPrXw6U3YPJrPU2dYbvGj8Y5z8VQmKR8C");
+        assert_eq!(payee.source, ValueSource::Profile("devnet".to_string()));
+
+        let context = resolve(
+            &ContextFlags::default(),
+            &config_file,
+            &[(
+                "TALLY_PAYEE",
+                "8rPqJKt2fT9xYw5zR3vN8mPdLkQcXnU1wVbHjGaFsYe4",
+            )],
+        );
+        assert_eq!(
+            context.payee,
+            Some(Resolved::new(
+                "8rPqJKt2fT9xYw5zR3vN8mPdLkQcXnU1wVbHjGaFsYe4".to_string(),
+                ValueSource::Env("TALLY_PAYEE")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_effective_config_json_includes_sources() {
+        let context = resolve(&ContextFlags::default(), &ConfigFile::new(), &[]);
+        let json = serde_json::to_value(EffectiveConfig::from(&context)).unwrap();
+
+        assert_eq!(json["rpc_url"]["source"], "profile 'devnet'");
+        assert!(json["program_id"].is_null());
+        assert_eq!(json["wallet_path"]["source"], "built-in default");
+    }
+}
@@ -2,15 +2,409 @@
 //!
 //! This module provides error types and functions that give users
 //! clear guidance on how to fix common problems.
+//!
+//! Failures the CLI recognizes are raised as a [`CliError`], which carries a
+//! stable [`ErrorCode`] and a recovery suggestion alongside the message. The
+//! code selects the process exit status:
+//!
+//! | Code                   | Exit |
+//! |------------------------|------|
+//! | `GENERAL`              | 1    |
+//! | `INVALID_ADDRESS`      | 3    |
+//! | `CONFIG_MISSING`       | 4    |
+//! | `ACCOUNT_NOT_FOUND`    | 5    |
+//! | `AUTHORITY_MISMATCH`   | 6    |
+//! | `INSUFFICIENT_BALANCE` | 7    |
+//! | `PROGRAM_PAUSED`       | 8    |
+//! | `TRANSACTION_FAILED`   | 9    |
+//! | `RPC_ERROR`            | 10   |
+//!
+//! Exit status 2 is left to argument parsing errors.
 
-// Allow dead_code for utility functions that are part of the error handling API
-// but not yet used in all commands
-#![allow(dead_code)]
-
-use anyhow::{anyhow, Result};
-use std::fmt::Write as _;
+use crate::utils::program_errors::{self, ErrorSource, ProgramErrorInfo};
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::{self, Write as _};
 use std::str::FromStr;
+use tally_sdk::solana_client::client_error::{ClientError, ClientErrorKind};
+use tally_sdk::solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use tally_sdk::solana_sdk::instruction::InstructionError;
 use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::transaction::TransactionError;
+
+/// Stable identifier of a failure category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Anything not covered by a more specific code
+    General,
+    /// An address argument is not a valid public key
+    InvalidAddress,
+    /// A required setting such as the program ID or payee is not configured
+    ConfigMissing,
+    /// An on-chain account does not exist
+    AccountNotFound,
+    /// The loaded keypair is not the authority of the account
+    AuthorityMismatch,
+    /// Not enough SOL or USDC to complete the operation
+    InsufficientBalance,
+    /// The program is paused by the platform authority
+    ProgramPaused,
+    /// The program rejected a transaction
+    TransactionFailed,
+    /// The RPC endpoint could not be reached or returned an error
+    #[serde(rename = "RPC_ERROR")]
+    Rpc,
+}
+
+impl ErrorCode {
+    /// Process exit status for this code
+    #[must_use]
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            Self::General => 1,
+            Self::InvalidAddress => 3,
+            Self::ConfigMissing => 4,
+            Self::AccountNotFound => 5,
+            Self::AuthorityMismatch => 6,
+            Self::InsufficientBalance => 7,
+            Self::ProgramPaused => 8,
+            Self::TransactionFailed => 9,
+            Self::Rpc => 10,
+        }
+    }
+}
+
+/// Custom error returned by an on-chain program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ProgramErrorCode {
+    /// Index of the failing instruction, when known
+    pub instruction: Option<u8>,
+    /// Error number, e.g. `6001` (`0x1771`)
+    pub code: u32,
+    /// Program that raised the error, when known from the logs
+    pub program_id: Option<String>,
+    /// Error table the code was decoded from
+    pub source: Option<ErrorSource>,
+    pub name: Option<String>,
+    /// What the error means
+    pub explanation: Option<String>,
+    /// How to fix it
+    pub fix: Option<String>,
+}
+
+impl ProgramErrorCode {
+    /// Decode `code`, raised by `program_id` if known
+    #[must_use]
+    pub fn decode(instruction: Option<u8>, code: u32, program_id: Option<String>) -> Self {
+        let decoded = program_errors::lookup(code, program_id.as_deref());
+        Self::with_info(instruction, code, program_id, decoded)
+    }
+
+    /// A code decoded from a known table entry, or left undecoded
+    #[must_use]
+    pub fn with_info(
+        instruction: Option<u8>,
+        code: u32,
+        program_id: Option<String>,
+        decoded: Option<(ErrorSource, &ProgramErrorInfo)>,
+    ) -> Self {
+        Self {
+            instruction,
+            code,
+            program_id,
+            source: decoded.map(|(source, _)| source),
+            name: decoded.map(|(_, info)| info.name.to_string()),
+            explanation: decoded.map(|(_, info)| info.explanation.to_string()),
+            fix: decoded.map(|(_, info)| info.fix.to_string()),
+        }
+    }
+
+    /// CLI error code for this program error
+    #[must_use]
+    pub fn category(&self) -> ErrorCode {
+        self.source
+            .and_then(|source| program_errors::find(source, self.code))
+            .map_or(ErrorCode::TransactionFailed, |info| info.category)
+    }
+
+    /// Explanation and fix as a suggestion, if the code was decoded
+    fn suggestion(&self) -> Option<String> {
+        match (&self.explanation, &self.fix) {
+            (Some(explanation), Some(fix)) => {
+                Some(format!("{explanation}\n\nTo fix this:\n  • {fix}"))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProgramErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(source) = self.source {
+            write!(f, "{} ", source.as_str())?;
+        }
+        write!(f, "program error {} ({:#x})", self.code, self.code)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+        if let Some(instruction) = self.instruction {
+            write!(f, " in instruction {instruction}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find a custom program error in an error message or transaction logs
+///
+/// Recognizes `custom program error: 0x1771` (optionally preceded by
+/// `Error processing Instruction N`) and Anchor's `Error Number: 6001`.
+#[must_use]
+pub fn parse_program_error(text: &str) -> Option<ProgramErrorCode> {
+    let instruction = text
+        .split("Error processing Instruction ")
+        .nth(1)
+        .and_then(|rest| leading_digits(rest).parse().ok());
+
+    if let Some(rest) = text.split("custom program error: 0x").nth(1) {
+        let hex: String = rest.chars().take_while(char::is_ascii_hexdigit).collect();
+        if let Ok(code) = u32::from_str_radix(&hex, 16) {
+            return Some(ProgramErrorCode::decode(instruction, code, None));
+        }
+    }
+    text.split("Error Number: ")
+        .nth(1)
+        .and_then(|rest| leading_digits(rest).parse().ok())
+        .map(|code| ProgramErrorCode::decode(instruction, code, None))
+}
+
+/// Find the program error in a failed transaction's logs and error message
+///
+/// The logs name the failing program, which decides how the code is read;
+/// the message adds the index of the failing instruction.
+#[must_use]
+pub fn find_program_error(message: &str, logs: &[String]) -> Option<ProgramErrorCode> {
+    let from_message = parse_program_error(message);
+    let Some((program_id, code)) = program_errors::failing_program(logs) else {
+        return from_message;
+    };
+    let instruction = from_message.and_then(|error| error.instruction);
+    Some(ProgramErrorCode::decode(
+        instruction,
+        code,
+        Some(program_id),
+    ))
+}
+
+/// Program logs from a transaction rejected in preflight simulation
+#[must_use]
+pub fn preflight_logs(error: &ClientError) -> Vec<String> {
+    match error.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => result.logs.clone().unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn leading_digits(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+/// A failure with a stable code and a suggestion on how to fix it
+///
+/// Wrap it in an [`anyhow::Error`] like any other error; `main` finds it in
+/// the error chain when printing the failure.
+#[derive(Debug, Clone)]
+pub struct CliError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// How to fix the problem
+    pub suggestion: Option<String>,
+    /// Program error behind a failed transaction
+    pub program_error: Option<ProgramErrorCode>,
+    /// Program logs of a failed transaction
+    pub logs: Vec<String>,
+}
+
+impl CliError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            suggestion: None,
+            program_error: None,
+            logs: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_program_error(mut self, program_error: Option<ProgramErrorCode>) -> Self {
+        self.program_error = program_error;
+        self
+    }
+
+    /// Keep the log lines relevant to the failure
+    #[must_use]
+    pub fn with_logs(mut self, logs: &[String]) -> Self {
+        self.logs = program_errors::relevant_logs(logs);
+        self
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n\n{suggestion}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Machine-readable description of a failed command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ErrorDetails {
+    pub code: ErrorCode,
+    /// Process exit status
+    pub exit_code: i32,
+    pub message: String,
+    /// How to fix the problem
+    pub suggestion: Option<String>,
+    /// Program error behind a failed transaction
+    pub program_error: Option<ProgramErrorCode>,
+    /// Program logs of a failed transaction
+    pub logs: Vec<String>,
+}
+
+impl ErrorDetails {
+    /// Classify an error
+    ///
+    /// Uses the first [`CliError`] in the chain. Otherwise RPC client errors
+    /// and program errors in the message are recognized, and anything else
+    /// is [`ErrorCode::General`].
+    #[must_use]
+    pub fn new(error: &anyhow::Error) -> Self {
+        let cli_error = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<CliError>())
+            .cloned()
+            .unwrap_or_else(|| classify(error));
+
+        // `anyhow::Error::downcast_ref` also sees through `.context()`, so
+        // only the outermost cause tells whether context was added on top
+        let is_top_level = error
+            .chain()
+            .next()
+            .and_then(|cause| cause.downcast_ref::<CliError>())
+            .is_some();
+        Self {
+            code: cli_error.code,
+            exit_code: cli_error.code.exit_code(),
+            message: if is_top_level {
+                cli_error.message
+            } else {
+                error.to_string()
+            },
+            suggestion: cli_error.suggestion,
+            program_error: cli_error.program_error,
+            logs: cli_error.logs,
+        }
+    }
+}
+
+/// Classify an error that is not a [`CliError`]
+fn classify(error: &anyhow::Error) -> CliError {
+    let message = error.to_string();
+    if let Some(client_error) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ClientError>())
+    {
+        return classify_client_error(client_error, message);
+    }
+
+    let full = format!("{error:#}");
+    let lower = full.to_lowercase();
+    if let Some(program_error) = parse_program_error(&full) {
+        program_failure(message, program_error, &[])
+    } else if lower.contains("insufficient funds") || lower.contains("insufficient lamports") {
+        CliError::new(ErrorCode::InsufficientBalance, message)
+    } else {
+        CliError::new(ErrorCode::General, message)
+    }
+}
+
+fn classify_client_error(error: &ClientError, message: String) -> CliError {
+    let logs = preflight_logs(error);
+    match error.get_transaction_error() {
+        Some(TransactionError::InstructionError(index, InstructionError::Custom(code))) => {
+            let program_error = program_errors::failing_program(&logs).map_or_else(
+                || ProgramErrorCode::decode(Some(index), code, None),
+                |(program_id, _)| ProgramErrorCode::decode(Some(index), code, Some(program_id)),
+            );
+            program_failure(message, program_error, &logs)
+        }
+        Some(
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. },
+        ) => CliError::new(ErrorCode::InsufficientBalance, message).with_logs(&logs),
+        Some(_) => CliError::new(ErrorCode::TransactionFailed, message).with_logs(&logs),
+        None => match error.kind() {
+            ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) | ClientErrorKind::RpcError(_) => {
+                CliError::new(ErrorCode::Rpc, message)
+            }
+            _ => CliError::new(ErrorCode::General, message),
+        },
+    }
+}
+
+/// A transaction rejected with `program_error`, explained when decoded
+fn program_failure(message: String, program_error: ProgramErrorCode, logs: &[String]) -> CliError {
+    let mut error = CliError::new(program_error.category(), message).with_logs(logs);
+    error.suggestion = program_error.suggestion();
+    error.with_program_error(Some(program_error))
+}
+
+/// Enhance a failed write transaction with the decoded program error
+///
+/// `action` describes what failed, e.g. "Failed to create payment terms".
+/// The program error is found in `logs` (from the preflight simulation or a
+/// re-simulation of the transaction) or in the error message, and its
+/// explanation and fix become the suggestion.
+#[must_use]
+pub fn enhance_transaction_error<E: std::fmt::Display>(
+    action: &str,
+    original_error: E,
+    logs: &[String],
+) -> anyhow::Error {
+    let message = format!("{action}: {original_error}");
+    if let Some(program_error) = find_program_error(&message, logs) {
+        return program_failure(message, program_error, logs).into();
+    }
+
+    let lower = message.to_lowercase();
+    let code = if lower.contains("insufficient funds") || lower.contains("insufficient lamports") {
+        ErrorCode::InsufficientBalance
+    } else if lower.contains("timeout") || lower.contains("connection") {
+        ErrorCode::Rpc
+    } else {
+        ErrorCode::TransactionFailed
+    };
+    CliError::new(code, message).with_logs(logs).into()
+}
 
 /// Parse a merchant PDA with enhanced error messages
 ///
@@ -23,10 +417,9 @@ use tally_sdk::solana_sdk::pubkey::Pubkey;
 /// Returns enhanced error with recovery suggestions if parsing fails
 pub fn parse_merchant_pda(merchant_str: &str, config_merchant: Option<&Pubkey>) -> Result<Pubkey> {
     Pubkey::from_str(merchant_str).map_err(|e| {
-        let mut error_msg = format!(
-            "Invalid merchant address: '{merchant_str}'\n\n\
-             Merchant addresses must be base58-encoded Solana public keys (44 characters).\n\n\
-             Did you mean to:"
+        let mut error_msg = String::from(
+            "Merchant addresses must be base58-encoded Solana public keys (44 characters).\n\n\
+             Did you mean to:",
         );
 
         // Suggest using saved merchant if available
@@ -47,7 +440,12 @@ pub fn parse_merchant_pda(merchant_str: &str, config_merchant: Option<&Pubkey>)
             .push_str("\n\nExample valid address: HkDq7K2RRStvPrXw6U3YPJrPU2dYbvGj8Y5z8VQmKR8C");
         write!(error_msg, "\n\nOriginal error: {e}").expect("Writing to String should not fail");
 
-        anyhow!(error_msg)
+        CliError::new(
+            ErrorCode::InvalidAddress,
+            format!("Invalid merchant address: '{merchant_str}'"),
+        )
+        .with_suggestion(error_msg)
+        .into()
     })
 }
 
@@ -57,10 +455,9 @@ pub fn parse_merchant_pda(merchant_str: &str, config_merchant: Option<&Pubkey>)
 /// Returns enhanced error with recovery suggestions if parsing fails
 pub fn parse_plan_pda(plan_str: &str, merchant: Option<&Pubkey>) -> Result<Pubkey> {
     Pubkey::from_str(plan_str).map_err(|e| {
-        let mut error_msg = format!(
-            "Invalid plan address: '{plan_str}'\n\n\
-             Plan addresses must be base58-encoded Solana public keys (44 characters).\n\n\
-             Did you mean to:"
+        let mut error_msg = String::from(
+            "Plan addresses must be base58-encoded Solana public keys (44 characters).\n\n\
+             Did you mean to:",
         );
 
         if let Some(merchant_pda) = merchant {
@@ -81,27 +478,12 @@ pub fn parse_plan_pda(plan_str: &str, merchant: Option<&Pubkey>) -> Result<Pubke
             .push_str("\n\nExample valid address: 8rPqJKt2fT9xYw5zR3vN8mPdLkQcXnU1wVbHjGaFsYe4");
         write!(error_msg, "\n\nOriginal error: {e}").expect("Writing to String should not fail");
 
-        anyhow!(error_msg)
-    })
-}
-
-/// Parse a subscription PDA with enhanced error messages
-///
-/// # Errors
-/// Returns enhanced error with recovery suggestions if parsing fails
-pub fn parse_subscription_pda(subscription_str: &str) -> Result<Pubkey> {
-    Pubkey::from_str(subscription_str).map_err(|e| {
-        let error_msg = format!(
-            "Invalid subscription address: '{subscription_str}'\n\n\
-             Subscription addresses must be base58-encoded Solana public keys (44 characters).\n\n\
-             Did you mean to:\n  \
-             • List subscriptions with: tally-merchant subscription list --plan <PLAN_PDA>\n  \
-             • Check the address you copied\n\n\
-             Example valid address: 9sPtLMn3uU1qYw6aS4oX9dQkPmN2wRbHkJgGbFtZvYe5\n\n\
-             Original error: {e}"
-        );
-
-        anyhow!(error_msg)
+        CliError::new(
+            ErrorCode::InvalidAddress,
+            format!("Invalid plan address: '{plan_str}'"),
+        )
+        .with_suggestion(error_msg)
+        .into()
     })
 }
 
@@ -111,16 +493,17 @@ pub fn parse_subscription_pda(subscription_str: &str) -> Result<Pubkey> {
 /// Returns enhanced error with network troubleshooting tips
 #[must_use]
 pub fn enhance_rpc_error(original_error: &anyhow::Error, rpc_url: &str) -> anyhow::Error {
-    anyhow!(
-        "{original_error}\n\n\
-         RPC Connection Troubleshooting:\n  \
+    CliError::new(ErrorCode::Rpc, original_error.to_string())
+        .with_suggestion(format!(
+            "RPC Connection Troubleshooting:\n  \
          • Check if the RPC endpoint is accessible: {rpc_url}\n  \
          • Try using a different RPC endpoint with --rpc-url\n  \
          • For devnet: https://api.devnet.solana.com\n  \
          • For mainnet: https://api.mainnet-beta.solana.com\n  \
          • Check your internet connection\n  \
          • The RPC endpoint may be rate-limiting your requests"
-    )
+        ))
+        .into()
 }
 
 /// Enhance account not found errors with suggestions
@@ -129,21 +512,21 @@ pub fn enhance_rpc_error(original_error: &anyhow::Error, rpc_url: &str) -> anyho
 /// Returns enhanced error with account troubleshooting tips
 #[must_use]
 pub fn enhance_account_not_found_error(account_type: &str, address: &Pubkey) -> anyhow::Error {
-    match account_type {
-        "merchant" => anyhow!(
-            "Merchant account not found at address: {address}\n\n\
-             This could mean:\n  \
+    let (message, suggestion) = match account_type {
+        "merchant" => (
+            format!("Merchant account not found at address: {address}"),
+            "This could mean:\n  \
              • The merchant account hasn't been created yet\n  \
              • You're using the wrong network (devnet vs mainnet)\n  \
              • The address is incorrect\n\n\
              To fix this:\n  \
              • Run 'tally-merchant init' to create a new merchant\n  \
              • Check you're on the correct network (--rpc-url)\n  \
-             • Verify the merchant address with: tally-merchant config get merchant"
+             • Verify the merchant address with: tally-merchant config get merchant",
         ),
-        "plan" => anyhow!(
-            "Plan account not found at address: {address}\n\n\
-             This could mean:\n  \
+        "plan" => (
+            format!("Plan account not found at address: {address}"),
+            "This could mean:\n  \
              • The plan hasn't been created yet\n  \
              • You're using the wrong network (devnet vs mainnet)\n  \
              • The plan may have been deactivated\n  \
@@ -151,27 +534,78 @@ pub fn enhance_account_not_found_error(account_type: &str, address: &Pubkey) ->
              To fix this:\n  \
              • List your plans with: tally-merchant plan list --merchant <MERCHANT_PDA>\n  \
              • Create a new plan with: tally-merchant plan create --help\n  \
-             • Check you're on the correct network (--rpc-url)"
+             • Check you're on the correct network (--rpc-url)",
         ),
-        "subscription" => anyhow!(
-            "Subscription account not found at address: {address}\n\n\
-             This could mean:\n  \
+        "subscription" => (
+            format!("Subscription account not found at address: {address}"),
+            "This could mean:\n  \
              • The subscription hasn't been created yet\n  \
              • You're using the wrong network (devnet vs mainnet)\n  \
              • The subscription may have been canceled\n  \
              • The address is incorrect\n\n\
              To fix this:\n  \
              • List subscriptions with: tally-merchant subscription list --plan <PLAN_PDA>\n  \
-             • Check you're on the correct network (--rpc-url)"
+             • Check you're on the correct network (--rpc-url)",
+        ),
+        "payee" => (
+            format!("Payee account not found at address: {address}"),
+            "This could mean:\n  \
+             • The payee hasn't been initialized yet\n  \
+             • You're using the wrong network (devnet vs mainnet)\n  \
+             • The address is incorrect\n\n\
+             To fix this:\n  \
+             • Run 'tally-merchant init' to create a new payee\n  \
+             • Check you're on the correct network (--rpc-url)\n  \
+             • Verify the payee address with: tally-merchant config get merchant",
+        ),
+        "payment agreement" => (
+            format!("Payment agreement account not found at address: {address}"),
+            "This could mean:\n  \
+             • The payer hasn't started the agreement yet\n  \
+             • You're using the wrong network (devnet vs mainnet)\n  \
+             • The address is incorrect\n\n\
+             To fix this:\n  \
+             • List agreements with: tally-merchant agreement list --payment-terms <PDA>\n  \
+             • Check you're on the correct network (--rpc-url)",
         ),
-        _ => anyhow!(
-            "{account_type} account not found at address: {address}\n\n\
-             Check:\n  \
+        _ => (
+            format!("{account_type} account not found at address: {address}"),
+            "Check:\n  \
              • The address is correct\n  \
              • You're on the right network (--rpc-url)\n  \
-             • The account has been created"
+             • The account has been created",
         ),
-    }
+    };
+    CliError::new(ErrorCode::AccountNotFound, message)
+        .with_suggestion(suggestion)
+        .into()
+}
+
+/// Error for a missing global config account
+#[must_use]
+pub fn config_account_not_found() -> anyhow::Error {
+    CliError::new(ErrorCode::AccountNotFound, "Config account not found")
+        .with_suggestion(
+            "The program's global config has not been initialized on this network.\n  \
+             • Check you're on the correct network (--rpc-url) and program (--program-id)\n  \
+             • The platform authority must run 'tally-merchant admin init-config' first",
+        )
+        .into()
+}
+
+/// Error for a write rejected because the program is paused
+#[must_use]
+pub fn program_paused() -> anyhow::Error {
+    CliError::new(
+        ErrorCode::ProgramPaused,
+        "The Tally program is paused by the platform authority",
+    )
+    .with_suggestion(
+        "Payments and payee changes are disabled until it is unpaused.\n  \
+         • Check the status with: tally-merchant config show\n  \
+         • The platform authority can resume it with: tally-merchant admin unpause",
+    )
+    .into()
 }
 
 /// Enhance insufficient balance errors with actionable steps
@@ -197,20 +631,22 @@ pub fn enhance_insufficient_balance_error(
         "Get SOL from a faucet or exchange"
     };
 
-    anyhow!(
-        "Insufficient SOL balance\n\n\
-         Current balance: {balance_sol:.6} SOL\n\
-         Required:        {required_sol:.6} SOL\n\
-         Shortage:        {shortage:.6} SOL\n\n\
-         {funding_instructions}\n\n\
-         After funding, wait a few seconds for the balance to update,\n\
-         then run your command again."
-    )
+    CliError::new(ErrorCode::InsufficientBalance, "Insufficient SOL balance")
+        .with_suggestion(format!(
+            "Current balance: {balance_sol:.6} SOL\n\
+             Required:        {required_sol:.6} SOL\n\
+             Shortage:        {shortage:.6} SOL\n\n\
+             {funding_instructions}\n\n\
+             After funding, wait a few seconds for the balance to update,\n\
+             then run your command again."
+        ))
+        .into()
 }
 
 /// Enhance merchant initialization errors with detailed diagnostics
 ///
-/// Analyzes the underlying SDK/RPC error and provides specific recovery steps
+/// Analyzes the underlying SDK/RPC error and provides specific recovery steps.
+/// A program error found in `logs` or the error is decoded first.
 ///
 /// # Errors
 /// Returns enhanced error with context-specific troubleshooting
@@ -219,111 +655,166 @@ pub fn enhance_payee_init_error<E: std::fmt::Display>(
     original_error: E,
     authority: &Pubkey,
     treasury: &Pubkey,
+    logs: &[String],
 ) -> anyhow::Error {
     let error_str = original_error.to_string().to_lowercase();
+    let program_error = find_program_error(&original_error.to_string(), logs);
 
-    // Detect specific error categories and provide targeted guidance
-    if error_str.contains("insufficient") && error_str.contains("funds") {
-        anyhow!(
-            "Failed to initialize merchant: Insufficient SOL balance\n\n\
-             Your wallet needs SOL for:\n  \
-             • Transaction fees (~0.000005 SOL)\n  \
-             • Rent for merchant account (~0.002 SOL)\n  \
-             • Rent for treasury ATA if creating (~0.002 SOL)\n\n\
+    // A decoded program error says exactly what went wrong
+    if let Some(decoded) = program_error.as_ref().filter(|e| e.explanation.is_some()) {
+        let explanation = decoded.explanation.as_deref().unwrap_or_default();
+        let fix = decoded.fix.as_deref().unwrap_or_default();
+        return CliError::new(
+            decoded.category(),
+            format!(
+                "Failed to initialize merchant: {}",
+                explanation.trim_end_matches('.')
+            ),
+        )
+        .with_suggestion(format!(
+            "To fix this:\n  \
+             • {fix}\n\n\
              Authority wallet: {authority}\n\
              Treasury account: {treasury}\n\n\
-             Solutions:\n  \
-             • Get devnet SOL: https://faucet.solana.com\n  \
-             • Check balance: solana balance {authority}\n  \
-             • Use a different wallet with --authority\n\n\
              Original error: {original_error}"
+        ))
+        .with_program_error(program_error)
+        .with_logs(logs)
+        .into();
+    }
+
+    // Detect specific error categories and provide targeted guidance
+    let (code, message, suggestion) = if error_str.contains("insufficient")
+        && error_str.contains("funds")
+    {
+        (
+            ErrorCode::InsufficientBalance,
+            "Failed to initialize merchant: Insufficient SOL balance",
+            format!(
+                "Your wallet needs SOL for:\n  \
+                 • Transaction fees (~0.000005 SOL)\n  \
+                 • Rent for merchant account (~0.002 SOL)\n  \
+                 • Rent for treasury ATA if creating (~0.002 SOL)\n\n\
+                 Authority wallet: {authority}\n\
+                 Treasury account: {treasury}\n\n\
+                 Solutions:\n  \
+                 • Get devnet SOL: https://faucet.solana.com\n  \
+                 • Check balance: solana balance {authority}\n  \
+                 • Use a different wallet with --authority\n\n\
+                 Original error: {original_error}"
+            ),
         )
     } else if error_str.contains("already in use") || error_str.contains("account already exists") {
-        anyhow!(
-            "Failed to initialize merchant: Merchant account already exists\n\n\
-             Authority wallet: {authority}\n\n\
-             This wallet has already been used to create a merchant account.\n\
-             Each wallet can only create one merchant.\n\n\
-             Solutions:\n  \
-             • Use a different wallet: tally-merchant init (select different wallet)\n  \
-             • View existing merchant: tally-merchant config get merchant\n  \
-             • If you want to manage the existing merchant, no action needed\n\n\
-             Original error: {original_error}"
+        (
+            ErrorCode::TransactionFailed,
+            "Failed to initialize merchant: Merchant account already exists",
+            format!(
+                "Authority wallet: {authority}\n\n\
+                 This wallet has already been used to create a merchant account.\n\
+                 Each wallet can only create one merchant.\n\n\
+                 Solutions:\n  \
+                 • Use a different wallet: tally-merchant init (select different wallet)\n  \
+                 • View existing merchant: tally-merchant config get merchant\n  \
+                 • If you want to manage the existing merchant, no action needed\n\n\
+                 Original error: {original_error}"
+            ),
         )
     } else if error_str.contains("invalid") && error_str.contains("account") {
-        anyhow!(
-            "Failed to initialize merchant: Invalid treasury account\n\n\
-             Treasury account: {treasury}\n\n\
-             The provided treasury account is invalid or doesn't meet requirements.\n\n\
-             Common issues:\n  \
-             • Account doesn't exist on-chain\n  \
-             • Account is not a USDC token account\n  \
-             • Account is owned by a different wallet\n  \
-             • Wrong network (devnet vs mainnet)\n\n\
-             Solutions:\n  \
-             • Let the CLI create the treasury automatically (press Enter when prompted)\n  \
-             • Verify the account exists: solana account {treasury}\n  \
-             • Check you're on the correct network (--rpc-url)\n  \
-             • Use your wallet's default USDC ATA instead\n\n\
-             Original error: {original_error}"
+        (
+            ErrorCode::TransactionFailed,
+            "Failed to initialize merchant: Invalid treasury account",
+            format!(
+                "Treasury account: {treasury}\n\n\
+                 The provided treasury account is invalid or doesn't meet requirements.\n\n\
+                 Common issues:\n  \
+                 • Account doesn't exist on-chain\n  \
+                 • Account is not a USDC token account\n  \
+                 • Account is owned by a different wallet\n  \
+                 • Wrong network (devnet vs mainnet)\n\n\
+                 Solutions:\n  \
+                 • Let the CLI create the treasury automatically (press Enter when prompted)\n  \
+                 • Verify the account exists: solana account {treasury}\n  \
+                 • Check you're on the correct network (--rpc-url)\n  \
+                 • Use your wallet's default USDC ATA instead\n\n\
+                 Original error: {original_error}"
+            ),
         )
     } else if error_str.contains("timeout") || error_str.contains("connection") {
-        anyhow!(
-            "Failed to initialize merchant: RPC connection error\n\n\
-             The transaction was sent but we couldn't confirm it completed.\n\n\
-             This could mean:\n  \
-             • RPC endpoint is slow or overloaded\n  \
-             • Network connectivity issues\n  \
-             • Transaction might have succeeded despite the timeout\n\n\
-             Solutions:\n  \
-             • Check if merchant was created: tally-merchant config get merchant\n  \
-             • Try a different RPC endpoint: --rpc-url https://api.devnet.solana.com\n  \
-             • Wait a minute and check transaction status on Solana Explorer\n  \
-             • Retry the command\n\n\
-             Authority wallet: {authority}\n\n\
-             Original error: {original_error}"
+        (
+            ErrorCode::Rpc,
+            "Failed to initialize merchant: RPC connection error",
+            format!(
+                "The transaction was sent but we couldn't confirm it completed.\n\n\
+                 This could mean:\n  \
+                 • RPC endpoint is slow or overloaded\n  \
+                 • Network connectivity issues\n  \
+                 • Transaction might have succeeded despite the timeout\n\n\
+                 Solutions:\n  \
+                 • Check if merchant was created: tally-merchant config get merchant\n  \
+                 • Try a different RPC endpoint: --rpc-url https://api.devnet.solana.com\n  \
+                 • Wait a minute and check transaction status on Solana Explorer\n  \
+                 • Retry the command\n\n\
+                 Authority wallet: {authority}\n\n\
+                 Original error: {original_error}"
+            ),
         )
-    } else if error_str.contains("program") && error_str.contains("failed") {
-        anyhow!(
-            "Failed to initialize merchant: Program execution error\n\n\
-             The Tally program rejected the transaction.\n\n\
-             This usually indicates:\n  \
-             • A constraint violation in the program\n  \
-             • Incorrect account configuration\n  \
-             • Program bug or incompatibility\n\n\
-             Debug information:\n  \
-             • Authority: {authority}\n  \
-             • Treasury: {treasury}\n\n\
-             Solutions:\n  \
-             • Verify you're using the correct program ID\n  \
-             • Check you're on the correct network (devnet vs mainnet)\n  \
-             • Report this issue: https://github.com/Tally-Pay/tally-cli/issues\n\n\
-             Full error details:\n{original_error}"
+    } else if program_error.is_some()
+        || (error_str.contains("program") && error_str.contains("failed"))
+    {
+        (
+            ErrorCode::TransactionFailed,
+            "Failed to initialize merchant: Program execution error",
+            format!(
+                "The Tally program rejected the transaction.\n\n\
+                 This usually indicates:\n  \
+                 • A constraint violation in the program\n  \
+                 • Incorrect account configuration\n  \
+                 • Program bug or incompatibility\n\n\
+                 Debug information:\n  \
+                 • Authority: {authority}\n  \
+                 • Treasury: {treasury}\n\n\
+                 Solutions:\n  \
+                 • Verify you're using the correct program ID\n  \
+                 • Check you're on the correct network (devnet vs mainnet)\n  \
+                 • Report this issue: https://github.com/Tally-Pay/tally-cli/issues\n\n\
+                 Full error details:\n{original_error}"
+            ),
         )
     } else {
         // Generic fallback with full error context
-        anyhow!(
-            "Failed to initialize merchant\n\n\
-             An unexpected error occurred during merchant initialization.\n\n\
-             Debug information:\n  \
-             • Authority: {authority}\n  \
-             • Treasury: {treasury}\n\n\
-             Solutions:\n  \
-             • Check your internet connection\n  \
-             • Verify RPC endpoint is accessible\n  \
-             • Ensure you have sufficient SOL for fees\n  \
-             • Try again in a few moments\n  \
-             • Check Solana network status: https://status.solana.com\n\n\
-             If this persists, report the issue at:\n\
-             https://github.com/Tally-Pay/tally-cli/issues\n\n\
-             Full error details:\n{original_error}"
+        (
+            ErrorCode::General,
+            "Failed to initialize merchant",
+            format!(
+                "An unexpected error occurred during merchant initialization.\n\n\
+                 Debug information:\n  \
+                 • Authority: {authority}\n  \
+                 • Treasury: {treasury}\n\n\
+                 Solutions:\n  \
+                 • Check your internet connection\n  \
+                 • Verify RPC endpoint is accessible\n  \
+                 • Ensure you have sufficient SOL for fees\n  \
+                 • Try again in a few moments\n  \
+                 • Check Solana network status: https://status.solana.com\n\n\
+                 If this persists, report the issue at:\n\
+                 https://github.com/Tally-Pay/tally-cli/issues\n\n\
+                 Full error details:\n{original_error}"
+            ),
         )
-    }
+    };
+
+    CliError::new(code, message)
+        .with_suggestion(suggestion)
+        .with_program_error(program_error)
+        .with_logs(logs)
+        .into()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::program_errors::TOKEN_PROGRAM_ID;
+    use anyhow::anyhow;
 
     #[test]
     fn test_parse_merchant_pda_with_invalid_string() {
@@ -382,7 +873,7 @@ mod tests {
         let authority = Pubkey::new_unique();
         let treasury = Pubkey::new_unique();
         let original_error = "insufficient funds for rent";
-        let error = enhance_payee_init_error(original_error, &authority, &treasury);
+        let error = enhance_payee_init_error(original_error, &authority, &treasury, &[]);
         let error_message = error.to_string();
         assert!(error_message.contains("Insufficient SOL balance"));
         assert!(error_message.contains("Transaction fees"));
@@ -396,7 +887,7 @@ mod tests {
         let authority = Pubkey::new_unique();
         let treasury = Pubkey::new_unique();
         let original_error = "account already in use";
-        let error = enhance_payee_init_error(original_error, &authority, &treasury);
+        let error = enhance_payee_init_error(original_error, &authority, &treasury, &[]);
         let error_message = error.to_string();
         assert!(error_message.contains("already exists"));
         assert!(error_message.contains("different wallet"));
@@ -408,7 +899,7 @@ mod tests {
         let authority = Pubkey::new_unique();
         let treasury = Pubkey::new_unique();
         let original_error = "invalid account data";
-        let error = enhance_payee_init_error(original_error, &authority, &treasury);
+        let error = enhance_payee_init_error(original_error, &authority, &treasury, &[]);
         let error_message = error.to_string();
         assert!(error_message.contains("Invalid treasury account"));
         assert!(error_message.contains(&treasury.to_string()));
@@ -420,7 +911,7 @@ mod tests {
         let authority = Pubkey::new_unique();
         let treasury = Pubkey::new_unique();
         let original_error = "connection timeout";
-        let error = enhance_payee_init_error(original_error, &authority, &treasury);
+        let error = enhance_payee_init_error(original_error, &authority, &treasury, &[]);
         let error_message = error.to_string();
         assert!(error_message.contains("RPC connection error"));
         assert!(error_message.contains("different RPC endpoint"));
@@ -432,7 +923,7 @@ mod tests {
         let authority = Pubkey::new_unique();
         let treasury = Pubkey::new_unique();
         let original_error = "program failed to complete";
-        let error = enhance_payee_init_error(original_error, &authority, &treasury);
+        let error = enhance_payee_init_error(original_error, &authority, &treasury, &[]);
         let error_message = error.to_string();
         assert!(error_message.contains("Program execution error"));
         assert!(error_message.contains("constraint violation"));
@@ -445,11 +936,103 @@ mod tests {
         let authority = Pubkey::new_unique();
         let treasury = Pubkey::new_unique();
         let original_error = "some unexpected error";
-        let error = enhance_payee_init_error(original_error, &authority, &treasury);
+        let error = enhance_payee_init_error(original_error, &authority, &treasury, &[]);
         let error_message = error.to_string();
         assert!(error_message.contains("unexpected error"));
         assert!(error_message.contains(&authority.to_string()));
         assert!(error_message.contains(&treasury.to_string()));
         assert!(error_message.contains("https://github.com/Tally-Pay/tally-cli/issues"));
     }
+
+    #[test]
+    fn test_parse_program_error() {
+        let error = parse_program_error(
+            "Transaction simulation failed: Error processing Instruction 1: \
+             custom program error: 0x7d1",
+        )
+        .unwrap();
+        assert_eq!(error.instruction, Some(1));
+        assert_eq!(error.code, 2001);
+        assert_eq!(error.name.as_deref(), Some("ConstraintHasOne"));
+
+        let error = parse_program_error("AnchorError occurred. Error Number: 6003.").unwrap();
+        assert_eq!(error.code, 6003);
+        assert_eq!(error.name.as_deref(), Some("NotDue"));
+
+        assert!(parse_program_error("connection refused").is_none());
+    }
+
+    #[test]
+    fn test_error_details_uses_cli_error_in_chain() {
+        let error = enhance_account_not_found_error("merchant", &Pubkey::new_unique())
+            .context("Failed to load payee");
+        let details = ErrorDetails::new(&error);
+        assert_eq!(details.code, ErrorCode::AccountNotFound);
+        assert_eq!(details.exit_code, 5);
+        assert_eq!(details.message, "Failed to load payee");
+        assert!(details.suggestion.unwrap().contains("tally-merchant init"));
+
+        let details = ErrorDetails::new(&parse_plan_pda("invalid", None).unwrap_err());
+        assert_eq!(details.code, ErrorCode::InvalidAddress);
+        assert_eq!(details.message, "Invalid plan address: 'invalid'");
+    }
+
+    #[test]
+    fn test_error_details_classifies_messages() {
+        let details = ErrorDetails::new(&anyhow!(
+            "Failed to deactivate payment terms: custom program error: 0x1770"
+        ));
+        assert_eq!(details.code, ErrorCode::TransactionFailed);
+        assert_eq!(details.program_error.unwrap().code, 6000);
+
+        let details = ErrorDetails::new(&anyhow!("boom"));
+        assert_eq!(details.code, ErrorCode::General);
+        assert_eq!(details.exit_code, 1);
+        assert_eq!(details.suggestion, None);
+    }
+
+    #[test]
+    fn test_enhance_payee_init_error_decodes_program_error() {
+        let logs = vec![
+            "Program log: Instruction: InitPayee".to_string(),
+            format!("Program {TOKEN_PROGRAM_ID} failed: custom program error: 0x1"),
+        ];
+        let error = enhance_payee_init_error(
+            "Error processing Instruction 0: custom program error: 0x1",
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &logs,
+        );
+        let details = ErrorDetails::new(&error);
+        assert_eq!(details.code, ErrorCode::InsufficientBalance);
+        assert!(details.message.contains("does not hold enough tokens"));
+        let program_error = details.program_error.unwrap();
+        assert_eq!(program_error.source, Some(ErrorSource::SplToken));
+        assert_eq!(program_error.name.as_deref(), Some("InsufficientFunds"));
+        assert_eq!(program_error.instruction, Some(0));
+        assert_eq!(details.logs, logs);
+    }
+
+    #[test]
+    fn test_enhance_transaction_error() {
+        let error = enhance_transaction_error(
+            "Failed to create payment terms",
+            "Transaction simulation failed: Error processing Instruction 0: \
+             custom program error: 0x1776",
+            &[],
+        );
+        let details = ErrorDetails::new(&error);
+        assert_eq!(details.code, ErrorCode::TransactionFailed);
+        assert!(details
+            .message
+            .starts_with("Failed to create payment terms: "));
+        assert!(details.suggestion.unwrap().contains("minimum period"));
+        assert_eq!(
+            details.program_error.unwrap().name.as_deref(),
+            Some("InvalidPeriod")
+        );
+
+        let error = enhance_transaction_error("Failed to withdraw", "connection reset", &[]);
+        assert_eq!(ErrorDetails::new(&error).code, ErrorCode::Rpc);
+    }
 }
@@ -0,0 +1,512 @@
+//! Encrypted keystore for CLI-managed wallets
+//!
+//! Keypairs are stored one per file in `keystore/` next to the config file
+//! (`~/.config/tally/keystore/<name>.json` on Linux), so teams without the
+//! Solana CLI can keep authority keys encrypted at rest. Profiles reference
+//! them as `keystore:<name>` in `wallet-path`.
+//!
+//! Each secret key is encrypted with AES-256-GCM-SIV under a key derived from
+//! the passphrase with Argon2id. The public key is stored in the clear (so
+//! `wallet list` needs no passphrase) and bound to the ciphertext as
+//! associated data, so editing it makes decryption fail.
+
+use crate::config_file::ConfigFile;
+use aes_gcm_siv::aead::rand_core::RngCore;
+use aes_gcm_siv::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce};
+use anyhow::{anyhow, Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use dialoguer::Password;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::{Keypair, Signer};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the keystore passphrase for non-interactive use
+pub const PASSPHRASE_ENV: &str = "TALLY_KEYSTORE_PASSPHRASE";
+
+const KEYSTORE_VERSION: u8 = 1;
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER_ALGORITHM: &str = "aes-256-gcm-siv";
+const SALT_LEN: usize = 16;
+
+/// Argon2id cost parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of passes
+    pub iterations: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    /// The Argon2id parameters recommended by OWASP (19 MiB, 2 passes)
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Key derivation settings stored with each wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfSection {
+    pub algorithm: String,
+    #[serde(flatten)]
+    pub params: KdfParams,
+    /// Base64 salt
+    pub salt: String,
+}
+
+/// Cipher settings stored with each wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherSection {
+    pub algorithm: String,
+    /// Base64 nonce
+    pub nonce: String,
+}
+
+/// One encrypted wallet file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreEntry {
+    pub version: u8,
+    pub name: String,
+    pub pubkey: String,
+    /// Unix timestamp
+    pub created_at: i64,
+    pub kdf: KdfSection,
+    pub cipher: CipherSection,
+    /// Base64 encrypted 64-byte keypair
+    pub ciphertext: String,
+}
+
+/// Directory of encrypted wallets
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    /// The keystore next to the config file
+    ///
+    /// # Errors
+    /// Returns error if the config directory cannot be determined
+    pub fn open_default() -> Result<Self> {
+        let config_path = ConfigFile::config_file_path()?;
+        let dir = config_path
+            .parent()
+            .context("Config file path has no parent directory")?
+            .join("keystore");
+        Ok(Self::at(dir))
+    }
+
+    /// A keystore in `dir`
+    #[must_use]
+    pub const fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File holding the wallet `name`
+    #[must_use]
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Encrypt `keypair` under `passphrase` and store it as `name`
+    ///
+    /// # Errors
+    /// Returns error if the name is invalid or taken, or the file cannot be written
+    pub fn create(
+        &self,
+        name: &str,
+        keypair: &Keypair,
+        passphrase: &str,
+        params: KdfParams,
+    ) -> Result<PathBuf> {
+        validate_name(name)?;
+        let path = self.path(name);
+        if path.exists() {
+            return Err(anyhow!(
+                "Keystore wallet '{name}' already exists at {}",
+                path.display()
+            ));
+        }
+
+        let entry = encrypt(name, keypair, passphrase, params)?;
+        let contents = serde_json::to_string_pretty(&entry)?;
+        create_private_dir(&self.dir)?;
+        write_private_file(&path, contents.as_bytes())?;
+        Ok(path)
+    }
+
+    /// Read a wallet's metadata without decrypting it
+    ///
+    /// # Errors
+    /// Returns error if the wallet does not exist or the file is invalid
+    pub fn read(&self, name: &str) -> Result<KeystoreEntry> {
+        validate_name(name)?;
+        let path = self.path(name);
+        if !path.exists() {
+            return Err(anyhow!(
+                "Keystore wallet '{name}' not found in {}\n\
+                 List wallets with 'tally-merchant wallet list', or create one with \
+                 'tally-merchant wallet new {name}'",
+                self.dir.display()
+            ));
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid keystore file {}", path.display()))
+    }
+
+    /// All wallets, sorted by name
+    ///
+    /// # Errors
+    /// Returns error if the directory or a wallet file cannot be read
+    pub fn list(&self) -> Result<Vec<KeystoreEntry>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut entries = Vec::new();
+        for file in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read {}", self.dir.display()))?
+        {
+            let path = file?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let contents = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                entries.push(
+                    serde_json::from_str(&contents)
+                        .with_context(|| format!("Invalid keystore file {}", path.display()))?,
+                );
+            }
+        }
+        entries.sort_by(|a: &KeystoreEntry, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Decrypt the wallet `name`
+    ///
+    /// # Errors
+    /// Returns error if the wallet does not exist or the passphrase is wrong
+    pub fn unlock(&self, name: &str, passphrase: &str) -> Result<Keypair> {
+        decrypt(&self.read(name)?, passphrase)
+    }
+}
+
+/// Check a wallet name is usable as a file name
+///
+/// # Errors
+/// Returns error unless the name is 1-64 letters, digits, `-` or `_`
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Invalid wallet name '{name}' - use 1-64 letters, digits, '-' or '_'"
+        ))
+    }
+}
+
+/// Read a passphrase from `TALLY_KEYSTORE_PASSPHRASE` or the terminal
+///
+/// With `confirm`, the terminal prompt asks twice and rejects an empty passphrase.
+///
+/// # Errors
+/// Returns error if the prompt fails
+pub fn read_passphrase(prompt: &str, confirm: bool) -> Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    let mut input = Password::new().with_prompt(prompt);
+    if confirm {
+        input = input
+            .with_confirmation("Repeat passphrase", "Passphrases do not match")
+            .validate_with(|value: &String| {
+                if value.chars().count() >= 8 {
+                    Ok(())
+                } else {
+                    Err("Use at least 8 characters")
+                }
+            });
+    }
+    input.interact().map(Zeroizing::new).with_context(|| {
+        format!("Failed to read passphrase - set {PASSPHRASE_ENV} to run non-interactively")
+    })
+}
+
+/// Load the keystore wallet `name`, asking for its passphrase
+///
+/// # Errors
+/// Returns error if the wallet does not exist or cannot be decrypted
+pub fn load_keypair(name: &str) -> Result<Keypair> {
+    let keystore = Keystore::open_default()?;
+    let entry = keystore.read(name)?;
+    let passphrase = read_passphrase(
+        &format!("Passphrase for keystore wallet '{name}' ({})", entry.pubkey),
+        false,
+    )?;
+    decrypt(&entry, &passphrase)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<Zeroizing<[u8; 32]>> {
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| anyhow!("Key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+fn encrypt(
+    name: &str,
+    keypair: &Keypair,
+    passphrase: &str,
+    params: KdfParams,
+) -> Result<KeystoreEntry> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, params)?;
+    let nonce = Aes256GcmSiv::generate_nonce(&mut OsRng);
+    let pubkey = keypair.pubkey().to_string();
+
+    let secret = Zeroizing::new(keypair.to_bytes());
+    let ciphertext = Aes256GcmSiv::new(key.as_ref().into())
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: secret.as_ref(),
+                aad: pubkey.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt keypair"))?;
+
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        });
+    Ok(KeystoreEntry {
+        version: KEYSTORE_VERSION,
+        name: name.to_string(),
+        pubkey,
+        created_at,
+        kdf: KdfSection {
+            algorithm: KDF_ALGORITHM.to_string(),
+            params,
+            salt: STANDARD.encode(salt),
+        },
+        cipher: CipherSection {
+            algorithm: CIPHER_ALGORITHM.to_string(),
+            nonce: STANDARD.encode(nonce),
+        },
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+fn decrypt(entry: &KeystoreEntry, passphrase: &str) -> Result<Keypair> {
+    if entry.version != KEYSTORE_VERSION
+        || entry.kdf.algorithm != KDF_ALGORITHM
+        || entry.cipher.algorithm != CIPHER_ALGORITHM
+    {
+        return Err(anyhow!(
+            "Keystore wallet '{}' uses an unsupported format (version {}, {} + {})",
+            entry.name,
+            entry.version,
+            entry.kdf.algorithm,
+            entry.cipher.algorithm
+        ));
+    }
+    let invalid = |what: &str| anyhow!("Keystore wallet '{}' has an invalid {what}", entry.name);
+    let salt = STANDARD
+        .decode(&entry.kdf.salt)
+        .map_err(|_| invalid("salt"))?;
+    let nonce: [u8; 12] = STANDARD
+        .decode(&entry.cipher.nonce)
+        .ok()
+        .and_then(|nonce| nonce.try_into().ok())
+        .ok_or_else(|| invalid("nonce"))?;
+    let ciphertext = STANDARD
+        .decode(&entry.ciphertext)
+        .map_err(|_| invalid("ciphertext"))?;
+
+    let key = derive_key(passphrase, &salt, entry.kdf.params)?;
+    let secret = Zeroizing::new(
+        Aes256GcmSiv::new(key.as_ref().into())
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: entry.pubkey.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                anyhow!(
+                    "Wrong passphrase for keystore wallet '{}' (or the file was modified)",
+                    entry.name
+                )
+            })?,
+    );
+    let keypair = Keypair::from_bytes(&secret).map_err(|_| invalid("keypair"))?;
+    if Pubkey::from_str(&entry.pubkey).ok() != Some(keypair.pubkey()) {
+        return Err(invalid("public key"));
+    }
+    Ok(keypair)
+}
+
+fn create_private_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create keystore directory {}", dir.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("Failed to restrict permissions on {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Write a new file readable only by the current user
+///
+/// # Errors
+/// Returns error if the file exists or cannot be written
+pub fn write_private_file(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(contents)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap parameters so tests run quickly
+    const TEST_PARAMS: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_create_and_unlock() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::at(dir.path().join("keystore"));
+        let keypair = Keypair::new();
+
+        let path = keystore
+            .create("merchant", &keypair, "correct horse", TEST_PARAMS)
+            .unwrap();
+        assert_eq!(path, keystore.path("merchant"));
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(&keypair.pubkey().to_string()));
+        assert!(!contents.contains(&STANDARD.encode(keypair.to_bytes())));
+
+        let unlocked = keystore.unlock("merchant", "correct horse").unwrap();
+        assert_eq!(unlocked.to_bytes(), keypair.to_bytes());
+
+        let error = keystore.unlock("merchant", "wrong").unwrap_err();
+        assert!(error.to_string().contains("Wrong passphrase"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::at(dir.path().join("keystore"));
+        let path = keystore
+            .create("merchant", &Keypair::new(), "passphrase", TEST_PARAMS)
+            .unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(keystore.dir()), 0o700);
+    }
+
+    #[test]
+    fn test_tampered_pubkey_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::at(dir.path().to_path_buf());
+        keystore
+            .create("merchant", &Keypair::new(), "passphrase", TEST_PARAMS)
+            .unwrap();
+
+        let mut entry = keystore.read("merchant").unwrap();
+        entry.pubkey = Keypair::new().pubkey().to_string();
+        assert!(decrypt(&entry, "passphrase").is_err());
+    }
+
+    #[test]
+    fn test_duplicate_and_invalid_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::at(dir.path().to_path_buf());
+        keystore
+            .create("merchant", &Keypair::new(), "passphrase", TEST_PARAMS)
+            .unwrap();
+
+        let error = keystore
+            .create("merchant", &Keypair::new(), "passphrase", TEST_PARAMS)
+            .unwrap_err();
+        assert!(error.to_string().contains("already exists"));
+        assert!(validate_name("../id").is_err());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("mainnet-authority_2").is_ok());
+        assert!(keystore.read("missing").is_err());
+    }
+
+    #[test]
+    fn test_list_sorted_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::at(dir.path().join("keystore"));
+        assert!(keystore.list().unwrap().is_empty());
+
+        for name in ["treasury", "authority"] {
+            keystore
+                .create(name, &Keypair::new(), "passphrase", TEST_PARAMS)
+                .unwrap();
+        }
+        let names: Vec<String> = keystore
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert_eq!(names, ["authority", "treasury"]);
+    }
+}
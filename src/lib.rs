@@ -7,7 +7,10 @@
 pub mod commands;
 pub mod config;
 pub mod config_file;
+pub mod context;
 pub mod errors;
+pub mod keystore;
+pub mod output;
 pub mod utils;
 
 // Re-export for easy access
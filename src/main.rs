@@ -8,14 +8,24 @@
 mod commands;
 mod config;
 mod config_file;
+mod context;
 mod errors;
+mod keystore;
+mod output;
 mod utils;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use config::TallyCliConfig;
 use config_file::ConfigFile;
-use tally_sdk::SimpleTallyClient;
+use context::{ContextFlags, RuntimeContext};
+use errors::{CliError, ErrorCode, ErrorDetails};
+use output::{Output, OutputFormat};
+use std::path::PathBuf;
+use tally_sdk::{SimpleTallyClient, UsdcAmount};
+use utils::amount::parse_usdc_amount;
+use utils::duration::parse_duration_secs;
+use utils::transaction::{TxMode, TxModeFlags};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -45,15 +55,78 @@ pub struct Cli {
     #[arg(long, global = true)]
     no_color: bool,
 
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Write the unsigned transaction to FILE (base64) instead of sending it
+    #[arg(long, global = true, value_name = "FILE")]
+    export_tx: Option<String>,
+
+    /// Sign with the local keypair but do not send; combine with --export-tx to save it
+    #[arg(long, global = true)]
+    sign_only: bool,
+
+    /// Blockhash (or durable nonce value) for exported transactions, skipping the RPC lookup
+    #[arg(long, global = true, value_name = "HASH")]
+    blockhash: Option<String>,
+
+    /// Durable nonce account for exported transactions, so they do not expire
+    #[arg(long, global = true, value_name = "PUBKEY")]
+    nonce: Option<String>,
+
+    /// Authority of the --nonce account (defaults to the one stored in the account)
+    #[arg(long, global = true, value_name = "PUBKEY")]
+    nonce_authority: Option<String>,
+
+    /// Act as a Squads multisig vault: emit a proposal instead of sending (payee commands)
+    #[arg(long, global = true, value_name = "ADDRESS")]
+    multisig: Option<String>,
+
+    /// Vault index within the --multisig (default 0)
+    #[arg(long, global = true, value_name = "INDEX")]
+    vault_index: Option<u8>,
+
+    /// Write the --multisig proposal JSON to FILE instead of printing it
+    #[arg(long, global = true, value_name = "FILE")]
+    proposal_out: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
-#[derive(Clone, Debug, clap::ValueEnum)]
-enum OutputFormat {
-    Human,
-    Json,
-    Csv,
+impl Cli {
+    /// The `--output` format, falling back to the configured default
+    fn output_format(&self, config: &TallyCliConfig) -> Result<OutputFormat> {
+        self.output
+            .clone()
+            .map_or_else(|| parse_output_format(&config.default_output_format), Ok)
+    }
+
+    /// Resolve what write commands do with their transaction
+    fn tx_mode(&self) -> Result<TxMode> {
+        if self.multisig.is_some() {
+            return Err(anyhow::anyhow!(
+                "--multisig only applies to payee and payment-terms commands"
+            ));
+        }
+        self.payee_tx_mode()
+    }
+
+    /// Like [`Cli::tx_mode`], but allows `--multisig` for payee-authority commands
+    fn payee_tx_mode(&self) -> Result<TxMode> {
+        TxMode::from_flags(&TxModeFlags {
+            dry_run: self.dry_run,
+            export_tx: self.export_tx.as_deref(),
+            sign_only: self.sign_only,
+            blockhash: self.blockhash.as_deref(),
+            nonce: self.nonce.as_deref(),
+            nonce_authority: self.nonce_authority.as_deref(),
+            multisig: self.multisig.as_deref(),
+            vault_index: self.vault_index,
+            proposal_out: self.proposal_out.as_deref(),
+        })
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -106,6 +179,75 @@ enum Commands {
         command: DashboardCommands,
     },
 
+    /// Platform-admin commands for the global config (platform authority only)
+    Admin {
+        #[command(subcommand)]
+        command: AdminCommands,
+    },
+
+    /// Keeper mode: execute payments that are due and earn keeper fees
+    Keeper {
+        #[command(subcommand)]
+        command: KeeperCommands,
+    },
+
+    /// Sign and submit transactions exported with --export-tx
+    Tx {
+        #[command(subcommand)]
+        command: TxCommands,
+    },
+
+    /// Manage wallets in the encrypted keystore
+    #[command(
+        long_about = "Create, import and export keypairs stored encrypted under the config\n\
+                             directory, for setups without the Solana CLI. Each wallet is protected\n\
+                             by its own passphrase (set TALLY_KEYSTORE_PASSPHRASE to skip the prompt).\n\n\
+                             Use a stored wallet anywhere a keypair is accepted as keystore:<NAME>.\n\n\
+                             Examples:\n  \
+                             tally-merchant wallet new merchant\n  \
+                             tally-merchant config set wallet-path keystore:merchant\n  \
+                             tally-merchant wallet import ops --from ~/.config/solana/id.json"
+    )]
+    Wallet {
+        #[command(subcommand)]
+        command: WalletCommands,
+    },
+
+    /// Diagnose configuration, RPC, program, wallet and payee setup
+    #[command(
+        long_about = "Run a series of checks against the active configuration and report each\n\
+                             one as pass, warn or fail with a hint on how to fix it.\n\n\
+                             Checks:\n\
+                             • Config file parses and the active profile exists\n\
+                             • RPC is reachable and serves the cluster its URL suggests\n\
+                             • Program ID is a deployed, executable program\n\
+                             • Global config account exists and is not paused\n\
+                             • Wallet loads and holds enough SOL for fees\n\
+                             • Saved payee exists and its treasury uses the expected mint\n\n\
+                             Exits non-zero if any check fails.\n\n\
+                             Examples:\n  \
+                             tally-merchant doctor\n  \
+                             tally-merchant doctor --output json"
+    )]
+    Doctor,
+
+    /// Explain a program error code or a failed transaction
+    #[command(
+        long_about = "Decode an on-chain error into what it means and how to fix it.\n\n\
+                             Accepts a custom program error code in hex or decimal, an error name,\n\
+                             or the signature of a failed transaction, whose error and program logs\n\
+                             are fetched from the RPC. A bare code below 100 is listed for every\n\
+                             program that uses it.\n\n\
+                             Examples:\n  \
+                             tally-merchant explain-error 0x1771\n  \
+                             tally-merchant explain-error InsufficientAllowance\n  \
+                             tally-merchant explain-error <SIGNATURE> --output json"
+    )]
+    ExplainError {
+        /// Error code (0x1771 or 6001), error name, or transaction signature
+        query: String,
+    },
+
     /// Generate and install shell completions
     #[command(
         long_about = "Generate and install shell completion scripts for your shell.\n\n\
@@ -140,14 +282,29 @@ enum Commands {
         #[arg(long)]
         print: bool,
 
-        /// Show what would be installed without making changes
-        #[arg(long)]
-        dry_run: bool,
-
         /// Remove installed completions
         #[arg(long)]
         uninstall: bool,
     },
+
+    /// Print the JSON Schema of a command's --output json result
+    #[command(
+        long_about = "Every command prints its --output json result in the same envelope:\n\n  \
+                             {\"schema_version\": 1, \"kind\": \"<KIND>\", \"success\": true, \"data\": {...}}\n\n\
+                             Failures print {\"schema_version\": 1, \"success\": false, \"error\": {...}}\n\
+                             (kind \"error\"). Run without a KIND to list every kind.\n\n\
+                             Errors carry a stable code, which also sets the exit status:\n  \
+                             GENERAL 1, INVALID_ADDRESS 3, CONFIG_MISSING 4, ACCOUNT_NOT_FOUND 5,\n  \
+                             AUTHORITY_MISMATCH 6, INSUFFICIENT_BALANCE 7, PROGRAM_PAUSED 8,\n  \
+                             TRANSACTION_FAILED 9, RPC_ERROR 10\n\n\
+                             Examples:\n  \
+                             tally-merchant schema\n  \
+                             tally-merchant schema payment-terms.list > payment-terms.list.json"
+    )]
+    Schema {
+        /// Result kind, e.g. payment-terms.list
+        kind: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -200,6 +357,9 @@ enum ConfigCommands {
 
     /// Show config file path
     Path,
+
+    /// Show the effective settings for this invocation and where each came from
+    Effective,
 }
 
 #[derive(Subcommand, Debug)]
@@ -264,7 +424,7 @@ enum PayeeCommands {
                              Contact the platform authority to upgrade to Pro (1.5%) or Enterprise (1.0%) tiers."
     )]
     Init {
-        /// Authority keypair for the merchant
+        /// Authority keypair or signer URI for the merchant
         #[arg(long)]
         authority: Option<String>,
 
@@ -282,6 +442,86 @@ enum PayeeCommands {
         #[arg(long)]
         payee: String,
     },
+
+    /// Point the payee at a new USDC treasury account
+    UpdateTreasury {
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// New USDC treasury token account
+        #[arg(long)]
+        treasury: String,
+
+        /// Authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Withdraw USDC from the payee treasury
+    #[command(long_about = "Withdraw USDC from the payee treasury.\n\n\
+                             Withdrawals are limited by the platform's max_withdrawal_amount\n\
+                             (see 'tally-merchant config show') and the treasury balance.\n\n\
+                             Examples:\n  \
+                             # Withdraw 250 USDC to your own USDC account\n  \
+                             tally-merchant payee withdraw --amount-usdc 250\n\n  \
+                             # Withdraw to a specific token account\n  \
+                             tally-merchant payee withdraw --amount-usdc 250 --destination <USDC_ATA>")]
+    Withdraw {
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// Amount in USDC (e.g., 250 or 1,250.50)
+        #[arg(long = "amount-usdc", value_parser = parse_usdc_amount)]
+        amount_usdc: UsdcAmount,
+
+        /// Destination token account (defaults to the authority's USDC ATA)
+        #[arg(long)]
+        destination: Option<String>,
+
+        /// Authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Propose a new payee authority (step 1 of 2)
+    SetAuthority {
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// Public key of the proposed new authority
+        #[arg(long)]
+        new_authority: String,
+
+        /// Current authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Accept a pending payee authority transfer (step 2 of 2)
+    AcceptAuthority {
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// New authority keypair or signer URI (must match the pending authority)
+        #[arg(long)]
+        authority: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -293,8 +533,9 @@ enum PaymentTermsCommands {
                              via Solana Actions (Blinks).\n\n\
                              Arguments:\n  \
                              --price-usdc: Price in USDC (e.g., 10.0 for $10/month)\n  \
-                             --period-days: Billing period in days (e.g., 30 for monthly)\n  \
-                             --period-months: Alternative to period-days (e.g., 1 for monthly)\n  \
+                             --period: Billing period such as 1w, 30d, 1mo, 1y or ISO-8601 P1M\n  \
+                             --period-days: Billing period in days (e.g., 30)\n  \
+                             --period-months: Billing period in average months of 30.44 days (12 = 1 year)\n  \
                              --grace-days: Days after missed payment before cancellation (default: 1)\n\n\
                              Examples:\n  \
                              # Create a $10/month premium plan\n  \
@@ -303,7 +544,7 @@ enum PaymentTermsCommands {
                              --id premium \\\n    \
                              --name \"Premium Plan\" \\\n    \
                              --price-usdc 10.0 \\\n    \
-                             --period-months 1\n\n  \
+                             --period 1mo\n\n  \
                              # Create a $50/quarter business plan with 3-day grace\n  \
                              tally-merchant plan create \\\n    \
                              --merchant <MERCHANT_PDA> \\\n    \
@@ -313,9 +554,9 @@ enum PaymentTermsCommands {
                              --period-days 90 \\\n    \
                              --grace-days 3")]
     Create {
-        /// Payee account address
+        /// Payee account address (defaults to merchant from active profile)
         #[arg(long)]
-        payee: String,
+        payee: Option<String>,
 
         /// Payment terms identifier (used in PDA)
         #[arg(
@@ -327,15 +568,25 @@ enum PaymentTermsCommands {
         /// Amount in USDC (e.g., 10.0 for $10 USDC)
         #[arg(
             long = "amount-usdc",
-            help = "Payment amount in USDC (e.g., 10.0 for $10/period)"
+            value_parser = parse_usdc_amount,
+            help = "Payment amount in USDC, up to 6 decimals (e.g., 19.99 for $19.99/period)"
+        )]
+        amount_usdc: UsdcAmount,
+
+        /// Billing period (e.g., 30d, 1w, 1mo, 1y or ISO-8601 P1M)
+        #[arg(
+            long,
+            value_parser = parse_duration_secs,
+            conflicts_with_all = ["period_days", "period_months"],
+            help = "Billing period such as 1w, 30d, 1mo, 1y or P1M (months are 30.44 days, years 365.2425 days)"
         )]
-        amount_usdc: f64,
+        period: Option<u64>,
 
         /// Billing period in days (e.g., 30 for monthly)
         #[arg(
             long = "period-days",
             conflicts_with = "period_months",
-            help = "Billing period in days (e.g., 30 for monthly, 365 for yearly)"
+            help = "Billing period in days (e.g., 30, or 7 for weekly)"
         )]
         period_days: Option<u32>,
 
@@ -343,11 +594,11 @@ enum PaymentTermsCommands {
         #[arg(
             long = "period-months",
             conflicts_with = "period_days",
-            help = "Billing period in months (e.g., 1 for monthly, 12 for yearly)"
+            help = "Billing period in average months of 30.44 days (e.g., 1 for monthly, 12 for yearly)"
         )]
         period_months: Option<u32>,
 
-        /// Authority keypair for the payee
+        /// Authority keypair or signer URI for the payee
         #[arg(long)]
         authority: Option<String>,
     },
@@ -358,6 +609,150 @@ enum PaymentTermsCommands {
         #[arg(long)]
         payee: String,
     },
+
+    /// Update the amount or period of existing payment terms
+    #[command(
+        long_about = "Update the amount and/or billing period of existing payment terms.\n\n\
+                             The authority keypair must own the payee the terms belong to.\n\
+                             You will be shown the current and new values before confirming.\n\n\
+                             Examples:\n  \
+                             # Raise the price to $15\n  \
+                             tally-merchant payment-terms update \\\n    \
+                             --payment-terms <TERMS_PDA> \\\n    \
+                             --amount-usdc 15.0\n\n  \
+                             # Switch to a quarterly period without prompting\n  \
+                             tally-merchant payment-terms update \\\n    \
+                             --payment-terms <TERMS_PDA> \\\n    \
+                             --period 3mo --yes"
+    )]
+    Update {
+        /// Payment terms account address
+        #[arg(long)]
+        payment_terms: String,
+
+        /// New amount in USDC (e.g., 15.0 for $15 USDC)
+        #[arg(long = "amount-usdc", value_parser = parse_usdc_amount)]
+        amount_usdc: Option<UsdcAmount>,
+
+        /// New billing period (e.g., 30d, 1w, 1mo, 1y or ISO-8601 P1M)
+        #[arg(
+            long,
+            value_parser = parse_duration_secs,
+            conflicts_with_all = ["period_days", "period_months"]
+        )]
+        period: Option<u64>,
+
+        /// New billing period in days
+        #[arg(long = "period-days", conflicts_with = "period_months")]
+        period_days: Option<u32>,
+
+        /// New billing period in average months of 30.44 days (convenient shortcut)
+        #[arg(long = "period-months", conflicts_with = "period_days")]
+        period_months: Option<u32>,
+
+        /// Authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Deactivate payment terms so no new agreements can be started
+    Deactivate {
+        /// Payment terms account address
+        #[arg(long)]
+        payment_terms: String,
+
+        /// Authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Reconcile payment terms with a declarative TOML catalog
+    #[command(
+        long_about = "Create, update and deactivate payment terms so the payee matches a catalog.\n\n\
+                             The catalog lists every payment terms the payee should offer:\n\n  \
+                             [[terms]]\n  \
+                             id = \"basic\"\n  \
+                             amount_usdc = 9.99\n  \
+                             period_days = 30\n\n  \
+                             [[terms]]\n  \
+                             id = \"pro-annual\"\n  \
+                             amount_usdc = 99.0\n  \
+                             period = \"1y\"\n\n\
+                             Periods are period_days, period_months or a period duration such as\n\
+                             \"1w\", \"1mo\", \"1y\" or \"P1M\"; months are 30.44 days and years 365.2425 days.\n\n\
+                             Terms missing on-chain are created, terms whose amount or period differ\n\
+                             are updated, and active terms not in the catalog are deactivated. The plan\n\
+                             is shown before anything is sent; re-running an applied catalog does nothing.\n\n\
+                             Examples:\n  \
+                             # Review and apply\n  \
+                             tally-merchant payment-terms apply -f plans.toml\n\n  \
+                             # Simulate without sending\n  \
+                             tally-merchant payment-terms apply -f staging.toml --payee <PAYEE_PDA> --dry-run"
+    )]
+    Apply {
+        /// Catalog file
+        #[arg(short = 'f', long = "file")]
+        file: PathBuf,
+
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// Authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Create payment terms in bulk from a CSV file
+    #[command(
+        long_about = "Create payment terms from a CSV file, one row per terms.\n\n\
+                             The file needs a header row with the columns id, amount_usdc and\n\
+                             period_days (or period_months, or period with a duration like 1mo):\n\n  \
+                             id,amount_usdc,period_days\n  \
+                             basic,9.99,30\n  \
+                             pro,29.99,30\n\n\
+                             Every row is validated before anything is sent and invalid rows are\n\
+                             reported with their line numbers. Rows are then created one at a time;\n\
+                             if some fail, fix them and re-run with --resume to skip the terms that\n\
+                             already exist.\n\n\
+                             Examples:\n  \
+                             tally-merchant payment-terms import --csv terms.csv\n\n  \
+                             # Continue after a partial failure\n  \
+                             tally-merchant payment-terms import --csv terms.csv --resume --yes"
+    )]
+    Import {
+        /// CSV file to import
+        #[arg(long)]
+        csv: PathBuf,
+
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// Authority keypair or signer URI for the payee
+        #[arg(long)]
+        authority: Option<String>,
+
+        /// Skip rows whose payment terms already exist
+        #[arg(long)]
+        resume: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -375,237 +770,610 @@ enum AgreementCommands {
         #[arg(long)]
         agreement: String,
     },
-}
 
-#[derive(Subcommand, Debug, Clone)]
-enum DashboardCommands {
-    /// Display merchant overview statistics
-    Overview {
-        /// Merchant account address (defaults to merchant from active profile)
+    /// Pause a payment agreement (stops payments until resumed)
+    Pause {
+        /// Payment agreement account address
         #[arg(long)]
-        merchant: Option<String>,
-    },
+        agreement: String,
 
-    /// Show analytics for a specific plan
-    Analytics {
-        /// Plan account address
+        /// Signer keypair or signer URI (defaults to the profile wallet)
         #[arg(long)]
-        plan: String,
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
     },
 
-    /// Monitor real-time events for a merchant
-    Events {
-        /// Merchant account address (defaults to merchant from active profile)
+    /// Resume a paused payment agreement
+    Resume {
+        /// Payment agreement account address
         #[arg(long)]
-        merchant: Option<String>,
+        agreement: String,
 
-        /// Only show events since this timestamp
+        /// Signer keypair or signer URI (defaults to the profile wallet)
         #[arg(long)]
-        since: Option<i64>,
+        authority: Option<String>,
     },
 
-    /// List subscriptions for a merchant with enhanced information
-    Subscriptions {
-        /// Merchant account address (defaults to merchant from active profile)
+    /// Close a payment agreement and reclaim rent
+    Close {
+        /// Payment agreement account address
         #[arg(long)]
-        merchant: Option<String>,
+        agreement: String,
 
-        /// Only show active subscriptions
+        /// Signer keypair or signer URI (defaults to the profile wallet)
         #[arg(long)]
-        active_only: bool,
+        authority: Option<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+/// Flags shared by every admin command
+#[derive(clap::Args, Debug)]
+struct AdminSignerArgs {
+    /// Platform authority keypair or signer URI (defaults to the profile wallet)
+    #[arg(long)]
+    authority: Option<String>,
 
-    let cli = Cli::parse();
+    /// Skip confirmation prompt
+    #[arg(short, long)]
+    yes: bool,
+}
 
-    // Initialize colors based on --no-color flag and NO_COLOR env var
-    utils::colors::init_colors(cli.no_color);
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum VolumeTierArg {
+    Standard,
+    Growth,
+    Scale,
+}
 
-    let config = TallyCliConfig::new();
+impl From<VolumeTierArg> for tally_sdk::program_types::VolumeTier {
+    fn from(tier: VolumeTierArg) -> Self {
+        match tier {
+            VolumeTierArg::Standard => Self::Standard,
+            VolumeTierArg::Growth => Self::Growth,
+            VolumeTierArg::Scale => Self::Scale,
+        }
+    }
+}
 
-    // Load config file (if it exists) for additional defaults
-    let config_file = ConfigFile::load().unwrap_or_else(|_| ConfigFile::new());
+#[derive(Subcommand, Debug)]
+enum AdminCommands {
+    /// Create the global config account
+    #[command(
+        long_about = "Create the global config account. The signer becomes the platform authority.\n\n\
+                             The allowed mint is taken from --usdc-mint (or the network default).\n\n\
+                             Example:\n  \
+                             tally-merchant admin init-config \\\n    \
+                             --min-platform-fee-bps 15 --max-platform-fee-bps 25 \\\n    \
+                             --keeper-fee-bps 25 --max-withdrawal-usdc 100000 --dry-run"
+    )]
+    InitConfig {
+        /// Lowest platform fee a payee can be charged (basis points)
+        #[arg(long)]
+        min_platform_fee_bps: u16,
 
-    let default_output_format = parse_output_format(&config.default_output_format)?;
-    let output_format = cli.output.as_ref().unwrap_or(&default_output_format);
+        /// Highest platform fee a payee can be charged (basis points)
+        #[arg(long)]
+        max_platform_fee_bps: u16,
 
-    // Check if this command needs SDK access (on-chain operations)
-    let needs_sdk = command_needs_sdk(&cli.command);
+        /// Fee paid to keepers for executing payments (basis points)
+        #[arg(long)]
+        keeper_fee_bps: u16,
 
-    // Only initialize SDK client if the command requires on-chain access
-    let result = if needs_sdk {
-        // Use configuration with precedence: CLI flags > env vars > config file > defaults
-        let rpc_url = cli
-            .rpc_url
-            .as_deref()
-            .or_else(|| {
-                std::env::var("TALLY_RPC_URL")
-                    .ok()
-                    .as_deref()
-                    .map(|_| config.default_rpc_url.as_str())
-            })
-            .or_else(|| config_file.active_profile().map(|p| p.rpc_url.as_str()))
-            .unwrap_or(&config.default_rpc_url);
-
-        // Program ID precedence
-        let program_id_from_config = cli.program_id.as_deref().or_else(|| {
-            config_file
-                .active_profile()
-                .and_then(|p| p.program_id.as_deref())
-        });
-
-        // Check if program ID is available before trying to create client
-        let program_id = if let Some(id) = program_id_from_config {
-            id
-        } else if std::env::var("TALLY_PROGRAM_ID").is_err() {
-            // Neither config nor env var has program ID
-            return Err(anyhow::anyhow!(
-                "This command requires connection to Solana.\n\
-                 \n\
-                 Program ID not configured. You can fix this by:\n\
-                 \n\
-                 1. Set the TALLY_PROGRAM_ID environment variable:\n\
-                    export TALLY_PROGRAM_ID=<your-program-id>\n\
-                 \n\
-                 2. Or configure it in your profile:\n\
-                    tally-merchant config init\n\
-                    tally-merchant config set program-id <your-program-id>\n\
-                 \n\
-                 3. Or pass it as a CLI flag:\n\
-                    tally-merchant --program-id <your-program-id> <command>\n\
-                 \n\
-                 See https://github.com/Tally-Pay/tally-cli for program IDs"
-            ));
-        } else {
-            // If env var is set, use empty string to let SDK read it
-            ""
-        };
+        /// Maximum amount per withdrawal in USDC
+        #[arg(long = "max-withdrawal-usdc", value_parser = parse_usdc_amount)]
+        max_withdrawal_usdc: UsdcAmount,
 
-        // Initialize Tally client
-        let tally_client = if program_id.is_empty() {
-            SimpleTallyClient::new(rpc_url)?
-        } else {
-            SimpleTallyClient::new_with_program_id(rpc_url, program_id)?
-        };
+        /// Shortest allowed payment period in seconds
+        #[arg(long, default_value_t = 86_400)]
+        min_period_seconds: u64,
 
-        // Execute command with SDK client
-        execute_command(&cli, Some(&tally_client), &config, &config_file).await
-    } else {
-        // Execute command without SDK client (config file operations)
-        execute_command(&cli, None, &config, &config_file).await
-    };
+        /// Number of periods approved as allowance when an agreement starts
+        #[arg(long, default_value_t = 3)]
+        default_allowance_periods: u8,
 
-    // Handle output formatting
-    match result {
-        Ok(output) => match output_format {
-            OutputFormat::Human => {
-                // Output is already formatted by commands, just print it
-                println!("{output}");
-            }
-            OutputFormat::Json => {
-                let json_output = serde_json::json!({
-                    "success": true,
-                    "data": output
-                });
-                println!("{}", serde_json::to_string_pretty(&json_output)?);
-            }
-            OutputFormat::Csv => {
-                // CSV is already formatted by commands, just print it
-                println!("{output}");
-            }
-        },
-        Err(e) => {
-            match output_format {
-                OutputFormat::Human => {
-                    eprintln!("{}: {e}", utils::colors::Theme::error("Error"));
-                }
-                OutputFormat::Json => {
-                    let json_output = serde_json::json!({
-                        "success": false,
-                        "error": e.to_string()
-                    });
-                    println!("{}", serde_json::to_string_pretty(&json_output)?);
-                }
-                OutputFormat::Csv => {
-                    // For CSV, output error as plain text to stderr
-                    eprintln!("Error: {e}");
-                }
-            }
-            std::process::exit(1);
-        }
-    }
+        /// Longest allowed grace period in seconds
+        #[arg(long, default_value_t = 604_800)]
+        max_grace_period_seconds: u64,
 
-    Ok(())
-}
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
 
-/// Check if a command requires SDK access (on-chain operations)
-const fn command_needs_sdk(command: &Commands) -> bool {
-    match command {
-        Commands::Config { command } => matches!(command, ConfigCommands::Show),
-        Commands::Init { .. }
-        | Commands::Payee { .. }
-        | Commands::PaymentTerms { .. }
-        | Commands::Agreement { .. }
-        | Commands::Dashboard { .. } => true,
-        Commands::Completions { .. } => false,
-    }
-}
+    /// Update platform fee bounds and/or the keeper fee
+    SetFees {
+        /// New minimum platform fee (basis points)
+        #[arg(long)]
+        min_platform_fee_bps: Option<u16>,
 
-/// Parse output format from string
-fn parse_output_format(format_str: &str) -> Result<OutputFormat> {
-    match format_str.to_lowercase().as_str() {
-        "human" => Ok(OutputFormat::Human),
-        "json" => Ok(OutputFormat::Json),
+        /// New maximum platform fee (basis points)
+        #[arg(long)]
+        max_platform_fee_bps: Option<u16>,
+
+        /// New keeper fee (basis points)
+        #[arg(long)]
+        keeper_fee_bps: Option<u16>,
+
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
+
+    /// Emergency pause: reject new agreements and payments
+    Pause {
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
+
+    /// Lift an emergency pause
+    Unpause {
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
+
+    /// Propose a new platform authority (step 1 of 2)
+    TransferAuthority {
+        /// Public key of the proposed platform authority
+        #[arg(long)]
+        new_authority: String,
+
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
+
+    /// Accept a pending platform authority transfer (step 2 of 2)
+    AcceptAuthority {
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
+
+    /// Move a payee to a different volume tier
+    SetPayeeTier {
+        /// Payee account address
+        #[arg(long)]
+        payee: String,
+
+        /// New volume tier
+        #[arg(long, value_enum)]
+        tier: VolumeTierArg,
+
+        #[command(flatten)]
+        signer: AdminSignerArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum KeeperCommands {
+    /// Scan a payee's agreements and execute payments that are due
+    #[command(
+        long_about = "Scan a payee's payment agreements and submit execute_payment for every\n\
+                             active agreement whose next payment time has passed. The keeper earns\n\
+                             the keeper fee configured in the program config.\n\n\
+                             Runs until Ctrl-C by default; the in-flight batch is allowed to finish.\n\n\
+                             Examples:\n  \
+                             # Run continuously, scanning every minute\n  \
+                             tally-merchant keeper run --payee <PAYEE_PDA>\n\n  \
+                             # Single pass for cron\n  \
+                             tally-merchant keeper run --once\n\n  \
+                             # List what would be charged without submitting transactions\n  \
+                             tally-merchant keeper run --dry-run"
+    )]
+    Run {
+        /// Payee account address (defaults to merchant from active profile)
+        #[arg(long)]
+        payee: Option<String>,
+
+        /// Keeper keypair path or local signer URI (`file:`, `stdin:`, `prompt:`, `keystore:`;
+        /// the keeper signs in parallel, so `exec:` and `usb://` are not supported)
+        #[arg(long)]
+        keeper: Option<String>,
+
+        /// Run a single scan and exit
+        #[arg(long)]
+        once: bool,
+
+        /// Maximum number of payments submitted in parallel
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..=64))]
+        concurrency: u16,
+
+        /// Seconds to wait between scans
+        #[arg(long, default_value_t = 60)]
+        interval_secs: u64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TxCommands {
+    /// Sign an exported transaction (works offline)
+    #[command(
+        long_about = "Add a signature to a transaction file written by --export-tx.\n\n\
+                             This command never contacts the network and is meant to run on the\n\
+                             offline machine that holds the authority key. Run it once per\n\
+                             required signer.\n\n\
+                             Example:\n  \
+                             tally-merchant tx sign withdraw.tx --keypair /media/usb/authority.json"
+    )]
+    Sign {
+        /// Transaction file to sign
+        file: PathBuf,
+
+        /// Keypair or signer URI to sign with (defaults to the profile wallet)
+        #[arg(long)]
+        keypair: Option<String>,
+
+        /// Write the signed transaction here instead of overwriting the input
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Submit a fully signed transaction file
+    #[command(
+        long_about = "Verify the signatures on a transaction file and submit it to the cluster.\n\n\
+                             Example:\n  \
+                             tally-merchant tx submit withdraw.tx"
+    )]
+    Submit {
+        /// Signed transaction file
+        file: PathBuf,
+    },
+
+    /// Decode a sent transaction's Tally instructions, events and token transfers
+    #[command(
+        long_about = "Fetch a confirmed transaction and show what it did: each instruction with\n\
+                             Tally instructions named and their arguments decoded, the payee, payment\n\
+                             terms and agreement accounts involved, the Tally events it emitted, and\n\
+                             how token balances of the treasury, platform, keeper and payer changed.\n\n\
+                             Use --output json to archive the decoded transaction.\n\n\
+                             Examples:\n  \
+                             tally-merchant tx show <SIGNATURE>\n  \
+                             tally-merchant tx show <SIGNATURE> --output json > audit/<SIGNATURE>.json"
+    )]
+    Show {
+        /// Transaction signature
+        signature: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WalletCommands {
+    /// Generate a new keypair and store it encrypted
+    New {
+        /// Wallet name (letters, digits, '-' and '_')
+        name: String,
+    },
+
+    /// Encrypt an existing keypair into the keystore
+    Import {
+        /// Wallet name (letters, digits, '-' and '_')
+        name: String,
+
+        /// Keypair or signer URI to import, e.g. a keypair file or prompt:
+        #[arg(long)]
+        from: String,
+    },
+
+    /// List keystore wallets
+    List,
+
+    /// Decrypt a wallet to a Solana CLI keypair file (or stdout)
+    Export {
+        /// Wallet name
+        name: String,
+
+        /// Write the keypair file here instead of printing it
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DashboardCommands {
+    /// Display merchant overview statistics
+    Overview {
+        /// Merchant account address (defaults to merchant from active profile)
+        #[arg(long)]
+        merchant: Option<String>,
+    },
+
+    /// Show analytics for a specific plan
+    Analytics {
+        /// Plan account address
+        #[arg(long)]
+        plan: String,
+    },
+
+    /// Show recent events for a merchant, or follow them live with --follow
+    Events {
+        /// Merchant account address (defaults to merchant from active profile)
+        #[arg(long)]
+        merchant: Option<String>,
+
+        /// Only show events since this timestamp
+        #[arg(long, conflicts_with = "follow")]
+        since: Option<i64>,
+
+        /// Stream events live over websocket until Ctrl-C
+        #[arg(long)]
+        follow: bool,
+
+        /// Websocket URL (derived from the RPC URL if omitted)
+        #[arg(long, requires = "follow")]
+        ws_url: Option<String>,
+    },
+
+    /// List subscriptions for a merchant with enhanced information
+    Subscriptions {
+        /// Merchant account address (defaults to merchant from active profile)
+        #[arg(long)]
+        merchant: Option<String>,
+
+        /// Only show active subscriptions
+        #[arg(long)]
+        active_only: bool,
+    },
+
+    /// Per-payment revenue ledger built from transaction history
+    #[command(
+        long_about = "Per-payment revenue ledger built from transaction history.
+
+Walks the transaction history of the payee account and its treasury, decodes
+every PaymentExecuted event for the payee and lists each payment with its
+gross amount, platform fee, keeper fee and the net amount the treasury
+received. Use --output csv to export it for accounting.
+
+Fetched signatures and transactions are cached next to the config file, so
+re-running the ledger only fetches what is new.
+
+Examples:
+  tally-merchant dashboard ledger --from 2025-01-01 --to 2025-03-31
+  tally-merchant dashboard ledger --from 2025-01-01 --output csv > q1.csv"
+    )]
+    Ledger {
+        /// Merchant account address (defaults to merchant from active profile)
+        #[arg(long)]
+        merchant: Option<String>,
+
+        /// Start of the period: a UTC date (YYYY-MM-DD) or unix timestamp
+        #[arg(long)]
+        from: Option<String>,
+
+        /// End of the period, inclusive: a UTC date (YYYY-MM-DD) or unix timestamp
+        #[arg(long)]
+        to: Option<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let cli = Cli::parse();
+
+    let config = TallyCliConfig::new();
+    // An invalid configured format is reported like any other error, in the
+    // human format since no other one could be selected
+    let format = cli.output_format(&config);
+    let output_format = format.as_ref().map_or(OutputFormat::Human, Clone::clone);
+
+    // Initialize colors based on --no-color flag and NO_COLOR env var; machine-readable
+    // output never carries color codes
+    utils::colors::init_colors(cli.no_color || output_format != OutputFormat::Human);
+
+    // Load config file (if it exists) for additional defaults
+    let config_file = ConfigFile::load().unwrap_or_else(|_| ConfigFile::new());
+
+    let result = match format {
+        Ok(_) => run(&cli, &config, &config_file).await,
+        Err(e) => Err(e),
+    };
+
+    // Handle output formatting
+    let error = match result.and_then(|output| Ok((output.render(&output_format)?, output))) {
+        Ok((rendered, output)) => {
+            if !rendered.is_empty() {
+                println!("{rendered}");
+            }
+            if output.success() {
+                return;
+            }
+            std::process::exit(1);
+        }
+        Err(e) => e,
+    };
+
+    std::process::exit(report_error(&error, &output_format));
+}
+
+/// Print `error` in `output_format` and return the exit code for it
+fn report_error(error: &anyhow::Error, output_format: &OutputFormat) -> i32 {
+    let details = ErrorDetails::new(error);
+    match output_format {
+        OutputFormat::Human => {
+            eprintln!(
+                "{}: {}",
+                utils::colors::Theme::error("Error"),
+                details.message
+            );
+            if let Some(suggestion) = &details.suggestion {
+                eprintln!("\n{suggestion}");
+            }
+            if let Some(program_error) = &details.program_error {
+                eprintln!(
+                    "\n{}",
+                    utils::colors::Theme::dim(&format!("Cause: {program_error}"))
+                );
+            }
+            if !details.logs.is_empty() {
+                eprintln!("\n{}", utils::colors::Theme::dim("Program logs:"));
+                for line in &details.logs {
+                    eprintln!("  {}", utils::colors::Theme::dim(line));
+                }
+            }
+        }
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            let envelope = output::error_envelope(details.clone());
+            let json = if *output_format == OutputFormat::Json {
+                serde_json::to_string_pretty(&envelope)
+            } else {
+                serde_json::to_string(&envelope)
+            };
+            match json {
+                Ok(json) => println!("{json}"),
+                Err(e) => eprintln!("Error: {} (failed to serialize: {e})", details.message),
+            }
+        }
+        OutputFormat::Csv => {
+            // For CSV, output error as plain text to stderr
+            eprintln!("Error: {error}");
+        }
+    }
+    details.exit_code
+}
+
+/// Resolve the runtime context and run the command
+///
+/// Every failure ends up in the returned error, so `main` renders it with the
+/// right exit code and, for JSON output, the error envelope.
+async fn run(cli: &Cli, config: &TallyCliConfig, config_file: &ConfigFile) -> Result<Output> {
+    // Resolve RPC URL, program ID, USDC mint, wallet and payee once for every command
+    let context = RuntimeContext::resolve(
+        &ContextFlags {
+            rpc_url: cli.rpc_url.as_deref(),
+            program_id: cli.program_id.as_deref(),
+            usdc_mint: cli.usdc_mint.as_deref(),
+        },
+        config,
+        config_file,
+    );
+    check_selected_profile(&cli.command, &context, config_file)?;
+
+    // Only initialize SDK client if the command requires on-chain access
+    if command_needs_sdk(&cli.command) {
+        let tally_client = context.tally_client()?;
+        execute_command(cli, Some(&tally_client), config, config_file, &context).await
+    } else {
+        // Execute command without SDK client (config file operations)
+        execute_command(cli, None, config, config_file, &context).await
+    }
+}
+
+/// Reject a `TALLY_PROFILE` naming a missing profile for commands that read profile values
+///
+/// `config` commands only warn, so the profile can still be created, and
+/// `doctor` reports it as a failing check. Commands that never read profile
+/// values run as usual.
+fn check_selected_profile(
+    command: &Commands,
+    context: &RuntimeContext,
+    config_file: &ConfigFile,
+) -> Result<()> {
+    let Err(error) = context.check_profile(config_file) else {
+        return Ok(());
+    };
+    match command {
+        Commands::Config { command } if !matches!(command, ConfigCommands::Show) => {
+            eprintln!("{} {error}", utils::colors::Theme::warning("Warning:"));
+            Ok(())
+        }
+        Commands::Doctor
+        | Commands::Wallet { .. }
+        | Commands::Completions { .. }
+        | Commands::Schema { .. } => Ok(()),
+        _ => Err(error),
+    }
+}
+
+/// Check if a command requires SDK access (on-chain operations)
+const fn command_needs_sdk(command: &Commands) -> bool {
+    match command {
+        Commands::Config { command } => matches!(command, ConfigCommands::Show),
+        Commands::Init { .. }
+        | Commands::Payee { .. }
+        | Commands::PaymentTerms { .. }
+        | Commands::Agreement { .. }
+        | Commands::Dashboard { .. }
+        | Commands::Admin { .. } => true,
+        Commands::Tx { command } => {
+            matches!(command, TxCommands::Submit { .. } | TxCommands::Show { .. })
+        }
+        // The keeper shares its own client with its blocking worker threads
+        Commands::Keeper { .. }
+        | Commands::Wallet { .. }
+        | Commands::Doctor
+        | Commands::ExplainError { .. }
+        | Commands::Completions { .. }
+        | Commands::Schema { .. } => false,
+    }
+}
+
+/// Parse output format from string
+fn parse_output_format(format_str: &str) -> Result<OutputFormat> {
+    match format_str.to_lowercase().as_str() {
+        "human" => Ok(OutputFormat::Human),
+        "json" => Ok(OutputFormat::Json),
         "csv" => Ok(OutputFormat::Csv),
-        _ => Err(anyhow::anyhow!("Invalid output format: {format_str}")),
+        "ndjson" => Ok(OutputFormat::Ndjson),
+        _ => Err(CliError::new(
+            ErrorCode::General,
+            format!("Invalid output format: {format_str}"),
+        )
+        .with_suggestion("Set TALLY_DEFAULT_OUTPUT_FORMAT to one of: human, json, csv, ndjson")
+        .into()),
     }
 }
 
 /// Execute config commands
 async fn execute_config_commands(
-    cli: &Cli,
     tally_client: Option<&SimpleTallyClient>,
-    _config: &TallyCliConfig,
+    context: &RuntimeContext,
     command: &ConfigCommands,
-) -> Result<String> {
-    match command {
-        ConfigCommands::Init { force } => commands::config_file_ops::init(*force),
+) -> Result<Output> {
+    Ok(match command {
+        ConfigCommands::Init { force } => commands::config_file_ops::init(*force)?.into(),
 
         ConfigCommands::Show => {
             let client = require_client(tally_client)?;
-            let output_format = match cli.output {
-                Some(OutputFormat::Json) => "json",
-                _ => "human",
-            };
-            let request = commands::show_config::ShowConfigRequest { output_format };
-            commands::execute_show_config(client, &request).await
+            commands::execute_show_config(client).await?.into()
         }
 
-        ConfigCommands::List { profile } => commands::config_file_ops::list(profile.as_deref()),
+        ConfigCommands::List { profile } => {
+            commands::config_file_ops::list(profile.as_deref())?.into()
+        }
 
         ConfigCommands::Get { key, profile } => {
-            commands::config_file_ops::get(key, profile.as_deref())
+            commands::config_file_ops::get(key, profile.as_deref())?.into()
         }
 
         ConfigCommands::Set {
             key,
             value,
             profile,
-        } => commands::config_file_ops::set(key, value, profile.as_deref()),
+        } => commands::config_file_ops::set(key, value, profile.as_deref())?.into(),
+
+        ConfigCommands::Path => commands::config_file_ops::path()?.into(),
 
-        ConfigCommands::Path => commands::config_file_ops::path(),
+        ConfigCommands::Effective => context::EffectiveConfig::from(context).into(),
 
         ConfigCommands::Profile { command } => match command {
-            ProfileCommands::List => commands::config_file_ops::list_profiles(),
-            ProfileCommands::Active => commands::config_file_ops::show_active_profile(),
+            ProfileCommands::List => commands::config_file_ops::list_profiles()?,
+            ProfileCommands::Active => commands::config_file_ops::show_active_profile()?,
             ProfileCommands::Show { profile } => {
-                commands::config_file_ops::show_profile(profile.as_deref())
+                commands::config_file_ops::show_profile(profile.as_deref())?
             }
-            ProfileCommands::Use { profile } => commands::config_file_ops::use_profile(profile),
+            ProfileCommands::Use { profile } => commands::config_file_ops::use_profile(profile)?,
             ProfileCommands::Create {
                 name,
                 rpc_url,
@@ -616,9 +1384,10 @@ async fn execute_config_commands(
                 rpc_url,
                 program_id.as_deref(),
                 usdc_mint.as_deref(),
-            ),
-        },
-    }
+            )?,
+        }
+        .into(),
+    })
 }
 
 /// Execute payee commands
@@ -626,86 +1395,250 @@ async fn execute_payee_commands(
     cli: &Cli,
     tally_client: &SimpleTallyClient,
     config: &TallyCliConfig,
+    context: &RuntimeContext,
     command: &PayeeCommands,
-) -> Result<String> {
-    match command {
+) -> Result<Output> {
+    let tx_mode = cli.payee_tx_mode()?;
+
+    Ok(match command {
         PayeeCommands::Init {
             authority,
             treasury,
+        } => commands::execute_init_payee(
+            tally_client,
+            Some(context.signer_path(authority.as_deref())),
+            treasury,
+            context.usdc_mint(),
+            &tx_mode,
+            config,
+        )
+        .await?
+        .into(),
+
+        PayeeCommands::Show { payee } => {
+            let request = commands::show_payee::ShowPayeeRequest { payee };
+            commands::execute_show_payee(tally_client, &request, config)
+                .await?
+                .into()
+        }
+
+        PayeeCommands::UpdateTreasury {
+            payee,
+            treasury,
+            authority,
+            yes,
         } => {
-            commands::execute_init_payee(
-                tally_client,
-                authority.as_deref(),
-                treasury,
-                cli.usdc_mint.as_deref(),
-                config,
-            )
-            .await
+            let request = commands::payee_treasury::UpdateTreasuryRequest {
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
+                treasury_str: treasury,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
+            };
+            commands::execute_update_payee_treasury(tally_client, &request, config)
+                .await?
+                .into()
         }
 
-        PayeeCommands::Show { payee } => {
-            let output_format = match cli.output {
-                Some(OutputFormat::Json) => "json",
-                _ => "human",
+        PayeeCommands::Withdraw {
+            payee,
+            amount_usdc,
+            destination,
+            authority,
+            yes,
+        } => {
+            let request = commands::payee_treasury::WithdrawRequest {
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
+                amount: *amount_usdc,
+                destination_str: destination.as_deref(),
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
             };
-            let request = commands::show_payee::ShowPayeeRequest {
-                payee,
-                output_format,
+            commands::execute_payee_withdraw(tally_client, &request, config)
+                .await?
+                .into()
+        }
+
+        PayeeCommands::SetAuthority {
+            payee,
+            new_authority,
+            authority,
+            yes,
+        } => {
+            let request = commands::payee_authority::SetAuthorityRequest {
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
+                new_authority_str: new_authority,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
             };
-            commands::execute_show_payee(tally_client, &request, config).await
+            commands::execute_set_payee_authority(tally_client, &request, config)
+                .await?
+                .into()
         }
+
+        PayeeCommands::AcceptAuthority { payee, authority } => {
+            let request = commands::payee_authority::AcceptAuthorityRequest {
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                tx_mode: &tx_mode,
+            };
+            commands::execute_accept_payee_authority(tally_client, &request, config)
+                .await?
+                .into()
+        }
+    })
+}
+
+/// Resolve the payee for a payee command
+///
+/// With `--multisig` and no explicit payee, this is the payee owned by the
+/// multisig vault rather than the profile's merchant.
+fn resolve_payee(
+    payee: Option<&str>,
+    context: &RuntimeContext,
+    tx_mode: &TxMode,
+) -> Result<tally_sdk::solana_sdk::pubkey::Pubkey> {
+    match (tx_mode, payee) {
+        (TxMode::Propose(options), None) => Ok(tally_sdk::pda_v2::payee(&options.vault())?.into()),
+        _ => commands::dashboard::resolve_merchant(payee, context),
     }
 }
 
+/// Resolve the billing period flags to seconds, warning when a month-based
+/// period will drift from calendar months
+fn resolve_period(
+    period: Option<u64>,
+    period_days: Option<u32>,
+    period_months: Option<u32>,
+) -> Option<u64> {
+    let period_secs = utils::duration::period_from_flags(period, period_days, period_months);
+    if let Some(secs) = period_secs {
+        utils::duration::warn_calendar_drift(secs);
+    }
+    period_secs
+}
+
 /// Execute payment terms commands
 async fn execute_payment_terms_commands(
     cli: &Cli,
     tally_client: &SimpleTallyClient,
     config: &TallyCliConfig,
+    context: &RuntimeContext,
     command: &PaymentTermsCommands,
-) -> Result<String> {
-    match command {
+) -> Result<Output> {
+    let tx_mode = cli.payee_tx_mode()?;
+    Ok(match command {
         PaymentTermsCommands::Create {
             payee,
             id,
             amount_usdc,
+            period,
             period_days,
             period_months,
             authority,
         } => {
-            // Convert period to days (prefer days, allow months as alternative)
-            let days = period_months.map_or_else(
-                || {
-                    period_days.map_or_else(
-                        || {
-                            Err(anyhow::anyhow!(
-                                "Either --period-days or --period-months is required"
-                            ))
-                        },
-                        |days| Ok(u64::from(days)),
-                    )
-                },
-                |months| Ok(u64::from(months) * 30),
-            )?;
+            let period_secs =
+                resolve_period(*period, *period_days, *period_months).ok_or_else(|| {
+                    anyhow::anyhow!("One of --period, --period-days or --period-months is required")
+                })?;
 
             let request = commands::create_payment_terms::CreatePaymentTermsRequest {
-                payee_str: payee,
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
                 terms_id: id,
-                amount_usdc_float: *amount_usdc,
-                period_days: days,
-                authority_path: authority.as_deref(),
+                amount: *amount_usdc,
+                period_secs,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                tx_mode: &tx_mode,
             };
-            commands::execute_create_payment_terms(tally_client, &request, config).await
+            commands::execute_create_payment_terms(tally_client, &request, config)
+                .await?
+                .into()
         }
 
         PaymentTermsCommands::List { payee } => {
-            let output_format = match cli.output {
-                Some(OutputFormat::Json) => commands::list_payment_terms::OutputFormat::Json,
-                _ => commands::list_payment_terms::OutputFormat::Human,
+            commands::execute_list_payment_terms(tally_client, payee)
+                .await?
+                .into()
+        }
+
+        PaymentTermsCommands::Update {
+            payment_terms,
+            amount_usdc,
+            period,
+            period_days,
+            period_months,
+            authority,
+            yes,
+        } => {
+            let request = commands::update_payment_terms::UpdatePaymentTermsRequest {
+                payment_terms_str: payment_terms,
+                amount: *amount_usdc,
+                period_secs: resolve_period(*period, *period_days, *period_months),
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
             };
-            commands::execute_list_payment_terms(tally_client, payee, &output_format).await
+            commands::execute_update_payment_terms(tally_client, &request, config)
+                .await?
+                .into()
         }
-    }
+
+        PaymentTermsCommands::Deactivate {
+            payment_terms,
+            authority,
+            yes,
+        } => {
+            let request = commands::deactivate_payment_terms::DeactivatePaymentTermsRequest {
+                payment_terms_str: payment_terms,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
+            };
+            commands::execute_deactivate_payment_terms(tally_client, &request, config)
+                .await?
+                .into()
+        }
+
+        PaymentTermsCommands::Apply {
+            file,
+            payee,
+            authority,
+            yes,
+        } => {
+            let request = commands::apply_payment_terms::ApplyPaymentTermsRequest {
+                catalog_path: file,
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
+            };
+            commands::execute_apply_payment_terms(tally_client, &request, config)
+                .await?
+                .into()
+        }
+
+        PaymentTermsCommands::Import {
+            csv,
+            payee,
+            authority,
+            resume,
+            yes,
+        } => {
+            let request = commands::import_payment_terms::ImportPaymentTermsRequest {
+                csv_path: csv,
+                payee: resolve_payee(payee.as_deref(), context, &tx_mode)?,
+                authority_path: Some(context.signer_path(authority.as_deref())),
+                resume: *resume,
+                skip_confirmation: *yes,
+                tx_mode: &tx_mode,
+            };
+            commands::execute_import_payment_terms(tally_client, &request, config)
+                .await?
+                .into()
+        }
+    })
 }
 
 /// Execute agreement commands
@@ -713,123 +1646,327 @@ async fn execute_agreement_commands(
     cli: &Cli,
     tally_client: &SimpleTallyClient,
     config: &TallyCliConfig,
+    context: &RuntimeContext,
     command: &AgreementCommands,
-) -> Result<String> {
-    match command {
+) -> Result<Output> {
+    Ok(match command {
         AgreementCommands::List { payment_terms } => {
-            let output_format = match cli.output {
-                Some(OutputFormat::Json) => commands::list_agreements::OutputFormat::Json,
-                _ => commands::list_agreements::OutputFormat::Human,
-            };
-            commands::execute_list_agreements(tally_client, payment_terms, &output_format, config)
-                .await
+            commands::execute_list_agreements(tally_client, payment_terms, config)
+                .await?
+                .into()
         }
 
         AgreementCommands::Show { agreement } => {
-            let output_format = match cli.output {
-                Some(OutputFormat::Json) => "json",
-                _ => "human",
-            };
-            let request = commands::show_agreement::ShowAgreementRequest {
+            let request = commands::show_agreement::ShowAgreementRequest { agreement };
+            commands::execute_show_agreement(tally_client, &request, config)
+                .await?
+                .into()
+        }
+
+        AgreementCommands::Pause {
+            agreement,
+            authority,
+            yes,
+        } => {
+            execute_agreement_lifecycle(
+                cli,
+                tally_client,
+                config,
                 agreement,
-                output_format,
-            };
-            commands::execute_show_agreement(tally_client, &request, config).await
+                commands::agreement_lifecycle::AgreementAction::Pause,
+                Some(context.signer_path(authority.as_deref())),
+                *yes,
+            )
+            .await?
         }
-    }
+
+        AgreementCommands::Resume {
+            agreement,
+            authority,
+        } => {
+            execute_agreement_lifecycle(
+                cli,
+                tally_client,
+                config,
+                agreement,
+                commands::agreement_lifecycle::AgreementAction::Resume,
+                Some(context.signer_path(authority.as_deref())),
+                true,
+            )
+            .await?
+        }
+
+        AgreementCommands::Close {
+            agreement,
+            authority,
+            yes,
+        } => {
+            execute_agreement_lifecycle(
+                cli,
+                tally_client,
+                config,
+                agreement,
+                commands::agreement_lifecycle::AgreementAction::Close,
+                Some(context.signer_path(authority.as_deref())),
+                *yes,
+            )
+            .await?
+        }
+    })
+}
+
+/// Execute a pause, resume or close agreement command
+async fn execute_agreement_lifecycle(
+    cli: &Cli,
+    tally_client: &SimpleTallyClient,
+    config: &TallyCliConfig,
+    agreement: &str,
+    action: commands::agreement_lifecycle::AgreementAction,
+    authority_path: Option<&str>,
+    skip_confirmation: bool,
+) -> Result<Output> {
+    let tx_mode = cli.tx_mode()?;
+    let request = commands::agreement_lifecycle::AgreementLifecycleRequest {
+        agreement,
+        action,
+        authority_path,
+        skip_confirmation,
+        tx_mode: &tx_mode,
+    };
+    Ok(
+        commands::execute_agreement_lifecycle(tally_client, &request, config)
+            .await?
+            .into(),
+    )
 }
 
 /// Execute dashboard commands
-fn execute_dashboard_commands(
+async fn execute_dashboard_commands(
     cli: &Cli,
     tally_client: &SimpleTallyClient,
     config: &TallyCliConfig,
-    config_file: &ConfigFile,
+    context: &RuntimeContext,
     command: &DashboardCommands,
-) -> Result<String> {
-    // Helper to resolve merchant from config when not provided
-    let get_merchant = |merchant_opt: &Option<String>| -> Result<String> {
-        merchant_opt.as_ref().map_or_else(
-            || {
-                config_file.active_profile().map_or_else(
-                    || {
-                        Err(anyhow::anyhow!(
-                            "No active profile configured and merchant not provided.\n\
-                             \n\
-                             Initialize your configuration first:\n\
-                                tally-merchant config init"
-                        ))
-                    },
-                    |profile| {
-                        profile.merchant.as_ref().map_or_else(
-                            || {
-                                Err(anyhow::anyhow!(
-                                    "Merchant not provided and not configured in active profile.\n\
-                                     \n\
-                                     You can fix this by:\n\
-                                     \n\
-                                     1. Pass the merchant as an argument:\n\
-                                        tally-merchant dashboard overview --merchant <MERCHANT_ADDRESS>\n\
-                                     \n\
-                                     2. Or configure it in your profile:\n\
-                                        tally-merchant config set merchant <MERCHANT_ADDRESS>\n\
-                                     \n\
-                                     If you haven't created a merchant yet, run:\n\
-                                        tally-merchant init"
-                                ))
-                            },
-                            |merchant| Ok(merchant.clone()),
-                        )
-                    },
-                )
-            },
-            |merchant| Ok(merchant.clone()),
-        )
-    };
+) -> Result<Output> {
+    use commands::dashboard::DashboardRequest;
+
+    let rpc_url = context.rpc_url.value.as_str();
+
+    // Live streaming is long-running and handled separately from one-shot queries
+    if let DashboardCommands::Events {
+        merchant,
+        follow: true,
+        ws_url,
+        ..
+    } = command
+    {
+        let request = commands::event_stream::FollowEventsRequest {
+            merchant: commands::dashboard::resolve_merchant(merchant.as_deref(), context)?,
+            rpc_url,
+            ws_url: ws_url.as_deref(),
+            output_format: &cli.output_format(config)?,
+        };
+        return Ok(commands::event_stream::follow(&request).await?.into());
+    }
 
-    // Resolve merchant for commands that need it
-    let command_with_merchant = match command {
+    // Resolve every argument (including the profile merchant) up front
+    let request = match command {
         DashboardCommands::Overview { merchant } => {
-            let merchant_addr = get_merchant(merchant)?;
-            DashboardCommands::Overview {
-                merchant: Some(merchant_addr),
-            }
-        }
-        DashboardCommands::Events { merchant, since } => {
-            let merchant_addr = get_merchant(merchant)?;
-            DashboardCommands::Events {
-                merchant: Some(merchant_addr),
-                since: *since,
-            }
+            DashboardRequest::overview(merchant.as_deref(), context)?
         }
+        DashboardCommands::Analytics { plan } => DashboardRequest::analytics(plan, context)?,
+        DashboardCommands::Events {
+            merchant, since, ..
+        } => DashboardRequest::events(merchant.as_deref(), *since, context, config)?,
         DashboardCommands::Subscriptions {
             merchant,
             active_only,
+        } => DashboardRequest::subscriptions(merchant.as_deref(), *active_only, context)?,
+        // The ledger is built from transaction history rather than account state
+        DashboardCommands::Ledger { merchant, from, to } => {
+            let request = commands::ledger::LedgerRequest {
+                merchant: commands::dashboard::resolve_merchant(merchant.as_deref(), context)?,
+                from: from
+                    .as_deref()
+                    .map(|from| commands::ledger::parse_period_bound(from, false))
+                    .transpose()?,
+                to: to
+                    .as_deref()
+                    .map(|to| commands::ledger::parse_period_bound(to, true))
+                    .transpose()?,
+                rpc_url,
+            };
+            return Ok(commands::ledger::execute(tally_client, &request)?.into());
+        }
+    };
+
+    commands::dashboard::execute(&request, rpc_url)
+}
+
+/// Execute admin commands
+async fn execute_admin_commands(
+    cli: &Cli,
+    tally_client: &SimpleTallyClient,
+    config: &TallyCliConfig,
+    runtime: &RuntimeContext,
+    command: &AdminCommands,
+) -> Result<Output> {
+    use commands::admin::{AdminAction, InitConfigSettings};
+    use std::str::FromStr;
+    use tally_sdk::program_types::UpdateConfigFeesArgs;
+    use tally_sdk::solana_sdk::pubkey::Pubkey;
+
+    let tx_mode = cli.tx_mode()?;
+    let (action, signer) = match command {
+        AdminCommands::InitConfig {
+            min_platform_fee_bps,
+            max_platform_fee_bps,
+            keeper_fee_bps,
+            max_withdrawal_usdc,
+            min_period_seconds,
+            default_allowance_periods,
+            max_grace_period_seconds,
+            signer,
         } => {
-            let merchant_addr = get_merchant(merchant)?;
-            DashboardCommands::Subscriptions {
-                merchant: Some(merchant_addr),
-                active_only: *active_only,
-            }
+            let allowed_mint = tally_sdk::get_usdc_mint(runtime.usdc_mint())
+                .map_err(|e| anyhow::anyhow!("Failed to parse USDC mint: {e}"))?;
+            let settings = InitConfigSettings {
+                min_platform_fee_bps: *min_platform_fee_bps,
+                max_platform_fee_bps: *max_platform_fee_bps,
+                keeper_fee_bps: *keeper_fee_bps,
+                max_withdrawal_amount: max_withdrawal_usdc.microlamports(),
+                min_period_seconds: *min_period_seconds,
+                default_allowance_periods: *default_allowance_periods,
+                max_grace_period_seconds: *max_grace_period_seconds,
+                allowed_mint,
+            };
+            (AdminAction::InitConfig(settings), signer)
+        }
+        AdminCommands::SetFees {
+            min_platform_fee_bps,
+            max_platform_fee_bps,
+            keeper_fee_bps,
+            signer,
+        } => {
+            let args = UpdateConfigFeesArgs {
+                min_platform_fee_bps: *min_platform_fee_bps,
+                max_platform_fee_bps: *max_platform_fee_bps,
+                keeper_fee_bps: *keeper_fee_bps,
+            };
+            (AdminAction::SetFees(args), signer)
         }
-        DashboardCommands::Analytics { .. } => {
-            // Analytics doesn't need merchant, use command as-is
-            command.clone()
+        AdminCommands::Pause { signer } => (AdminAction::SetPaused(true), signer),
+        AdminCommands::Unpause { signer } => (AdminAction::SetPaused(false), signer),
+        AdminCommands::TransferAuthority {
+            new_authority,
+            signer,
+        } => {
+            let new_authority = Pubkey::from_str(new_authority).map_err(|e| {
+                anyhow::anyhow!("Invalid new authority address '{new_authority}': {e}")
+            })?;
+            (AdminAction::TransferAuthority { new_authority }, signer)
+        }
+        AdminCommands::AcceptAuthority { signer } => (AdminAction::AcceptAuthority, signer),
+        AdminCommands::SetPayeeTier {
+            payee,
+            tier,
+            signer,
+        } => {
+            let payee = Pubkey::from_str(payee)
+                .map_err(|e| anyhow::anyhow!("Invalid payee address '{payee}': {e}"))?;
+            let action = AdminAction::SetPayeeTier {
+                payee,
+                tier: (*tier).into(),
+            };
+            (action, signer)
         }
     };
 
-    let output_format = match cli.output {
-        Some(OutputFormat::Json) => commands::dashboard::OutputFormat::Json,
-        Some(OutputFormat::Csv) => commands::dashboard::OutputFormat::Csv,
-        _ => commands::dashboard::OutputFormat::Human,
+    let request = commands::admin::AdminRequest {
+        action,
+        authority_path: Some(runtime.signer_path(signer.authority.as_deref())),
+        skip_confirmation: signer.yes,
+        tx_mode: &tx_mode,
     };
-    let rpc_url = cli.rpc_url.as_deref().unwrap_or(&config.default_rpc_url);
-    commands::dashboard::execute(
-        tally_client,
-        &command_with_merchant,
-        &output_format,
-        rpc_url,
-    )
+    Ok(commands::execute_admin(tally_client, &request, config)
+        .await?
+        .into())
+}
+
+/// Execute keeper commands
+async fn execute_keeper_commands(
+    cli: &Cli,
+    config: &TallyCliConfig,
+    context: &RuntimeContext,
+    command: &KeeperCommands,
+) -> Result<Output> {
+    let tx_mode = cli.tx_mode()?;
+    if matches!(tx_mode, TxMode::Export(_)) {
+        return Err(anyhow::anyhow!(
+            "The keeper signs and submits payments itself and cannot export transactions"
+        ));
+    }
+
+    match command {
+        KeeperCommands::Run {
+            payee,
+            keeper,
+            once,
+            concurrency,
+            interval_secs,
+        } => {
+            let request = commands::keeper::KeeperRunRequest {
+                payee: commands::dashboard::resolve_merchant(payee.as_deref(), context)?,
+                rpc_url: &context.rpc_url.value,
+                keeper_path: Some(context.signer_path(keeper.as_deref())),
+                once: *once,
+                dry_run: tx_mode == TxMode::DryRun,
+                concurrency: usize::from(*concurrency),
+                interval: std::time::Duration::from_secs(*interval_secs),
+            };
+            let tally_client = std::sync::Arc::new(context.tally_client()?);
+            Ok(commands::execute_keeper_run(tally_client, &request, config)
+                .await?
+                .into())
+        }
+    }
+}
+
+/// Execute offline transaction commands
+async fn execute_tx_commands(
+    tally_client: Option<&SimpleTallyClient>,
+    config: &TallyCliConfig,
+    context: &RuntimeContext,
+    command: &TxCommands,
+) -> Result<Output> {
+    Ok(match command {
+        TxCommands::Sign {
+            file,
+            keypair,
+            output,
+            yes,
+        } => {
+            let request = commands::tx::SignTxRequest {
+                input: file,
+                output: output.as_deref(),
+                keypair_path: Some(context.signer_path(keypair.as_deref())),
+                skip_confirmation: *yes,
+            };
+            commands::execute_tx_sign(&request)?.into()
+        }
+        TxCommands::Submit { file } => {
+            let client = require_client(tally_client)?;
+            let request = commands::tx::SubmitTxRequest { input: file };
+            commands::execute_tx_submit(client, &request, config)
+                .await?
+                .into()
+        }
+        TxCommands::Show { signature } => {
+            let client = require_client(tally_client)?;
+            let request = commands::tx::ShowTxRequest { context, signature };
+            commands::execute_tx_show(client, &request)?.into()
+        }
+    })
 }
 
 /// Main command router
@@ -838,37 +1975,75 @@ async fn execute_command(
     tally_client: Option<&SimpleTallyClient>,
     config: &TallyCliConfig,
     config_file: &ConfigFile,
-) -> Result<String> {
-    match &cli.command {
+    context: &RuntimeContext,
+) -> Result<Output> {
+    Ok(match &cli.command {
         Commands::Init { skip_plan } => {
             let client = require_client(tally_client)?;
-            commands::execute_init_wizard(client, config, *skip_plan).await
+            commands::execute_init_wizard(
+                client,
+                config,
+                *skip_plan,
+                context.usdc_mint(),
+                &context.wallet_path.value,
+                &cli.tx_mode()?,
+            )
+            .await?
+            .into()
         }
         Commands::Config { command } => {
-            execute_config_commands(cli, tally_client, config, command).await
+            execute_config_commands(tally_client, context, command).await?
         }
         Commands::Payee { command } => {
             let client = require_client(tally_client)?;
-            execute_payee_commands(cli, client, config, command).await
+            execute_payee_commands(cli, client, config, context, command).await?
         }
         Commands::PaymentTerms { command } => {
             let client = require_client(tally_client)?;
-            execute_payment_terms_commands(cli, client, config, command).await
+            execute_payment_terms_commands(cli, client, config, context, command).await?
         }
         Commands::Agreement { command } => {
             let client = require_client(tally_client)?;
-            execute_agreement_commands(cli, client, config, command).await
+            execute_agreement_commands(cli, client, config, context, command).await?
         }
         Commands::Dashboard { command } => {
             let client = require_client(tally_client)?;
-            execute_dashboard_commands(cli, client, config, config_file, command)
+            execute_dashboard_commands(cli, client, config, context, command).await?
+        }
+        Commands::Admin { command } => {
+            let client = require_client(tally_client)?;
+            execute_admin_commands(cli, client, config, context, command).await?
+        }
+        Commands::Keeper { command } => {
+            execute_keeper_commands(cli, config, context, command).await?
+        }
+        Commands::Tx { command } => {
+            execute_tx_commands(tally_client, config, context, command).await?
+        }
+        Commands::Wallet { command } => match command {
+            WalletCommands::New { name } => commands::wallet::new_wallet(name)?.into(),
+            WalletCommands::Import { name, from } => commands::wallet::import(name, from)?.into(),
+            WalletCommands::List => commands::wallet::list()?.into(),
+            WalletCommands::Export { name, output, yes } => {
+                commands::wallet::export(name, output.as_deref(), *yes)?.into()
+            }
+        },
+        Commands::Doctor => {
+            let request = commands::doctor::DoctorRequest {
+                context,
+                config_file,
+            };
+            commands::execute_doctor(&request).into()
+        }
+        Commands::ExplainError { query } => {
+            let request = commands::explain_error::ExplainErrorRequest { context, query };
+            commands::execute_explain_error(&request)?.into()
         }
         Commands::Completions {
             shell,
             install,
             yes,
             print,
-            dry_run,
             uninstall,
         } => {
             use clap::CommandFactory;
@@ -879,7 +2054,7 @@ async fn execute_command(
                 CompletionAction::Print
             } else if *uninstall {
                 CompletionAction::Uninstall
-            } else if *dry_run {
+            } else if cli.dry_run {
                 CompletionAction::DryRun
             } else if *install || *yes {
                 CompletionAction::Install
@@ -893,73 +2068,21 @@ async fn execute_command(
                 skip_confirm: *yes,
             };
             let cmd = Cli::command();
-            commands::completions::execute(&args, cmd)
+            commands::completions::execute(&args, cmd)?.into()
         }
-    }
+        Commands::Schema { kind } => commands::schema::execute(kind.as_deref())?,
+    })
 }
 
 /// Helper to require SDK client with helpful error message
 fn require_client(client: Option<&SimpleTallyClient>) -> Result<&SimpleTallyClient> {
-    client.ok_or_else(|| {
-        anyhow::anyhow!(
-            "This command requires connection to Solana.\n\
-             \n\
-             Program ID not configured. You can fix this by:\n\
-             \n\
-             1. Set the TALLY_PROGRAM_ID environment variable:\n\
-                export TALLY_PROGRAM_ID=<your-program-id>\n\
-             \n\
-             2. Or configure it in your profile:\n\
-                tally-merchant config init\n\
-                tally-merchant config set program-id <your-program-id>\n\
-             \n\
-             3. Or pass it as a CLI flag:\n\
-                tally-merchant --program-id <your-program-id> <command>\n\
-             \n\
-             See https://github.com/Tally-Pay/tally-cli for program IDs"
-        )
-    })
+    client.ok_or_else(context::program_id_missing)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_usdc_to_micro_units_valid() {
-        assert_eq!(usdc_to_micro_units(10.0).unwrap(), 10_000_000);
-        assert_eq!(usdc_to_micro_units(0.5).unwrap(), 500_000);
-        assert_eq!(usdc_to_micro_units(100.25).unwrap(), 100_250_000);
-    }
-
-    #[test]
-    fn test_usdc_to_micro_units_zero() {
-        assert_eq!(usdc_to_micro_units(0.0).unwrap(), 0);
-    }
-
-    #[test]
-    fn test_usdc_to_micro_units_negative() {
-        let result = usdc_to_micro_units(-1.0);
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("greater than or equal to 0"));
-    }
-
-    #[test]
-    fn test_usdc_to_micro_units_too_large() {
-        let result = usdc_to_micro_units(2_000_000.0);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("seems too high"));
-    }
-
-    #[test]
-    fn test_usdc_to_micro_units_edge_case_max() {
-        // Test maximum allowed value
-        assert_eq!(usdc_to_micro_units(1_000_000.0).unwrap(), 1_000_000_000_000);
-    }
-
     #[test]
     fn test_parse_output_format_human() {
         let result = parse_output_format("human").unwrap();
@@ -978,6 +2101,12 @@ mod tests {
         assert!(matches!(result, OutputFormat::Csv));
     }
 
+    #[test]
+    fn test_parse_output_format_ndjson() {
+        let result = parse_output_format("ndjson").unwrap();
+        assert!(matches!(result, OutputFormat::Ndjson));
+    }
+
     #[test]
     fn test_parse_output_format_case_insensitive() {
         assert!(matches!(
@@ -998,6 +2127,50 @@ mod tests {
         ));
     }
 
+    fn context_with_profile(profile: &str, config_file: &ConfigFile) -> RuntimeContext {
+        RuntimeContext::resolve_with(
+            &ContextFlags::default(),
+            &TallyCliConfig::new(),
+            config_file,
+            |var| (var == "TALLY_PROFILE").then(|| profile.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_missing_tally_profile_allows_profile_create() {
+        let config_file = ConfigFile::new();
+        let context = context_with_profile("staging", &config_file);
+        let cli = Cli::try_parse_from([
+            "tally-merchant",
+            "config",
+            "profile",
+            "create",
+            "staging",
+            "--rpc-url",
+            "https://rpc.example.com",
+        ])
+        .unwrap();
+
+        assert!(check_selected_profile(&cli.command, &context, &config_file).is_ok());
+    }
+
+    #[test]
+    fn test_missing_tally_profile_rejects_commands_reading_profile() {
+        let config_file = ConfigFile::new();
+        let context = context_with_profile("staging", &config_file);
+        let cli = Cli::try_parse_from([
+            "tally-merchant",
+            "payment-terms",
+            "list",
+            "--payee",
+            "11111111111111111111111111111111",
+        ])
+        .unwrap();
+
+        let error = check_selected_profile(&cli.command, &context, &config_file).unwrap_err();
+        assert!(error.to_string().contains("'staging'"));
+    }
+
     #[test]
     fn test_parse_output_format_invalid() {
         let result = parse_output_format("invalid");
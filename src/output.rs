@@ -0,0 +1,339 @@
+//! Command results and how they are printed
+//!
+//! Commands return a typed result implementing [`CommandOutput`], and `main`
+//! renders it in the format selected with `--output`:
+//!
+//! | Format   | Output                                                        |
+//! |----------|---------------------------------------------------------------|
+//! | `human`  | [`CommandOutput::human`]                                      |
+//! | `json`   | The result wrapped in an [`Envelope`], pretty-printed         |
+//! | `ndjson` | The same envelope on one line, or one per record for streams  |
+//! | `csv`    | [`CommandOutput::csv`] for tables, human text for the rest    |
+//!
+//! Every JSON result has the same envelope, so integrations can dispatch on
+//! `kind` and validate `data` against the schema printed by
+//! `tally-merchant schema <KIND>`:
+//!
+//! ```json
+//! {"schema_version": 1, "kind": "payment-terms.list", "success": true, "data": {}}
+//! {"schema_version": 1, "success": false, "error": {"code": "RPC_ERROR", "message": "..."}}
+//! ```
+//!
+//! Error codes and exit statuses are listed in [`crate::errors`].
+//!
+//! Adding a field to a result keeps [`SCHEMA_VERSION`]; renaming or removing
+//! a field, or changing its type, bumps it.
+
+use crate::errors::ErrorDetails;
+use anyhow::Result;
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::fmt::Display;
+
+/// Version of the envelope and result schemas
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Output format selected with `--output`
+#[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+    Csv,
+    Ndjson,
+}
+
+/// A command's typed result
+pub trait CommandOutput: Serialize + JsonSchema {
+    /// Stable name of the result's shape, e.g. `payment-terms.list`
+    const KIND: &'static str;
+
+    /// Render for `--output human`
+    ///
+    /// # Errors
+    /// Returns error if formatting fails
+    fn human(&self) -> Result<String>;
+
+    /// Render for `--output csv`, if the result is a table
+    fn csv(&self) -> Option<Result<String>> {
+        None
+    }
+
+    /// Render for `--output ndjson`, if the result is a stream of records
+    ///
+    /// Results listing events print one envelope per record, see
+    /// [`ndjson_records`], so log shippers can ingest them line by line.
+    fn ndjson(&self) -> Option<Result<String>> {
+        None
+    }
+
+    /// Whether the command succeeded
+    ///
+    /// A result reporting a failure, such as a failing doctor check, is
+    /// still printed but makes the process exit non-zero.
+    fn success(&self) -> bool {
+        true
+    }
+}
+
+/// Successful result as printed for `--output json` and `--output ndjson`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Envelope<T> {
+    pub schema_version: u32,
+    /// Result kind, see `tally-merchant schema`
+    pub kind: String,
+    /// `false` if the command ran but reported a failure
+    pub success: bool,
+    pub data: T,
+}
+
+/// Failed command as printed for `--output json` and `--output ndjson`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ErrorEnvelope {
+    pub schema_version: u32,
+    /// Always `false`
+    pub success: bool,
+    pub error: ErrorDetails,
+}
+
+/// A result that is only a message, e.g. from `config set`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Message {
+    pub message: String,
+}
+
+impl CommandOutput for Message {
+    const KIND: &'static str = "message";
+
+    fn human(&self) -> Result<String> {
+        Ok(self.message.clone())
+    }
+}
+
+impl From<String> for Output {
+    fn from(message: String) -> Self {
+        Message { message }.into()
+    }
+}
+
+/// A command result with its type erased, as passed back to `main`
+pub struct Output(Box<dyn Render>);
+
+/// Object-safe view of [`CommandOutput`]
+trait Render {
+    fn kind(&self) -> &'static str;
+    fn data(&self) -> Result<Value>;
+    fn human(&self) -> Result<String>;
+    fn csv(&self) -> Option<Result<String>>;
+    fn ndjson(&self) -> Option<Result<String>>;
+    fn success(&self) -> bool;
+}
+
+impl<T: CommandOutput> Render for T {
+    fn kind(&self) -> &'static str {
+        T::KIND
+    }
+
+    fn data(&self) -> Result<Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    fn human(&self) -> Result<String> {
+        CommandOutput::human(self)
+    }
+
+    fn csv(&self) -> Option<Result<String>> {
+        CommandOutput::csv(self)
+    }
+
+    fn ndjson(&self) -> Option<Result<String>> {
+        CommandOutput::ndjson(self)
+    }
+
+    fn success(&self) -> bool {
+        CommandOutput::success(self)
+    }
+}
+
+impl<T: CommandOutput + 'static> From<T> for Output {
+    fn from(result: T) -> Self {
+        Self(Box::new(result))
+    }
+}
+
+impl Output {
+    /// Result kind, e.g. `payment-terms.list`
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        self.0.kind()
+    }
+
+    /// Whether the command succeeded, see [`CommandOutput::success`]
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.0.success()
+    }
+
+    /// The result wrapped in its JSON envelope
+    ///
+    /// # Errors
+    /// Returns error if the result cannot be serialized
+    pub fn envelope(&self) -> Result<Envelope<Value>> {
+        Ok(Envelope {
+            schema_version: SCHEMA_VERSION,
+            kind: self.kind().to_string(),
+            success: self.success(),
+            data: self.0.data()?,
+        })
+    }
+
+    /// Render in `format`
+    ///
+    /// # Errors
+    /// Returns error if formatting or serialization fails
+    pub fn render(&self, format: &OutputFormat) -> Result<String> {
+        match format {
+            OutputFormat::Human => self.0.human(),
+            OutputFormat::Json => Ok(serde_json::to_string_pretty(&self.envelope()?)?),
+            OutputFormat::Ndjson => self
+                .0
+                .ndjson()
+                .unwrap_or_else(|| Ok(serde_json::to_string(&self.envelope()?)?)),
+            OutputFormat::Csv => self.0.csv().unwrap_or_else(|| self.0.human()),
+        }
+    }
+}
+
+/// Each record in its own envelope, one compact line per record
+///
+/// # Errors
+/// Returns error if a record cannot be serialized
+pub fn ndjson_records<T: CommandOutput>(records: &[T]) -> Result<String> {
+    let lines = records
+        .iter()
+        .map(|record| {
+            serde_json::to_string(&Envelope {
+                schema_version: SCHEMA_VERSION,
+                kind: T::KIND.to_string(),
+                success: true,
+                data: record,
+            })
+        })
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// The JSON envelope for a failed command
+#[must_use]
+pub const fn error_envelope(error: ErrorDetails) -> ErrorEnvelope {
+    ErrorEnvelope {
+        schema_version: SCHEMA_VERSION,
+        success: false,
+        error,
+    }
+}
+
+/// JSON Schema of the envelope holding a `T`
+///
+/// `kind` is pinned to [`CommandOutput::KIND`] so a document validates
+/// against exactly one result schema.
+#[must_use]
+pub fn schema<T: CommandOutput>() -> Value {
+    let mut schema = schemars::schema_for!(Envelope<T>);
+    schema.insert(
+        "title".to_string(),
+        Value::from(format!("tally-merchant {} (v{SCHEMA_VERSION})", T::KIND)),
+    );
+    if let Some(kind) = schema.pointer_mut("/properties/kind") {
+        kind["const"] = Value::from(T::KIND);
+    }
+    if let Some(version) = schema.pointer_mut("/properties/schema_version") {
+        version["const"] = Value::from(SCHEMA_VERSION);
+    }
+    schema.to_value()
+}
+
+/// JSON Schema of the error envelope
+#[must_use]
+pub fn error_schema() -> Value {
+    let mut schema = schemars::schema_for!(ErrorEnvelope);
+    schema.insert(
+        "title".to_string(),
+        Value::from(format!("tally-merchant error (v{SCHEMA_VERSION})")),
+    );
+    schema.to_value()
+}
+
+/// Serialize a value with its `Display` form, e.g. a `Pubkey` as base58
+///
+/// Pair with `#[schemars(with = "String")]`.
+///
+/// # Errors
+/// Returns the serializer's error
+pub fn serialize_display<T: Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, JsonSchema)]
+    struct Greeting {
+        name: String,
+    }
+
+    impl CommandOutput for Greeting {
+        const KIND: &'static str = "test.greeting";
+
+        fn human(&self) -> Result<String> {
+            Ok(format!("Hello, {}", self.name))
+        }
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let output = Output::from(Greeting {
+            name: "payee".to_string(),
+        });
+        assert_eq!(output.render(&OutputFormat::Human).unwrap(), "Hello, payee");
+        // Results that are not tables fall back to human text
+        assert_eq!(output.render(&OutputFormat::Csv).unwrap(), "Hello, payee");
+
+        let json: Value =
+            serde_json::from_str(&output.render(&OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["kind"], "test.greeting");
+        assert_eq!(json["success"], true);
+        assert_eq!(json["data"]["name"], "payee");
+
+        let line = output.render(&OutputFormat::Ndjson).unwrap();
+        assert!(!line.contains('\n'));
+        assert_eq!(serde_json::from_str::<Value>(&line).unwrap(), json);
+    }
+
+    #[test]
+    fn test_error_envelope() {
+        let details = ErrorDetails::new(&anyhow::anyhow!("boom"));
+        let json = serde_json::to_value(error_envelope(details)).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["error"]["code"], "GENERAL");
+        assert_eq!(json["error"]["exit_code"], 1);
+        assert_eq!(json["error"]["message"], "boom");
+    }
+
+    #[test]
+    fn test_schema_pins_kind() {
+        let schema = schema::<Greeting>();
+        assert_eq!(schema["properties"]["kind"]["const"], "test.greeting");
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        assert!(schema.to_string().contains("\"name\""));
+    }
+}
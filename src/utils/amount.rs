@@ -0,0 +1,241 @@
+//! Exact decimal parsing of USDC amounts
+//!
+//! Amounts are parsed from their decimal text straight into micro-units
+//! (1 USDC = 1,000,000 micro-units) without going through `f64`, so `19.99`
+//! is exactly 19,990,000 micro-units and matches what ends up on-chain.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer};
+use tally_sdk::UsdcAmount;
+
+/// Micro-units in one USDC
+pub const MICRO_UNITS_PER_USDC: u64 = 1_000_000;
+
+/// Decimal places supported by USDC
+pub const USDC_DECIMALS: usize = 6;
+
+/// Largest amount accepted from user input, in micro-units (1,000,000 USDC)
+pub const MAX_AMOUNT_MICRO_UNITS: u64 = 1_000_000 * MICRO_UNITS_PER_USDC;
+
+/// Parse a USDC amount such as `19.99`, `$1,250.50` or `0.000001` into micro-units
+///
+/// A leading `$` and comma thousands separators are accepted. Zero is allowed;
+/// commands that need a positive amount check that themselves.
+///
+/// # Errors
+/// Returns error if the amount is negative, malformed, has more than 6 decimal
+/// places, or exceeds 1,000,000 USDC
+pub fn parse_usdc_micro_units(input: &str) -> Result<u64> {
+    let trimmed = input.trim();
+    let unsigned = trimmed.strip_prefix('$').unwrap_or(trimmed).trim_start();
+    if unsigned.starts_with('-') || trimmed.starts_with('-') {
+        return Err(anyhow!(
+            "Amount must be greater than or equal to 0 USDC, got '{input}'"
+        ));
+    }
+    let invalid =
+        || anyhow!("Invalid amount '{input}' - expected a decimal USDC amount like 19.99");
+
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    let whole = strip_thousands_separators(whole).ok_or_else(invalid)?;
+    if !whole.bytes().all(|b| b.is_ascii_digit()) || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    if fraction.len() > USDC_DECIMALS {
+        return Err(anyhow!(
+            "Amount '{input}' has more than {USDC_DECIMALS} decimal places - USDC is precise to 0.000001"
+        ));
+    }
+
+    let too_high = || {
+        anyhow!(
+            "Amount {input} USDC seems too high (maximum {} USDC)",
+            MAX_AMOUNT_MICRO_UNITS / MICRO_UNITS_PER_USDC
+        )
+    };
+    let whole_units: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| too_high())?
+    };
+    let fraction_units: u64 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<USDC_DECIMALS$}")
+            .parse()
+            .map_err(|_| invalid())?
+    };
+
+    let micro_units = whole_units
+        .checked_mul(MICRO_UNITS_PER_USDC)
+        .and_then(|units| units.checked_add(fraction_units))
+        .ok_or_else(too_high)?;
+    if micro_units > MAX_AMOUNT_MICRO_UNITS {
+        return Err(too_high());
+    }
+    Ok(micro_units)
+}
+
+/// Parse a USDC amount into a [`UsdcAmount`]; usable as a clap `value_parser`
+///
+/// # Errors
+/// See [`parse_usdc_micro_units`]
+pub fn parse_usdc_amount(input: &str) -> Result<UsdcAmount> {
+    parse_usdc_micro_units(input).map(UsdcAmount::from_microlamports)
+}
+
+/// Format micro-units as an exact decimal USDC amount with all 6 places, e.g. `19.990000`
+#[must_use]
+pub fn format_usdc_micro_units(micro_units: u64) -> String {
+    format!(
+        "{}.{:0USDC_DECIMALS$}",
+        micro_units / MICRO_UNITS_PER_USDC,
+        micro_units % MICRO_UNITS_PER_USDC
+    )
+}
+
+/// Remove comma thousands separators, checking they group digits by three
+fn strip_thousands_separators(whole: &str) -> Option<String> {
+    if !whole.contains(',') {
+        return Some(whole.to_string());
+    }
+    let mut groups = whole.split(',');
+    let first = groups.next()?;
+    if first.is_empty() || first.len() > 3 {
+        return None;
+    }
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+    Some(digits)
+}
+
+/// Deserialize a USDC amount written as a string (`"19.99"`) or a number
+///
+/// Numbers are converted through their shortest decimal representation,
+/// which is exact for every amount up to the 1,000,000 USDC limit.
+///
+/// # Errors
+/// Returns the deserializer's error if the value is not a valid amount
+pub fn deserialize_usdc_amount<'de, D>(deserializer: D) -> Result<UsdcAmount, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawAmount {
+        Text(String),
+        Integer(u64),
+        Float(f64),
+    }
+
+    let text = match RawAmount::deserialize(deserializer)? {
+        RawAmount::Text(text) => text,
+        RawAmount::Integer(value) => value.to_string(),
+        RawAmount::Float(value) => value.to_string(),
+    };
+    parse_usdc_amount(&text).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_usdc_valid() {
+        assert_eq!(parse_usdc_micro_units("10").unwrap(), 10_000_000);
+        assert_eq!(parse_usdc_micro_units("0.5").unwrap(), 500_000);
+        assert_eq!(parse_usdc_micro_units("100.25").unwrap(), 100_250_000);
+        assert_eq!(parse_usdc_micro_units("19.99").unwrap(), 19_990_000);
+        assert_eq!(parse_usdc_micro_units("0.000001").unwrap(), 1);
+        assert_eq!(parse_usdc_micro_units(".5").unwrap(), 500_000);
+        assert_eq!(parse_usdc_micro_units(" 7. ").unwrap(), 7_000_000);
+    }
+
+    #[test]
+    fn test_parse_usdc_zero() {
+        assert_eq!(parse_usdc_micro_units("0").unwrap(), 0);
+        assert_eq!(parse_usdc_micro_units("0.000000").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_usdc_dollar_and_separators() {
+        assert_eq!(parse_usdc_micro_units("$19.99").unwrap(), 19_990_000);
+        assert_eq!(parse_usdc_micro_units("$ 1,250.50").unwrap(), 1_250_500_000);
+        assert_eq!(parse_usdc_micro_units("999,999").unwrap(), 999_999_000_000);
+        assert!(parse_usdc_micro_units("1,00").is_err());
+        assert!(parse_usdc_micro_units("1000,000").is_err());
+        assert!(parse_usdc_micro_units(",100").is_err());
+    }
+
+    #[test]
+    fn test_parse_usdc_negative() {
+        for input in ["-1", "$-1", "-$1"] {
+            let err = parse_usdc_micro_units(input).unwrap_err().to_string();
+            assert!(err.contains("greater than or equal to 0"), "{input}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_parse_usdc_too_precise() {
+        let err = parse_usdc_micro_units("1.0000001").unwrap_err().to_string();
+        assert!(err.contains("more than 6 decimal places"));
+    }
+
+    #[test]
+    fn test_parse_usdc_malformed() {
+        for input in ["", ".", "abc", "1.2.3", "1e3", "+5", "1 000", "12.5x"] {
+            assert!(parse_usdc_micro_units(input).is_err(), "accepted {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_usdc_too_large() {
+        let err = parse_usdc_micro_units("2000000").unwrap_err().to_string();
+        assert!(err.contains("seems too high"));
+        assert!(parse_usdc_micro_units("1000000.000001").is_err());
+        assert!(parse_usdc_micro_units("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_parse_usdc_edge_case_max() {
+        assert_eq!(
+            parse_usdc_micro_units("1,000,000").unwrap(),
+            MAX_AMOUNT_MICRO_UNITS
+        );
+    }
+
+    #[test]
+    fn test_format_usdc_micro_units() {
+        assert_eq!(format_usdc_micro_units(0), "0.000000");
+        assert_eq!(format_usdc_micro_units(1), "0.000001");
+        assert_eq!(format_usdc_micro_units(19_990_000), "19.990000");
+        assert_eq!(format_usdc_micro_units(u64::MAX), "18446744073709.551615");
+    }
+
+    #[test]
+    fn test_deserialize_usdc_amount() {
+        #[derive(Deserialize)]
+        struct Row {
+            #[serde(deserialize_with = "deserialize_usdc_amount")]
+            amount: UsdcAmount,
+        }
+
+        for (toml, expected) in [
+            ("amount = \"19.99\"", 19_990_000),
+            ("amount = 19.99", 19_990_000),
+            ("amount = 20", 20_000_000),
+        ] {
+            let row: Row = toml::from_str(toml).unwrap();
+            assert_eq!(row.amount.microlamports(), expected, "{toml}");
+        }
+        assert!(toml::from_str::<Row>("amount = \"1.0000001\"").is_err());
+    }
+}
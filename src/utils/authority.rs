@@ -0,0 +1,85 @@
+//! Payee authority validation shared by payment terms commands
+
+use crate::errors::{enhance_account_not_found_error, CliError, ErrorCode};
+use anyhow::{Context, Result};
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::SimpleTallyClient;
+
+/// Fetch `payee` and verify that `signer` is its current on-chain authority
+///
+/// Payment terms commands call this before building a transaction, so a
+/// payee whose authority was moved with `payee set-authority` keeps working
+/// with the new key.
+///
+/// # Errors
+/// Returns error if the payee account cannot be fetched or `signer` is not its authority
+pub fn verify_payee_authority(
+    tally_client: &SimpleTallyClient,
+    payee: &Pubkey,
+    signer: &Pubkey,
+) -> Result<()> {
+    let account = tally_client
+        .get_payee(payee)
+        .context("Failed to fetch payee account - check RPC connection and account state")?
+        .ok_or_else(|| enhance_account_not_found_error("payee", payee))?;
+    ensure_current_authority(payee, &account.authority, signer)
+}
+
+/// Verify that `signer` is the current on-chain authority of `payee`
+///
+/// The stored authority is compared rather than the one the payee PDA was
+/// derived from, which changes once the authority has been transferred.
+///
+/// # Errors
+/// Returns error if `signer` is not the payee's authority
+pub fn ensure_current_authority(payee: &Pubkey, authority: &Pubkey, signer: &Pubkey) -> Result<()> {
+    if authority != signer {
+        return Err(CliError::new(
+            ErrorCode::AuthorityMismatch,
+            format!(
+                "Authority mismatch: payee {payee} is controlled by {authority}, but the loaded \
+                 keypair is {signer}."
+            ),
+        )
+        .with_suggestion("Pass --authority <KEYPAIR> with the payee authority keypair.")
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_current_authority_matches() {
+        let payee = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        assert!(ensure_current_authority(&payee, &authority, &authority).is_ok());
+    }
+
+    #[test]
+    fn test_transferred_authority_can_manage_terms() {
+        // The payee address keeps pointing at the account created by `original`
+        let payee = Pubkey::new_unique();
+        let original = Pubkey::new_unique();
+        let transferred = Pubkey::new_unique();
+
+        // Update and deactivate both check the signer against the stored authority
+        assert!(ensure_current_authority(&payee, &transferred, &transferred).is_ok());
+        assert!(ensure_current_authority(&payee, &transferred, &original).is_err());
+    }
+
+    #[test]
+    fn test_ensure_current_authority_mismatch() {
+        let payee = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let signer = Pubkey::new_unique();
+        let err = ensure_current_authority(&payee, &authority, &signer)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Authority mismatch"));
+        assert!(err.contains(&authority.to_string()));
+        assert!(err.contains(&signer.to_string()));
+    }
+}
@@ -0,0 +1,435 @@
+//! Billing period durations
+//!
+//! On-chain periods are a fixed number of seconds, so calendar units are
+//! converted with explicit, documented lengths:
+//!
+//! | Unit  | Seconds    | Length                                   |
+//! |-------|------------|------------------------------------------|
+//! | day   | 86,400     | 24 hours                                 |
+//! | week  | 604,800    | 7 days                                   |
+//! | month | 2,629,746  | 30.436875 days (average Gregorian month) |
+//! | year  | 31,556,952 | 365.2425 days (average Gregorian year)   |
+//!
+//! Twelve months are exactly one year, and a monthly period stays aligned with
+//! the calendar on average, but individual renewals drift from the same
+//! calendar day because real months are 28-31 days long.
+
+use crate::utils::colors::Theme;
+use anyhow::{anyhow, Result};
+
+/// Seconds in a minute
+pub const SECONDS_PER_MINUTE: u64 = 60;
+
+/// Seconds in an hour
+pub const SECONDS_PER_HOUR: u64 = 3_600;
+
+/// Seconds in a day
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Seconds in a week
+pub const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+
+/// Seconds in an average Gregorian month (365.2425 / 12 days)
+pub const SECONDS_PER_MONTH: u64 = 2_629_746;
+
+/// Seconds in an average Gregorian year (365.2425 days)
+pub const SECONDS_PER_YEAR: u64 = 12 * SECONDS_PER_MONTH;
+
+/// Parse a duration such as `1w`, `30d`, `1mo`, `1y`, `1y6mo` or ISO-8601 `P1M`
+/// into seconds
+///
+/// Units are `s`, `min`, `h`, `d`, `w`, `mo` and `y` (or their spelled-out
+/// forms); months and years use the average Gregorian lengths documented in
+/// this module. A bare `m` is rejected as ambiguous.
+///
+/// # Errors
+/// Returns error if the duration is malformed, zero or too large
+pub fn parse_duration_secs(input: &str) -> Result<u64> {
+    let trimmed = input.trim();
+    let secs = match trimmed.strip_prefix(['P', 'p']) {
+        Some(designators) => parse_iso8601(designators, input)?,
+        None => parse_units(trimmed, input)?,
+    };
+    if secs == 0 {
+        return Err(anyhow!("Duration '{input}' must be greater than zero"));
+    }
+    Ok(secs)
+}
+
+/// Parse a `<number><unit>` sequence like `30d` or `1y 6mo`
+fn parse_units(text: &str, input: &str) -> Result<u64> {
+    let invalid = || {
+        anyhow!("Invalid duration '{input}' - expected a number and unit like 30d, 1w, 1mo or 1y, or ISO-8601 like P1M")
+    };
+
+    if text.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut rest = text;
+    let mut total: u64 = 0;
+    while !rest.trim_start().is_empty() {
+        rest = rest.trim_start();
+        let digits_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits_end == 0 {
+            return Err(invalid());
+        }
+        let value: u64 = rest[..digits_end].parse().map_err(|_| too_large(input))?;
+        rest = rest[digits_end..].trim_start();
+
+        let unit_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_end];
+        rest = &rest[unit_end..];
+        if unit.eq_ignore_ascii_case("m") {
+            return Err(anyhow!(
+                "Ambiguous unit 'm' in '{input}' - use 'min' for minutes or 'mo' for months"
+            ));
+        }
+        let unit_secs = unit_seconds(unit).ok_or_else(invalid)?;
+        total = value
+            .checked_mul(unit_secs)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| too_large(input))?;
+    }
+    Ok(total)
+}
+
+/// Seconds in one of the accepted short or spelled-out units
+fn unit_seconds(unit: &str) -> Option<u64> {
+    let secs = match unit.to_ascii_lowercase().as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "min" | "mins" | "minute" | "minutes" => SECONDS_PER_MINUTE,
+        "h" | "hr" | "hrs" | "hour" | "hours" => SECONDS_PER_HOUR,
+        "d" | "day" | "days" => SECONDS_PER_DAY,
+        "w" | "wk" | "wks" | "week" | "weeks" => SECONDS_PER_WEEK,
+        "mo" | "mos" | "month" | "months" => SECONDS_PER_MONTH,
+        "y" | "yr" | "yrs" | "year" | "years" => SECONDS_PER_YEAR,
+        _ => return None,
+    };
+    Some(secs)
+}
+
+/// Parse the part of an ISO-8601 duration after the leading `P`
+/// (`nYnMnWnD` then optionally `TnHnMnS`)
+fn parse_iso8601(designators: &str, input: &str) -> Result<u64> {
+    let invalid = || {
+        anyhow!(
+            "Invalid ISO-8601 duration '{input}' - expected a form like P1M, P1Y, P2W or P1DT12H"
+        )
+    };
+
+    let (date, time) = match designators.split_once(['T', 't']) {
+        Some((_, "")) => return Err(invalid()),
+        Some((date, time)) => (date, Some(time)),
+        None => (designators, None),
+    };
+    if date.is_empty() && time.is_none() {
+        return Err(invalid());
+    }
+
+    let mut total: u64 = 0;
+    for (part, is_time) in [(date, false), (time.unwrap_or(""), true)] {
+        let mut value = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                value.push(c);
+                continue;
+            }
+            let unit_secs = match (c.to_ascii_uppercase(), is_time) {
+                ('Y', false) => SECONDS_PER_YEAR,
+                ('M', false) => SECONDS_PER_MONTH,
+                ('W', false) => SECONDS_PER_WEEK,
+                ('D', false) => SECONDS_PER_DAY,
+                ('H', true) => SECONDS_PER_HOUR,
+                ('M', true) => SECONDS_PER_MINUTE,
+                ('S', true) => 1,
+                _ => return Err(invalid()),
+            };
+            if value.is_empty() {
+                return Err(invalid());
+            }
+            let amount: u64 = value.parse().map_err(|_| too_large(input))?;
+            total = amount
+                .checked_mul(unit_secs)
+                .and_then(|secs| total.checked_add(secs))
+                .ok_or_else(|| too_large(input))?;
+            value.clear();
+        }
+        if !value.is_empty() {
+            return Err(invalid());
+        }
+    }
+    Ok(total)
+}
+
+fn too_large(input: &str) -> anyhow::Error {
+    anyhow!("Duration '{input}' is too large")
+}
+
+/// Resolve a billing period from `--period`, `--period-days` or `--period-months`
+///
+/// Months use the average Gregorian month, so `--period-months 12` is one year.
+#[must_use]
+pub fn period_from_flags(
+    period_secs: Option<u64>,
+    period_days: Option<u32>,
+    period_months: Option<u32>,
+) -> Option<u64> {
+    period_secs
+        .or_else(|| period_days.map(|days| u64::from(days) * SECONDS_PER_DAY))
+        .or_else(|| period_months.map(|months| u64::from(months) * SECONDS_PER_MONTH))
+}
+
+/// Resolve a billing period from catalog or CSV fields: a `period` duration,
+/// `period_days` or `period_months`, exactly one of which must be set
+///
+/// # Errors
+/// Returns error if none or several of the fields are set, or the duration is
+/// invalid
+pub fn period_from_fields(
+    period: Option<&str>,
+    period_days: Option<u64>,
+    period_months: Option<u64>,
+) -> Result<u64> {
+    let too_large = || anyhow!("Period is too large");
+    match (period, period_days, period_months) {
+        (Some(period), None, None) => parse_duration_secs(period),
+        (None, Some(days), None) => days.checked_mul(SECONDS_PER_DAY).ok_or_else(too_large),
+        (None, None, Some(months)) => months.checked_mul(SECONDS_PER_MONTH).ok_or_else(too_large),
+        (None, None, None) => Err(anyhow!(
+            "period (e.g. \"1mo\"), period_days or period_months is required"
+        )),
+        _ => Err(anyhow!(
+            "Set only one of period, period_days and period_months"
+        )),
+    }
+}
+
+/// Format a period in seconds as the largest exact unit, e.g. `1 month`,
+/// `2 weeks` or `1 day 12 hours`
+#[must_use]
+pub fn format_period(secs: u64) -> String {
+    for (unit_secs, unit) in [
+        (SECONDS_PER_YEAR, "year"),
+        (SECONDS_PER_MONTH, "month"),
+        (SECONDS_PER_WEEK, "week"),
+    ] {
+        if secs > 0 && secs.is_multiple_of(unit_secs) {
+            return plural(secs / unit_secs, unit);
+        }
+    }
+
+    let parts: Vec<String> = [
+        (secs / SECONDS_PER_DAY, "day"),
+        (secs % SECONDS_PER_DAY / SECONDS_PER_HOUR, "hour"),
+        (secs % SECONDS_PER_HOUR / SECONDS_PER_MINUTE, "minute"),
+        (secs % SECONDS_PER_MINUTE, "second"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, unit)| plural(count, unit))
+    .collect();
+
+    if parts.is_empty() {
+        plural(0, "second")
+    } else {
+        parts.join(" ")
+    }
+}
+
+fn plural(count: u64, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+/// Explain how a month- or year-based period drifts from the calendar
+///
+/// Returns `None` for periods that are not whole average months.
+#[must_use]
+pub fn calendar_drift_warning(secs: u64) -> Option<String> {
+    if secs == 0 || !secs.is_multiple_of(SECONDS_PER_MONTH) {
+        return None;
+    }
+    // Hundredths of a day, rounded half up
+    let centidays =
+        (u128::from(secs) * 100 + u128::from(SECONDS_PER_DAY / 2)) / u128::from(SECONDS_PER_DAY);
+    let days = format!("{}.{:02}", centidays / 100, centidays % 100);
+    let calendar = if secs.is_multiple_of(SECONDS_PER_YEAR) {
+        "calendar years are 365 or 366 days"
+    } else {
+        "calendar months are 28 to 31 days"
+    };
+    Some(format!(
+        "A {} period is a fixed {days} days on-chain (average Gregorian length); \
+         renewals will not always fall on the same calendar day because {calendar}",
+        format_period(secs)
+    ))
+}
+
+/// Print the [`calendar_drift_warning`] for a period to stderr, if any
+pub fn warn_calendar_drift(secs: u64) {
+    if let Some(warning) = calendar_drift_warning(secs) {
+        eprintln!("{} {warning}", Theme::warning("⚠"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_short_units() {
+        assert_eq!(parse_duration_secs("1w").unwrap(), SECONDS_PER_WEEK);
+        assert_eq!(parse_duration_secs("30d").unwrap(), 30 * SECONDS_PER_DAY);
+        assert_eq!(parse_duration_secs("1mo").unwrap(), SECONDS_PER_MONTH);
+        assert_eq!(parse_duration_secs("1y").unwrap(), SECONDS_PER_YEAR);
+        assert_eq!(parse_duration_secs("12h").unwrap(), 12 * SECONDS_PER_HOUR);
+        assert_eq!(
+            parse_duration_secs(" 90 days ").unwrap(),
+            90 * SECONDS_PER_DAY
+        );
+        assert_eq!(
+            parse_duration_secs("1y6mo").unwrap(),
+            18 * SECONDS_PER_MONTH
+        );
+        assert_eq!(
+            parse_duration_secs("1 day 12 hours").unwrap(),
+            SECONDS_PER_DAY + 12 * SECONDS_PER_HOUR
+        );
+    }
+
+    #[test]
+    fn test_parse_iso8601() {
+        assert_eq!(parse_duration_secs("P1M").unwrap(), SECONDS_PER_MONTH);
+        assert_eq!(parse_duration_secs("P1Y").unwrap(), SECONDS_PER_YEAR);
+        assert_eq!(parse_duration_secs("P2W").unwrap(), 2 * SECONDS_PER_WEEK);
+        assert_eq!(
+            parse_duration_secs("P1DT12H").unwrap(),
+            SECONDS_PER_DAY + 12 * SECONDS_PER_HOUR
+        );
+        assert_eq!(
+            parse_duration_secs("PT30M").unwrap(),
+            30 * SECONDS_PER_MINUTE
+        );
+        assert_eq!(
+            parse_duration_secs("p1y2m").unwrap(),
+            SECONDS_PER_YEAR + 2 * SECONDS_PER_MONTH
+        );
+    }
+
+    #[test]
+    fn test_twelve_months_is_one_year() {
+        assert_eq!(SECONDS_PER_YEAR, 31_556_952);
+        assert_eq!(
+            parse_duration_secs("12mo").unwrap(),
+            parse_duration_secs("1y").unwrap()
+        );
+        assert_eq!(
+            period_from_flags(None, None, Some(12)),
+            Some(SECONDS_PER_YEAR)
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for input in [
+            "", "30", "d", "1x", "-1d", "1.5d", "P", "PT", "P1H", "PT1D", "P1", "1d P1M",
+        ] {
+            assert!(parse_duration_secs(input).is_err(), "accepted {input:?}");
+        }
+        assert!(parse_duration_secs("0d")
+            .unwrap_err()
+            .to_string()
+            .contains("greater than zero"));
+        assert!(parse_duration_secs("99999999999999999999y").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_ambiguous_m() {
+        let err = parse_duration_secs("1m").unwrap_err().to_string();
+        assert!(err.contains("'min' for minutes or 'mo' for months"));
+    }
+
+    #[test]
+    fn test_period_from_flags() {
+        assert_eq!(period_from_flags(Some(60), None, None), Some(60));
+        assert_eq!(
+            period_from_flags(None, Some(30), None),
+            Some(30 * SECONDS_PER_DAY)
+        );
+        assert_eq!(
+            period_from_flags(None, None, Some(1)),
+            Some(SECONDS_PER_MONTH)
+        );
+        assert_eq!(period_from_flags(None, None, None), None);
+    }
+
+    #[test]
+    fn test_period_from_fields() {
+        assert_eq!(
+            period_from_fields(Some("P1Y"), None, None).unwrap(),
+            SECONDS_PER_YEAR
+        );
+        assert_eq!(
+            period_from_fields(None, Some(7), None).unwrap(),
+            SECONDS_PER_WEEK
+        );
+        assert_eq!(
+            period_from_fields(None, None, Some(12)).unwrap(),
+            SECONDS_PER_YEAR
+        );
+        assert!(period_from_fields(None, None, None)
+            .unwrap_err()
+            .to_string()
+            .contains("is required"));
+        assert!(period_from_fields(Some("1w"), Some(7), None)
+            .unwrap_err()
+            .to_string()
+            .contains("only one"));
+        assert!(period_from_fields(None, Some(u64::MAX), None).is_err());
+    }
+
+    #[test]
+    fn test_format_period() {
+        assert_eq!(format_period(SECONDS_PER_MONTH), "1 month");
+        assert_eq!(format_period(3 * SECONDS_PER_MONTH), "3 months");
+        assert_eq!(format_period(SECONDS_PER_YEAR), "1 year");
+        assert_eq!(format_period(2 * SECONDS_PER_WEEK), "2 weeks");
+        assert_eq!(format_period(30 * SECONDS_PER_DAY), "30 days");
+        assert_eq!(format_period(SECONDS_PER_DAY), "1 day");
+        assert_eq!(format_period(93_784), "1 day 2 hours 3 minutes 4 seconds");
+        assert_eq!(format_period(0), "0 seconds");
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        for secs in [
+            SECONDS_PER_YEAR,
+            6 * SECONDS_PER_MONTH,
+            SECONDS_PER_WEEK,
+            30 * SECONDS_PER_DAY,
+            93_784,
+        ] {
+            assert_eq!(parse_duration_secs(&format_period(secs)).unwrap(), secs);
+        }
+    }
+
+    #[test]
+    fn test_calendar_drift_warning() {
+        let warning = calendar_drift_warning(SECONDS_PER_MONTH).unwrap();
+        assert!(warning.contains("30.44 days"), "{warning}");
+        assert!(warning.contains("28 to 31 days"));
+        let warning = calendar_drift_warning(SECONDS_PER_YEAR).unwrap();
+        assert!(warning.contains("365.24 days"), "{warning}");
+        assert!(warning.contains("365 or 366"));
+        assert!(calendar_drift_warning(30 * SECONDS_PER_DAY).is_none());
+        assert!(calendar_drift_warning(SECONDS_PER_WEEK).is_none());
+    }
+}
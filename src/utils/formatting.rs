@@ -1,8 +1,11 @@
 //! Output formatting utilities for the Tally CLI
 
-use crate::config::TallyCliConfig;
+use crate::output::CommandOutput;
+use crate::utils::amount::format_usdc_micro_units;
+use crate::utils::duration::format_period;
 use anyhow::Result;
-use serde_json;
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tally_sdk::solana_sdk::pubkey::Pubkey;
 use tally_sdk::{PaymentPeriod, UsdcAmount};
@@ -32,6 +35,20 @@ pub fn detect_network(rpc_url: &str) -> String {
     }
 }
 
+/// Lamports per SOL
+const LAMPORTS_PER_SOL_U64: u64 = 1_000_000_000;
+
+/// Convert lamports to SOL for display purposes
+///
+/// Uses integer arithmetic to avoid f64 precision loss.
+/// Returns formatted string with 9 decimal places.
+#[must_use]
+pub fn lamports_to_sol(lamports: u64) -> String {
+    let whole = lamports / LAMPORTS_PER_SOL_U64;
+    let fractional = lamports % LAMPORTS_PER_SOL_U64;
+    format!("{whole}.{fractional:09}")
+}
+
 /// Format unix timestamp to human-readable date
 ///
 /// Returns "Invalid" for timestamps that cannot be converted to valid dates,
@@ -101,9 +118,245 @@ pub struct AgreementInfo {
     pub last_amount: UsdcAmount,
 }
 
+/// Payment terms as reported in JSON and CSV
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PaymentTermsEntry {
+    pub address: String,
+    pub terms_id: String,
+    /// Exact decimal amount, e.g. "10.500000"
+    pub amount_usdc: String,
+    pub amount_microlamports: u64,
+    pub period_seconds: u64,
+    pub period_days: u64,
+    /// e.g. "1 month"
+    pub period_display: String,
+}
+
+impl From<&PaymentTermsInfo> for PaymentTermsEntry {
+    fn from(terms: &PaymentTermsInfo) -> Self {
+        Self {
+            address: terms.address.to_string(),
+            terms_id: terms.terms_id.clone(),
+            amount_usdc: format_usdc_micro_units(terms.amount.microlamports()),
+            amount_microlamports: terms.amount.microlamports(),
+            period_seconds: terms.period.seconds(),
+            period_days: terms.period.as_days(),
+            period_display: format_period(terms.period.seconds()),
+        }
+    }
+}
+
+/// CSV header for [`PaymentTermsEntry`] rows, in field order
+pub const PAYMENT_TERMS_CSV_HEADER: [&str; 7] = [
+    "address",
+    "terms_id",
+    "amount_usdc",
+    "amount_microlamports",
+    "period_seconds",
+    "period_days",
+    "period_display",
+];
+
+/// A payment agreement as reported in JSON and CSV
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AgreementEntry {
+    pub address: String,
+    pub payment_terms: String,
+    pub payer: String,
+    pub next_payment_ts: i64,
+    pub next_payment_human: String,
+    pub active: bool,
+    pub payment_count: u32,
+    pub last_amount_microlamports: u64,
+    /// Exact decimal amount, e.g. "10.500000"
+    pub last_amount_usdc: String,
+    pub last_amount_display: String,
+    pub created_ts: i64,
+    pub created_human: String,
+}
+
+impl From<&AgreementInfo> for AgreementEntry {
+    fn from(agreement: &AgreementInfo) -> Self {
+        Self {
+            address: agreement.address.to_string(),
+            payment_terms: agreement.payment_terms.to_string(),
+            payer: agreement.payer.to_string(),
+            next_payment_ts: agreement.next_payment_ts,
+            next_payment_human: format_timestamp(agreement.next_payment_ts),
+            active: agreement.active,
+            payment_count: agreement.payment_count,
+            last_amount_microlamports: agreement.last_amount.microlamports(),
+            last_amount_usdc: format_usdc_micro_units(agreement.last_amount.microlamports()),
+            last_amount_display: agreement.last_amount.to_string(),
+            created_ts: agreement.created_ts,
+            created_human: format_timestamp(agreement.created_ts),
+        }
+    }
+}
+
+/// CSV header for [`AgreementEntry`] rows, in field order
+pub const AGREEMENT_CSV_HEADER: [&str; 12] = [
+    "address",
+    "payment_terms",
+    "payer",
+    "next_payment_ts",
+    "next_payment_human",
+    "active",
+    "payment_count",
+    "last_amount_microlamports",
+    "last_amount_usdc",
+    "last_amount_display",
+    "created_ts",
+    "created_human",
+];
+
+/// Format rows as CSV under `header`, which lists the row fields in order
+///
+/// The header is written even when there are no rows.
+///
+/// # Errors
+///
+/// Returns an error if CSV serialization fails
+pub fn format_csv<T: Serialize>(header: &[&str], rows: &[T]) -> Result<String> {
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    wtr.write_record(header)?;
+    for row in rows {
+        wtr.serialize(row)?;
+    }
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
+/// A decoded Tally event for display
+#[derive(Debug, Clone)]
+pub struct EventInfo {
+    pub event_type: String,
+    pub timestamp: i64,
+    pub payee: Pubkey,
+    pub payer: Option<Pubkey>,
+    pub payment_terms: Option<Pubkey>,
+    pub amount: Option<u64>,
+    pub signature: Option<String>,
+}
+
+/// An event as shown in command results
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EventEntry {
+    pub event_type: String,
+    pub timestamp: i64,
+    pub payer: Option<String>,
+    pub payment_terms: Option<String>,
+    pub amount_microlamports: Option<u64>,
+    /// Exact decimal amount, e.g. "10.500000"
+    pub amount_usdc: Option<String>,
+    pub signature: Option<String>,
+}
+
+impl From<&EventInfo> for EventEntry {
+    fn from(event: &EventInfo) -> Self {
+        Self {
+            event_type: event.event_type.clone(),
+            timestamp: event.timestamp,
+            payer: event.payer.map(|p| p.to_string()),
+            payment_terms: event.payment_terms.map(|p| p.to_string()),
+            amount_microlamports: event.amount,
+            amount_usdc: event.amount.map(format_usdc_micro_units),
+            signature: event.signature.clone(),
+        }
+    }
+}
+
+impl CommandOutput for EventEntry {
+    const KIND: &'static str = "event";
+
+    fn human(&self) -> Result<String> {
+        Ok(format_event_human(self))
+    }
+}
+
+/// Format a single event for human-readable output
+#[must_use]
+pub fn format_event_human(event: &EventEntry) -> String {
+    use crate::utils::colors::Theme;
+    use std::fmt::Write;
+
+    let mut output = String::new();
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::info("Event:"),
+        Theme::highlight(&event.event_type)
+    )
+    .unwrap();
+    writeln!(
+        &mut output,
+        "  {} {} ({})",
+        Theme::dim("Timestamp:"),
+        event.timestamp,
+        format_timestamp(event.timestamp)
+    )
+    .unwrap();
+    if let Some(payer) = &event.payer {
+        writeln!(&mut output, "  {} {payer}", Theme::dim("Subscriber:")).unwrap();
+    }
+    if let Some(payment_terms) = &event.payment_terms {
+        writeln!(&mut output, "  {} {payment_terms}", Theme::dim("Plan:")).unwrap();
+    }
+    if let Some(amount) = event.amount_microlamports {
+        writeln!(
+            &mut output,
+            "  {} {} USDC",
+            Theme::dim("Amount:"),
+            Theme::value(&UsdcAmount::from_microlamports(amount).to_string())
+        )
+        .unwrap();
+    }
+    if let Some(signature) = &event.signature {
+        writeln!(&mut output, "  {} {signature}", Theme::dim("Signature:")).unwrap();
+    }
+
+    output
+}
+
+/// CSV header used for event output
+pub const EVENT_CSV_HEADER: [&str; 7] = [
+    "event_type",
+    "timestamp",
+    "payer",
+    "payment_terms",
+    "amount_microlamports",
+    "amount_usdc",
+    "signature",
+];
+
+/// Format events as CSV, optionally preceded by [`EVENT_CSV_HEADER`]
+///
+/// Streaming callers write the header once and then pass `include_header = false`
+/// for each batch of rows.
+///
+/// # Errors
+///
+/// Returns an error if CSV serialization fails
+pub fn format_events_csv(events: &[EventEntry], include_header: bool) -> Result<String> {
+    let mut wtr = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+
+    if include_header {
+        wtr.write_record(EVENT_CSV_HEADER)?;
+    }
+
+    for event in events {
+        wtr.serialize(event)?;
+    }
+
+    Ok(String::from_utf8(wtr.into_inner()?)?)
+}
+
 /// Format payment terms for human-readable output
 #[must_use]
-pub fn format_payment_terms_human(terms_list: &[PaymentTermsInfo], payee_pda: &Pubkey) -> String {
+pub fn format_payment_terms_human(terms_list: &[PaymentTermsEntry], payee_pda: &str) -> String {
     use crate::utils::colors::Theme;
     use std::fmt::Write;
 
@@ -113,7 +366,7 @@ pub fn format_payment_terms_human(terms_list: &[PaymentTermsInfo], payee_pda: &P
         &mut output,
         "{} {}",
         Theme::info("Payee:"),
-        Theme::highlight(&payee_pda.to_string())
+        Theme::highlight(payee_pda)
     )
     .unwrap();
     writeln!(
@@ -145,15 +398,21 @@ pub fn format_payment_terms_human(terms_list: &[PaymentTermsInfo], payee_pda: &P
                 &mut output,
                 "  {} {}",
                 Theme::dim("Amount:"),
-                terms.amount
+                UsdcAmount::from_microlamports(terms.amount_microlamports)
+            )
+            .unwrap();
+            writeln!(
+                &mut output,
+                "  {} {}",
+                Theme::dim("Period:"),
+                terms.period_display
             )
             .unwrap();
-            writeln!(&mut output, "  {} {}", Theme::dim("Period:"), terms.period).unwrap();
             writeln!(
                 &mut output,
                 "  {} {}",
                 Theme::dim("Address:"),
-                Theme::dim(&terms.address.to_string())
+                Theme::dim(&terms.address)
             )
             .unwrap();
             writeln!(&mut output).unwrap();
@@ -163,34 +422,9 @@ pub fn format_payment_terms_human(terms_list: &[PaymentTermsInfo], payee_pda: &P
     output
 }
 
-/// Format payment terms for JSON output
-///
-/// # Errors
-///
-/// Returns an error if JSON serialization fails
-pub fn format_payment_terms_json(terms_list: &[PaymentTermsInfo]) -> Result<String> {
-    let json = serde_json::to_string_pretty(&serde_json::json!({
-        "payment_terms": terms_list.iter().map(|t| serde_json::json!({
-            "address": t.address.to_string(),
-            "terms_id": t.terms_id,
-            "amount_usdc": t.amount.usdc(),
-            "amount_microlamports": t.amount.microlamports(),
-            "period_seconds": t.period.seconds(),
-            "period_days": t.period.as_days(),
-            "period_display": t.period.to_string(),
-        })).collect::<Vec<_>>(),
-        "count": terms_list.len(),
-    }))?;
-    Ok(json)
-}
-
 /// Format payment agreements for human-readable output
 #[must_use]
-pub fn format_agreements_human(
-    agreements: &[AgreementInfo],
-    payment_terms_pda: &Pubkey,
-    _config: &TallyCliConfig,
-) -> String {
+pub fn format_agreements_human(agreements: &[AgreementEntry], payment_terms_pda: &str) -> String {
     use crate::utils::colors::Theme;
     use std::fmt::Write;
 
@@ -200,7 +434,7 @@ pub fn format_agreements_human(
         &mut output,
         "{} {}",
         Theme::info("Payment Terms:"),
-        Theme::highlight(&payment_terms_pda.to_string())
+        Theme::highlight(payment_terms_pda)
     )
     .unwrap();
     writeln!(
@@ -230,7 +464,7 @@ pub fn format_agreements_human(
                 &mut output,
                 "{} {}",
                 Theme::info("Payer:"),
-                Theme::value(&agreement.payer.to_string())
+                Theme::value(&agreement.payer)
             )
             .unwrap();
             writeln!(&mut output, "  {} {}", Theme::dim("Status:"), status).unwrap();
@@ -245,21 +479,21 @@ pub fn format_agreements_human(
                 &mut output,
                 "  {} {}",
                 Theme::dim("Last Amount:"),
-                agreement.last_amount
+                agreement.last_amount_display
             )
             .unwrap();
             writeln!(
                 &mut output,
                 "  {} {}",
                 Theme::dim("Next Payment:"),
-                format_timestamp(agreement.next_payment_ts)
+                agreement.next_payment_human
             )
             .unwrap();
             writeln!(
                 &mut output,
                 "  {} {}",
                 Theme::dim("Address:"),
-                Theme::dim(&agreement.address.to_string())
+                Theme::dim(&agreement.address)
             )
             .unwrap();
             writeln!(&mut output).unwrap();
@@ -269,35 +503,6 @@ pub fn format_agreements_human(
     output
 }
 
-/// Format payment agreements for JSON output
-///
-/// # Errors
-///
-/// Returns an error if JSON serialization fails
-pub fn format_agreements_json(
-    agreements: &[AgreementInfo],
-    _config: &TallyCliConfig,
-) -> Result<String> {
-    let json = serde_json::to_string_pretty(&serde_json::json!({
-        "agreements": agreements.iter().map(|a| serde_json::json!({
-            "address": a.address.to_string(),
-            "payment_terms": a.payment_terms.to_string(),
-            "payer": a.payer.to_string(),
-            "next_payment_ts": a.next_payment_ts,
-            "next_payment_human": format_timestamp(a.next_payment_ts),
-            "active": a.active,
-            "payment_count": a.payment_count,
-            "last_amount_microlamports": a.last_amount.microlamports(),
-            "last_amount_usdc": a.last_amount.usdc(),
-            "last_amount_display": a.last_amount.to_string(),
-            "created_ts": a.created_ts,
-            "created_human": format_timestamp(a.created_ts),
-        })).collect::<Vec<_>>(),
-        "count": agreements.len(),
-    }))?;
-    Ok(json)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,6 +540,94 @@ mod tests {
         assert_eq!(detect_network("https://my-private-node.com:8899"), "custom");
     }
 
+    #[test]
+    fn test_format_payment_terms_periods() {
+        let terms = vec![PaymentTermsEntry::from(&PaymentTermsInfo {
+            address: Pubkey::new_unique(),
+            terms_id: "pro".to_string(),
+            amount: UsdcAmount::from_microlamports(10_000_001),
+            period: PaymentPeriod::from_seconds(crate::utils::duration::SECONDS_PER_MONTH).unwrap(),
+        })];
+
+        let human = format_payment_terms_human(&terms, &Pubkey::new_unique().to_string());
+        assert!(human.contains("1 month"));
+
+        let json = serde_json::to_value(&terms).unwrap();
+        assert_eq!(json[0]["period_display"], "1 month");
+        assert_eq!(json[0]["period_seconds"], 2_629_746);
+        assert_eq!(json[0]["amount_usdc"], "10.000001");
+
+        let csv = format_csv(&PAYMENT_TERMS_CSV_HEADER, &terms).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), PAYMENT_TERMS_CSV_HEADER.join(","));
+        let row = lines.next().unwrap();
+        assert!(row.contains(",pro,"));
+        assert!(row.ends_with(",10.000001,10000001,2629746,30,1 month"));
+    }
+
+    fn sample_events() -> Vec<EventInfo> {
+        vec![
+            EventInfo {
+                event_type: "PaymentExecuted".to_string(),
+                timestamp: 1_700_000_000,
+                payee: Pubkey::new_unique(),
+                payer: Some(Pubkey::new_unique()),
+                payment_terms: Some(Pubkey::new_unique()),
+                amount: Some(10_500_000),
+                signature: Some("5sig".to_string()),
+            },
+            EventInfo {
+                event_type: "PaymentAgreementPaused".to_string(),
+                timestamp: 1_700_000_100,
+                payee: Pubkey::new_unique(),
+                payer: None,
+                payment_terms: None,
+                amount: None,
+                signature: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_event_entry_json() {
+        let events = sample_events();
+        let json =
+            serde_json::to_value(events.iter().map(EventEntry::from).collect::<Vec<_>>()).unwrap();
+
+        let array = json.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array[0]["event_type"], "PaymentExecuted");
+        assert_eq!(array[0]["timestamp"], 1_700_000_000);
+        assert_eq!(array[0]["payer"], events[0].payer.unwrap().to_string());
+        assert_eq!(array[0]["amount_microlamports"], 10_500_000);
+        assert_eq!(array[0]["amount_usdc"], "10.500000");
+        assert_eq!(array[0]["signature"], "5sig");
+        assert!(array[1]["amount_microlamports"].is_null());
+        assert!(array[1]["payer"].is_null());
+    }
+
+    #[test]
+    fn test_format_events_csv_header_and_rows() {
+        let events = sample_events();
+        let entries: Vec<EventEntry> = events.iter().map(EventEntry::from).collect();
+        let output = format_events_csv(&entries, true).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], EVENT_CSV_HEADER.join(","));
+        assert!(lines[1].starts_with("PaymentExecuted,1700000000,"));
+        assert!(lines[1].ends_with(",10500000,10.500000,5sig"));
+        assert_eq!(lines[2], "PaymentAgreementPaused,1700000100,,,,,");
+    }
+
+    #[test]
+    fn test_format_events_csv_without_header() {
+        let entries: Vec<EventEntry> = sample_events().iter().map(EventEntry::from).collect();
+        let output = format_events_csv(&entries, false).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(!output.starts_with("event_type"));
+    }
+
     #[test]
     fn test_detect_network_case_insensitive() {
         assert_eq!(detect_network("HTTPS://API.DEVNET.SOLANA.COM"), "devnet");
@@ -2,6 +2,15 @@
 //!
 //! This module contains shared utilities used across multiple commands.
 
+pub mod amount;
+pub mod authority;
 pub mod colors;
+pub mod duration;
 pub mod formatting;
+pub mod multisig;
+pub mod offline;
+pub mod program_errors;
 pub mod progress;
+pub mod signer;
+pub mod transaction;
+pub mod tx_decode;
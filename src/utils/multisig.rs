@@ -0,0 +1,297 @@
+//! Multisig proposals for payees controlled by a Squads multisig
+//!
+//! With `--multisig <ADDRESS>` the payee authority is the multisig's vault
+//! PDA rather than a local keypair. Commands build their instructions with the
+//! vault as signer and emit them as a [`Proposal`] for multisig members to
+//! review and approve, instead of submitting a transaction.
+
+use crate::utils::colors::Theme;
+use crate::utils::transaction::TxPlan;
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use tally_sdk::solana_sdk::instruction::Instruction;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+
+/// Squads v4 multisig program (`SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf`)
+pub const SQUADS_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    6, 129, 196, 206, 71, 226, 35, 104, 184, 177, 85, 94, 200, 135, 175, 9, 46, 252, 126, 251, 182,
+    108, 163, 245, 47, 191, 104, 212, 172, 156, 183, 168,
+]);
+
+/// Format version of the proposal payload
+pub const PROPOSAL_VERSION: u32 = 1;
+
+/// Which multisig vault acts as authority and where to write the proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigOptions {
+    /// Multisig account address
+    pub multisig: Pubkey,
+    /// Vault index within the multisig (0 for the default vault)
+    pub vault_index: u8,
+    /// File to write the proposal to; it is printed when `None`
+    pub path: Option<PathBuf>,
+}
+
+impl MultisigOptions {
+    /// The vault PDA that signs for the multisig
+    #[must_use]
+    pub fn vault(&self) -> Pubkey {
+        derive_vault(&self.multisig, self.vault_index)
+    }
+}
+
+/// Derive a Squads v4 vault PDA
+#[must_use]
+pub fn derive_vault(multisig: &Pubkey, vault_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"multisig", multisig.as_ref(), b"vault", &[vault_index]],
+        &SQUADS_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Instructions for multisig members to review and approve
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Proposal {
+    pub version: u32,
+    /// Human-readable description of the operation
+    pub summary: String,
+    pub multisig: String,
+    pub vault_index: u8,
+    pub vault: String,
+    pub instructions: Vec<ProposalInstruction>,
+}
+
+/// One instruction of a [`Proposal`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ProposalInstruction {
+    pub program_id: String,
+    pub accounts: Vec<ProposalAccount>,
+    /// Instruction data, base64-encoded
+    pub data: String,
+}
+
+/// An account referenced by a [`ProposalInstruction`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ProposalAccount {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl From<&Instruction> for ProposalInstruction {
+    fn from(instruction: &Instruction) -> Self {
+        Self {
+            program_id: instruction.program_id.to_string(),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| ProposalAccount {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: STANDARD.encode(&instruction.data),
+        }
+    }
+}
+
+impl Proposal {
+    /// Build the proposal for a plan signed by the multisig vault
+    #[must_use]
+    pub fn new(plan: &TxPlan, options: &MultisigOptions) -> Self {
+        Self {
+            version: PROPOSAL_VERSION,
+            summary: plan.title.clone(),
+            multisig: options.multisig.to_string(),
+            vault_index: options.vault_index,
+            vault: options.vault().to_string(),
+            instructions: plan.instructions.iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Serialize as pretty-printed JSON
+    ///
+    /// # Errors
+    /// Returns error if serialization fails
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize multisig proposal")
+    }
+}
+
+/// Write a proposal as JSON
+///
+/// # Errors
+/// Returns error if the proposal cannot be serialized or the file written
+pub fn write_proposal_file(path: &Path, proposal: &Proposal) -> Result<()> {
+    std::fs::write(path, format!("{}\n", proposal.to_json()?))
+        .with_context(|| format!("Failed to write proposal file {}", path.display()))
+}
+
+/// A proposal emitted by a command run with `--multisig`
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ProposalReport {
+    /// File the proposal was written to, if any
+    pub file: Option<String>,
+    pub proposal: Proposal,
+}
+
+/// Build a plan's multisig proposal and write it if asked
+///
+/// # Errors
+/// Returns error if the proposal cannot be serialized or written
+pub fn propose(plan: &TxPlan, options: &MultisigOptions) -> Result<ProposalReport> {
+    let proposal = Proposal::new(plan, options);
+    if let Some(path) = &options.path {
+        write_proposal_file(path, &proposal)?;
+    }
+    Ok(ProposalReport {
+        file: options.path.as_ref().map(|p| p.display().to_string()),
+        proposal,
+    })
+}
+
+/// Format a proposal report, printing the proposal JSON unless it was written
+///
+/// # Errors
+/// Returns error if formatting or serialization fails
+pub fn format_proposal_report(report: &ProposalReport) -> Result<String> {
+    let mut output = format_proposal(&report.proposal)?;
+    match &report.file {
+        Some(file) => write!(
+            &mut output,
+            "\n{} {}",
+            Theme::success("Proposal written to"),
+            Theme::highlight(file)
+        )?,
+        None => write!(&mut output, "\n{}", report.proposal.to_json()?)?,
+    }
+    Ok(output)
+}
+
+/// Format the human-readable summary of a proposal
+///
+/// # Errors
+/// Returns error if string formatting fails
+pub fn format_proposal(proposal: &Proposal) -> Result<String> {
+    let mut output = String::new();
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::header("Multisig proposal:"),
+        proposal.summary
+    )?;
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::info("Multisig:"),
+        Theme::highlight(&proposal.multisig)
+    )?;
+    writeln!(
+        &mut output,
+        "{} {} (index {})",
+        Theme::info("Vault (signer):"),
+        Theme::highlight(&proposal.vault),
+        proposal.vault_index
+    )?;
+    writeln!(&mut output, "{}", Theme::info("Instructions:"))?;
+    for (i, instruction) in proposal.instructions.iter().enumerate() {
+        writeln!(
+            &mut output,
+            "  {}. program {}",
+            i + 1,
+            instruction.program_id
+        )?;
+        for account in &instruction.accounts {
+            let flags = match (account.is_signer, account.is_writable) {
+                (true, true) => "signer, writable",
+                (true, false) => "signer",
+                (false, true) => "writable",
+                (false, false) => "readonly",
+            };
+            writeln!(&mut output, "     {} {}", account.pubkey, Theme::dim(flags))?;
+        }
+    }
+    write!(
+        &mut output,
+        "{}",
+        Theme::dim("Create a vault transaction with these instructions in your multisig app, then collect approvals.")
+    )?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use tally_sdk::solana_sdk::instruction::AccountMeta;
+
+    fn options(path: Option<PathBuf>) -> MultisigOptions {
+        MultisigOptions {
+            multisig: Pubkey::new_unique(),
+            vault_index: 0,
+            path,
+        }
+    }
+
+    fn plan(vault: Pubkey) -> TxPlan {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(vault, true),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            ],
+        );
+        TxPlan::new("Create payment terms 'pro'", vault, vec![instruction])
+    }
+
+    #[test]
+    fn test_squads_program_id() {
+        assert_eq!(
+            SQUADS_PROGRAM_ID,
+            Pubkey::from_str("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_vault_depends_on_index() {
+        let multisig = Pubkey::new_unique();
+        assert_eq!(derive_vault(&multisig, 0), derive_vault(&multisig, 0));
+        assert_ne!(derive_vault(&multisig, 0), derive_vault(&multisig, 1));
+    }
+
+    #[test]
+    fn test_proposal_from_plan() {
+        let options = options(None);
+        let vault = options.vault();
+        let proposal = Proposal::new(&plan(vault), &options);
+
+        assert_eq!(proposal.vault, vault.to_string());
+        assert_eq!(proposal.instructions.len(), 1);
+        let instruction = &proposal.instructions[0];
+        assert_eq!(instruction.data, STANDARD.encode([1, 2, 3]));
+        assert!(instruction.accounts[0].is_signer && instruction.accounts[0].is_writable);
+        assert!(!instruction.accounts[1].is_signer && !instruction.accounts[1].is_writable);
+    }
+
+    #[test]
+    fn test_propose_writes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proposal.json");
+        let options = options(Some(path.clone()));
+        let report = propose(&plan(options.vault()), &options).unwrap();
+        let output = format_proposal_report(&report).unwrap();
+        assert!(output.contains("Proposal written to"));
+
+        let written: Proposal =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written.version, PROPOSAL_VERSION);
+        assert_eq!(written.multisig, options.multisig.to_string());
+    }
+}
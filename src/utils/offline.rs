@@ -0,0 +1,492 @@
+//! Offline signing: export unsigned transactions, sign them elsewhere, submit later
+//!
+//! Transactions are exchanged as base64-encoded bincode, the wire format used
+//! by Solana RPC, so files can also be inspected or signed by other tools.
+
+use crate::output::serialize_display;
+use crate::utils::colors::Theme;
+use crate::utils::transaction::TxPlan;
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use tally_sdk::solana_sdk::hash::Hash;
+use tally_sdk::solana_sdk::message::Message;
+use tally_sdk::solana_sdk::nonce::state::{State as NonceState, Versions as NonceVersions};
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::{Signature, Signer};
+use tally_sdk::solana_sdk::signer::SignerError;
+use tally_sdk::solana_sdk::system_instruction;
+use tally_sdk::solana_sdk::transaction::Transaction;
+use tally_sdk::SimpleTallyClient;
+
+/// Durable nonce to build an exported transaction with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceOptions {
+    /// Nonce account address
+    pub account: Pubkey,
+    /// Nonce authority; read from the nonce account when not given
+    pub authority: Option<Pubkey>,
+}
+
+/// How to build and where to write an exported transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// File to write; the transaction is printed when `None`
+    pub path: Option<PathBuf>,
+    /// Sign with the local keypair before exporting (`--sign-only`)
+    pub sign: bool,
+    /// Explicit blockhash (or nonce value), skipping the RPC lookup
+    pub blockhash: Option<Hash>,
+    pub nonce: Option<NonceOptions>,
+}
+
+/// Signature state of one required signer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct SignerStatus {
+    #[serde(serialize_with = "serialize_display")]
+    #[schemars(with = "String")]
+    pub pubkey: Pubkey,
+    pub signed: bool,
+}
+
+/// A transaction exported for offline signing
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ExportReport {
+    pub title: String,
+    /// File the transaction was written to, if any
+    pub file: Option<String>,
+    /// Recent blockhash, or the durable nonce value with `nonce_account`
+    pub blockhash: String,
+    pub nonce_account: Option<String>,
+    pub signers: Vec<SignerStatus>,
+    /// Base64-encoded transaction
+    pub transaction: String,
+}
+
+/// Encode a transaction as base64 bincode
+///
+/// # Errors
+/// Returns error if the transaction cannot be serialized
+pub fn encode_transaction(transaction: &Transaction) -> Result<String> {
+    let bytes = bincode::serialize(transaction).context("Failed to serialize transaction")?;
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Decode a base64 bincode transaction, ignoring surrounding whitespace
+///
+/// # Errors
+/// Returns error if the input is not valid base64 or not a transaction
+pub fn decode_transaction(encoded: &str) -> Result<Transaction> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .context("Transaction is not valid base64")?;
+    bincode::deserialize(&bytes).context("Data is not a serialized Solana transaction")
+}
+
+/// Read a transaction exported with `--export-tx`
+///
+/// # Errors
+/// Returns error if the file cannot be read or does not contain a transaction
+pub fn read_transaction_file(path: &Path) -> Result<Transaction> {
+    let encoded = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transaction file {}", path.display()))?;
+    decode_transaction(&encoded)
+        .with_context(|| format!("Invalid transaction file {}", path.display()))
+}
+
+/// Write a transaction as a single base64 line
+///
+/// # Errors
+/// Returns error if the transaction cannot be encoded or the file written
+pub fn write_transaction_file(path: &Path, transaction: &Transaction) -> Result<()> {
+    let encoded = encode_transaction(transaction)?;
+    std::fs::write(path, format!("{encoded}\n"))
+        .with_context(|| format!("Failed to write transaction file {}", path.display()))
+}
+
+/// List the required signers and whether each has signed
+#[must_use]
+pub fn signer_status(transaction: &Transaction) -> Vec<SignerStatus> {
+    let required = usize::from(transaction.message.header.num_required_signatures);
+    transaction
+        .message
+        .account_keys
+        .iter()
+        .take(required)
+        .enumerate()
+        .map(|(i, pubkey)| SignerStatus {
+            pubkey: *pubkey,
+            signed: transaction
+                .signatures
+                .get(i)
+                .is_some_and(|signature| *signature != Signature::default()),
+        })
+        .collect()
+}
+
+/// Build an unsigned transaction for a plan
+///
+/// With a nonce, an `AdvanceNonceAccount` instruction is prepended and
+/// `blockhash` must be the nonce account's stored value.
+#[must_use]
+pub fn build_transaction(
+    plan: &TxPlan,
+    blockhash: &Hash,
+    nonce: Option<(&Pubkey, &Pubkey)>,
+) -> Transaction {
+    let mut instructions = Vec::with_capacity(plan.instructions.len() + 1);
+    if let Some((account, authority)) = nonce {
+        instructions.push(system_instruction::advance_nonce_account(
+            account, authority,
+        ));
+    }
+    instructions.extend(plan.instructions.iter().cloned());
+
+    let message = Message::new_with_blockhash(&instructions, Some(&plan.fee_payer), blockhash);
+    Transaction::new_unsigned(message)
+}
+
+/// Add `signer`'s signature, keeping signatures already present
+///
+/// # Errors
+/// Returns error if `signer` is not a required signer of the transaction or
+/// fails to sign
+pub fn sign_transaction(transaction: &mut Transaction, signer: &dyn Signer) -> Result<()> {
+    let blockhash = transaction.message.recent_blockhash;
+    transaction
+        .try_partial_sign(&[signer], blockhash)
+        .map_err(|e| match e {
+            SignerError::KeypairPubkeyMismatch => anyhow!(
+                "{} is not a required signer of this transaction.\n\
+                 Required signers: {}",
+                signer.pubkey(),
+                signer_status(transaction)
+                    .iter()
+                    .map(|s| s.pubkey.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            e => anyhow!("Failed to sign as {}: {e}", signer.pubkey()),
+        })
+}
+
+/// Read the stored durable nonce and authority from nonce account data
+///
+/// # Errors
+/// Returns error if the data is not an initialized nonce account
+pub fn parse_nonce_account(data: &[u8]) -> Result<(Hash, Pubkey)> {
+    let versions: NonceVersions =
+        bincode::deserialize(data).context("Account is not a nonce account")?;
+    match versions.state() {
+        NonceState::Initialized(nonce) => Ok((nonce.blockhash(), nonce.authority)),
+        NonceState::Uninitialized => Err(anyhow!("Nonce account is not initialized")),
+    }
+}
+
+/// Build a plan's transaction for offline signing and write it if asked
+///
+/// `signer` is only used with `--sign-only`.
+///
+/// # Errors
+/// Returns error if the blockhash or nonce cannot be resolved, signing fails,
+/// or the file cannot be written
+pub fn export(
+    tally_client: &SimpleTallyClient,
+    plan: &TxPlan,
+    options: &ExportOptions,
+    signer: Option<&dyn Signer>,
+) -> Result<ExportReport> {
+    let (blockhash, nonce) = if let Some(nonce) = &options.nonce {
+        // The nonce account stores both the nonce value and its authority
+        let (blockhash, authority) = match (options.blockhash, nonce.authority) {
+            (Some(hash), Some(authority)) => (hash, authority),
+            (hash, authority) => {
+                let account = tally_client
+                    .rpc_client
+                    .get_account(&nonce.account)
+                    .with_context(|| format!("Failed to fetch nonce account {}", nonce.account))?;
+                let (stored_hash, stored_authority) = parse_nonce_account(&account.data)
+                    .with_context(|| format!("Invalid nonce account {}", nonce.account))?;
+                (
+                    hash.unwrap_or(stored_hash),
+                    authority.unwrap_or(stored_authority),
+                )
+            }
+        };
+        (blockhash, Some((nonce.account, authority)))
+    } else {
+        let blockhash = match options.blockhash {
+            Some(hash) => hash,
+            None => tally_client
+                .rpc_client
+                .get_latest_blockhash()
+                .context("Failed to fetch recent blockhash - check RPC connection")?,
+        };
+        (blockhash, None)
+    };
+
+    let mut transaction = build_transaction(
+        plan,
+        &blockhash,
+        nonce
+            .as_ref()
+            .map(|(account, authority)| (account, authority)),
+    );
+    if options.sign {
+        let signer =
+            signer.context("--sign-only needs the signing keypair - pass --authority <KEYPAIR>")?;
+        sign_transaction(&mut transaction, signer)?;
+    }
+
+    if let Some(path) = &options.path {
+        write_transaction_file(path, &transaction)?;
+    }
+
+    Ok(ExportReport {
+        title: plan.title.clone(),
+        file: options.path.as_ref().map(|p| p.display().to_string()),
+        blockhash: blockhash.to_string(),
+        nonce_account: nonce.map(|(account, _)| account.to_string()),
+        signers: signer_status(&transaction),
+        transaction: encode_transaction(&transaction)?,
+    })
+}
+
+/// Format an export report with the next steps for signing and submitting
+///
+/// # Errors
+/// Returns error if string formatting fails
+pub fn format_export(report: &ExportReport) -> Result<String> {
+    let mut output = String::new();
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::success("Transaction exported:"),
+        report.title
+    )?;
+    if let Some(file) = &report.file {
+        writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("File:"),
+            Theme::highlight(file)
+        )?;
+    }
+    match &report.nonce_account {
+        Some(account) => writeln!(
+            &mut output,
+            "{} {} (durable nonce {account})",
+            Theme::info("Blockhash:"),
+            report.blockhash
+        )?,
+        None => writeln!(
+            &mut output,
+            "{} {} {}",
+            Theme::info("Blockhash:"),
+            report.blockhash,
+            Theme::dim("(recent - submit within about a minute, or use --nonce)")
+        )?,
+    }
+    write!(&mut output, "{}", format_signer_status(&report.signers)?)?;
+
+    let file = report.file.as_deref().unwrap_or("<FILE>");
+    writeln!(&mut output, "\n{}", Theme::info("Next steps:"))?;
+    if !report.signers.iter().all(|status| status.signed) {
+        writeln!(
+            &mut output,
+            "  tally-merchant tx sign {file} --keypair <KEYPAIR>   # once per missing signer"
+        )?;
+    }
+    write!(&mut output, "  tally-merchant tx submit {file}")?;
+
+    if report.file.is_none() {
+        write!(&mut output, "\n\n{}", report.transaction)?;
+    }
+
+    Ok(output)
+}
+
+/// Format the required signers with their signature state
+///
+/// # Errors
+/// Returns error if string formatting fails
+pub fn format_signer_status(signers: &[SignerStatus]) -> Result<String> {
+    let mut output = String::new();
+    writeln!(&mut output, "{}", Theme::info("Signers:"))?;
+    for status in signers {
+        let state = if status.signed {
+            Theme::success("signed")
+        } else {
+            Theme::warning("missing")
+        };
+        writeln!(&mut output, "  {} {state}", status.pubkey)?;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tally_sdk::solana_sdk::instruction::{AccountMeta, Instruction};
+    use tally_sdk::solana_sdk::signature::Keypair;
+
+    fn plan_with_signers(fee_payer: &Keypair, other: &Keypair) -> TxPlan {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1, 2, 3],
+            vec![
+                AccountMeta::new(fee_payer.pubkey(), true),
+                AccountMeta::new_readonly(other.pubkey(), true),
+                AccountMeta::new(Pubkey::new_unique(), false),
+            ],
+        );
+        TxPlan::new("Test", fee_payer.pubkey(), vec![instruction])
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let transaction = build_transaction(
+            &plan_with_signers(&payer, &other),
+            &Hash::new_unique(),
+            None,
+        );
+
+        let encoded = encode_transaction(&transaction).unwrap();
+        let decoded = decode_transaction(&format!("  {encoded}\n")).unwrap();
+        assert_eq!(decoded, transaction);
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(decode_transaction("not base64!").is_err());
+        assert!(decode_transaction(&STANDARD.encode([1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn test_partial_signing() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let mut transaction = build_transaction(
+            &plan_with_signers(&payer, &other),
+            &Hash::new_unique(),
+            None,
+        );
+        assert!(signer_status(&transaction).iter().all(|s| !s.signed));
+
+        sign_transaction(&mut transaction, &other).unwrap();
+        let status = signer_status(&transaction);
+        assert_eq!(status.len(), 2);
+        assert!(status
+            .iter()
+            .any(|s| s.pubkey == other.pubkey() && s.signed));
+        assert!(!transaction.is_signed());
+
+        sign_transaction(&mut transaction, &payer).unwrap();
+        assert!(transaction.is_signed());
+        assert!(transaction.verify().is_ok());
+    }
+
+    #[test]
+    fn test_sign_rejects_unrelated_keypair() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let mut transaction = build_transaction(
+            &plan_with_signers(&payer, &other),
+            &Hash::new_unique(),
+            None,
+        );
+
+        let stranger = Keypair::new();
+        let err = sign_transaction(&mut transaction, &stranger)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not a required signer"));
+        assert!(err.contains(&payer.pubkey().to_string()));
+    }
+
+    #[test]
+    fn test_build_transaction_with_nonce() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let nonce_value = Hash::new_unique();
+        let transaction = build_transaction(
+            &plan_with_signers(&payer, &other),
+            &nonce_value,
+            Some((&nonce_account, &payer.pubkey())),
+        );
+
+        assert_eq!(transaction.message.recent_blockhash, nonce_value);
+        assert_eq!(transaction.message.instructions.len(), 2);
+        let first = &transaction.message.instructions[0];
+        let program = transaction.message.account_keys[usize::from(first.program_id_index)];
+        assert_eq!(program, tally_sdk::solana_sdk::system_program::id());
+    }
+
+    #[test]
+    fn test_parse_nonce_account() {
+        use tally_sdk::solana_sdk::nonce::state::{Data, DurableNonce};
+
+        let authority = Pubkey::new_unique();
+        let durable = DurableNonce::from_blockhash(&Hash::new_unique());
+        let data = Data::new(authority, durable, 5_000);
+        let versions = NonceVersions::new(NonceState::Initialized(data));
+        let bytes = bincode::serialize(&versions).unwrap();
+
+        let (hash, parsed_authority) = parse_nonce_account(&bytes).unwrap();
+        assert_eq!(hash, *durable.as_hash());
+        assert_eq!(parsed_authority, authority);
+
+        let uninitialized =
+            bincode::serialize(&NonceVersions::new(NonceState::Uninitialized)).unwrap();
+        assert!(parse_nonce_account(&uninitialized).is_err());
+    }
+
+    #[test]
+    fn test_format_export_next_steps() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let transaction = build_transaction(
+            &plan_with_signers(&payer, &other),
+            &Hash::new_unique(),
+            None,
+        );
+        let report = ExportReport {
+            title: "Withdraw".to_string(),
+            file: None,
+            blockhash: transaction.message.recent_blockhash.to_string(),
+            nonce_account: None,
+            signers: signer_status(&transaction),
+            transaction: encode_transaction(&transaction).unwrap(),
+        };
+
+        let output = format_export(&report).unwrap();
+        assert!(output.contains("tally-merchant tx sign <FILE>"));
+        assert!(output.ends_with(&report.transaction));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["signers"][0]["pubkey"], payer.pubkey().to_string());
+        assert_eq!(json["signers"][0]["signed"], false);
+    }
+
+    #[test]
+    fn test_transaction_file_roundtrip() {
+        let payer = Keypair::new();
+        let other = Keypair::new();
+        let transaction = build_transaction(
+            &plan_with_signers(&payer, &other),
+            &Hash::new_unique(),
+            None,
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("payment.tx");
+        write_transaction_file(&path, &transaction).unwrap();
+        assert_eq!(read_transaction_file(&path).unwrap(), transaction);
+    }
+}
@@ -0,0 +1,740 @@
+//! Decode custom program errors into explanations and fixes
+//!
+//! A failed transaction only reports `custom program error: 0x1771`. The
+//! number means different things depending on the program that raised it, so
+//! the failing program is taken from the transaction logs when available:
+//!
+//! | Program          | Codes                                                 |
+//! |------------------|-------------------------------------------------------|
+//! | Tally            | 6000 and up, declared by the program                  |
+//! | Anchor framework | 100 - 5999, raised by account constraints in Tally    |
+//! | SPL Token        | 0 - 19, from token transfers and account checks       |
+//! | System program   | 0 - 8, from account creation and transfers            |
+
+use crate::errors::ErrorCode;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// SPL Token program
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// SPL Token-2022 program, which shares the SPL Token error codes
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PNnBqCXEpPxuEb";
+/// System program
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// Number of log lines kept by [`relevant_logs`]
+const RELEVANT_LOG_LINES: usize = 15;
+
+/// Which error table a code was decoded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorSource {
+    Tally,
+    Anchor,
+    SplToken,
+    System,
+}
+
+impl ErrorSource {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Tally => "Tally",
+            Self::Anchor => "Anchor",
+            Self::SplToken => "SPL Token",
+            Self::System => "System program",
+        }
+    }
+
+    const fn table(self) -> &'static [ProgramErrorInfo] {
+        match self {
+            Self::Tally => TALLY_ERRORS,
+            Self::Anchor => ANCHOR_ERRORS,
+            Self::SplToken => TOKEN_ERRORS,
+            Self::System => SYSTEM_ERRORS,
+        }
+    }
+}
+
+/// What a program error code means and how to fix it
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProgramErrorInfo {
+    pub code: u32,
+    pub name: &'static str,
+    /// Failure category reported as the CLI error code
+    pub category: ErrorCode,
+    pub explanation: &'static str,
+    pub fix: &'static str,
+}
+
+const fn info(
+    code: u32,
+    name: &'static str,
+    category: ErrorCode,
+    explanation: &'static str,
+    fix: &'static str,
+) -> ProgramErrorInfo {
+    ProgramErrorInfo {
+        code,
+        name,
+        category,
+        explanation,
+        fix,
+    }
+}
+
+/// Errors declared by the Tally program, in declaration order from 6000
+///
+/// Kept in step with the program's IDL by `test_tally_errors_match_idl`.
+const TALLY_ERRORS: &[ProgramErrorInfo] = &[
+    info(
+        6000,
+        "AgreementInactive",
+        ErrorCode::TransactionFailed,
+        "The payment agreement is paused or closed.",
+        "Check it with 'tally-merchant agreement show <AGREEMENT>'; the payer can resume it.",
+    ),
+    info(
+        6001,
+        "InsufficientAllowance",
+        ErrorCode::InsufficientBalance,
+        "The payer's USDC delegate allowance does not cover this payment.",
+        "The payer must approve a new allowance before the payment can be collected.",
+    ),
+    info(
+        6002,
+        "InsufficientFunds",
+        ErrorCode::InsufficientBalance,
+        "The payer's USDC balance is too low for this payment.",
+        "The payer must top up their USDC token account.",
+    ),
+    info(
+        6003,
+        "NotDue",
+        ErrorCode::TransactionFailed,
+        "The payment is not due yet.",
+        "Wait until the next payment time shown by 'tally-merchant agreement show <AGREEMENT>'.",
+    ),
+    info(
+        6004,
+        "Unauthorized",
+        ErrorCode::AuthorityMismatch,
+        "The signer is not allowed to perform this action.",
+        "Pass --authority with the payee authority keypair, or the platform authority for admin \
+         commands.",
+    ),
+    info(
+        6005,
+        "InvalidAmount",
+        ErrorCode::TransactionFailed,
+        "The amount is zero or outside the allowed range.",
+        "Use a positive USDC amount, e.g. --amount 9.99.",
+    ),
+    info(
+        6006,
+        "InvalidPeriod",
+        ErrorCode::TransactionFailed,
+        "The payment period is shorter than the platform minimum.",
+        "Check the minimum period with 'tally-merchant config show' and use a longer --period.",
+    ),
+    info(
+        6007,
+        "AgreementAlreadyActive",
+        ErrorCode::TransactionFailed,
+        "The payment agreement is already active.",
+        "No action needed.",
+    ),
+    info(
+        6008,
+        "AgreementAlreadyPaused",
+        ErrorCode::TransactionFailed,
+        "The payment agreement is already paused.",
+        "No action needed; resume it with 'tally-merchant agreement resume <AGREEMENT>'.",
+    ),
+    info(
+        6009,
+        "PaymentTermsInactive",
+        ErrorCode::TransactionFailed,
+        "The payment terms have been deactivated.",
+        "List active terms with 'tally-merchant payment-terms list' and use one of them.",
+    ),
+    info(
+        6010,
+        "InvalidTreasury",
+        ErrorCode::TransactionFailed,
+        "The treasury is not a USDC token account owned by the payee authority.",
+        "Use the authority's USDC associated token account as the treasury.",
+    ),
+    info(
+        6011,
+        "WrongMint",
+        ErrorCode::TransactionFailed,
+        "A token account does not hold the platform's allowed USDC mint.",
+        "Check --usdc-mint and that the token accounts hold that mint.",
+    ),
+    info(
+        6012,
+        "FeeOutOfRange",
+        ErrorCode::TransactionFailed,
+        "The fee is outside the bounds in the global config.",
+        "Check the fee bounds with 'tally-merchant config show'.",
+    ),
+    info(
+        6013,
+        "WithdrawalLimitExceeded",
+        ErrorCode::TransactionFailed,
+        "The withdrawal exceeds the maximum allowed per transaction.",
+        "Withdraw a smaller amount; the limit is shown by 'tally-merchant config show'.",
+    ),
+    info(
+        6014,
+        "ProgramPaused",
+        ErrorCode::ProgramPaused,
+        "The program is paused by the platform authority.",
+        "Wait for the platform authority to run 'tally-merchant admin unpause'.",
+    ),
+    info(
+        6015,
+        "ArithmeticOverflow",
+        ErrorCode::TransactionFailed,
+        "An amount calculation overflowed.",
+        "Use smaller amounts; report the issue at https://github.com/Tally-Pay/tally-cli/issues.",
+    ),
+    info(
+        6016,
+        "NoPendingAuthority",
+        ErrorCode::AuthorityMismatch,
+        "There is no pending authority transfer to accept.",
+        "The current authority must first run 'tally-merchant payee set-authority'.",
+    ),
+];
+
+/// Anchor framework errors raised by the Tally program's account checks
+const ANCHOR_ERRORS: &[ProgramErrorInfo] = &[
+    info(
+        100,
+        "InstructionMissing",
+        ErrorCode::TransactionFailed,
+        "The instruction data is missing its discriminator.",
+        "Check that --program-id points at a Tally deployment compatible with this CLI.",
+    ),
+    info(
+        101,
+        "InstructionFallbackNotFound",
+        ErrorCode::TransactionFailed,
+        "The program does not know this instruction.",
+        "The deployed program is older or newer than this CLI; check --program-id and upgrade.",
+    ),
+    info(
+        102,
+        "InstructionDidNotDeserialize",
+        ErrorCode::TransactionFailed,
+        "The program could not decode the instruction arguments.",
+        "The deployed program is older or newer than this CLI; check --program-id and upgrade.",
+    ),
+    info(
+        2000,
+        "ConstraintMut",
+        ErrorCode::TransactionFailed,
+        "An account that must be writable was passed read-only.",
+        "Report the issue at https://github.com/Tally-Pay/tally-cli/issues.",
+    ),
+    info(
+        2001,
+        "ConstraintHasOne",
+        ErrorCode::AuthorityMismatch,
+        "An account does not belong to the signer or payee it was passed with.",
+        "Check that --authority and the payee or payment terms addresses belong together.",
+    ),
+    info(
+        2002,
+        "ConstraintSigner",
+        ErrorCode::AuthorityMismatch,
+        "A required signer did not sign the transaction.",
+        "Pass --authority with the keypair that controls the account.",
+    ),
+    info(
+        2003,
+        "ConstraintRaw",
+        ErrorCode::TransactionFailed,
+        "An account failed one of the program's checks.",
+        "Check the addresses passed to the command and the program logs.",
+    ),
+    info(
+        2006,
+        "ConstraintSeeds",
+        ErrorCode::TransactionFailed,
+        "An account address does not match the PDA derived for it.",
+        "Check the payee and payment terms addresses, and that --program-id is correct.",
+    ),
+    info(
+        2012,
+        "ConstraintAddress",
+        ErrorCode::TransactionFailed,
+        "An account is not at the address the program expects.",
+        "Check --usdc-mint and --program-id for this network.",
+    ),
+    info(
+        2014,
+        "ConstraintTokenMint",
+        ErrorCode::TransactionFailed,
+        "A token account holds the wrong mint.",
+        "Use a USDC token account for the configured --usdc-mint.",
+    ),
+    info(
+        2015,
+        "ConstraintTokenOwner",
+        ErrorCode::AuthorityMismatch,
+        "A token account is owned by a different wallet.",
+        "Use a token account owned by the payee authority.",
+    ),
+    info(
+        3001,
+        "AccountDiscriminatorNotFound",
+        ErrorCode::TransactionFailed,
+        "An account has no data where a Tally account was expected.",
+        "Check the address; it may not be a Tally account.",
+    ),
+    info(
+        3002,
+        "AccountDiscriminatorMismatch",
+        ErrorCode::TransactionFailed,
+        "An account is a different kind of Tally account than expected.",
+        "Check that each address is the right account type, e.g. a payee and not payment terms.",
+    ),
+    info(
+        3007,
+        "AccountOwnedByWrongProgram",
+        ErrorCode::TransactionFailed,
+        "An account belongs to a different program.",
+        "Check that you are on the right network (--rpc-url) and program (--program-id).",
+    ),
+    info(
+        3010,
+        "AccountNotSigner",
+        ErrorCode::AuthorityMismatch,
+        "An account that must sign did not.",
+        "Pass --authority with the keypair that controls the account.",
+    ),
+    info(
+        3012,
+        "AccountNotInitialized",
+        ErrorCode::AccountNotFound,
+        "An account the program needs does not exist yet.",
+        "Create it first, e.g. with 'tally-merchant init' for a payee, and check --rpc-url.",
+    ),
+];
+
+/// SPL Token program errors
+const TOKEN_ERRORS: &[ProgramErrorInfo] = &[
+    info(
+        0,
+        "NotRentExempt",
+        ErrorCode::InsufficientBalance,
+        "A token account does not hold enough SOL to be rent exempt.",
+        "Fund the fee payer with more SOL.",
+    ),
+    info(
+        1,
+        "InsufficientFunds",
+        ErrorCode::InsufficientBalance,
+        "A token account does not hold enough tokens for the transfer.",
+        "Check the USDC balance of the sending account.",
+    ),
+    info(
+        2,
+        "InvalidMint",
+        ErrorCode::TransactionFailed,
+        "The mint is invalid.",
+        "Check --usdc-mint for this network.",
+    ),
+    info(
+        3,
+        "MintMismatch",
+        ErrorCode::TransactionFailed,
+        "The token account holds a different mint.",
+        "Use token accounts for the configured --usdc-mint.",
+    ),
+    info(
+        4,
+        "OwnerMismatch",
+        ErrorCode::AuthorityMismatch,
+        "The token account is owned by a different wallet, or the delegate is not approved.",
+        "Check the token account owner; for payments the payer must approve the delegate.",
+    ),
+    info(
+        5,
+        "FixedSupply",
+        ErrorCode::TransactionFailed,
+        "The mint has a fixed supply.",
+        "No new tokens can be minted.",
+    ),
+    info(
+        6,
+        "AlreadyInUse",
+        ErrorCode::TransactionFailed,
+        "The token account is already initialized.",
+        "Use the existing account instead of creating it again.",
+    ),
+    info(
+        7,
+        "InvalidNumberOfProvidedSigners",
+        ErrorCode::AuthorityMismatch,
+        "The wrong number of signers was provided.",
+        "Check the signers passed to the command.",
+    ),
+    info(
+        8,
+        "InvalidNumberOfRequiredSigners",
+        ErrorCode::AuthorityMismatch,
+        "The wrong number of required signers was provided.",
+        "Check the signers passed to the command.",
+    ),
+    info(
+        9,
+        "UninitializedState",
+        ErrorCode::AccountNotFound,
+        "A token account is not initialized.",
+        "Create the associated token account first, or let the CLI create it.",
+    ),
+    info(
+        10,
+        "NativeNotSupported",
+        ErrorCode::TransactionFailed,
+        "The instruction does not support native SOL token accounts.",
+        "Use a USDC token account.",
+    ),
+    info(
+        11,
+        "NonNativeHasBalance",
+        ErrorCode::TransactionFailed,
+        "A token account still holds tokens and cannot be closed.",
+        "Withdraw the balance first.",
+    ),
+    info(
+        12,
+        "InvalidInstruction",
+        ErrorCode::TransactionFailed,
+        "The token instruction is invalid.",
+        "Report the issue at https://github.com/Tally-Pay/tally-cli/issues.",
+    ),
+    info(
+        13,
+        "InvalidState",
+        ErrorCode::TransactionFailed,
+        "A token account is in an invalid state.",
+        "Check the token account with 'solana account <ADDRESS>'.",
+    ),
+    info(
+        14,
+        "Overflow",
+        ErrorCode::TransactionFailed,
+        "A token amount overflowed.",
+        "Use a smaller amount.",
+    ),
+    info(
+        15,
+        "AuthorityTypeNotSupported",
+        ErrorCode::TransactionFailed,
+        "The account does not support this authority type.",
+        "Check the token account type.",
+    ),
+    info(
+        16,
+        "MintCannotFreeze",
+        ErrorCode::TransactionFailed,
+        "The mint has no freeze authority.",
+        "No action possible on this mint.",
+    ),
+    info(
+        17,
+        "AccountFrozen",
+        ErrorCode::TransactionFailed,
+        "A token account is frozen by the mint's freeze authority.",
+        "The token issuer must thaw the account.",
+    ),
+    info(
+        18,
+        "MintDecimalsMismatch",
+        ErrorCode::TransactionFailed,
+        "The amount's decimals do not match the mint.",
+        "Check --usdc-mint; USDC uses 6 decimals.",
+    ),
+    info(
+        19,
+        "NonNativeNotSupported",
+        ErrorCode::TransactionFailed,
+        "The instruction only supports native SOL token accounts.",
+        "Report the issue at https://github.com/Tally-Pay/tally-cli/issues.",
+    ),
+];
+
+/// System program errors
+const SYSTEM_ERRORS: &[ProgramErrorInfo] = &[
+    info(
+        0,
+        "AccountAlreadyInUse",
+        ErrorCode::TransactionFailed,
+        "The account being created already exists.",
+        "The account was created earlier, e.g. the payee is already initialized; use it as is.",
+    ),
+    info(
+        1,
+        "ResultWithNegativeLamports",
+        ErrorCode::InsufficientBalance,
+        "The fee payer does not have enough SOL.",
+        "Fund the fee payer; on devnet use https://faucet.solana.com.",
+    ),
+    info(
+        2,
+        "InvalidProgramId",
+        ErrorCode::TransactionFailed,
+        "Cannot assign the account to the given program.",
+        "Check --program-id.",
+    ),
+    info(
+        3,
+        "InvalidAccountDataLength",
+        ErrorCode::TransactionFailed,
+        "Cannot allocate account data of this length.",
+        "Report the issue at https://github.com/Tally-Pay/tally-cli/issues.",
+    ),
+    info(
+        4,
+        "MaxSeedLengthExceeded",
+        ErrorCode::TransactionFailed,
+        "A seed is longer than 32 bytes.",
+        "Use a shorter payment terms ID.",
+    ),
+    info(
+        5,
+        "AddressWithSeedMismatch",
+        ErrorCode::TransactionFailed,
+        "The address does not match its seed derivation.",
+        "Check the addresses passed to the command.",
+    ),
+    info(
+        6,
+        "NonceNoRecentBlockhashes",
+        ErrorCode::TransactionFailed,
+        "The nonce advance found no recent blockhashes.",
+        "Retry in a few seconds.",
+    ),
+    info(
+        7,
+        "NonceBlockhashNotExpired",
+        ErrorCode::TransactionFailed,
+        "The stored nonce is still valid and cannot be advanced yet.",
+        "Retry in a few seconds.",
+    ),
+    info(
+        8,
+        "NonceUnexpectedBlockhashValue",
+        ErrorCode::TransactionFailed,
+        "The transaction's blockhash does not match the durable nonce.",
+        "Re-export the transaction with the current nonce value (--blockhash).",
+    ),
+];
+
+/// Which table decodes errors raised by `program_id`
+///
+/// Unknown programs are assumed to be Tally, whose own errors start at 6000
+/// and whose Anchor framework errors sit below that.
+#[must_use]
+pub fn source_for(code: u32, program_id: Option<&str>) -> Option<ErrorSource> {
+    match program_id {
+        Some(TOKEN_PROGRAM_ID | TOKEN_2022_PROGRAM_ID) => Some(ErrorSource::SplToken),
+        Some(SYSTEM_PROGRAM_ID) => Some(ErrorSource::System),
+        _ if code >= 6000 => Some(ErrorSource::Tally),
+        _ if code >= 100 => Some(ErrorSource::Anchor),
+        // Small codes are shared by SPL Token and the System program
+        _ => None,
+    }
+}
+
+/// Look up a code raised by `program_id`, see [`source_for`]
+#[must_use]
+pub fn lookup(
+    code: u32,
+    program_id: Option<&str>,
+) -> Option<(ErrorSource, &'static ProgramErrorInfo)> {
+    let source = source_for(code, program_id)?;
+    find(source, code).map(|info| (source, info))
+}
+
+/// Every table entry with this code, for a code whose program is unknown
+#[must_use]
+pub fn candidates(code: u32) -> Vec<(ErrorSource, &'static ProgramErrorInfo)> {
+    [
+        ErrorSource::Tally,
+        ErrorSource::Anchor,
+        ErrorSource::SplToken,
+        ErrorSource::System,
+    ]
+    .into_iter()
+    .filter_map(|source| find(source, code).map(|info| (source, info)))
+    .collect()
+}
+
+/// Look up an error by name, e.g. `InsufficientAllowance`
+#[must_use]
+pub fn find_by_name(name: &str) -> Vec<(ErrorSource, &'static ProgramErrorInfo)> {
+    [
+        ErrorSource::Tally,
+        ErrorSource::Anchor,
+        ErrorSource::SplToken,
+        ErrorSource::System,
+    ]
+    .into_iter()
+    .flat_map(|source| {
+        source
+            .table()
+            .iter()
+            .filter(|info| info.name.eq_ignore_ascii_case(name))
+            .map(move |info| (source, info))
+    })
+    .collect()
+}
+
+/// The entry for `code` in one table
+#[must_use]
+pub fn find(source: ErrorSource, code: u32) -> Option<&'static ProgramErrorInfo> {
+    source.table().iter().find(|info| info.code == code)
+}
+
+/// The program and code of the first `Program <ID> failed: custom program
+/// error: 0x..` line
+///
+/// The innermost failing program logs first, so this is the program that
+/// raised the error even when it was called through Tally.
+#[must_use]
+pub fn failing_program(logs: &[String]) -> Option<(String, u32)> {
+    logs.iter().find_map(|line| {
+        let rest = line.strip_prefix("Program ")?;
+        let (program_id, reason) = rest.split_once(" failed: ")?;
+        let hex = reason.strip_prefix("custom program error: 0x")?;
+        let code = u32::from_str_radix(hex.trim(), 16).ok()?;
+        Some((program_id.to_string(), code))
+    })
+}
+
+/// The log lines worth showing for a failed transaction
+///
+/// Keeps program messages and failures, dropping invoke, success and compute
+/// unit bookkeeping, and returns at most the last 15 of them.
+#[must_use]
+pub fn relevant_logs(logs: &[String]) -> Vec<String> {
+    let kept: Vec<&String> = logs
+        .iter()
+        .filter(|line| {
+            !(line.ends_with(" success")
+                || line.contains(" consumed ")
+                || (line.starts_with("Program ") && line.contains(" invoke [")))
+        })
+        .collect();
+    kept[kept.len().saturating_sub(RELEVANT_LOG_LINES)..]
+        .iter()
+        .map(|line| (*line).clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| (*line).to_string()).collect()
+    }
+
+    #[test]
+    fn test_lookup_by_program() {
+        let (source, info) = lookup(1, Some(TOKEN_PROGRAM_ID)).unwrap();
+        assert_eq!(source, ErrorSource::SplToken);
+        assert_eq!(info.name, "InsufficientFunds");
+        assert_eq!(info.category, ErrorCode::InsufficientBalance);
+
+        let (source, info) = lookup(0, Some(SYSTEM_PROGRAM_ID)).unwrap();
+        assert_eq!(source, ErrorSource::System);
+        assert_eq!(info.name, "AccountAlreadyInUse");
+
+        assert_eq!(lookup(6001, None).unwrap().1.name, "InsufficientAllowance");
+        assert_eq!(lookup(2006, None).unwrap().1.name, "ConstraintSeeds");
+        // Small codes need the program to be decoded
+        assert!(lookup(1, None).is_none());
+        assert_eq!(candidates(1).len(), 2);
+    }
+
+    #[test]
+    fn test_find_by_name() {
+        let found = find_by_name("insufficientfunds");
+        assert_eq!(found.len(), 2);
+        assert!(found
+            .iter()
+            .any(|(source, _)| *source == ErrorSource::Tally));
+        assert!(find_by_name("Nope").is_empty());
+    }
+
+    #[test]
+    fn test_tally_errors_are_sequential() {
+        for (index, info) in TALLY_ERRORS.iter().enumerate() {
+            assert_eq!(info.code as usize, 6000 + index, "{}", info.name);
+        }
+    }
+
+    /// Compare the table with the errors in the program's Anchor IDL
+    ///
+    /// The IDL is read from `TALLY_IDL`, or from the protocol workspace the
+    /// SDK dependency points at once `anchor build` has generated it.
+    #[test]
+    fn test_tally_errors_match_idl() {
+        let path = std::env::var("TALLY_IDL").unwrap_or_else(|_| {
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../tally-protocol/target/idl/tally_protocol.json"
+            )
+            .to_string()
+        });
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            eprintln!("Skipping: no IDL at {path} - run 'anchor build' or set TALLY_IDL");
+            return;
+        };
+        let idl: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        let declared: Vec<(u64, String)> = idl["errors"]
+            .as_array()
+            .expect("IDL has no errors")
+            .iter()
+            .map(|error| {
+                (
+                    error["code"].as_u64().unwrap(),
+                    error["name"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        let known: Vec<(u64, String)> = TALLY_ERRORS
+            .iter()
+            .map(|info| (u64::from(info.code), info.name.to_string()))
+            .collect();
+        assert_eq!(known, declared, "TALLY_ERRORS is out of date with {path}");
+    }
+
+    #[test]
+    fn test_failing_program_and_relevant_logs() {
+        let logs = logs(&[
+            "Program Tally111 invoke [1]",
+            "Program log: Instruction: ExecutePayment",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Error: insufficient funds",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4000 of 190000 units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+            "Program Tally111 failed: custom program error: 0x1",
+        ]);
+        assert_eq!(
+            failing_program(&logs),
+            Some((TOKEN_PROGRAM_ID.to_string(), 1))
+        );
+
+        let relevant = relevant_logs(&logs);
+        assert_eq!(relevant.len(), 4);
+        assert_eq!(relevant[0], "Program log: Instruction: ExecutePayment");
+    }
+}
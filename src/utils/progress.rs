@@ -20,6 +20,22 @@ pub fn create_spinner(message: &str) -> ProgressBar {
     spinner
 }
 
+/// Create a progress bar for a known number of steps (e.g., a batch of transactions)
+///
+/// # Panics
+/// Panics if the progress bar template is invalid (should never happen with valid template)
+#[must_use]
+pub fn create_progress_bar(len: u64, message: &str) -> ProgressBar {
+    let bar = ProgressBar::new(len);
+    bar.set_style(
+        ProgressStyle::with_template("{spinner:.cyan} [{bar:30.cyan/blue}] {pos}/{len} {msg}")
+            .expect("Valid progress bar template")
+            .progress_chars("=> "),
+    );
+    bar.set_message(message.to_string());
+    bar
+}
+
 /// Finish a progress indicator with a success message
 pub fn finish_progress_success(progress: &ProgressBar, message: &str) {
     progress.finish_with_message(format!("✓ {message}"));
@@ -41,6 +57,15 @@ mod tests {
         spinner.finish();
     }
 
+    #[test]
+    fn test_create_progress_bar() {
+        let bar = create_progress_bar(3, "Test");
+        bar.inc(1);
+        assert_eq!(bar.position(), 1);
+        assert_eq!(bar.length(), Some(3));
+        bar.finish();
+    }
+
     #[test]
     fn test_finish_progress_success() {
         let progress = create_spinner("Test");
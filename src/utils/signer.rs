@@ -0,0 +1,583 @@
+//! Signer URIs: where a command's signing key comes from
+//!
+//! Every keypair flag (`--authority`, `--keypair`, `--keeper`) and the profile
+//! `wallet-path` accept either a keypair file path or one of these URIs.
+//! Commands that hand the key to SDK helpers, such as the keeper, need a local
+//! keypair and reject `usb://` and `exec:`.
+//!
+//! | URI                 | Signer                                                  |
+//! |---------------------|---------------------------------------------------------|
+//! | `file:<PATH>`       | JSON keypair file (same as a bare path)                 |
+//! | `stdin:`            | JSON keypair read from standard input                   |
+//! | `prompt:`           | Seed phrase (and optional passphrase) typed at a prompt |
+//! | `keystore:<NAME>`   | Encrypted wallet from `tally-merchant wallet new`       |
+//! | `usb://ledger`      | Ledger hardware wallet, `?key=<ACCOUNT>[/<CHANGE>]`     |
+//! | `exec:<COMMAND>`    | External signer process, see below                      |
+//!
+//! # External signer protocol
+//!
+//! `exec:` keeps the key out of this process entirely: the command is split
+//! on whitespace and run once per request, with an action appended to its
+//! arguments.
+//!
+//! - `<COMMAND> pubkey` prints the signer's base58 public key.
+//! - `<COMMAND> sign <PUBKEY>` reads the base64-encoded message to sign as one
+//!   line on stdin and prints the base58 signature.
+//!
+//! The helper's stderr is passed through so it can ask the user to confirm on
+//! a device. A non-zero exit status fails the command, and every signature is
+//! verified against the public key before it is used.
+//!
+//! # Hardware wallets
+//!
+//! `usb://ledger` signs on a connected Ledger running the Solana app. Without
+//! a `key` query the default `m/44'/501'` account is used; `?key=1/0` selects
+//! `m/44'/501'/1'/0'`. Each signature must be approved on the device.
+
+use crate::context::expand_tilde;
+use crate::keystore;
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use dialoguer::Password;
+use solana_derivation_path::DerivationPath;
+use solana_remote_wallet::locator::Locator;
+use solana_remote_wallet::remote_keypair::{generate_remote_keypair, RemoteKeypair};
+use solana_remote_wallet::remote_wallet::maybe_wallet_manager;
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use tally_sdk::load_keypair;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::{
+    keypair_from_seed_phrase_and_passphrase, read_keypair, Keypair, Signature,
+};
+use tally_sdk::solana_sdk::signer::{Signer, SignerError};
+
+/// Where to load a signer from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerSource {
+    /// Keypair file path
+    File(String),
+    /// Keypair JSON on stdin
+    Stdin,
+    /// Seed phrase prompt
+    Prompt,
+    /// Wallet in the encrypted keystore
+    Keystore(String),
+    /// Hardware wallet URI, e.g. `usb://ledger?key=0`
+    Usb(String),
+    /// External signer command line
+    Exec(String),
+}
+
+impl SignerSource {
+    /// Parse a keypair path or signer URI
+    ///
+    /// # Errors
+    /// Returns error for an unknown `scheme://` URI, an invalid keystore
+    /// wallet name or an empty `exec:` command
+    pub fn parse(uri: &str) -> Result<Self> {
+        if let Some(path) = uri
+            .strip_prefix("file://")
+            .or_else(|| uri.strip_prefix("file:"))
+        {
+            return Ok(Self::File(path.to_string()));
+        }
+        if matches!(uri, "stdin:" | "stdin://") {
+            return Ok(Self::Stdin);
+        }
+        if matches!(uri, "prompt:" | "prompt://") {
+            return Ok(Self::Prompt);
+        }
+        if let Some(name) = uri.strip_prefix("keystore:") {
+            let name = name.trim_start_matches("//");
+            keystore::validate_name(name)?;
+            return Ok(Self::Keystore(name.to_string()));
+        }
+        if uri.starts_with("usb://") {
+            return Ok(Self::Usb(uri.to_string()));
+        }
+        if let Some(command) = uri.strip_prefix("exec:") {
+            let command = command.trim();
+            if command.is_empty() {
+                return Err(anyhow!(
+                    "'exec:' needs a command, e.g. exec:/usr/local/bin/my-signer"
+                ));
+            }
+            return Ok(Self::Exec(command.to_string()));
+        }
+        if let Some((scheme, _)) = uri.split_once("://") {
+            return Err(anyhow!(
+                "Unknown signer URI scheme '{scheme}://'.\n\
+                 Use a keypair path or one of: file:, stdin:, prompt:, keystore:, usb://ledger, exec:"
+            ));
+        }
+        Ok(Self::File(uri.to_string()))
+    }
+}
+
+/// Load any signer, including external ones
+///
+/// # Errors
+/// Returns error if the URI is invalid or the signer cannot be loaded
+pub fn load_signer(uri: &str) -> Result<Box<dyn Signer>> {
+    match SignerSource::parse(uri)? {
+        SignerSource::Exec(command) => Ok(Box::new(ExecSigner::new(&command)?)),
+        SignerSource::Usb(uri) => Ok(Box::new(load_remote_keypair(&uri)?)),
+        source => Ok(Box::new(load_keypair_from(source)?)),
+    }
+}
+
+/// Public key of a signer, without unlocking keystore wallets
+///
+/// # Errors
+/// Returns error if the URI is invalid or the signer cannot be loaded
+pub fn signer_pubkey(uri: &str) -> Result<Pubkey> {
+    match stored_signer_pubkey(uri)? {
+        Some(pubkey) => Ok(pubkey),
+        None => Ok(load_signer(uri)?.pubkey()),
+    }
+}
+
+/// Public key of a keypair file or keystore wallet, without any interaction
+///
+/// Returns `None` for signers that would prompt, read stdin, run a program
+/// or talk to a hardware wallet to learn their key.
+///
+/// # Errors
+/// Returns error if the URI is invalid or the keypair file or keystore
+/// wallet cannot be read
+pub fn stored_signer_pubkey(uri: &str) -> Result<Option<Pubkey>> {
+    match SignerSource::parse(uri)? {
+        SignerSource::File(path) => Ok(Some(load_keypair(Some(&expand_tilde(&path)))?.pubkey())),
+        SignerSource::Keystore(name) => {
+            let entry = keystore::Keystore::open_default()?.read(&name)?;
+            Pubkey::from_str(&entry.pubkey)
+                .map(Some)
+                .map_err(|e| anyhow!("Keystore wallet '{name}' has an invalid public key: {e}"))
+        }
+        SignerSource::Stdin
+        | SignerSource::Prompt
+        | SignerSource::Usb(_)
+        | SignerSource::Exec(_) => Ok(None),
+    }
+}
+
+/// Whether the signer behind `uri` exists, without loading or unlocking it
+///
+/// Only keypair files and keystore wallets can be missing; other signers are
+/// assumed present and report their own errors when loaded.
+///
+/// # Errors
+/// Returns error if the URI is invalid or the keystore cannot be opened
+pub fn signer_exists(uri: &str) -> Result<bool> {
+    Ok(match SignerSource::parse(uri)? {
+        SignerSource::File(path) => std::path::Path::new(&expand_tilde(&path)).exists(),
+        SignerSource::Keystore(name) => keystore::Keystore::open_default()?.path(&name).exists(),
+        _ => true,
+    })
+}
+
+/// Load a signer that must be a local keypair
+///
+/// For code that hands the key to SDK helpers taking a [`Keypair`].
+///
+/// # Errors
+/// Returns error if the URI is invalid, names an external signer, or the
+/// keypair cannot be loaded
+pub fn load_signer_keypair(uri: &str) -> Result<Keypair> {
+    match SignerSource::parse(uri)? {
+        source @ (SignerSource::Exec(_) | SignerSource::Usb(_)) => Err(not_a_keypair(&source)),
+        source => load_keypair_from(source),
+    }
+}
+
+fn not_a_keypair(source: &SignerSource) -> anyhow::Error {
+    let scheme = if matches!(source, SignerSource::Usb(_)) {
+        "a hardware wallet"
+    } else {
+        "an 'exec:' signer"
+    };
+    anyhow!(
+        "This command needs a local keypair and cannot use {scheme}.\n\
+         Pass a keypair file, 'keystore:', 'stdin:' or 'prompt:' instead."
+    )
+}
+
+fn load_keypair_from(source: SignerSource) -> Result<Keypair> {
+    match source {
+        SignerSource::File(path) => Ok(load_keypair(Some(&expand_tilde(&path)))?),
+        SignerSource::Stdin => read_keypair(&mut std::io::stdin())
+            .map_err(|e| anyhow!("Failed to read keypair JSON from stdin: {e}")),
+        SignerSource::Prompt => prompt_seed_phrase(),
+        SignerSource::Keystore(name) => keystore::load_keypair(&name),
+        source @ (SignerSource::Usb(_) | SignerSource::Exec(_)) => Err(not_a_keypair(&source)),
+    }
+}
+
+/// Derivation path from the `key` query of a hardware wallet URI
+fn derivation_path(uri: &str) -> Result<DerivationPath> {
+    let key = uri
+        .split_once('?')
+        .into_iter()
+        .flat_map(|(_, query)| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("key="));
+    key.map_or_else(
+        || Ok(DerivationPath::default()),
+        |key| {
+            DerivationPath::from_key_str(key)
+                .map_err(|e| anyhow!("Invalid key '{key}' in '{uri}': {e}"))
+        },
+    )
+}
+
+/// Connect to the hardware wallet named by `uri`
+fn load_remote_keypair(uri: &str) -> Result<RemoteKeypair> {
+    let locator = Locator::new_from_path(uri)
+        .map_err(|e| anyhow!("Invalid hardware wallet URI '{uri}': {e}"))?;
+    let derivation_path = derivation_path(uri)?;
+    let wallet_manager = maybe_wallet_manager()
+        .map_err(|e| anyhow!("Failed to access USB devices: {e}"))?
+        .ok_or_else(|| {
+            anyhow!(
+                "No hardware wallet found for '{uri}'.\n\
+                 Connect and unlock the device, then open the Solana app."
+            )
+        })?;
+    generate_remote_keypair(
+        locator,
+        derivation_path,
+        &wallet_manager,
+        false,
+        "authority",
+    )
+    .map_err(|e| anyhow!("Failed to load hardware wallet '{uri}': {e}"))
+}
+
+/// Read a seed phrase and passphrase, from the terminal or piped on stdin
+fn prompt_seed_phrase() -> Result<Keypair> {
+    if !std::io::stdin().is_terminal() {
+        return keypair_from_seed_lines(std::io::stdin().lock());
+    }
+    let phrase = Password::new()
+        .with_prompt("Seed phrase")
+        .interact()
+        .context("Failed to read seed phrase")?;
+    let passphrase = Password::new()
+        .with_prompt("Passphrase (empty for none)")
+        .allow_empty_password(true)
+        .interact()
+        .context("Failed to read passphrase")?;
+    keypair_from_seed(&phrase, &passphrase)
+}
+
+/// Derive a keypair from a seed phrase line and an optional passphrase line
+fn keypair_from_seed_lines(reader: impl BufRead) -> Result<Keypair> {
+    let mut lines = reader.lines();
+    let phrase = lines
+        .next()
+        .transpose()
+        .context("Failed to read seed phrase from stdin")?
+        .ok_or_else(|| anyhow!("Expected a seed phrase on stdin"))?;
+    let passphrase = lines
+        .next()
+        .transpose()
+        .context("Failed to read passphrase from stdin")?
+        .unwrap_or_default();
+    keypair_from_seed(&phrase, &passphrase)
+}
+
+fn keypair_from_seed(phrase: &str, passphrase: &str) -> Result<Keypair> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !matches!(phrase.split(' ').count(), 12 | 15 | 18 | 21 | 24) {
+        return Err(anyhow!("Seed phrase must have 12, 15, 18, 21 or 24 words"));
+    }
+    keypair_from_seed_phrase_and_passphrase(&phrase, passphrase.trim_end_matches(['\r', '\n']))
+        .map_err(|e| anyhow!("Invalid seed phrase: {e}"))
+}
+
+/// A signer that delegates to an external process
+///
+/// See the module documentation for the protocol.
+#[derive(Debug)]
+pub struct ExecSigner {
+    program: String,
+    args: Vec<String>,
+    pubkey: Pubkey,
+}
+
+impl ExecSigner {
+    /// Start using the external signer `command`, asking it for its public key
+    ///
+    /// # Errors
+    /// Returns error if the command fails or prints an invalid public key
+    pub fn new(command: &str) -> Result<Self> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        let program = words
+            .next()
+            .ok_or_else(|| anyhow!("External signer command is empty"))?;
+        let mut signer = Self {
+            program,
+            args: words.collect(),
+            pubkey: Pubkey::default(),
+        };
+
+        let output = signer.run(&["pubkey"], None).map_err(|e| anyhow!("{e}"))?;
+        signer.pubkey = Pubkey::from_str(output.trim()).map_err(|e| {
+            anyhow!(
+                "External signer '{}' printed an invalid public key '{}': {e}",
+                signer.program,
+                output.trim()
+            )
+        })?;
+        Ok(signer)
+    }
+
+    /// Run the helper with `action` appended, optionally writing `input` to stdin
+    fn run(&self, action: &[&str], input: Option<&str>) -> Result<String, SignerError> {
+        let failed =
+            |e: String| SignerError::Custom(format!("External signer '{}' {e}", self.program));
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .args(action)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| failed(format!("could not be started: {e}")))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Some(input) = input {
+                writeln!(stdin, "{input}").map_err(|e| failed(format!("closed stdin: {e}")))?;
+            }
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| failed(format!("failed: {e}")))?;
+        if !output.status.success() {
+            return Err(failed(format!("exited with {}", output.status)));
+        }
+        String::from_utf8(output.stdout).map_err(|_| failed("printed invalid UTF-8".to_string()))
+    }
+}
+
+impl Signer for ExecSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let output = self.run(
+            &["sign", &self.pubkey.to_string()],
+            Some(&STANDARD.encode(message)),
+        )?;
+        let signature = Signature::from_str(output.trim()).map_err(|e| {
+            SignerError::Custom(format!(
+                "External signer '{}' printed an invalid signature: {e}",
+                self.program
+            ))
+        })?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom(format!(
+                "External signer '{}' returned a signature that does not verify for {}",
+                self.program, self.pubkey
+            )));
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str =
+        "park remain person kitchen mule spell knee armed position rail grid ankle";
+
+    #[test]
+    fn test_parse_signer_source() {
+        assert_eq!(
+            SignerSource::parse("~/keys/id.json").unwrap(),
+            SignerSource::File("~/keys/id.json".to_string())
+        );
+        assert_eq!(
+            SignerSource::parse("file:/keys/id.json").unwrap(),
+            SignerSource::File("/keys/id.json".to_string())
+        );
+        assert_eq!(
+            SignerSource::parse("file:///keys/id.json").unwrap(),
+            SignerSource::File("/keys/id.json".to_string())
+        );
+        assert_eq!(SignerSource::parse("stdin:").unwrap(), SignerSource::Stdin);
+        assert_eq!(
+            SignerSource::parse("prompt://").unwrap(),
+            SignerSource::Prompt
+        );
+        assert_eq!(
+            SignerSource::parse("keystore:merchant").unwrap(),
+            SignerSource::Keystore("merchant".to_string())
+        );
+        assert!(SignerSource::parse("keystore:../id").is_err());
+        assert_eq!(
+            SignerSource::parse("usb://ledger?key=0").unwrap(),
+            SignerSource::Usb("usb://ledger?key=0".to_string())
+        );
+        assert_eq!(
+            SignerSource::parse("exec:/bin/signer --slot 2").unwrap(),
+            SignerSource::Exec("/bin/signer --slot 2".to_string())
+        );
+        assert!(SignerSource::parse("exec: ").is_err());
+        assert!(SignerSource::parse("s3://bucket/key.json").is_err());
+    }
+
+    #[test]
+    fn test_signer_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        let path = path.to_str().unwrap();
+        assert!(!signer_exists(path).unwrap());
+        std::fs::write(path, "[]").unwrap();
+        assert!(signer_exists(path).unwrap());
+        assert!(signer_exists(&format!("file:{path}")).unwrap());
+        assert!(signer_exists("usb://ledger").unwrap());
+        assert!(signer_exists("s3://bucket/key.json").is_err());
+    }
+
+    #[test]
+    fn test_stored_signer_pubkey_skips_interactive_signers() {
+        use tally_sdk::solana_sdk::signature::write_keypair_file;
+
+        for uri in ["stdin:", "prompt:", "usb://ledger", "exec:/bin/false"] {
+            assert_eq!(stored_signer_pubkey(uri).unwrap(), None, "{uri}");
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        let keypair = Keypair::new();
+        write_keypair_file(&keypair, &path).unwrap();
+        assert_eq!(
+            stored_signer_pubkey(path.to_str().unwrap()).unwrap(),
+            Some(keypair.pubkey())
+        );
+    }
+
+    #[test]
+    fn test_ledger_derivation_path() {
+        assert_eq!(
+            derivation_path("usb://ledger").unwrap(),
+            DerivationPath::default()
+        );
+        assert_eq!(
+            derivation_path("usb://ledger?key=1/0").unwrap(),
+            DerivationPath::new_bip44(Some(1), Some(0))
+        );
+        assert_eq!(
+            derivation_path("usb://ledger/BsNsvfXqQTtJnagwFWdBS7FBXgnsK8VZ5CmuznN85swK?key=2")
+                .unwrap(),
+            DerivationPath::new_bip44(Some(2), None)
+        );
+        assert!(derivation_path("usb://ledger?key=x").is_err());
+    }
+
+    #[test]
+    fn test_external_signers_not_accepted_as_keypair() {
+        let error = load_signer_keypair("exec:/bin/signer")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("local keypair"));
+        let error = load_signer_keypair("usb://ledger").unwrap_err().to_string();
+        assert!(error.contains("hardware wallet"));
+    }
+
+    #[test]
+    fn test_keypair_from_seed_lines() {
+        let expected = keypair_from_seed_phrase_and_passphrase(PHRASE, "").unwrap();
+        let keypair = keypair_from_seed_lines(format!("  {PHRASE}  \n").as_bytes()).unwrap();
+        assert_eq!(keypair.pubkey(), expected.pubkey());
+
+        let expected = keypair_from_seed_phrase_and_passphrase(PHRASE, "hunter2").unwrap();
+        let keypair = keypair_from_seed_lines(format!("{PHRASE}\nhunter2\n").as_bytes()).unwrap();
+        assert_eq!(keypair.pubkey(), expected.pubkey());
+
+        assert!(keypair_from_seed_lines(&b"park remain person\n"[..]).is_err());
+        assert!(keypair_from_seed_lines(&b""[..]).is_err());
+    }
+
+    /// Write a fake external signer that answers for `keypair` and signs only `message`
+    #[cfg(unix)]
+    fn fake_signer(dir: &std::path::Path, keypair: &Keypair, message: &[u8]) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("fake-signer");
+        let script = format!(
+            "#!/bin/sh\n\
+             case \"$1\" in\n\
+               pubkey) echo {pubkey} ;;\n\
+               sign)\n\
+                 [ \"$2\" = {pubkey} ] || exit 3\n\
+                 read -r message\n\
+                 [ \"$message\" = {message} ] || exit 4\n\
+                 echo {signature} ;;\n\
+               *) exit 2 ;;\n\
+             esac\n",
+            pubkey = keypair.pubkey(),
+            message = STANDARD.encode(message),
+            signature = keypair.sign_message(message),
+        );
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.display().to_string()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_signer_protocol() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = Keypair::new();
+        let message = b"tally message";
+        let helper = fake_signer(dir.path(), &keypair, message);
+
+        let signer = load_signer(&format!("exec:{helper}")).unwrap();
+        assert_eq!(signer.pubkey(), keypair.pubkey());
+        assert_eq!(
+            signer.try_sign_message(message).unwrap(),
+            keypair.sign_message(message)
+        );
+
+        // The helper refuses other messages
+        assert!(signer.try_sign_message(b"other message").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_signer_rejects_wrong_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let keypair = Keypair::new();
+        let impostor = Keypair::new();
+        let message = b"tally message";
+        // Answers with keypair's address but signs with another key
+        let helper = fake_signer(dir.path(), &impostor, message);
+        let script = std::fs::read_to_string(&helper).unwrap().replace(
+            &impostor.pubkey().to_string(),
+            &keypair.pubkey().to_string(),
+        );
+        std::fs::write(&helper, script).unwrap();
+
+        let signer = ExecSigner::new(&helper).unwrap();
+        let error = signer.try_sign_message(message).unwrap_err().to_string();
+        assert!(error.contains("does not verify"));
+    }
+
+    #[test]
+    fn test_exec_signer_missing_program() {
+        let error = ExecSigner::new("/nonexistent/tally-signer")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("could not be started"));
+    }
+}
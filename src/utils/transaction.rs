@@ -0,0 +1,1024 @@
+//! Transaction planning, simulation and export
+//!
+//! Write commands describe the transaction they are about to submit as a
+//! [`TxPlan`]. Unless the [`TxMode`] is [`TxMode::Send`], the plan is not sent:
+//! `--dry-run` simulates it against the RPC and reports a [`SimulationReport`],
+//! `--export-tx`/`--sign-only` hand it to [`crate::utils::offline`], and
+//! `--multisig` turns it into a [`crate::utils::multisig`] proposal. External
+//! signers (see [`crate::utils::signer`]) also send plans, with [`send_plan`].
+//!
+//! Whatever the mode, a command reports a [`TxOutcome`]: its own result when
+//! the transaction was sent, or the simulation, export or proposal otherwise.
+//! A sent transaction the program rejects is decoded with [`explain_failure`].
+
+use crate::errors::{enhance_transaction_error, preflight_logs};
+use crate::output::{serialize_display, CommandOutput};
+use crate::utils::colors::Theme;
+use crate::utils::formatting::lamports_to_sol;
+use crate::utils::multisig::{self, MultisigOptions, ProposalReport};
+use crate::utils::offline::{self, ExportOptions, ExportReport, NonceOptions};
+use crate::utils::progress;
+use crate::utils::signer::{self, SignerSource};
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tally_sdk::solana_client::rpc_config::RpcSimulateTransactionConfig;
+use tally_sdk::solana_sdk::commitment_config::CommitmentConfig;
+use tally_sdk::solana_sdk::hash::Hash;
+use tally_sdk::solana_sdk::instruction::Instruction;
+use tally_sdk::solana_sdk::message::Message;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::{Keypair, Signature, Signer};
+use tally_sdk::solana_sdk::transaction::Transaction;
+use tally_sdk::SimpleTallyClient;
+
+/// What a write command does with its transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TxMode {
+    /// Sign with the local keypair and submit
+    #[default]
+    Send,
+    /// Simulate against the RPC without signing (`--dry-run`)
+    DryRun,
+    /// Build for offline signing (`--export-tx` / `--sign-only`)
+    Export(ExportOptions),
+    /// Emit a proposal signed by a multisig vault (`--multisig`)
+    Propose(MultisigOptions),
+}
+
+/// Raw values of the global transaction flags
+#[derive(Debug, Default)]
+pub struct TxModeFlags<'a> {
+    pub dry_run: bool,
+    pub export_tx: Option<&'a str>,
+    pub sign_only: bool,
+    pub blockhash: Option<&'a str>,
+    pub nonce: Option<&'a str>,
+    pub nonce_authority: Option<&'a str>,
+    pub multisig: Option<&'a str>,
+    pub vault_index: Option<u8>,
+    pub proposal_out: Option<&'a str>,
+}
+
+impl TxMode {
+    /// Resolve the mode from the global transaction flags
+    ///
+    /// # Errors
+    /// Returns error if the flags conflict or an address or hash is invalid
+    pub fn from_flags(flags: &TxModeFlags<'_>) -> Result<Self> {
+        let exporting = flags.export_tx.is_some() || flags.sign_only;
+        if flags.dry_run && exporting {
+            return Err(anyhow!(
+                "--dry-run cannot be combined with --export-tx or --sign-only"
+            ));
+        }
+        if !exporting && (flags.blockhash.is_some() || flags.nonce.is_some()) {
+            return Err(anyhow!(
+                "--blockhash and --nonce only apply with --export-tx or --sign-only"
+            ));
+        }
+        if flags.nonce_authority.is_some() && flags.nonce.is_none() {
+            return Err(anyhow!("--nonce-authority requires --nonce"));
+        }
+        if flags.multisig.is_none() && (flags.vault_index.is_some() || flags.proposal_out.is_some())
+        {
+            return Err(anyhow!(
+                "--vault-index and --proposal-out only apply with --multisig"
+            ));
+        }
+
+        if let Some(multisig) = flags.multisig {
+            if flags.dry_run || exporting {
+                return Err(anyhow!(
+                    "--multisig cannot be combined with --dry-run, --export-tx or --sign-only"
+                ));
+            }
+            return Ok(Self::Propose(MultisigOptions {
+                multisig: parse_address(multisig, "multisig")?,
+                vault_index: flags.vault_index.unwrap_or_default(),
+                path: flags.proposal_out.map(PathBuf::from),
+            }));
+        }
+
+        if flags.dry_run {
+            return Ok(Self::DryRun);
+        }
+        if !exporting {
+            return Ok(Self::Send);
+        }
+
+        let blockhash = flags
+            .blockhash
+            .map(|hash| {
+                Hash::from_str(hash).map_err(|e| anyhow!("Invalid blockhash '{hash}': {e}"))
+            })
+            .transpose()?;
+        let nonce = flags
+            .nonce
+            .map(|account| -> Result<NonceOptions> {
+                Ok(NonceOptions {
+                    account: parse_address(account, "nonce account")?,
+                    authority: flags
+                        .nonce_authority
+                        .map(|authority| parse_address(authority, "nonce authority"))
+                        .transpose()?,
+                })
+            })
+            .transpose()?;
+
+        Ok(Self::Export(ExportOptions {
+            path: flags.export_tx.map(PathBuf::from),
+            sign: flags.sign_only,
+            blockhash,
+            nonce,
+        }))
+    }
+
+    /// Whether the transaction is signed and submitted
+    #[must_use]
+    pub const fn is_send(&self) -> bool {
+        matches!(self, Self::Send)
+    }
+
+    /// Whether the local private key is needed
+    ///
+    /// Simulations and unsigned exports only need the signer's address.
+    #[must_use]
+    pub const fn needs_private_key(&self) -> bool {
+        match self {
+            Self::Send => true,
+            Self::DryRun | Self::Propose(_) => false,
+            Self::Export(options) => options.sign,
+        }
+    }
+}
+
+fn parse_address(value: &str, what: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| anyhow!("Invalid {what} address '{value}': {e}"))
+}
+
+/// The signer of a command's transaction
+///
+/// When the private key is not needed (see [`TxMode::needs_private_key`]) the
+/// `--authority` flag may name an address instead of a keypair file, so the
+/// key can stay on an offline machine, and a `keystore:` wallet resolves to
+/// its address without being unlocked. `exec:` and `usb://` signer URIs load
+/// as [`TxSigner::External`].
+#[derive(Debug)]
+pub enum TxSigner {
+    Keypair(Keypair),
+    Address(Pubkey),
+    /// A signer whose key never enters this process
+    External(Box<dyn Signer>),
+}
+
+/// How [`TxSigner::load`] gets a signer, decided before any key is opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SignerKind {
+    /// An address given in place of a keypair, or the multisig vault
+    Address(Pubkey),
+    /// The public key of a keystore wallet, read without unlocking it
+    KeystoreAddress,
+    /// A hardware wallet or `exec:` signer
+    External,
+    /// A local keypair from a path or signer URI
+    Keypair,
+    /// The Solana CLI default keypair
+    DefaultKeypair,
+}
+
+impl SignerKind {
+    /// Decide how to load the signer at `path` for `mode`
+    ///
+    /// Modes that do not sign never unlock a keystore wallet or ask for a
+    /// seed phrase, since only the address is needed.
+    fn resolve(path: Option<&str>, mode: &TxMode) -> Result<Self> {
+        if let TxMode::Propose(options) = mode {
+            return Ok(Self::Address(options.vault()));
+        }
+        let Some(path) = path else {
+            return Ok(Self::DefaultKeypair);
+        };
+        let signs = mode.needs_private_key();
+        if !signs {
+            if let Ok(address) = Pubkey::from_str(path) {
+                return Ok(Self::Address(address));
+            }
+        }
+        match SignerSource::parse(path)? {
+            SignerSource::Usb(_) | SignerSource::Exec(_) => Ok(Self::External),
+            SignerSource::Keystore(_) if !signs => Ok(Self::KeystoreAddress),
+            SignerSource::Prompt if !signs => Err(anyhow!(
+                "'prompt:' asks for a seed phrase, but only the signer's address is needed here.\n\
+                 Pass --authority <ADDRESS> instead."
+            )),
+            _ => Ok(Self::Keypair),
+        }
+    }
+}
+
+impl TxSigner {
+    /// Load the signer for `mode` from a keypair path, a signer URI or, if
+    /// allowed, an address
+    ///
+    /// In [`TxMode::Propose`] the signer is always the multisig vault and
+    /// `path` is ignored.
+    ///
+    /// # Errors
+    /// Returns error if the signer cannot be loaded
+    pub fn load(path: Option<&str>, mode: &TxMode) -> Result<Self> {
+        let uri = path.unwrap_or_default();
+        match SignerKind::resolve(path, mode)? {
+            SignerKind::Address(address) => Ok(Self::Address(address)),
+            SignerKind::KeystoreAddress => Ok(Self::Address(signer::signer_pubkey(uri)?)),
+            SignerKind::External => Ok(Self::External(signer::load_signer(uri)?)),
+            SignerKind::Keypair => Ok(Self::Keypair(signer::load_signer_keypair(uri)?)),
+            SignerKind::DefaultKeypair => Ok(Self::Keypair(tally_sdk::load_keypair(None)?)),
+        }
+    }
+
+    #[must_use]
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            Self::Keypair(keypair) => keypair.pubkey(),
+            Self::Address(address) => *address,
+            Self::External(signer) => signer.pubkey(),
+        }
+    }
+
+    /// The keypair, if this signer has one
+    #[must_use]
+    pub const fn keypair(&self) -> Option<&Keypair> {
+        match self {
+            Self::Keypair(keypair) => Some(keypair),
+            Self::Address(_) | Self::External(_) => None,
+        }
+    }
+
+    /// Anything that can sign, keypair or external
+    #[must_use]
+    pub fn signer(&self) -> Option<&dyn Signer> {
+        match self {
+            Self::Keypair(keypair) => Some(keypair),
+            Self::Address(_) => None,
+            Self::External(signer) => Some(signer.as_ref()),
+        }
+    }
+
+    /// Take the keypair needed to sign and send
+    ///
+    /// # Errors
+    /// Returns error if only an address was given
+    pub fn into_keypair(self) -> Result<Keypair> {
+        match self {
+            Self::Keypair(keypair) => Ok(keypair),
+            Self::Address(address) => Err(anyhow!(
+                "Signing requires the keypair for {address}, but only its address was given.\n\
+                 Pass --authority <KEYPAIR>, or use --export-tx to sign on another machine."
+            )),
+            Self::External(signer) => Err(anyhow!(
+                "{} is an external signer, which this operation does not support.\n\
+                 Pass --authority <KEYPAIR>, or use --export-tx to sign on another machine.",
+                signer.pubkey()
+            )),
+        }
+    }
+}
+
+/// A transaction a command intends to submit
+#[derive(Debug, Clone)]
+pub struct TxPlan {
+    /// Short description of the operation, e.g. "Initialize payee"
+    pub title: String,
+    pub fee_payer: Pubkey,
+    pub instructions: Vec<Instruction>,
+}
+
+impl TxPlan {
+    #[must_use]
+    pub fn new(
+        title: impl Into<String>,
+        fee_payer: Pubkey,
+        instructions: Vec<Instruction>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            fee_payer,
+            instructions,
+        }
+    }
+}
+
+/// An account referenced by a planned transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct TouchedAccount {
+    #[serde(serialize_with = "serialize_display")]
+    #[schemars(with = "String")]
+    pub pubkey: Pubkey,
+    pub signer: bool,
+    pub writable: bool,
+    /// Whether the account already exists on-chain
+    pub exists: bool,
+}
+
+impl TouchedAccount {
+    /// Whether submitting the transaction would create this account
+    ///
+    /// Anything writable that does not exist yet and is not a signer is an
+    /// account the program initializes (a PDA or an associated token account).
+    #[must_use]
+    pub const fn would_be_created(&self) -> bool {
+        self.writable && !self.signer && !self.exists
+    }
+}
+
+/// Result of simulating a [`TxPlan`]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SimulationReport {
+    pub title: String,
+    /// Transaction error reported by the simulation, if it would fail
+    pub error: Option<String>,
+    pub compute_units: Option<u64>,
+    pub fee_lamports: u64,
+    pub logs: Vec<String>,
+    pub accounts: Vec<TouchedAccount>,
+}
+
+impl SimulationReport {
+    /// Accounts the transaction would create
+    pub fn created_accounts(&self) -> impl Iterator<Item = &TouchedAccount> {
+        self.accounts.iter().filter(|a| a.would_be_created())
+    }
+}
+
+/// What a write command did with its transaction
+///
+/// Only [`TxMode::Send`] produces [`TxOutcome::Sent`], carrying the command's
+/// own result. In JSON the variant is the `mode` field next to its contents.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum TxOutcome<T> {
+    /// Signed, submitted and confirmed
+    Sent(T),
+    /// Simulated with `--dry-run`
+    Simulated(SimulationReport),
+    /// Written for offline signing with `--export-tx` or `--sign-only`
+    Exported(ExportReport),
+    /// Emitted as a multisig proposal with `--multisig`
+    Proposed(ProposalReport),
+}
+
+impl<T: CommandOutput> CommandOutput for TxOutcome<T> {
+    const KIND: &'static str = T::KIND;
+
+    fn human(&self) -> Result<String> {
+        match self {
+            Self::Sent(result) => result.human(),
+            Self::Simulated(report) => format_simulation(report),
+            Self::Exported(report) => offline::format_export(report),
+            Self::Proposed(report) => multisig::format_proposal_report(report),
+        }
+    }
+
+    /// A dry run whose simulation fails is a failure, so `--dry-run` works as a CI gate
+    fn success(&self) -> bool {
+        match self {
+            Self::Sent(result) => result.success(),
+            Self::Simulated(report) => report.error.is_none(),
+            Self::Exported(_) | Self::Proposed(_) => true,
+        }
+    }
+}
+
+/// List the accounts a message references, in message order
+///
+/// `exists` is left `false`; [`simulate`] fills it in from the RPC.
+#[must_use]
+pub fn touched_accounts(message: &Message) -> Vec<TouchedAccount> {
+    message
+        .account_keys
+        .iter()
+        .enumerate()
+        .map(|(i, pubkey)| TouchedAccount {
+            pubkey: *pubkey,
+            signer: message.is_signer(i),
+            writable: message.is_writable(i),
+            exists: false,
+        })
+        .collect()
+}
+
+/// Simulate a planned transaction without signing or sending it
+///
+/// Signature verification is disabled and the RPC substitutes a recent
+/// blockhash, so no keypair is needed beyond the fee payer's address.
+///
+/// # Errors
+/// Returns error if any RPC call fails. A transaction that would fail on-chain
+/// is not an error; it is reported in [`SimulationReport::error`].
+pub fn simulate(tally_client: &SimpleTallyClient, plan: &TxPlan) -> Result<SimulationReport> {
+    let rpc = &tally_client.rpc_client;
+
+    let blockhash = rpc
+        .get_latest_blockhash()
+        .context("Failed to fetch recent blockhash - check RPC connection")?;
+    let message =
+        Message::new_with_blockhash(&plan.instructions, Some(&plan.fee_payer), &blockhash);
+    let fee_lamports = rpc
+        .get_fee_for_message(&message)
+        .context("Failed to estimate transaction fee")?;
+
+    let mut accounts = touched_accounts(&message);
+    let keys: Vec<Pubkey> = accounts.iter().map(|a| a.pubkey).collect();
+    let existing = rpc
+        .get_multiple_accounts(&keys)
+        .context("Failed to fetch accounts referenced by the transaction")?;
+    for (account, fetched) in accounts.iter_mut().zip(existing) {
+        account.exists = fetched.is_some();
+    }
+
+    let transaction = Transaction::new_unsigned(message);
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..RpcSimulateTransactionConfig::default()
+    };
+    let result = rpc
+        .simulate_transaction_with_config(&transaction, config)
+        .context("Failed to simulate transaction")?
+        .value;
+
+    Ok(SimulationReport {
+        title: plan.title.clone(),
+        error: result.err.map(|e| e.to_string()),
+        compute_units: result.units_consumed,
+        fee_lamports,
+        logs: result.logs.unwrap_or_default(),
+        accounts,
+    })
+}
+
+/// Format a simulation report for human output
+///
+/// # Errors
+/// Returns error if string formatting fails
+pub fn format_simulation(report: &SimulationReport) -> Result<String> {
+    let mut output = String::new();
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::header("Dry run:"),
+        report.title
+    )?;
+
+    match &report.error {
+        None => writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Simulation:"),
+            Theme::success("succeeded")
+        )?,
+        Some(error) => writeln!(
+            &mut output,
+            "{} {}",
+            Theme::info("Simulation:"),
+            Theme::error(&format!("failed - {error}"))
+        )?,
+    }
+    writeln!(
+        &mut output,
+        "{} {}",
+        Theme::info("Compute units:"),
+        Theme::value(
+            &report
+                .compute_units
+                .map_or_else(|| "unknown".to_string(), |units| units.to_string())
+        )
+    )?;
+    writeln!(
+        &mut output,
+        "{} {} SOL ({} lamports)",
+        Theme::info("Estimated fee:"),
+        Theme::value(&lamports_to_sol(report.fee_lamports)),
+        report.fee_lamports
+    )?;
+
+    writeln!(&mut output, "\n{}", Theme::header("Accounts touched:"))?;
+    for account in &report.accounts {
+        writeln!(
+            &mut output,
+            "  {} {}",
+            account.pubkey,
+            Theme::dim(&account_flags(account))
+        )?;
+    }
+
+    let created: Vec<&TouchedAccount> = report.created_accounts().collect();
+    if !created.is_empty() {
+        writeln!(
+            &mut output,
+            "\n{}",
+            Theme::header("Accounts to be created:")
+        )?;
+        for account in created {
+            writeln!(
+                &mut output,
+                "  {}",
+                Theme::highlight(&account.pubkey.to_string())
+            )?;
+        }
+    }
+
+    if !report.logs.is_empty() {
+        writeln!(&mut output, "\n{}", Theme::header("Program logs:"))?;
+        for line in &report.logs {
+            writeln!(&mut output, "  {}", Theme::dim(line))?;
+        }
+    }
+
+    write!(
+        &mut output,
+        "\n{}",
+        Theme::dim("No transaction was submitted.")
+    )?;
+
+    Ok(output)
+}
+
+/// Describe an account's role, e.g. "(signer, writable)"
+fn account_flags(account: &TouchedAccount) -> String {
+    let mut flags = Vec::new();
+    if account.signer {
+        flags.push("signer");
+    }
+    flags.push(if account.writable {
+        "writable"
+    } else {
+        "readonly"
+    });
+    if account.would_be_created() {
+        flags.push("new");
+    }
+    format!("({})", flags.join(", "))
+}
+
+/// Handle a plan that is not being sent: simulate, export or propose it
+///
+/// # Errors
+/// Returns error if called in [`TxMode::Send`], or if simulating,
+/// exporting or writing the proposal fails
+pub fn simulate_or_export<T>(
+    tally_client: &SimpleTallyClient,
+    plan: &TxPlan,
+    mode: &TxMode,
+    signer: &TxSigner,
+) -> Result<TxOutcome<T>> {
+    match mode {
+        TxMode::Send => Err(anyhow!("{} must be signed and sent", plan.title)),
+        TxMode::DryRun => Ok(TxOutcome::Simulated(dry_run(tally_client, plan)?)),
+        TxMode::Export(options) => Ok(TxOutcome::Exported(offline::export(
+            tally_client,
+            plan,
+            options,
+            signer.signer(),
+        )?)),
+        TxMode::Propose(options) => Ok(TxOutcome::Proposed(multisig::propose(plan, options)?)),
+    }
+}
+
+/// Handle several plans that are not being sent, one transaction each
+///
+/// Commands that send one transaction per change preview them the same way,
+/// since a dozen changes do not fit in one transaction. With more than one
+/// plan, an output file such as `plan.json` becomes `plan-1.json`,
+/// `plan-2.json` and so on, in plan order.
+///
+/// # Errors
+/// Returns error if several plans would share one durable nonce, or if any
+/// plan fails to simulate, export or propose
+pub fn simulate_or_export_each<T>(
+    tally_client: &SimpleTallyClient,
+    plans: &[TxPlan],
+    mode: &TxMode,
+    signer: &TxSigner,
+) -> Result<Vec<TxOutcome<T>>> {
+    if plans.len() <= 1 {
+        return plans
+            .iter()
+            .map(|plan| simulate_or_export(tally_client, plan, mode, signer))
+            .collect();
+    }
+    if let TxMode::Export(ExportOptions { nonce: Some(_), .. }) = mode {
+        return Err(anyhow!(
+            "A durable nonce can only be used by one transaction, but {} are needed.\n\
+             Export without --nonce, or make the changes one at a time.",
+            plans.len()
+        ));
+    }
+    plans
+        .iter()
+        .enumerate()
+        .map(|(i, plan)| {
+            simulate_or_export(tally_client, plan, &numbered_mode(mode, i + 1), signer)
+        })
+        .collect()
+}
+
+/// The mode for the `n`th of several plans, numbering its output file
+fn numbered_mode(mode: &TxMode, n: usize) -> TxMode {
+    let mut mode = mode.clone();
+    if let TxMode::Export(ExportOptions {
+        path: Some(path), ..
+    })
+    | TxMode::Propose(MultisigOptions {
+        path: Some(path), ..
+    }) = &mut mode
+    {
+        *path = numbered_path(path, n);
+    }
+    mode
+}
+
+/// Insert `-n` before a file's extension, e.g. `plan.json` -> `plan-2.json`
+fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+    let name = match path.extension() {
+        Some(extension) => format!("{stem}-{n}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{n}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Sign a plan with `signer` and submit it
+///
+/// # Errors
+/// Returns error if the plan needs other signers, signing fails, or the
+/// transaction is rejected
+pub fn send(
+    tally_client: &SimpleTallyClient,
+    plan: &TxPlan,
+    signer: &dyn Signer,
+) -> Result<Signature> {
+    let blockhash = tally_client
+        .rpc_client
+        .get_latest_blockhash()
+        .context("Failed to fetch recent blockhash - check RPC connection")?;
+    let mut transaction = offline::build_transaction(plan, &blockhash, None);
+    offline::sign_transaction(&mut transaction, signer)?;
+    if !transaction.is_signed() {
+        return Err(anyhow!(
+            "{} needs signatures from more than {}",
+            plan.title,
+            signer.pubkey()
+        ));
+    }
+    tally_client
+        .rpc_client
+        .send_and_confirm_transaction(&transaction)
+        .map_err(|e| {
+            enhance_transaction_error(&format!("{} failed", plan.title), &e, &preflight_logs(&e))
+        })
+}
+
+/// Explain why a transaction sent by the SDK failed
+///
+/// The SDK's error only carries the message, so the failure is decoded with
+/// [`enhance_transaction_error`] using the logs from [`failure_logs`].
+#[must_use]
+pub fn explain_failure<E: std::fmt::Display>(
+    tally_client: &SimpleTallyClient,
+    plan: impl FnOnce() -> Result<TxPlan>,
+    action: &str,
+    error: E,
+) -> anyhow::Error {
+    let message = error.to_string();
+    let logs = failure_logs(tally_client, plan, &message);
+    enhance_transaction_error(action, message, &logs)
+}
+
+/// Program logs of a failed send, recovered by simulating the plan again
+///
+/// Empty when the failure does not point at the program, or the simulation
+/// itself fails.
+pub fn failure_logs(
+    tally_client: &SimpleTallyClient,
+    plan: impl FnOnce() -> Result<TxPlan>,
+    message: &str,
+) -> Vec<String> {
+    if !is_program_failure(message) {
+        return Vec::new();
+    }
+    plan()
+        .and_then(|plan| simulate(tally_client, &plan))
+        .map(|report| report.logs)
+        .unwrap_or_default()
+}
+
+/// Whether an error message reports a transaction rejected by a program
+#[must_use]
+pub fn is_program_failure(message: &str) -> bool {
+    message.contains("custom program error")
+        || message.contains("Error processing Instruction")
+        || message.contains("Transaction simulation failed")
+}
+
+/// Send a plan with an external signer, showing a spinner while waiting
+///
+/// Commands use this instead of the SDK's convenience methods, which only
+/// sign with a local [`Keypair`], and build their result from the signature.
+///
+/// # Errors
+/// Returns error if signing or sending fails
+pub fn send_plan(
+    tally_client: &SimpleTallyClient,
+    plan: &TxPlan,
+    signer: &dyn Signer,
+) -> Result<Signature> {
+    let spinner = progress::create_spinner(&format!("{}...", plan.title));
+    let result = send(tally_client, plan, signer);
+
+    match &result {
+        Ok(_) => progress::finish_progress_success(&spinner, &plan.title),
+        Err(_) => progress::finish_progress_error(&spinner, &plan.title),
+    }
+    result
+}
+
+/// Simulate a plan, showing a spinner while waiting
+///
+/// # Errors
+/// Returns error if the simulation RPC calls fail
+pub fn dry_run(tally_client: &SimpleTallyClient, plan: &TxPlan) -> Result<SimulationReport> {
+    let spinner = progress::create_spinner("Simulating transaction...");
+    let result = simulate(tally_client, plan);
+
+    match &result {
+        Ok(report) if report.error.is_none() => {
+            progress::finish_progress_success(&spinner, "Simulation succeeded");
+        }
+        Ok(_) => progress::finish_progress_error(&spinner, "Simulation reported an error"),
+        Err(_) => progress::finish_progress_error(&spinner, "Failed to simulate transaction"),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tally_sdk::solana_sdk::instruction::AccountMeta;
+
+    fn sample_plan() -> (TxPlan, Pubkey, Pubkey, Pubkey) {
+        let payer = Pubkey::new_unique();
+        let pda = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let instruction = Instruction::new_with_bytes(
+            program,
+            &[0],
+            vec![AccountMeta::new(payer, true), AccountMeta::new(pda, false)],
+        );
+        let plan = TxPlan::new("Test", payer, vec![instruction]);
+        (plan, payer, pda, program)
+    }
+
+    fn simulation(error: Option<&str>) -> SimulationReport {
+        SimulationReport {
+            title: "Test".to_string(),
+            error: error.map(str::to_string),
+            compute_units: Some(5_000),
+            fee_lamports: 5_000,
+            logs: Vec::new(),
+            accounts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_failed_simulation_is_not_success() {
+        let passed: TxOutcome<crate::commands::admin::AdminSubmitted> =
+            TxOutcome::Simulated(simulation(None));
+        assert!(passed.success());
+
+        let failed: TxOutcome<crate::commands::admin::AdminSubmitted> =
+            TxOutcome::Simulated(simulation(Some("custom program error: 0x1770")));
+        assert!(!failed.success());
+        assert!(!crate::output::Output::from(failed).success());
+    }
+
+    #[test]
+    fn test_tx_mode_from_flags() {
+        assert_eq!(
+            TxMode::from_flags(&TxModeFlags::default()).unwrap(),
+            TxMode::Send
+        );
+        let flags = TxModeFlags {
+            dry_run: true,
+            ..TxModeFlags::default()
+        };
+        assert_eq!(TxMode::from_flags(&flags).unwrap(), TxMode::DryRun);
+
+        let nonce = Pubkey::new_unique();
+        let nonce_str = nonce.to_string();
+        let flags = TxModeFlags {
+            export_tx: Some("withdraw.tx"),
+            nonce: Some(&nonce_str),
+            ..TxModeFlags::default()
+        };
+        let TxMode::Export(options) = TxMode::from_flags(&flags).unwrap() else {
+            panic!("expected export mode");
+        };
+        assert_eq!(options.path, Some(PathBuf::from("withdraw.tx")));
+        assert!(!options.sign);
+        assert_eq!(options.nonce.unwrap().account, nonce);
+    }
+
+    #[test]
+    fn test_tx_mode_rejects_conflicts() {
+        let flags = TxModeFlags {
+            dry_run: true,
+            sign_only: true,
+            ..TxModeFlags::default()
+        };
+        assert!(TxMode::from_flags(&flags).is_err());
+
+        let flags = TxModeFlags {
+            blockhash: Some("11111111111111111111111111111111"),
+            ..TxModeFlags::default()
+        };
+        assert!(TxMode::from_flags(&flags).is_err());
+
+        let flags = TxModeFlags {
+            sign_only: true,
+            blockhash: Some("not-a-hash"),
+            ..TxModeFlags::default()
+        };
+        assert!(TxMode::from_flags(&flags).is_err());
+
+        let multisig = Pubkey::new_unique().to_string();
+        let flags = TxModeFlags {
+            multisig: Some(&multisig),
+            export_tx: Some("withdraw.tx"),
+            ..TxModeFlags::default()
+        };
+        assert!(TxMode::from_flags(&flags).is_err());
+
+        let flags = TxModeFlags {
+            proposal_out: Some("proposal.json"),
+            ..TxModeFlags::default()
+        };
+        assert!(TxMode::from_flags(&flags).is_err());
+    }
+
+    #[test]
+    fn test_multisig_signer_is_vault() {
+        let multisig = Pubkey::new_unique();
+        let multisig_str = multisig.to_string();
+        let flags = TxModeFlags {
+            multisig: Some(&multisig_str),
+            vault_index: Some(1),
+            ..TxModeFlags::default()
+        };
+        let mode = TxMode::from_flags(&flags).unwrap();
+        assert!(!mode.needs_private_key());
+
+        let signer = TxSigner::load(Some("/nonexistent/keypair.json"), &mode).unwrap();
+        assert_eq!(signer.pubkey(), multisig::derive_vault(&multisig, 1));
+        assert!(signer.keypair().is_none());
+    }
+
+    #[test]
+    fn test_tx_signer_accepts_address_without_private_key() {
+        let address = Pubkey::new_unique();
+        let address_str = address.to_string();
+
+        let signer = TxSigner::load(Some(&address_str), &TxMode::DryRun).unwrap();
+        assert_eq!(signer.pubkey(), address);
+        assert!(signer.keypair().is_none());
+        assert!(signer.into_keypair().is_err());
+    }
+
+    #[test]
+    fn test_signer_kind_dispatches_external_uris() {
+        // Resolving the kind never opens the device or runs the command
+        for uri in ["usb://ledger", "usb://ledger?key=1/0", "exec:/bin/signer"] {
+            assert_eq!(
+                SignerKind::resolve(Some(uri), &TxMode::Send).unwrap(),
+                SignerKind::External
+            );
+            assert_eq!(
+                SignerKind::resolve(Some(uri), &TxMode::DryRun).unwrap(),
+                SignerKind::External
+            );
+        }
+        assert_eq!(
+            SignerKind::resolve(Some("~/id.json"), &TxMode::Send).unwrap(),
+            SignerKind::Keypair
+        );
+        assert_eq!(
+            SignerKind::resolve(None, &TxMode::Send).unwrap(),
+            SignerKind::DefaultKeypair
+        );
+    }
+
+    #[test]
+    fn test_signer_kind_does_not_unlock_keys_without_signing() {
+        assert_eq!(
+            SignerKind::resolve(Some("keystore:merchant"), &TxMode::DryRun).unwrap(),
+            SignerKind::KeystoreAddress
+        );
+        assert_eq!(
+            SignerKind::resolve(Some("keystore:merchant"), &TxMode::Send).unwrap(),
+            SignerKind::Keypair
+        );
+        let error = SignerKind::resolve(Some("prompt:"), &TxMode::DryRun).unwrap_err();
+        assert!(error.to_string().contains("--authority <ADDRESS>"));
+        assert_eq!(
+            SignerKind::resolve(Some("prompt:"), &TxMode::Send).unwrap(),
+            SignerKind::Keypair
+        );
+    }
+
+    #[test]
+    fn test_numbered_path() {
+        assert_eq!(
+            numbered_path(Path::new("out/plan.json"), 2),
+            PathBuf::from("out/plan-2.json")
+        );
+        assert_eq!(numbered_path(Path::new("plan"), 1), PathBuf::from("plan-1"));
+    }
+
+    #[test]
+    fn test_touched_accounts_roles() {
+        let (plan, payer, pda, program) = sample_plan();
+        let accounts = touched_accounts(&Message::new(&plan.instructions, Some(&plan.fee_payer)));
+
+        let find = |key: &Pubkey| accounts.iter().find(|a| a.pubkey == *key).unwrap();
+        assert!(find(&payer).signer && find(&payer).writable);
+        assert!(!find(&pda).signer && find(&pda).writable);
+        assert!(!find(&program).signer && !find(&program).writable);
+        assert_eq!(accounts.len(), 3);
+    }
+
+    #[test]
+    fn test_would_be_created() {
+        let (plan, payer, pda, _) = sample_plan();
+        let mut accounts =
+            touched_accounts(&Message::new(&plan.instructions, Some(&plan.fee_payer)));
+
+        let created: Vec<Pubkey> = accounts
+            .iter()
+            .filter(|a| a.would_be_created())
+            .map(|a| a.pubkey)
+            .collect();
+        assert_eq!(created, vec![pda]);
+        assert!(!created.contains(&payer));
+
+        for account in &mut accounts {
+            account.exists = true;
+        }
+        assert!(!accounts.iter().any(TouchedAccount::would_be_created));
+    }
+
+    #[test]
+    fn test_format_simulation() {
+        let (plan, _, pda, _) = sample_plan();
+        let report = SimulationReport {
+            title: "Initialize payee".to_string(),
+            error: None,
+            compute_units: Some(12_345),
+            fee_lamports: 5_000,
+            logs: vec!["Program log: Instruction: InitPayee".to_string()],
+            accounts: touched_accounts(&Message::new(&plan.instructions, Some(&plan.fee_payer))),
+        };
+
+        let output = format_simulation(&report).unwrap();
+        assert!(output.contains("Dry run: Initialize payee"));
+        assert!(output.contains("Compute units: 12345"));
+        assert!(output.contains("0.000005000 SOL (5000 lamports)"));
+        assert!(output.contains("Accounts to be created:"));
+        assert!(output.contains(&pda.to_string()));
+        assert!(output.contains("Instruction: InitPayee"));
+        assert!(output.contains("No transaction was submitted."));
+    }
+
+    #[test]
+    fn test_format_simulation_failure() {
+        let report = SimulationReport {
+            title: "Withdraw".to_string(),
+            error: Some("custom program error: 0x1771".to_string()),
+            compute_units: None,
+            fee_lamports: 5_000,
+            logs: Vec::new(),
+            accounts: Vec::new(),
+        };
+
+        let output = format_simulation(&report).unwrap();
+        assert!(output.contains("failed - custom program error: 0x1771"));
+        assert!(output.contains("Compute units: unknown"));
+        assert!(!output.contains("Accounts to be created:"));
+    }
+}
@@ -0,0 +1,744 @@
+//! Decode sent transactions: Tally instructions, accounts, events and token
+//! balance changes
+//!
+//! Transactions are fetched with the raw `getTransaction` method in base64
+//! encoding, so the message is decoded into the same types the CLI signs,
+//! while the status metadata is read from the JSON response.
+//!
+//! Tally is an Anchor program: instruction data starts with the first 8
+//! bytes of `sha256("global:<instruction>")` followed by the borsh-encoded
+//! arguments, and account data with the first 8 bytes of
+//! `sha256("account:<Account>")`.
+
+use crate::errors::{CliError, ErrorCode, ProgramErrorCode};
+use crate::utils::program_errors::{
+    self, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use borsh::BorshDeserialize;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use tally_sdk::program_types::{
+    CreatePaymentTermsArgs, InitConfigArgs, UpdateConfigFeesArgs, UpdatePaymentTermsArgs,
+    VolumeTier,
+};
+use tally_sdk::solana_client::rpc_client::RpcClient;
+use tally_sdk::solana_client::rpc_request::RpcRequest;
+use tally_sdk::solana_sdk::hash::hashv;
+use tally_sdk::solana_sdk::pubkey::Pubkey;
+use tally_sdk::solana_sdk::signature::Signature;
+use tally_sdk::solana_sdk::transaction::VersionedTransaction;
+use tally_sdk::{parse_events_from_logs, TallyEvent};
+use tracing::warn;
+
+/// Associated Token Account program
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+/// Compute Budget program
+const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Arguments a Tally instruction carries after its discriminator
+#[derive(Debug, Clone, Copy)]
+enum Args {
+    None,
+    InitConfig,
+    UpdateConfigFees,
+    Paused,
+    NewAuthority,
+    Amount,
+    VolumeTier,
+    CreatePaymentTerms,
+    UpdatePaymentTerms,
+}
+
+/// Tally instructions and their arguments
+///
+/// Arguments this CLI doesn't know (e.g. those of `init_payee` and
+/// `start_agreement`) are shown as raw data.
+const INSTRUCTIONS: &[(&str, Args)] = &[
+    ("init_config", Args::InitConfig),
+    ("update_config_fees", Args::UpdateConfigFees),
+    ("set_config_paused", Args::Paused),
+    ("transfer_platform_authority", Args::NewAuthority),
+    ("accept_platform_authority", Args::None),
+    ("init_payee", Args::None),
+    ("update_payee_treasury", Args::None),
+    ("withdraw_payee_treasury", Args::Amount),
+    ("set_payee_pending_authority", Args::NewAuthority),
+    ("accept_payee_authority", Args::None),
+    ("set_payee_volume_tier", Args::VolumeTier),
+    ("create_payment_terms", Args::CreatePaymentTerms),
+    ("update_payment_terms", Args::UpdatePaymentTerms),
+    ("deactivate_payment_terms", Args::None),
+    ("start_agreement", Args::None),
+    ("execute_payment", Args::None),
+    ("pause_agreement", Args::None),
+    ("resume_agreement", Args::None),
+    ("close_agreement", Args::None),
+];
+
+/// Tally accounts by the Anchor account name, with the kind shown for them
+const ACCOUNTS: &[(&str, &str)] = &[
+    ("Config", "config"),
+    ("Payee", "payee"),
+    ("PaymentTerms", "payment-terms"),
+    ("PaymentAgreement", "payment-agreement"),
+];
+
+/// A confirmed transaction as returned by `getTransaction`
+#[derive(Debug, Clone)]
+pub struct FetchedTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub transaction: VersionedTransaction,
+    /// Static account keys followed by those loaded from lookup tables
+    pub account_keys: Vec<Pubkey>,
+    /// The response's `meta` object
+    pub meta: Value,
+}
+
+impl FetchedTransaction {
+    /// Decode a `getTransaction` response in base64 encoding
+    ///
+    /// # Errors
+    /// Returns error if the transaction or a loaded address can't be decoded
+    pub fn from_response(response: &Value) -> Result<Self> {
+        let encoded = response["transaction"][0]
+            .as_str()
+            .ok_or_else(|| anyhow!("RPC response has no base64-encoded transaction"))?;
+        let bytes = STANDARD
+            .decode(encoded)
+            .context("Failed to decode transaction base64")?;
+        let transaction: VersionedTransaction =
+            bincode::deserialize(&bytes).context("Failed to decode transaction")?;
+
+        let meta = response["meta"].clone();
+        let mut account_keys = transaction.message.static_account_keys().to_vec();
+        for group in ["writable", "readonly"] {
+            for address in meta["loadedAddresses"][group]
+                .as_array()
+                .into_iter()
+                .flatten()
+            {
+                let address = address
+                    .as_str()
+                    .and_then(|address| address.parse().ok())
+                    .ok_or_else(|| anyhow!("Invalid loaded address {address}"))?;
+                account_keys.push(address);
+            }
+        }
+
+        Ok(Self {
+            slot: response["slot"].as_u64().unwrap_or_default(),
+            block_time: response["blockTime"].as_i64(),
+            transaction,
+            account_keys,
+            meta,
+        })
+    }
+
+    /// Program log lines
+    #[must_use]
+    pub fn logs(&self) -> Vec<String> {
+        log_messages(&self.meta)
+    }
+
+    /// Fee paid, in lamports
+    #[must_use]
+    pub fn fee(&self) -> u64 {
+        self.meta["fee"].as_u64().unwrap_or_default()
+    }
+}
+
+/// Fetch a confirmed transaction
+///
+/// # Errors
+/// Returns error if the RPC request fails or the transaction is not found
+pub fn fetch_transaction(rpc: &RpcClient, signature: &Signature) -> Result<FetchedTransaction> {
+    let response: Value = rpc
+        .send(
+            RpcRequest::GetTransaction,
+            json!([
+                signature.to_string(),
+                {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]),
+        )
+        .map_err(|e| anyhow!("Failed to fetch transaction {signature}: {e}"))?;
+    if response.is_null() {
+        return Err(CliError::new(
+            ErrorCode::General,
+            format!("Transaction {signature} not found at {}", rpc.url()),
+        )
+        .with_suggestion(
+            "Check that --rpc-url points at the cluster the transaction was sent to, \
+             and that it has been confirmed.",
+        )
+        .into());
+    }
+    FetchedTransaction::from_response(&response)
+}
+
+/// Log lines from a transaction's `meta`
+#[must_use]
+pub fn log_messages(meta: &Value) -> Vec<String> {
+    meta["logMessages"]
+        .as_array()
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| line.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The error of a failed transaction and the program error behind it
+///
+/// Returns `None` if the transaction succeeded.
+#[must_use]
+pub fn transaction_error(meta: &Value) -> Option<(String, Option<ProgramErrorCode>)> {
+    let error = &meta["err"];
+    if error.is_null() {
+        return None;
+    }
+
+    // {"InstructionError": [0, {"Custom": 6001}]}
+    let instruction_error = &error["InstructionError"];
+    let instruction = instruction_error[0]
+        .as_u64()
+        .and_then(|index| u8::try_from(index).ok());
+    let custom = instruction_error[1]["Custom"]
+        .as_u64()
+        .and_then(|code| u32::try_from(code).ok());
+
+    let program_error = match (program_errors::failing_program(&log_messages(meta)), custom) {
+        (Some((program_id, code)), _) => Some(ProgramErrorCode::decode(
+            instruction,
+            code,
+            Some(program_id),
+        )),
+        (None, Some(code)) => Some(ProgramErrorCode::decode(instruction, code, None)),
+        (None, None) => None,
+    };
+    Some((error.to_string(), program_error))
+}
+
+/// One top-level instruction of a transaction
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DecodedInstruction {
+    pub index: usize,
+    pub program_id: String,
+    /// Name of a well-known program, e.g. `Tally` or `SPL Token`
+    pub program: Option<String>,
+    /// Instruction name, for Tally instructions
+    pub name: Option<String>,
+    /// Decoded arguments, for Tally instructions that take any
+    pub args: Option<Value>,
+    pub accounts: Vec<String>,
+    /// Instruction data as hex, when it was not decoded
+    pub data: Option<String>,
+}
+
+/// Decode the top-level instructions, naming Tally's and their arguments
+#[must_use]
+pub fn decode_instructions(
+    fetched: &FetchedTransaction,
+    program_id: &Pubkey,
+) -> Vec<DecodedInstruction> {
+    let key = |index: u8| {
+        fetched
+            .account_keys
+            .get(usize::from(index))
+            .map_or_else(|| format!("<invalid index {index}>"), ToString::to_string)
+    };
+
+    fetched
+        .transaction
+        .message
+        .instructions()
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let program = key(instruction.program_id_index);
+            let accounts = instruction.accounts.iter().map(|&i| key(i)).collect();
+            let mut decoded = DecodedInstruction {
+                index,
+                program: program_name(&program, program_id).map(str::to_string),
+                program_id: program,
+                name: None,
+                args: None,
+                accounts,
+                data: None,
+            };
+            if decoded.program_id != program_id.to_string() {
+                decoded.data = Some(to_hex(&instruction.data));
+                return decoded;
+            }
+            let Some((name, args)) = decode_tally_instruction(&instruction.data) else {
+                decoded.data = Some(to_hex(&instruction.data));
+                return decoded;
+            };
+            decoded.name = Some(name.to_string());
+            match args {
+                DecodedArgs::None => {}
+                DecodedArgs::Decoded(args) => decoded.args = Some(args),
+                DecodedArgs::Raw(rest) => decoded.data = Some(to_hex(rest)),
+            }
+            decoded
+        })
+        .collect()
+}
+
+/// Name of a well-known program
+fn program_name(address: &str, tally_program_id: &Pubkey) -> Option<&'static str> {
+    match address {
+        TOKEN_PROGRAM_ID => Some("SPL Token"),
+        TOKEN_2022_PROGRAM_ID => Some("SPL Token-2022"),
+        SYSTEM_PROGRAM_ID => Some("System"),
+        ASSOCIATED_TOKEN_PROGRAM_ID => Some("Associated Token"),
+        COMPUTE_BUDGET_PROGRAM_ID => Some("Compute Budget"),
+        _ if address == tally_program_id.to_string() => Some("Tally"),
+        _ => None,
+    }
+}
+
+/// Arguments of a Tally instruction
+enum DecodedArgs<'a> {
+    None,
+    Decoded(Value),
+    /// Bytes that don't match what the CLI expects for the instruction
+    Raw(&'a [u8]),
+}
+
+/// Name a Tally instruction and decode its arguments
+fn decode_tally_instruction(data: &[u8]) -> Option<(&'static str, DecodedArgs<'_>)> {
+    let (discriminator, rest) = data.split_at_checked(8)?;
+    let (name, args) = INSTRUCTIONS
+        .iter()
+        .find(|(name, _)| anchor_discriminator("global", name) == discriminator)?;
+    Some((name, decode_args(*args, rest)))
+}
+
+/// Decode borsh-encoded arguments, which must use every byte
+fn decode_args(args: Args, data: &[u8]) -> DecodedArgs<'_> {
+    let mut buf = data;
+    let value = match args {
+        Args::None if data.is_empty() => return DecodedArgs::None,
+        Args::None => None,
+        Args::InitConfig => InitConfigArgs::deserialize(&mut buf).ok().map(|args| {
+            json!({
+                "platform_authority": args.platform_authority.to_string(),
+                "max_platform_fee_bps": args.max_platform_fee_bps,
+                "min_platform_fee_bps": args.min_platform_fee_bps,
+                "min_period_seconds": args.min_period_seconds,
+                "default_allowance_periods": args.default_allowance_periods,
+                "allowed_mint": args.allowed_mint.to_string(),
+                "max_withdrawal_amount": args.max_withdrawal_amount,
+                "max_grace_period_seconds": args.max_grace_period_seconds,
+                "keeper_fee_bps": args.keeper_fee_bps,
+            })
+        }),
+        Args::UpdateConfigFees => UpdateConfigFeesArgs::deserialize(&mut buf)
+            .ok()
+            .map(|args| {
+                json!({
+                    "min_platform_fee_bps": args.min_platform_fee_bps,
+                    "max_platform_fee_bps": args.max_platform_fee_bps,
+                    "keeper_fee_bps": args.keeper_fee_bps,
+                })
+            }),
+        Args::Paused => bool::deserialize(&mut buf)
+            .ok()
+            .map(|paused| json!({ "paused": paused })),
+        Args::NewAuthority => <[u8; 32]>::deserialize(&mut buf)
+            .ok()
+            .map(|bytes| json!({ "new_authority": Pubkey::new_from_array(bytes).to_string() })),
+        Args::Amount => u64::deserialize(&mut buf)
+            .ok()
+            .map(|amount| json!({ "amount": amount })),
+        Args::VolumeTier => VolumeTier::deserialize(&mut buf)
+            .ok()
+            .map(|tier| json!({ "volume_tier": format!("{tier:?}") })),
+        Args::CreatePaymentTerms => {
+            CreatePaymentTermsArgs::deserialize(&mut buf)
+                .ok()
+                .map(|args| {
+                    json!({
+                        "terms_id": args.terms_id,
+                        "amount_usdc": args.amount_usdc,
+                        "period_secs": args.period_secs,
+                    })
+                })
+        }
+        Args::UpdatePaymentTerms => {
+            UpdatePaymentTermsArgs::deserialize(&mut buf)
+                .ok()
+                .map(|args| {
+                    json!({
+                        "amount_usdc": args.amount_usdc,
+                        "period_secs": args.period_secs,
+                    })
+                })
+        }
+    };
+    match value {
+        Some(value) if buf.is_empty() => DecodedArgs::Decoded(value),
+        _ => DecodedArgs::Raw(data),
+    }
+}
+
+/// First 8 bytes of `sha256("<namespace>:<name>")`
+fn anchor_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = hashv(&[namespace.as_bytes(), b":", name.as_bytes()]).to_bytes();
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// Kind of a Tally account, from the discriminator its data starts with
+#[must_use]
+pub fn account_kind(data: &[u8]) -> Option<&'static str> {
+    let discriminator = data.get(..8)?;
+    ACCOUNTS
+        .iter()
+        .find(|(name, _)| anchor_discriminator("account", name) == discriminator)
+        .map(|(_, kind)| *kind)
+}
+
+/// A Tally account a transaction touched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct TallyAccount {
+    pub address: String,
+    /// `config`, `payee`, `payment-terms` or `payment-agreement`
+    pub kind: String,
+}
+
+/// A Tally event emitted by a transaction
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DecodedEvent {
+    pub name: String,
+    pub payee: Option<String>,
+    pub payment_terms: Option<String>,
+    pub payer: Option<String>,
+    /// USDC base units
+    pub amount: Option<u64>,
+    pub keeper: Option<String>,
+    /// USDC base units
+    pub keeper_fee: Option<u64>,
+    pub payment_number: Option<u32>,
+    /// USDC base units
+    pub current_allowance: Option<u64>,
+    pub reason: Option<String>,
+    /// Event data as hex, for events the SDK doesn't know
+    pub data: Option<String>,
+}
+
+impl DecodedEvent {
+    fn new(name: &str, payee: &Pubkey, payment_terms: &Pubkey, subscriber: &Pubkey) -> Self {
+        Self {
+            name: name.to_string(),
+            payee: Some(payee.to_string()),
+            payment_terms: Some(payment_terms.to_string()),
+            payer: Some(subscriber.to_string()),
+            amount: None,
+            keeper: None,
+            keeper_fee: None,
+            payment_number: None,
+            current_allowance: None,
+            reason: None,
+            data: None,
+        }
+    }
+}
+
+/// Decode the Tally events in a transaction's logs
+#[must_use]
+pub fn decode_events(logs: &[String]) -> Vec<DecodedEvent> {
+    let parsed = parse_events_from_logs(logs);
+    for error in &parsed.errors {
+        warn!(
+            "Failed to decode event (log line {}): {}",
+            error.log_index, error.error
+        );
+    }
+    parsed.events.iter().map(decode_event).collect()
+}
+
+fn decode_event(event: &TallyEvent) -> DecodedEvent {
+    match event {
+        TallyEvent::PaymentAgreementStarted(e) => DecodedEvent {
+            amount: Some(e.amount),
+            ..DecodedEvent::new(
+                "PaymentAgreementStarted",
+                &e.payee,
+                &e.payment_terms,
+                &e.payer,
+            )
+        },
+        TallyEvent::PaymentExecuted(e) => DecodedEvent {
+            amount: Some(e.amount),
+            keeper: Some(e.keeper.to_string()),
+            keeper_fee: Some(e.keeper_fee),
+            payment_number: Some(e.payment_number),
+            ..DecodedEvent::new("PaymentExecuted", &e.payee, &e.payment_terms, &e.payer)
+        },
+        TallyEvent::PaymentAgreementPaused(e) => DecodedEvent::new(
+            "PaymentAgreementPaused",
+            &e.payee,
+            &e.payment_terms,
+            &e.payer,
+        ),
+        TallyEvent::PaymentAgreementResumed(e) => DecodedEvent {
+            payment_number: Some(e.payments_completed),
+            ..DecodedEvent::new(
+                "PaymentAgreementResumed",
+                &e.payee,
+                &e.payment_terms,
+                &e.payer,
+            )
+        },
+        TallyEvent::PaymentFailed(e) => DecodedEvent {
+            reason: Some(e.reason.clone()),
+            ..DecodedEvent::new("PaymentFailed", &e.payee, &e.payment_terms, &e.payer)
+        },
+        TallyEvent::LowAllowanceWarning(e) => DecodedEvent {
+            current_allowance: Some(e.current_allowance),
+            ..DecodedEvent::new("LowAllowanceWarning", &e.payee, &e.payment_terms, &e.payer)
+        },
+        TallyEvent::Unknown {
+            discriminator,
+            data,
+        } => DecodedEvent {
+            name: "Unknown".to_string(),
+            payee: None,
+            payment_terms: None,
+            payer: None,
+            amount: None,
+            keeper: None,
+            keeper_fee: None,
+            payment_number: None,
+            current_allowance: None,
+            reason: None,
+            data: Some(format!("{}{}", to_hex(discriminator), to_hex(data))),
+        },
+    }
+}
+
+/// How a token account's balance changed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct TokenBalanceChange {
+    pub account: String,
+    pub owner: Option<String>,
+    pub mint: String,
+    /// Whose account it is: `treasury`, `payee-authority`, `platform`, `keeper` or `payer`
+    pub role: Option<String>,
+    /// Base units before the transaction
+    pub pre_amount: u64,
+    /// Base units after the transaction
+    pub post_amount: u64,
+    /// `post_amount - pre_amount`
+    pub change: i64,
+    pub decimals: u8,
+}
+
+/// Token balances that changed, in account order
+///
+/// An account missing before the transaction was created by it, and one
+/// missing after was closed; both count as a zero balance.
+#[must_use]
+pub fn token_balance_changes(meta: &Value, account_keys: &[Pubkey]) -> Vec<TokenBalanceChange> {
+    let mut balances: BTreeMap<u64, TokenBalanceChange> = BTreeMap::new();
+    for (field, is_post) in [("preTokenBalances", false), ("postTokenBalances", true)] {
+        for balance in meta[field].as_array().into_iter().flatten() {
+            let Some(index) = balance["accountIndex"].as_u64() else {
+                continue;
+            };
+            let amount = balance["uiTokenAmount"]["amount"]
+                .as_str()
+                .and_then(|amount| amount.parse().ok())
+                .unwrap_or_default();
+            let entry = balances.entry(index).or_insert_with(|| TokenBalanceChange {
+                account: usize::try_from(index)
+                    .ok()
+                    .and_then(|i| account_keys.get(i))
+                    .map_or_else(|| format!("<invalid index {index}>"), ToString::to_string),
+                owner: balance["owner"].as_str().map(str::to_string),
+                mint: balance["mint"].as_str().unwrap_or_default().to_string(),
+                role: None,
+                pre_amount: 0,
+                post_amount: 0,
+                change: 0,
+                decimals: balance["uiTokenAmount"]["decimals"]
+                    .as_u64()
+                    .and_then(|d| u8::try_from(d).ok())
+                    .unwrap_or_default(),
+            });
+            if is_post {
+                entry.post_amount = amount;
+            } else {
+                entry.pre_amount = amount;
+            }
+        }
+    }
+
+    balances
+        .into_values()
+        .filter_map(|mut balance| {
+            let change = i128::from(balance.post_amount) - i128::from(balance.pre_amount);
+            balance.change = i64::try_from(change).unwrap_or(i64::MAX);
+            (change != 0).then_some(balance)
+        })
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::to_vec;
+    use tally_sdk::solana_sdk::hash::Hash;
+    use tally_sdk::solana_sdk::instruction::{AccountMeta, Instruction};
+    use tally_sdk::solana_sdk::message::{Message, VersionedMessage};
+
+    fn instruction_data(name: &str, args: &[u8]) -> Vec<u8> {
+        [anchor_discriminator("global", name).as_slice(), args].concat()
+    }
+
+    fn fetched(program_id: &Pubkey, instructions: &[Instruction]) -> FetchedTransaction {
+        let payer = Pubkey::new_unique();
+        let message = Message::new_with_blockhash(instructions, Some(&payer), &Hash::default());
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        };
+        let response = json!({
+            "slot": 42,
+            "blockTime": 1_700_000_000,
+            "transaction": [STANDARD.encode(bincode::serialize(&transaction).unwrap()), "base64"],
+            "meta": {"err": null, "fee": 5000, "logMessages": [], "loadedAddresses": {
+                "writable": [program_id.to_string()], "readonly": []
+            }}
+        });
+        FetchedTransaction::from_response(&response).unwrap()
+    }
+
+    #[test]
+    fn test_decode_instructions() {
+        let program_id = Pubkey::new_unique();
+        let terms = Pubkey::new_unique();
+        let args = CreatePaymentTermsArgs {
+            terms_id: "pro".to_string(),
+            terms_id_bytes: [0; 32],
+            amount_usdc: 10_000_000,
+            period_secs: 2_592_000,
+        };
+        let fetched = fetched(
+            &program_id,
+            &[
+                Instruction::new_with_bytes(
+                    program_id,
+                    &instruction_data("create_payment_terms", &to_vec(&args).unwrap()),
+                    vec![AccountMeta::new(terms, false)],
+                ),
+                Instruction::new_with_bytes(
+                    program_id,
+                    &instruction_data("start_agreement", &[1, 2]),
+                    vec![],
+                ),
+                Instruction::new_with_bytes(Pubkey::new_unique(), &[7], vec![]),
+            ],
+        );
+        assert_eq!(fetched.slot, 42);
+        assert_eq!(fetched.fee(), 5000);
+        // The loaded address follows the static keys
+        assert_eq!(fetched.account_keys.last(), Some(&program_id));
+
+        let decoded = decode_instructions(&fetched, &program_id);
+        assert_eq!(decoded[0].program.as_deref(), Some("Tally"));
+        assert_eq!(decoded[0].name.as_deref(), Some("create_payment_terms"));
+        let args = decoded[0].args.as_ref().unwrap();
+        assert_eq!(args["terms_id"], "pro");
+        assert_eq!(args["amount_usdc"], 10_000_000);
+        assert_eq!(decoded[0].accounts, vec![terms.to_string()]);
+
+        // Unknown arguments are kept as raw data
+        assert_eq!(decoded[1].name.as_deref(), Some("start_agreement"));
+        assert_eq!(decoded[1].data.as_deref(), Some("0102"));
+
+        assert_eq!(decoded[2].name, None);
+        assert_eq!(decoded[2].data.as_deref(), Some("07"));
+    }
+
+    #[test]
+    fn test_account_kind() {
+        let mut data = anchor_discriminator("account", "PaymentTerms").to_vec();
+        data.extend([0; 16]);
+        assert_eq!(account_kind(&data), Some("payment-terms"));
+        assert_eq!(account_kind(&[0; 16]), None);
+        assert_eq!(account_kind(&[]), None);
+    }
+
+    #[test]
+    fn test_token_balance_changes() {
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let balance = |index: u64, amount: &str| {
+            json!({
+                "accountIndex": index,
+                "mint": "USDC",
+                "owner": "Owner",
+                "uiTokenAmount": {"amount": amount, "decimals": 6}
+            })
+        };
+        let meta = json!({
+            "preTokenBalances": [balance(0, "5000000"), balance(2, "7")],
+            "postTokenBalances": [balance(0, "0"), balance(1, "4900000"), balance(2, "7")]
+        });
+        let changes = token_balance_changes(&meta, &keys);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].account, keys[0].to_string());
+        assert_eq!(changes[0].change, -5_000_000);
+        assert_eq!(changes[1].pre_amount, 0);
+        assert_eq!(changes[1].change, 4_900_000);
+    }
+
+    #[test]
+    fn test_transaction_error() {
+        assert!(transaction_error(&json!({"err": null})).is_none());
+
+        let meta = json!({
+            "err": {"InstructionError": [1, {"Custom": 1}]},
+            "logMessages": [
+                format!("Program {TOKEN_PROGRAM_ID} invoke [2]"),
+                "Program log: Error: insufficient funds",
+                format!("Program {TOKEN_PROGRAM_ID} failed: custom program error: 0x1"),
+            ]
+        });
+        let (error, program_error) = transaction_error(&meta).unwrap();
+        assert!(error.contains("InstructionError"));
+        // The logs show the token program raised the code, not Tally
+        let program_error = program_error.unwrap();
+        assert_eq!(
+            program_error.source,
+            Some(program_errors::ErrorSource::SplToken)
+        );
+        assert_eq!(program_error.instruction, Some(1));
+        assert_eq!(program_error.name.as_deref(), Some("InsufficientFunds"));
+    }
+}